@@ -0,0 +1,69 @@
+use crate::db::{certificate_repo, host_repo, proxy_repo};
+use crate::models::certificate::{CertificateInfo, TrustedCertificate};
+use crate::models::host::{FtpsMode, Protocol};
+use crate::services::certificate::{
+    decode_certificate, fetch_ftps_certificate, inspect_certificate,
+};
+use crate::SharedDatabase;
+use tauri::State;
+
+/// 获取 FTPS/WebDAVS 主机当前出示的证书，供前端展示并询问是否信任
+#[tauri::command]
+pub async fn fetch_host_certificate(
+    host_id: i64,
+    db: State<'_, SharedDatabase>,
+) -> Result<CertificateInfo, String> {
+    let (host, proxy) = {
+        let key = db.encryption_key();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let host = host_repo::get_by_id(&conn, host_id, key)?
+            .ok_or_else(|| format!("Host {} not found", host_id))?;
+        let proxy = proxy_repo::resolve(&conn, &host, key)?;
+        (host, proxy)
+    };
+    // HTTPS 在 TCP 建立后立即握手，与隐式 FTPS 相同
+    let mode = match host.protocol {
+        Protocol::Ftps => host.ftps_mode,
+        Protocol::Webdavs => FtpsMode::Implicit,
+        _ => return Err("仅 FTPS 和 WebDAVS 主机支持证书信任".to_string()),
+    };
+    tokio::task::spawn_blocking(move || {
+        fetch_ftps_certificate(&host.host, host.port, mode, proxy.as_ref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 信任并固定证书；指纹与 subject 由后端根据证书内容重新计算
+#[tauri::command]
+pub fn trust_host_certificate(
+    host_id: i64,
+    certificate: String,
+    db: State<'_, SharedDatabase>,
+) -> Result<TrustedCertificate, String> {
+    let der = decode_certificate(&certificate)?;
+    let info = inspect_certificate(&der)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    certificate_repo::upsert(&conn, &TrustedCertificate::new(host_id, info))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_trusted_certificate(
+    host_id: i64,
+    db: State<'_, SharedDatabase>,
+) -> Result<Option<TrustedCertificate>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    certificate_repo::get_by_host(&conn, host_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_trusted_certificate(
+    host_id: i64,
+    db: State<'_, SharedDatabase>,
+) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    certificate_repo::delete_by_host(&conn, host_id)
+        .map_err(|e| e.to_string())
+        .map(|_| ())
+}
@@ -1,15 +1,54 @@
-use crate::db::host_repo;
-use crate::models::host::Host;
-use crate::services::connection::{ConnectionManager, FileEntry};
+use crate::db::{certificate_repo, host_key_repo, host_repo, proxy_repo};
+use crate::models::file_op::{ConflictPolicy, FileOpResult};
+use crate::models::host::{Host, Protocol};
+use crate::services::auth_prompt::AuthPromptBroker;
+use crate::services::connection::{
+    apply_recursive, ConnectOptions, ConnectionManager, ConnectionTrait, FileEntry, JumpHost,
+};
+use crate::services::file_op;
 use crate::validation::host::validate_host;
 use crate::SharedDatabase;
+use std::sync::Arc;
 use tauri::State;
 
+/// 加载主机连接所需的附加信息（已固定的证书、已信任的主机密钥、跳板机链、代理），
+/// 并接入键盘交互认证的提示通道
+pub(crate) fn load_connect_options(
+    db: &SharedDatabase,
+    host: &Host,
+    prompts: &AuthPromptBroker,
+) -> Result<ConnectOptions, String> {
+    let mut options = ConnectOptions {
+        auth_prompter: Some(Arc::new(prompts.clone())),
+        ..Default::default()
+    };
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    if let Some(host_id) = host.id {
+        options.trusted_certificate =
+            certificate_repo::get_by_host(&conn, host_id).map_err(|e| e.to_string())?;
+    }
+    options.proxy = proxy_repo::resolve(&conn, host, db.encryption_key())?;
+    if host.protocol == Protocol::Sftp {
+        options.trusted_host_key =
+            host_key_repo::get(&conn, &host.host, host.port).map_err(|e| e.to_string())?;
+        for jump in host_repo::get_jump_chain(&conn, host, db.encryption_key())? {
+            let trusted_host_key =
+                host_key_repo::get(&conn, &jump.host, jump.port).map_err(|e| e.to_string())?;
+            options.jump_hosts.push(JumpHost {
+                host: jump,
+                trusted_host_key,
+            });
+        }
+    }
+    Ok(options)
+}
+
 #[tauri::command]
 pub async fn connect_host(
     host_id: i64,
     db: State<'_, SharedDatabase>,
     manager: State<'_, ConnectionManager>,
+    prompts: State<'_, AuthPromptBroker>,
 ) -> Result<(), String> {
     let host = {
         let key = db.encryption_key();
@@ -18,9 +57,10 @@ pub async fn connect_host(
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("Host {} not found", host_id))?
     };
+    let options = load_connect_options(&db, &host, &prompts)?;
 
     let manager = manager.inner().clone();
-    tokio::task::spawn_blocking(move || manager.connect(&host))
+    tokio::task::spawn_blocking(move || manager.connect(&host, &options))
         .await
         .map_err(|e| e.to_string())?
 }
@@ -37,9 +77,14 @@ pub async fn disconnect_host(
 }
 
 #[tauri::command]
-pub async fn test_connection(host: Host) -> Result<(), String> {
+pub async fn test_connection(
+    host: Host,
+    db: State<'_, SharedDatabase>,
+    prompts: State<'_, AuthPromptBroker>,
+) -> Result<(), String> {
     validate_host(&host)?;
-    tokio::task::spawn_blocking(move || ConnectionManager::test_connection(&host))
+    let options = load_connect_options(&db, &host, &prompts)?;
+    tokio::task::spawn_blocking(move || ConnectionManager::test_connection(&host, &options))
         .await
         .map_err(|e| e.to_string())?
 }
@@ -48,6 +93,7 @@ pub async fn test_connection(host: Host) -> Result<(), String> {
 pub async fn test_connection_by_id(
     host_id: i64,
     db: State<'_, SharedDatabase>,
+    prompts: State<'_, AuthPromptBroker>,
 ) -> Result<(), String> {
     let host = {
         let key = db.encryption_key();
@@ -56,11 +102,22 @@ pub async fn test_connection_by_id(
             .map_err(|e| e.to_string())?
             .ok_or_else(|| format!("Host {} not found", host_id))?
     };
-    tokio::task::spawn_blocking(move || ConnectionManager::test_connection(&host))
+    let options = load_connect_options(&db, &host, &prompts)?;
+    tokio::task::spawn_blocking(move || ConnectionManager::test_connection(&host, &options))
         .await
         .map_err(|e| e.to_string())?
 }
 
+/// 回答 `ssh-auth-prompt` 事件；answers 为 null 表示取消登录
+#[tauri::command]
+pub fn respond_auth_prompt(
+    request_id: String,
+    answers: Option<Vec<String>>,
+    prompts: State<'_, AuthPromptBroker>,
+) -> Result<(), String> {
+    prompts.respond(&request_id, answers)
+}
+
 #[tauri::command]
 pub fn connection_status(
     host_id: i64,
@@ -152,6 +209,44 @@ pub async fn rename_remote(
     .map_err(|e| e.to_string())?
 }
 
+/// 把多个文件或目录复制到同一主机的 dest_dir 下，返回每项的结果
+#[tauri::command]
+pub async fn copy_remote_items(
+    host_id: i64,
+    paths: Vec<String>,
+    dest_dir: String,
+    conflict_policy: Option<ConflictPolicy>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<Vec<FileOpResult>, String> {
+    let conn = manager.get_connection(host_id)?;
+    let policy = conflict_policy.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        Ok(file_op::copy_items(&mut **conn, &paths, &dest_dir, policy))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 把多个文件或目录移动到同一主机的 dest_dir 下，返回每项的结果
+#[tauri::command]
+pub async fn move_remote_items(
+    host_id: i64,
+    paths: Vec<String>,
+    dest_dir: String,
+    conflict_policy: Option<ConflictPolicy>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<Vec<FileOpResult>, String> {
+    let conn = manager.get_connection(host_id)?;
+    let policy = conflict_policy.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        Ok(file_op::move_items(&mut **conn, &paths, &dest_dir, policy))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub async fn remote_file_exists(
     host_id: i64,
@@ -181,3 +276,80 @@ pub async fn remote_file_size(
     .await
     .map_err(|e| e.to_string())?
 }
+
+#[tauri::command]
+pub async fn stat_remote(
+    host_id: i64,
+    path: String,
+    manager: State<'_, ConnectionManager>,
+) -> Result<FileEntry, String> {
+    let conn = manager.get_connection(host_id)?;
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        conn.stat(&path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 对单个路径执行修改；recursive 且为目录时递归处理其下所有条目，返回处理的条目数
+fn apply_to_path(
+    conn: &mut dyn ConnectionTrait,
+    path: &str,
+    recursive: bool,
+    op: &mut dyn FnMut(&mut dyn ConnectionTrait, &str) -> Result<(), String>,
+) -> Result<u64, String> {
+    if recursive {
+        let entry = conn.stat(path)?;
+        if entry.is_dir && !entry.is_symlink {
+            return apply_recursive(conn, path, op);
+        }
+    }
+    op(conn, path)?;
+    Ok(1)
+}
+
+#[tauri::command]
+pub async fn set_remote_permissions(
+    host_id: i64,
+    path: String,
+    mode: u32,
+    recursive: bool,
+    manager: State<'_, ConnectionManager>,
+) -> Result<u64, String> {
+    if mode > 0o7777 {
+        return Err(format!("无效的权限值: {:o}", mode));
+    }
+    let conn = manager.get_connection(host_id)?;
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        apply_to_path(&mut **conn, &path, recursive, &mut |c, p| {
+            c.set_permissions(p, mode)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn set_remote_owner(
+    host_id: i64,
+    path: String,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    recursive: bool,
+    manager: State<'_, ConnectionManager>,
+) -> Result<u64, String> {
+    if uid.is_none() && gid.is_none() {
+        return Err("请至少指定属主或属组".to_string());
+    }
+    let conn = manager.get_connection(host_id)?;
+    tokio::task::spawn_blocking(move || {
+        let mut conn = conn.lock().map_err(|e| e.to_string())?;
+        apply_to_path(&mut **conn, &path, recursive, &mut |c, p| {
+            c.set_owner(p, uid, gid)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
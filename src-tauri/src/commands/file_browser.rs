@@ -1,5 +1,7 @@
 use crate::services::connection::FileEntry;
+use crate::services::local_client::read_dir_entries;
 use crate::utils::path::normalize_and_validate;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,41 +10,16 @@ pub struct LocalFileEntry {
     pub path: String,
     pub is_dir: bool,
     pub size: u64,
-    pub modified: Option<String>,
+    pub modified: Option<DateTime<Utc>>,
 }
 
 #[tauri::command]
 pub fn list_local_dir(path: String) -> Result<Vec<FileEntry>, String> {
     let safe_path = normalize_and_validate(&path)?;
-    let entries = std::fs::read_dir(&safe_path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-
-    let mut files = Vec::new();
-    for entry in entries {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let metadata = entry.metadata().map_err(|e| e.to_string())?;
-        let name = entry.file_name().to_string_lossy().to_string();
-
-        files.push(FileEntry {
-            name,
-            path: entry.path().to_string_lossy().to_string(),
-            is_dir: metadata.is_dir(),
-            size: metadata.len(),
-            modified: metadata
-                .modified()
-                .ok()
-                .and_then(|t| {
-                    t.duration_since(std::time::UNIX_EPOCH)
-                        .ok()
-                        .map(|d| d.as_secs().to_string())
-                }),
-        });
-    }
-
+    let mut files = read_dir_entries(&safe_path)?;
     files.sort_by(|a, b| {
         b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name))
     });
-
     Ok(files)
 }
 
@@ -67,4 +44,29 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_local_dir_symlinks() {
+        let temp = std::env::temp_dir().join("ftx_test_list_local_symlinks");
+        let _ = fs::remove_dir_all(&temp);
+        fs::create_dir_all(temp.join("real")).unwrap();
+        std::os::unix::fs::symlink("real", temp.join("link")).unwrap();
+        std::os::unix::fs::symlink("missing", temp.join("dangling")).unwrap();
+
+        let entries = list_local_dir(temp.to_string_lossy().to_string()).unwrap();
+        let link = entries.iter().find(|e| e.name == "link").unwrap();
+        assert!(link.is_symlink);
+        assert!(link.is_dir);
+        assert_eq!(link.link_target.as_deref(), Some("real"));
+        let dangling = entries.iter().find(|e| e.name == "dangling").unwrap();
+        assert!(dangling.is_symlink);
+        assert!(!dangling.is_dir);
+        let real = entries.iter().find(|e| e.name == "real").unwrap();
+        assert!(!real.is_symlink);
+        assert!(real.mode.is_some());
+        assert!(real.uid.is_some());
+
+        let _ = fs::remove_dir_all(&temp);
+    }
 }
@@ -16,6 +16,7 @@ pub fn create_host(db: State<'_, SharedDatabase>, host: Host) -> Result<Host, St
     validate_host(&host)?;
     let key = db.encryption_key();
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    host_repo::get_jump_chain(&conn, &host, key)?;
     host_repo::insert(&conn, &host, key).map_err(|e| e.to_string())
 }
 
@@ -24,6 +25,8 @@ pub fn update_host(db: State<'_, SharedDatabase>, host: Host) -> Result<(), Stri
     validate_host(&host)?;
     let key = db.encryption_key();
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    // 保存前确认跳板机存在且不会形成循环
+    host_repo::get_jump_chain(&conn, &host, key)?;
     host_repo::update(&conn, &host, key)
         .map_err(|e| e.to_string())
         .map(|_| ())
@@ -0,0 +1,74 @@
+use crate::db::host_repo;
+use crate::models::host_import::{HostImportEntry, HostImportResult};
+use crate::services::host_import::{dedupe_sources, import_entries, review};
+use crate::services::ssh_config::{parse_config, to_import_entries};
+use crate::services::{filezilla, winscp};
+use crate::utils::path::normalize_and_validate;
+use crate::SharedDatabase;
+use tauri::State;
+
+/// 解析 OpenSSH 配置生成导入预览，path 为空时读取 ~/.ssh/config
+#[tauri::command]
+pub fn preview_ssh_config_import(
+    db: State<'_, SharedDatabase>,
+    path: Option<String>,
+) -> Result<Vec<HostImportEntry>, String> {
+    let home = dirs::home_dir().ok_or_else(|| "无法确定用户主目录".to_string())?;
+    let config = match path {
+        Some(p) => normalize_and_validate(&p)?,
+        None => home.join(".ssh").join("config"),
+    };
+    if !config.is_file() {
+        return Err(format!("未找到 SSH 配置文件: {}", config.display()));
+    }
+    // 未配置 User 时 ssh 使用本机用户名
+    let local_user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let entries = to_import_entries(&parse_config(&config, &home)?, &local_user);
+    review_entries(&db, entries)
+}
+
+/// 解析 FileZilla 的 sitemanager.xml 或 WinSCP 导出的 ini，按内容识别格式
+#[tauri::command]
+pub fn preview_site_import(
+    db: State<'_, SharedDatabase>,
+    path: String,
+) -> Result<Vec<HostImportEntry>, String> {
+    let file = normalize_and_validate(&path)?;
+    let content = std::fs::read_to_string(&file).map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut entries = if content.contains("<FileZilla3") {
+        filezilla::parse_site_manager(&content)?
+    } else if content.contains("[Sessions\\") {
+        winscp::parse_ini(&content)?
+    } else {
+        return Err(
+            "无法识别的文件格式，请选择 FileZilla 的 sitemanager.xml 或 WinSCP 导出的 ini"
+                .to_string(),
+        );
+    };
+    dedupe_sources(&mut entries);
+    review_entries(&db, entries)
+}
+
+fn review_entries(
+    db: &SharedDatabase,
+    mut entries: Vec<HostImportEntry>,
+) -> Result<Vec<HostImportEntry>, String> {
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let existing = host_repo::get_all(&conn, key)?;
+    review(&mut entries, &existing);
+    Ok(entries)
+}
+
+/// 导入预览中选中的记录，返回每条记录的导入结果
+#[tauri::command]
+pub fn import_hosts(
+    db: State<'_, SharedDatabase>,
+    entries: Vec<HostImportEntry>,
+) -> Result<Vec<HostImportResult>, String> {
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    import_entries(&conn, entries, key)
+}
@@ -0,0 +1,73 @@
+use crate::commands::connection::load_connect_options;
+use crate::db::{host_key_repo, host_repo};
+use crate::models::host::{Host, Protocol};
+use crate::models::host_key::{HostKeyInfo, KnownHostKey};
+use crate::services::auth_prompt::AuthPromptBroker;
+use crate::services::connection::ConnectionManager;
+use crate::services::host_key::{decode_host_key, inspect_host_key};
+use crate::SharedDatabase;
+use tauri::State;
+
+fn load_sftp_host(db: &SharedDatabase, host_id: i64) -> Result<Host, String> {
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let host = host_repo::get_by_id(&conn, host_id, key)?
+        .ok_or_else(|| format!("Host {} not found", host_id))?;
+    if host.protocol != Protocol::Sftp {
+        return Err("仅 SFTP 主机支持主机密钥信任".to_string());
+    }
+    Ok(host)
+}
+
+/// 获取 SFTP 主机当前出示的主机密钥（算法与 SHA-256 指纹），供前端询问是否信任。
+/// 经跳板机时每一跳的主机密钥须已受信任，并照常认证
+#[tauri::command]
+pub async fn fetch_host_key(
+    host_id: i64,
+    db: State<'_, SharedDatabase>,
+    prompts: State<'_, AuthPromptBroker>,
+) -> Result<HostKeyInfo, String> {
+    let host = load_sftp_host(&db, host_id)?;
+    let options = load_connect_options(&db, &host, &prompts)?;
+    tokio::task::spawn_blocking(move || ConnectionManager::fetch_host_key(&host, &options))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 信任主机密钥；已保存的密钥与之不同时需显式传入 replace，避免误覆盖
+#[tauri::command]
+pub fn trust_host_key(
+    host_id: i64,
+    key: String,
+    replace: bool,
+    db: State<'_, SharedDatabase>,
+) -> Result<KnownHostKey, String> {
+    let host = load_sftp_host(&db, host_id)?;
+    let info = inspect_host_key(&host.host, host.port, &decode_host_key(&key)?)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    if let Some(existing) =
+        host_key_repo::get(&conn, &host.host, host.port).map_err(|e| e.to_string())?
+    {
+        if existing.key != info.key && !replace {
+            return Err(format!(
+                "已保存的主机密钥（{}）与新密钥不同，确认服务器更换过密钥后请选择替换",
+                existing.fingerprint
+            ));
+        }
+    }
+    host_key_repo::upsert(&conn, &KnownHostKey::new(info)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_known_host_keys(db: State<'_, SharedDatabase>) -> Result<Vec<KnownHostKey>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    host_key_repo::get_all(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_known_host_key(id: i64, db: State<'_, SharedDatabase>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    host_key_repo::delete(&conn, id)
+        .map_err(|e| e.to_string())
+        .map(|_| ())
+}
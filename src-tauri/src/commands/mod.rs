@@ -1,5 +1,12 @@
 pub mod bookmark;
+pub mod certificate;
 pub mod connection;
 pub mod file_browser;
 pub mod host;
+pub mod host_import;
+pub mod host_key;
+pub mod port_forward;
+pub mod proxy;
+pub mod remote_exec;
+pub mod terminal;
 pub mod transfer;
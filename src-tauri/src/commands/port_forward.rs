@@ -0,0 +1,89 @@
+use crate::db::port_forward_repo;
+use crate::models::port_forward::{PortForward, PortForwardStatus};
+use crate::services::connection::ConnectionManager;
+use crate::services::port_forward::PortForwardManager;
+use crate::validation::port_forward::validate_port_forward;
+use crate::SharedDatabase;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_port_forwards(
+    db: State<'_, SharedDatabase>,
+    host_id: i64,
+) -> Result<Vec<PortForward>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    port_forward_repo::get_by_host(&conn, host_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_port_forward(
+    db: State<'_, SharedDatabase>,
+    forward: PortForward,
+) -> Result<PortForward, String> {
+    validate_port_forward(&forward)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    port_forward_repo::insert(&conn, &forward).map_err(|e| e.to_string())
+}
+
+/// 修改对运行中的转发不生效，重新启动后按新定义监听
+#[tauri::command]
+pub fn update_port_forward(
+    db: State<'_, SharedDatabase>,
+    forward: PortForward,
+) -> Result<(), String> {
+    validate_port_forward(&forward)?;
+    let id = forward.id.ok_or("转发定义缺少 id")?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    if !port_forward_repo::update(&conn, &forward).map_err(|e| e.to_string())? {
+        return Err(format!("Port forward {} not found", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_port_forward(
+    db: State<'_, SharedDatabase>,
+    forwards: State<'_, PortForwardManager>,
+    id: i64,
+) -> Result<(), String> {
+    forwards.stop(id)?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    port_forward_repo::delete(&conn, id)
+        .map_err(|e| e.to_string())
+        .map(|_| ())
+}
+
+/// 在主机已建立的 SSH 连接上启动转发，主机需先连接
+#[tauri::command]
+pub async fn start_port_forward(
+    id: i64,
+    db: State<'_, SharedDatabase>,
+    manager: State<'_, ConnectionManager>,
+    forwards: State<'_, PortForwardManager>,
+) -> Result<PortForwardStatus, String> {
+    let forward = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        port_forward_repo::get_by_id(&conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Port forward {} not found", id))?
+    };
+    let conn = manager.get_connection(forward.host_id)?;
+    let forwards = forwards.inner().clone();
+    tokio::task::spawn_blocking(move || forwards.start(&forward, conn))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn stop_port_forward(forwards: State<'_, PortForwardManager>, id: i64) -> Result<(), String> {
+    forwards.stop(id)
+}
+
+/// 主机下启动过的转发的运行状态和流量统计
+#[tauri::command]
+pub fn get_port_forward_statuses(
+    forwards: State<'_, PortForwardManager>,
+    host_id: i64,
+) -> Result<Vec<PortForwardStatus>, String> {
+    forwards.statuses(host_id)
+}
@@ -0,0 +1,59 @@
+use crate::db::proxy_repo;
+use crate::models::proxy::ProxyConfig;
+use crate::validation::proxy::validate_proxy;
+use crate::SharedDatabase;
+use tauri::State;
+
+/// 返回给前端的代理设置不包含密码，保存时留空表示沿用已保存的密码
+fn without_password(proxy: Option<ProxyConfig>) -> Option<ProxyConfig> {
+    proxy.map(|p| ProxyConfig {
+        password: None,
+        ..p
+    })
+}
+
+#[tauri::command]
+pub fn get_global_proxy(db: State<'_, SharedDatabase>) -> Result<Option<ProxyConfig>, String> {
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    proxy_repo::get_global(&conn, key).map(without_password)
+}
+
+/// 保存全局代理，传入 None 时清除
+#[tauri::command]
+pub fn set_global_proxy(
+    db: State<'_, SharedDatabase>,
+    proxy: Option<ProxyConfig>,
+) -> Result<(), String> {
+    if let Some(ref p) = proxy {
+        validate_proxy(p)?;
+    }
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    proxy_repo::set_global(&conn, proxy.as_ref(), key)
+}
+
+#[tauri::command]
+pub fn get_host_proxy(
+    db: State<'_, SharedDatabase>,
+    host_id: i64,
+) -> Result<Option<ProxyConfig>, String> {
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    proxy_repo::get_for_host(&conn, host_id, key).map(without_password)
+}
+
+/// 保存主机自定义代理，仅在主机代理模式为 custom 时生效；传入 None 时清除
+#[tauri::command]
+pub fn set_host_proxy(
+    db: State<'_, SharedDatabase>,
+    host_id: i64,
+    proxy: Option<ProxyConfig>,
+) -> Result<(), String> {
+    if let Some(ref p) = proxy {
+        validate_proxy(p)?;
+    }
+    let key = db.encryption_key();
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    proxy_repo::set_for_host(&conn, host_id, proxy.as_ref(), key)
+}
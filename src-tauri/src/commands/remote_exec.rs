@@ -0,0 +1,50 @@
+use crate::services::connection::ConnectionManager;
+use crate::services::remote_exec::{run_command, OutputStream};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Serialize, Clone)]
+struct RemoteExecOutput {
+    exec_id: String,
+    stream: OutputStream,
+    data: String,
+}
+
+/// 在主机的 SSH 会话上执行命令，输出通过 `remote-exec-output` 事件按 exec_id 推送，返回退出码
+#[tauri::command]
+pub async fn run_remote_command(
+    host_id: i64,
+    exec_id: String,
+    command: String,
+    working_dir: Option<String>,
+    timeout_secs: Option<u64>,
+    app: AppHandle,
+    manager: State<'_, ConnectionManager>,
+) -> Result<i32, String> {
+    if command.trim().is_empty() {
+        return Err("命令不能为空".to_string());
+    }
+    let conn = manager.get_connection(host_id)?;
+    tokio::task::spawn_blocking(move || {
+        let on_output = |stream: OutputStream, data: String| {
+            let _ = app.emit(
+                "remote-exec-output",
+                RemoteExecOutput {
+                    exec_id: exec_id.clone(),
+                    stream,
+                    data,
+                },
+            );
+        };
+        run_command(
+            &conn,
+            &command,
+            working_dir.as_deref(),
+            timeout_secs.filter(|s| *s > 0).map(Duration::from_secs),
+            &on_output,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,55 @@
+use crate::services::connection::ConnectionManager;
+use crate::services::terminal::TerminalManager;
+use tauri::State;
+
+/// 在已连接主机上打开终端，返回终端 ID；输出经 `terminal-output` 事件推送，
+/// shell 退出或连接中断时发送 `terminal-exit`
+#[tauri::command]
+pub async fn open_terminal(
+    host_id: i64,
+    cols: u32,
+    rows: u32,
+    manager: State<'_, ConnectionManager>,
+    terminals: State<'_, TerminalManager>,
+) -> Result<String, String> {
+    let conn = manager.get_connection(host_id)?;
+    let terminals = terminals.inner().clone();
+    tokio::task::spawn_blocking(move || terminals.open(host_id, conn, cols, rows))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn write_terminal(
+    terminal_id: String,
+    data: String,
+    terminals: State<'_, TerminalManager>,
+) -> Result<(), String> {
+    terminals.write(&terminal_id, &data)
+}
+
+#[tauri::command]
+pub fn resize_terminal(
+    terminal_id: String,
+    cols: u32,
+    rows: u32,
+    terminals: State<'_, TerminalManager>,
+) -> Result<(), String> {
+    terminals.resize(&terminal_id, cols, rows)
+}
+
+#[tauri::command]
+pub fn close_terminal(
+    terminal_id: String,
+    terminals: State<'_, TerminalManager>,
+) -> Result<(), String> {
+    terminals.close(&terminal_id)
+}
+
+#[tauri::command]
+pub fn list_terminals(
+    host_id: i64,
+    terminals: State<'_, TerminalManager>,
+) -> Result<Vec<String>, String> {
+    terminals.list(host_id)
+}
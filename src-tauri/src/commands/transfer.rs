@@ -1,41 +1,285 @@
 use crate::db::transfer_repo;
-use crate::models::transfer::{TransferDirection, TransferHistory};
-use crate::services::connection::ConnectionManager;
+use crate::models::transfer::{SymlinkPolicy, TransferDirection, TransferHistory};
+use crate::services::connection::{ConnectionManager, ConnectionTrait};
 use crate::services::transfer_engine::{TransferEngine, TransferTask};
 use crate::utils::path::{normalize_and_validate, normalize_path_for_create, sanitize_filename};
 use crate::SharedDatabase;
+use std::collections::HashSet;
+use std::path::{Component, Path};
 use tauri::State;
 
 struct DirWalkResult {
     files: Vec<(String, String, String, u64)>,
     dirs: Vec<String>,
+    /// 需要在目标端重建的符号链接：(链接路径, 链接目标)
+    symlinks: Vec<(String, String)>,
 }
 
-fn collect_local_dir_entries(local_dir: &str, remote_dir: &str) -> Result<DirWalkResult, String> {
+fn collect_local_dir_entries(
+    local_dir: &str,
+    remote_dir: &str,
+    policy: SymlinkPolicy,
+) -> Result<DirWalkResult, String> {
     let safe_local = normalize_and_validate(local_dir)?;
+    let root_real = std::fs::canonicalize(&safe_local)
+        .map_err(|e| format!("读取目录失败 {}: {}", safe_local.display(), e))?;
     let mut files = Vec::new();
     let mut dirs = Vec::new();
-    let mut queue = vec![(safe_local.to_string_lossy().to_string(), remote_dir.to_string())];
+    let mut symlinks = Vec::new();
+    // 每个待遍历目录都带上自身及祖先的真实路径，用于识别循环链接
+    let mut queue = vec![(safe_local, remote_dir.to_string(), vec![root_real])];
 
-    while let Some((local, remote)) = queue.pop() {
+    while let Some((local, remote, ancestors)) = queue.pop() {
         dirs.push(remote.clone());
         let entries = std::fs::read_dir(&local)
-            .map_err(|e| format!("读取目录失败 {}: {}", local, e))?;
+            .map_err(|e| format!("读取目录失败 {}: {}", local.display(), e))?;
         for entry in entries {
             let entry = entry.map_err(|e| e.to_string())?;
-            let metadata = entry.metadata().map_err(|e| e.to_string())?;
+            let file_type = entry.file_type().map_err(|e| e.to_string())?;
             let name = entry.file_name().to_string_lossy().to_string();
-            let entry_local = entry.path().to_string_lossy().to_string();
+            let entry_path = entry.path();
             let entry_remote = format!("{}/{}", remote.trim_end_matches('/'), name);
-            if metadata.is_dir() {
-                queue.push((entry_local, entry_remote));
+
+            if file_type.is_symlink() {
+                match policy {
+                    SymlinkPolicy::Skip => {}
+                    SymlinkPolicy::Preserve => {
+                        let target = std::fs::read_link(&entry_path).map_err(|e| e.to_string())?;
+                        symlinks.push((entry_remote, target.to_string_lossy().to_string()));
+                    }
+                    SymlinkPolicy::Follow => {
+                        // 悬空链接直接跳过
+                        let metadata = match std::fs::metadata(&entry_path) {
+                            Ok(m) => m,
+                            Err(_) => continue,
+                        };
+                        if !metadata.is_dir() {
+                            let local_str = entry_path.to_string_lossy().to_string();
+                            files.push((local_str, entry_remote, name, metadata.len()));
+                            continue;
+                        }
+                        let real = std::fs::canonicalize(&entry_path).map_err(|e| e.to_string())?;
+                        let is_cycle = ancestors.iter().any(|a| a.starts_with(&real));
+                        if !is_cycle {
+                            let mut chain = ancestors.clone();
+                            chain.push(real);
+                            queue.push((entry_path, entry_remote, chain));
+                        }
+                    }
+                }
+            } else if file_type.is_dir() {
+                let mut chain = ancestors.clone();
+                chain.push(ancestors[ancestors.len() - 1].join(&name));
+                queue.push((entry_path, entry_remote, chain));
             } else {
-                files.push((entry_local, entry_remote, name, metadata.len()));
+                let metadata = entry.metadata().map_err(|e| e.to_string())?;
+                let local_str = entry_path.to_string_lossy().to_string();
+                files.push((local_str, entry_remote, name, metadata.len()));
+            }
+        }
+    }
+
+    Ok(DirWalkResult {
+        files,
+        dirs,
+        symlinks,
+    })
+}
+
+/// 把远程链接目标解析为规范化的绝对路径（处理相对路径、`.` 与 `..`）
+fn resolve_remote_link(dir: &str, target: &str) -> String {
+    let joined = if target.starts_with('/') {
+        target.to_string()
+    } else {
+        format!("{}/{}", dir.trim_end_matches('/'), target)
+    };
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
             }
+            s => parts.push(s),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+fn is_remote_ancestor(ancestor: &str, path: &str) -> bool {
+    ancestor == "/"
+        || path == ancestor
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 远程链接目标只能按字符串解析，目标路径中再含链接时可能识别不出循环，
+/// 因此额外限制一条路径上跟随的链接数，与 Linux 的 MAXSYMLINKS 一致
+const MAX_SYMLINK_HOPS: usize = 40;
+
+fn collect_remote_dir_entries(
+    conn: &mut dyn ConnectionTrait,
+    remote_dir: &str,
+    local_dir: &str,
+    policy: SymlinkPolicy,
+) -> Result<DirWalkResult, String> {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut symlinks = Vec::new();
+    let root_real = resolve_remote_link("/", remote_dir);
+    // 跟随链接时为判断目标类型已列出的目录内容随队列带上，避免重复列目录
+    let mut queue = vec![(
+        remote_dir.to_string(),
+        local_dir.to_string(),
+        vec![root_real],
+        0,
+        None,
+    )];
+
+    while let Some((remote, local, ancestors, hops, listed)) = queue.pop() {
+        dirs.push(local.clone());
+        let entries = match listed {
+            Some(entries) => entries,
+            None => conn.list_dir(&remote)?,
+        };
+        for entry in entries {
+            if entry.name == "." || entry.name == ".." {
+                continue;
+            }
+            let safe_name = sanitize_filename(&entry.name).map_err(|e| e.to_string())?;
+            let entry_local = std::path::Path::new(&local)
+                .join(&safe_name)
+                .to_string_lossy()
+                .to_string();
+            let entry_remote = format!("{}/{}", remote.trim_end_matches('/'), entry.name);
+            let current_real = &ancestors[ancestors.len() - 1];
+
+            if entry.is_symlink {
+                match policy {
+                    SymlinkPolicy::Skip => continue,
+                    SymlinkPolicy::Preserve => {
+                        if let Some(target) = entry.link_target {
+                            symlinks.push((entry_local, target));
+                        }
+                        continue;
+                    }
+                    SymlinkPolicy::Follow => {}
+                }
+                // FTP 列表无法得知链接目标的类型：SIZE 成功的是文件，能列出内容的是目录
+                if !entry.is_dir {
+                    if let Ok(size) = conn.file_size(&entry.path) {
+                        files.push((entry.path, entry_local, entry.name, size));
+                        continue;
+                    }
+                }
+                let real = match entry.link_target {
+                    Some(ref target) => resolve_remote_link(current_real, target),
+                    None => resolve_remote_link(current_real, &entry.name),
+                };
+                let is_cycle = ancestors.iter().any(|a| is_remote_ancestor(&real, a));
+                if is_cycle || hops >= MAX_SYMLINK_HOPS {
+                    continue;
+                }
+                // 悬空或无权访问的链接直接跳过
+                let Ok(listed) = conn.list_dir(&entry_remote) else {
+                    continue;
+                };
+                let mut chain = ancestors.clone();
+                chain.push(real);
+                queue.push((entry_remote, entry_local, chain, hops + 1, Some(listed)));
+            } else if entry.is_dir {
+                let mut chain = ancestors.clone();
+                chain.push(resolve_remote_link(current_real, &entry.name));
+                queue.push((entry_remote, entry_local, chain, hops, None));
+            } else {
+                files.push((entry.path, entry_local, entry.name, entry.size));
+            }
+        }
+    }
+
+    Ok(DirWalkResult {
+        files,
+        dirs,
+        symlinks,
+    })
+}
+
+/// 校验要在本地重建的符号链接。链接目标由服务器决定，只接受解析后仍在下载目录内的
+/// 相对路径，且链接不能与下载的文件或目录重名，否则文件会经链接写到下载目录之外
+fn check_preserved_links(local_root: &str, walk: &DirWalkResult) -> Result<(), String> {
+    let root = Path::new(local_root);
+    let links: HashSet<&Path> = walk
+        .symlinks
+        .iter()
+        .map(|(link, _)| Path::new(link.as_str()))
+        .collect();
+    for (link, target) in &walk.symlinks {
+        if walk.dirs.contains(link) || walk.files.iter().any(|f| &f.1 == link) {
+            return Err(format!("符号链接与下载的文件或目录重名: {}", link));
+        }
+        let outside = || format!("符号链接指向下载目录之外，已拒绝: {} -> {}", link, target);
+        let mut resolved = Path::new(link)
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(outside)?;
+        for component in Path::new(target).components() {
+            match component {
+                Component::Normal(name) => resolved.push(name),
+                Component::CurDir => {}
+                // 经由链接的 `..` 回到的是链接目标的上级，按字符串无法判断位置
+                Component::ParentDir
+                    if !links.contains(resolved.as_path())
+                        && resolved.pop()
+                        && resolved.starts_with(root) => {}
+                _ => return Err(outside()),
+            }
+        }
+        if !resolved.starts_with(root) {
+            return Err(outside());
+        }
+    }
+    Ok(())
+}
+
+/// 在远程创建符号链接：重复上传时替换旧链接，但绝不覆盖普通文件或目录
+fn create_remote_symlink(
+    conn: &mut dyn ConnectionTrait,
+    target: &str,
+    link: &str,
+) -> Result<(), String> {
+    let Err(e) = conn.create_symlink(target, link) else {
+        return Ok(());
+    };
+    match conn.stat(link) {
+        Ok(entry) if entry.is_symlink => {
+            conn.remove_file(link)
+                .map_err(|e| format!("删除旧符号链接失败 {}: {}", link, e))?;
+            conn.create_symlink(target, link)
+                .map_err(|e| format!("创建符号链接失败 {}: {}", link, e))
         }
+        Ok(_) => Err(format!("远程已存在同名文件，无法创建符号链接: {}", link)),
+        Err(_) => Err(format!("创建符号链接失败 {}: {}", link, e)),
     }
+}
+
+#[cfg(unix)]
+fn create_local_symlink(target: &str, link: &str) -> Result<(), String> {
+    let link_path = std::path::Path::new(link);
+    // 重复下载时替换旧链接，但绝不覆盖普通文件或目录
+    if let Ok(meta) = std::fs::symlink_metadata(link_path) {
+        if !meta.file_type().is_symlink() {
+            return Err(format!("本地已存在同名文件，无法创建符号链接: {}", link));
+        }
+        std::fs::remove_file(link_path).map_err(|e| e.to_string())?;
+    }
+    std::os::unix::fs::symlink(target, link_path)
+        .map_err(|e| format!("创建符号链接失败 {}: {}", link, e))
+}
 
-    Ok(DirWalkResult { files, dirs })
+#[cfg(not(unix))]
+fn create_local_symlink(_target: &str, link: &str) -> Result<(), String> {
+    Err(format!("当前系统不支持保留符号链接: {}", link))
 }
 
 #[tauri::command]
@@ -133,18 +377,49 @@ pub fn retry_transfer(
             .ok_or_else(|| format!("History {} not found", history_id))?
     };
 
-    let direction = match history.direction {
-        TransferDirection::Upload => "upload",
-        TransferDirection::Download => "download",
+    let task = match (history.direction, history.dest_host_id) {
+        (TransferDirection::Remote, Some(dest_host_id)) => TransferTask::remote(
+            history.host_id,
+            history.filename,
+            history.remote_path,
+            dest_host_id,
+            history.local_path,
+            history.file_size,
+        ),
+        (TransferDirection::Remote, None) => return Err("目标主机已删除，无法重试".to_string()),
+        (direction, _) => TransferTask::new(
+            history.host_id,
+            history.filename,
+            history.local_path,
+            history.remote_path,
+            direction.as_str().to_string(),
+            history.file_size,
+        ),
     };
+    engine.submit_task(task)
+}
 
-    let task = TransferTask::new(
-        history.host_id,
-        history.filename,
-        history.local_path,
-        history.remote_path,
-        direction.to_string(),
-        history.file_size,
+/// 把源主机上的文件直接传到另一台已连接的主机，不经过本地磁盘
+#[tauri::command]
+pub fn start_remote_transfer(
+    source_host_id: i64,
+    source_path: String,
+    dest_host_id: i64,
+    dest_path: String,
+    filename: String,
+    file_size: u64,
+    engine: State<'_, TransferEngine>,
+) -> Result<String, String> {
+    if source_host_id == dest_host_id {
+        return Err("源主机与目标主机不能相同".to_string());
+    }
+    let task = TransferTask::remote(
+        source_host_id,
+        filename,
+        source_path,
+        dest_host_id,
+        dest_path,
+        file_size,
     );
     engine.submit_task(task)
 }
@@ -158,8 +433,9 @@ pub fn get_resume_records(
     let mut stmt = conn
         .prepare(
             "SELECT id, transfer_id, host_id, remote_path, local_path, direction,
-                    file_size, transferred_bytes, checksum, created_at
-             FROM resume_records WHERE host_id = ?1 ORDER BY created_at DESC",
+                    file_size, transferred_bytes, checksum, created_at, dest_host_id
+             FROM resume_records WHERE host_id = ?1 OR dest_host_id = ?1
+             ORDER BY created_at DESC",
         )
         .map_err(|e| e.to_string())?;
 
@@ -179,6 +455,7 @@ pub fn get_resume_records(
                 transferred_bytes: row.get(7)?,
                 checksum: row.get(8)?,
                 created_at: row.get(9)?,
+                dest_host_id: row.get(10)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -205,24 +482,37 @@ pub async fn start_directory_upload(
     host_id: i64,
     local_dir: String,
     remote_dir: String,
+    symlink_policy: Option<SymlinkPolicy>,
     manager: State<'_, ConnectionManager>,
     engine: State<'_, TransferEngine>,
 ) -> Result<Vec<String>, String> {
     let _ = normalize_and_validate(&local_dir)?;
-    let entries = collect_local_dir_entries(&local_dir, &remote_dir)?;
+    let policy = symlink_policy.unwrap_or_default();
+    let entries = collect_local_dir_entries(&local_dir, &remote_dir, policy)?;
 
     let conn_arc = manager.get_connection(host_id)?;
     let engine = engine.inner().clone();
     let dirs = entries.dirs;
-    tokio::task::spawn_blocking(move || {
+    let symlinks = entries.symlinks;
+    let link_failures = tokio::task::spawn_blocking(move || {
         let mut conn = conn_arc.lock().map_err(|e| e.to_string())?;
         for dir in &dirs {
             let _ = conn.mkdir(dir);
         }
-        Ok::<(), String>(())
+        // 单个链接失败不影响其余链接和文件的上传，逐个通知前端
+        let mut failures = Vec::new();
+        for (link, target) in &symlinks {
+            if let Err(e) = create_remote_symlink(&mut **conn, target, link) {
+                failures.push((link.clone(), e));
+            }
+        }
+        Ok::<_, String>(failures)
     })
     .await
     .map_err(|e| e.to_string())??;
+    for (link, error) in &link_failures {
+        engine.notify_failure(link, error);
+    }
 
     let mut transfer_ids = Vec::new();
     for (local_path, remote_path, filename, file_size) in entries.files {
@@ -245,6 +535,7 @@ pub async fn start_directory_download(
     host_id: i64,
     remote_dir: String,
     local_dir: String,
+    symlink_policy: Option<SymlinkPolicy>,
     manager: State<'_, ConnectionManager>,
     engine: State<'_, TransferEngine>,
 ) -> Result<Vec<String>, String> {
@@ -252,40 +543,20 @@ pub async fn start_directory_download(
     let safe_local_str = safe_local_dir.to_string_lossy().to_string();
     let conn_arc = manager.get_connection(host_id)?;
     let engine = engine.inner().clone();
+    let policy = symlink_policy.unwrap_or_default();
 
-    let (files, dirs_to_create) = tokio::task::spawn_blocking(move || {
+    let entries = tokio::task::spawn_blocking(move || {
         let mut conn = conn_arc.lock().map_err(|e| e.to_string())?;
-        let mut files: Vec<(String, String, String, u64)> = Vec::new();
-        let mut dirs: Vec<String> = Vec::new();
-        let mut queue = vec![(remote_dir, safe_local_str)];
-
-        while let Some((remote, local)) = queue.pop() {
-            dirs.push(local.clone());
-            let entries = conn.list_dir(&remote)?;
-            for entry in entries {
-                if entry.name == "." || entry.name == ".." {
-                    continue;
-                }
-                let safe_name = sanitize_filename(&entry.name)
-                    .map_err(|e| e.to_string())?;
-                let entry_local = std::path::Path::new(&local)
-                    .join(&safe_name)
-                    .to_string_lossy()
-                    .to_string();
-                if entry.is_dir {
-                    let entry_remote =
-                        format!("{}/{}", remote.trim_end_matches('/'), entry.name);
-                    queue.push((entry_remote, entry_local));
-                } else {
-                    files.push((entry.path, entry_local, entry.name, entry.size));
-                }
-            }
-        }
-
-        Ok::<_, String>((files, dirs))
+        collect_remote_dir_entries(&mut **conn, &remote_dir, &safe_local_str, policy)
     })
     .await
     .map_err(|e| e.to_string())??;
+    check_preserved_links(&safe_local_dir.to_string_lossy(), &entries)?;
+    if !cfg!(unix) && !entries.symlinks.is_empty() {
+        return Err("当前系统不支持保留符号链接".to_string());
+    }
+    let files = entries.files;
+    let dirs_to_create = entries.dirs;
 
     for dir in &dirs_to_create {
         let _ = normalize_path_for_create(dir)?;
@@ -293,25 +564,37 @@ pub async fn start_directory_download(
             .map_err(|e| format!("创建本地目录失败 {}: {}", dir, e))?;
     }
 
-    let mut transfer_ids = Vec::new();
-    for (remote_path, local_path, filename, file_size) in files {
-        let task = TransferTask::new(
-            host_id,
-            filename,
-            local_path,
-            remote_path,
-            "download".to_string(),
-            file_size,
-        );
-        transfer_ids.push(engine.submit_task(task)?);
-    }
-
-    Ok(transfer_ids)
+    let tasks = files
+        .into_iter()
+        .map(|(remote_path, local_path, filename, file_size)| {
+            TransferTask::new(
+                host_id,
+                filename,
+                local_path,
+                remote_path,
+                "download".to_string(),
+                file_size,
+            )
+        })
+        .collect();
+    // 下载全部结束后再创建保留的符号链接，避免文件经同名链接写到别处；
+    // 链接创建失败不影响已下载的文件，逐个通知前端
+    let symlinks = entries.symlinks;
+    let link_engine = engine.clone();
+    engine.submit_batch(tasks, move || {
+        for (link, target) in &symlinks {
+            if let Err(e) = create_local_symlink(target, link) {
+                link_engine.notify_failure(link, &e);
+            }
+        }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::connection::FileEntry;
+    use std::collections::HashMap;
 
     #[test]
     fn test_collect_local_dir_entries_flat() {
@@ -321,8 +604,12 @@ mod tests {
         std::fs::write(temp.join("a.txt"), "hello").unwrap();
         std::fs::write(temp.join("b.txt"), "world").unwrap();
 
-        let result =
-            collect_local_dir_entries(&temp.to_string_lossy(), "/remote/testdir").unwrap();
+        let result = collect_local_dir_entries(
+            &temp.to_string_lossy(),
+            "/remote/testdir",
+            SymlinkPolicy::Follow,
+        )
+        .unwrap();
 
         assert_eq!(result.dirs.len(), 1);
         assert_eq!(result.dirs[0], "/remote/testdir");
@@ -343,8 +630,12 @@ mod tests {
         std::fs::write(temp.join("sub1/mid.txt"), "mid").unwrap();
         std::fs::write(temp.join("sub1/sub2/deep.txt"), "deep").unwrap();
 
-        let result =
-            collect_local_dir_entries(&temp.to_string_lossy(), "/remote/nested").unwrap();
+        let result = collect_local_dir_entries(
+            &temp.to_string_lossy(),
+            "/remote/nested",
+            SymlinkPolicy::Follow,
+        )
+        .unwrap();
 
         assert_eq!(result.dirs.len(), 3);
         assert!(result.dirs.contains(&"/remote/nested".to_string()));
@@ -366,8 +657,12 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp);
         std::fs::create_dir_all(&temp).unwrap();
 
-        let result =
-            collect_local_dir_entries(&temp.to_string_lossy(), "/remote/empty").unwrap();
+        let result = collect_local_dir_entries(
+            &temp.to_string_lossy(),
+            "/remote/empty",
+            SymlinkPolicy::Follow,
+        )
+        .unwrap();
 
         assert_eq!(result.dirs.len(), 1);
         assert_eq!(result.dirs[0], "/remote/empty");
@@ -383,8 +678,12 @@ mod tests {
         std::fs::create_dir_all(&temp).unwrap();
         std::fs::write(temp.join("f.txt"), "data").unwrap();
 
-        let result =
-            collect_local_dir_entries(&temp.to_string_lossy(), "/remote/dir/").unwrap();
+        let result = collect_local_dir_entries(
+            &temp.to_string_lossy(),
+            "/remote/dir/",
+            SymlinkPolicy::Follow,
+        )
+        .unwrap();
 
         let file_remote = &result.files[0].1;
         assert!(
@@ -398,7 +697,336 @@ mod tests {
 
     #[test]
     fn test_collect_local_dir_entries_nonexistent() {
-        let result = collect_local_dir_entries("/nonexistent/path/xyz", "/remote/dir");
+        let result = collect_local_dir_entries(
+            "/nonexistent/path/xyz",
+            "/remote/dir",
+            SymlinkPolicy::Follow,
+        );
         assert!(result.is_err());
     }
+
+    #[cfg(unix)]
+    fn make_symlink_tree(name: &str) -> std::path::PathBuf {
+        let temp = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&temp);
+        std::fs::create_dir_all(temp.join("data")).unwrap();
+        std::fs::write(temp.join("data/a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink("data", temp.join("data_link")).unwrap();
+        std::os::unix::fs::symlink("..", temp.join("data/loop")).unwrap();
+        std::os::unix::fs::symlink("data/a.txt", temp.join("a_link.txt")).unwrap();
+        temp
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_local_dir_entries_follow_skips_cycles() {
+        let temp = make_symlink_tree("ftx_test_dir_collect_follow");
+
+        let result = collect_local_dir_entries(
+            &temp.to_string_lossy(),
+            "/r",
+            SymlinkPolicy::Follow,
+        )
+        .unwrap();
+
+        let mut remotes: Vec<&str> = result.files.iter().map(|f| f.1.as_str()).collect();
+        remotes.sort();
+        assert_eq!(remotes, vec!["/r/a_link.txt", "/r/data/a.txt", "/r/data_link/a.txt"]);
+        assert!(result.dirs.contains(&"/r/data_link".to_string()));
+        assert!(!result.dirs.iter().any(|d| d.contains("loop")));
+        assert!(result.symlinks.is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_local_dir_entries_skip_and_preserve() {
+        let temp = make_symlink_tree("ftx_test_dir_collect_skip");
+
+        let skipped =
+            collect_local_dir_entries(&temp.to_string_lossy(), "/r", SymlinkPolicy::Skip).unwrap();
+        assert_eq!(skipped.files.len(), 1);
+        assert_eq!(skipped.dirs.len(), 2);
+        assert!(skipped.symlinks.is_empty());
+
+        let preserved =
+            collect_local_dir_entries(&temp.to_string_lossy(), "/r", SymlinkPolicy::Preserve)
+                .unwrap();
+        assert_eq!(preserved.files.len(), 1);
+        let mut links = preserved.symlinks.clone();
+        links.sort();
+        assert_eq!(
+            links,
+            vec![
+                ("/r/a_link.txt".to_string(), "data/a.txt".to_string()),
+                ("/r/data/loop".to_string(), "..".to_string()),
+                ("/r/data_link".to_string(), "data".to_string()),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&temp);
+    }
+
+    #[test]
+    fn test_resolve_remote_link() {
+        assert_eq!(resolve_remote_link("/srv/app", "../shared"), "/srv/shared");
+        assert_eq!(resolve_remote_link("/srv/app/", "./logs"), "/srv/app/logs");
+        assert_eq!(resolve_remote_link("/srv/app", "/var/www"), "/var/www");
+        assert_eq!(resolve_remote_link("/", "../.."), "/");
+        assert!(is_remote_ancestor("/srv", "/srv/app"));
+        assert!(is_remote_ancestor("/", "/srv"));
+        assert!(!is_remote_ancestor("/srv/ap", "/srv/app"));
+    }
+
+    /// 内存中的远程目录树，链接目标只在列目录时体现
+    struct TreeClient {
+        dirs: HashMap<String, Vec<FileEntry>>,
+    }
+
+    fn remote_entry(dir: &str, name: &str, is_dir: bool, link_target: Option<&str>) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: format!("{}/{}", dir.trim_end_matches('/'), name),
+            is_dir,
+            size: 1,
+            modified: None,
+            permissions: None,
+            is_symlink: link_target.is_some(),
+            link_target: link_target.map(|t| t.to_string()),
+            mode: None,
+            uid: None,
+            gid: None,
+            owner: None,
+            group: None,
+        }
+    }
+
+    impl ConnectionTrait for TreeClient {
+        fn connect(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+        fn disconnect(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+            let real = resolve_remote_link("/", path);
+            // 模拟服务器解析路径中的链接：/srv/current/x 实际位于 /srv/releases/x
+            let real = real.replacen("/srv/current", "/srv/releases", 1);
+            let real = real.replacen("/srv/releases/up", "/srv", 1);
+            self.dirs
+                .get(&real)
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .map(|e| remote_entry(path, &e.name, e.is_dir, e.link_target.as_deref()))
+                        .collect()
+                })
+                .ok_or_else(|| format!("No such directory: {}", path))
+        }
+        fn file_size(&mut self, path: &str) -> Result<u64, String> {
+            if path.ends_with(".txt") {
+                Ok(1)
+            } else {
+                Err("not a file".to_string())
+            }
+        }
+        fn file_exists(&mut self, _path: &str) -> Result<bool, String> {
+            Ok(true)
+        }
+        fn upload(
+            &mut self,
+            _local_path: &str,
+            _remote_path: &str,
+            _offset: u64,
+            _progress: Option<&dyn Fn(u64, u64)>,
+        ) -> Result<u64, String> {
+            Ok(0)
+        }
+        fn download(
+            &mut self,
+            _remote_path: &str,
+            _local_path: &str,
+            _offset: u64,
+            _progress: Option<&dyn Fn(u64, u64)>,
+        ) -> Result<u64, String> {
+            Ok(0)
+        }
+        fn mkdir(&mut self, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn remove_file(&mut self, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn remove_dir(&mut self, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn rename(&mut self, _from: &str, _to: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn create_symlink(&mut self, _target: &str, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn stat(&mut self, _path: &str) -> Result<FileEntry, String> {
+            Err("not supported".to_string())
+        }
+        fn set_permissions(&mut self, _path: &str, _mode: u32) -> Result<(), String> {
+            Ok(())
+        }
+        fn set_owner(
+            &mut self,
+            _path: &str,
+            _uid: Option<u32>,
+            _gid: Option<u32>,
+        ) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn tree_client() -> TreeClient {
+        let mut dirs = HashMap::new();
+        dirs.insert(
+            "/srv".to_string(),
+            vec![
+                remote_entry("/srv", "releases", true, None),
+                // FTP 列表中指向目录的链接 is_dir 为 false
+                remote_entry("/srv", "current", false, Some("releases")),
+                remote_entry("/srv", "notes.txt", false, Some("releases/app.txt")),
+                remote_entry("/srv", "broken", false, Some("missing")),
+            ],
+        );
+        dirs.insert(
+            "/srv/releases".to_string(),
+            vec![
+                remote_entry("/srv/releases", "app.txt", false, None),
+                remote_entry("/srv/releases", "up", false, Some("..")),
+            ],
+        );
+        TreeClient { dirs }
+    }
+
+    #[test]
+    fn test_collect_remote_dir_entries_follow_detects_cycles() {
+        let mut conn = tree_client();
+        let result =
+            collect_remote_dir_entries(&mut conn, "/srv", "/local", SymlinkPolicy::Follow).unwrap();
+
+        let mut remotes: Vec<&str> = result.files.iter().map(|f| f.0.as_str()).collect();
+        remotes.sort();
+        assert_eq!(
+            remotes,
+            vec!["/srv/current/app.txt", "/srv/notes.txt", "/srv/releases/app.txt"]
+        );
+        assert!(result.dirs.contains(&"/local/current".to_string()));
+        assert!(!result.dirs.iter().any(|d| d.ends_with("/up")));
+        // 悬空链接既不是文件也列不出内容，跳过而不中断遍历
+        assert!(!result.dirs.iter().any(|d| d.ends_with("/broken")));
+    }
+
+    #[test]
+    fn test_collect_remote_dir_entries_skip_and_preserve() {
+        let mut conn = tree_client();
+        let skipped =
+            collect_remote_dir_entries(&mut conn, "/srv", "/local", SymlinkPolicy::Skip).unwrap();
+        assert_eq!(skipped.files.len(), 1);
+        assert_eq!(skipped.dirs, vec!["/local", "/local/releases"]);
+
+        let preserved =
+            collect_remote_dir_entries(&mut conn, "/srv", "/local", SymlinkPolicy::Preserve)
+                .unwrap();
+        let mut links = preserved.symlinks.clone();
+        links.sort();
+        assert_eq!(
+            links,
+            vec![
+                ("/local/broken".to_string(), "missing".to_string()),
+                ("/local/current".to_string(), "releases".to_string()),
+                ("/local/notes.txt".to_string(), "releases/app.txt".to_string()),
+                ("/local/releases/up".to_string(), "..".to_string()),
+            ]
+        );
+        assert!(check_preserved_links("/local", &preserved).is_ok());
+    }
+
+    #[test]
+    fn test_check_preserved_links_rejects_escapes() {
+        let walk = |symlinks: &[(&str, &str)]| DirWalkResult {
+            files: vec![(
+                "/srv/a.txt".to_string(),
+                "/local/a.txt".to_string(),
+                "a.txt".to_string(),
+                1,
+            )],
+            dirs: vec!["/local".to_string(), "/local/sub".to_string()],
+            symlinks: symlinks
+                .iter()
+                .map(|(l, t)| (l.to_string(), t.to_string()))
+                .collect(),
+        };
+        let ok = walk(&[("/local/sub/up", ".."), ("/local/link", "sub/./x")]);
+        assert!(check_preserved_links("/local", &ok).is_ok());
+
+        for bad in [
+            walk(&[("/local/etc", "/etc")]),
+            walk(&[("/local/sub/out", "../../home")]),
+            walk(&[("/local/out", "../local/../other")]),
+            // 与下载的文件、目录重名
+            walk(&[("/local/a.txt", "sub")]),
+            walk(&[("/local/sub", ".")]),
+            // 经由另一条链接的 `..`
+            walk(&[("/local/here", "sub"), ("/local/x", "here/../..")]),
+        ] {
+            assert!(check_preserved_links("/local", &bad).is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_local_symlink_replaces_only_links() {
+        let temp = std::env::temp_dir().join("ftx_test_create_local_symlink");
+        let _ = std::fs::remove_dir_all(&temp);
+        std::fs::create_dir_all(&temp).unwrap();
+        let link = temp.join("link").to_string_lossy().to_string();
+
+        create_local_symlink("first", &link).unwrap();
+        create_local_symlink("second", &link).unwrap();
+        assert_eq!(std::fs::read_link(&link).unwrap().to_string_lossy(), "second");
+
+        let file = temp.join("file").to_string_lossy().to_string();
+        std::fs::write(&file, "keep").unwrap();
+        assert!(create_local_symlink("x", &file).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep");
+
+        let _ = std::fs::remove_dir_all(&temp);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_remote_symlink_replaces_only_links() {
+        use crate::services::local_client::LocalClient;
+
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join("file"), "keep").unwrap();
+        let mut client = LocalClient::new(temp.path());
+        client.connect().unwrap();
+
+        create_remote_symlink(&mut client, "first", "/link").unwrap();
+        create_remote_symlink(&mut client, "second", "/link").unwrap();
+        assert_eq!(
+            std::fs::read_link(temp.path().join("link"))
+                .unwrap()
+                .to_string_lossy(),
+            "second"
+        );
+
+        let err = create_remote_symlink(&mut client, "x", "/file").unwrap_err();
+        assert!(err.contains("同名文件"));
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("file")).unwrap(),
+            "keep"
+        );
+    }
 }
@@ -0,0 +1,133 @@
+use rusqlite::{params, Connection};
+
+use crate::models::certificate::TrustedCertificate;
+
+/// 每个主机只固定一张证书，重复信任时覆盖旧记录
+pub fn upsert(
+    conn: &Connection,
+    cert: &TrustedCertificate,
+) -> Result<TrustedCertificate, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO trusted_certificates (host_id, fingerprint, subject, certificate) \
+         VALUES (?1, ?2, ?3, ?4) \
+         ON CONFLICT(host_id) DO UPDATE SET fingerprint = excluded.fingerprint, \
+         subject = excluded.subject, certificate = excluded.certificate, \
+         trusted_at = datetime('now')",
+        params![cert.host_id, cert.fingerprint, cert.subject, cert.certificate],
+    )?;
+    get_by_host(conn, cert.host_id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+pub fn get_by_host(
+    conn: &Connection,
+    host_id: i64,
+) -> Result<Option<TrustedCertificate>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, host_id, fingerprint, subject, certificate, trusted_at \
+         FROM trusted_certificates WHERE host_id = ?1",
+    )?;
+    let mut rows = stmt.query_map(params![host_id], row_to_certificate)?;
+    match rows.next() {
+        Some(row) => Ok(Some(row?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete_by_host(conn: &Connection, host_id: i64) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute(
+        "DELETE FROM trusted_certificates WHERE host_id = ?1",
+        params![host_id],
+    )?;
+    Ok(changed > 0)
+}
+
+fn row_to_certificate(row: &rusqlite::Row) -> Result<TrustedCertificate, rusqlite::Error> {
+    Ok(TrustedCertificate {
+        id: row.get(0)?,
+        host_id: row.get(1)?,
+        fingerprint: row.get(2)?,
+        subject: row.get(3)?,
+        certificate: row.get(4)?,
+        trusted_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{host_repo, migrations};
+    use crate::models::certificate::CertificateInfo;
+    use crate::models::host::{Host, Protocol};
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    fn insert_host(conn: &Connection) -> i64 {
+        let h = Host::new("ftps".into(), "10.0.0.2".into(), 21, Protocol::Ftps, "u".into());
+        host_repo::insert(conn, &h, None).unwrap().id.unwrap()
+    }
+
+    fn sample(host_id: i64, fingerprint: &str) -> TrustedCertificate {
+        TrustedCertificate::new(
+            host_id,
+            CertificateInfo {
+                fingerprint: fingerprint.into(),
+                subject: "CN=ftp.internal".into(),
+                certificate: "MIIB".into(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_upsert_and_get() {
+        let conn = setup_db();
+        let host_id = insert_host(&conn);
+        let saved = upsert(&conn, &sample(host_id, "AA:BB")).unwrap();
+        assert!(saved.id.is_some());
+        assert!(saved.trusted_at.is_some());
+
+        let fetched = get_by_host(&conn, host_id).unwrap().unwrap();
+        assert_eq!(fetched.fingerprint, "AA:BB");
+        assert_eq!(fetched.subject, "CN=ftp.internal");
+    }
+
+    #[test]
+    fn test_upsert_replaces_existing() {
+        let conn = setup_db();
+        let host_id = insert_host(&conn);
+        upsert(&conn, &sample(host_id, "AA:BB")).unwrap();
+        upsert(&conn, &sample(host_id, "CC:DD")).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM trusted_certificates", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        let fetched = get_by_host(&conn, host_id).unwrap().unwrap();
+        assert_eq!(fetched.fingerprint, "CC:DD");
+    }
+
+    #[test]
+    fn test_delete_by_host() {
+        let conn = setup_db();
+        let host_id = insert_host(&conn);
+        upsert(&conn, &sample(host_id, "AA:BB")).unwrap();
+
+        assert!(delete_by_host(&conn, host_id).unwrap());
+        assert!(get_by_host(&conn, host_id).unwrap().is_none());
+        assert!(!delete_by_host(&conn, host_id).unwrap());
+    }
+
+    #[test]
+    fn test_cascade_on_host_delete() {
+        let conn = setup_db();
+        let host_id = insert_host(&conn);
+        upsert(&conn, &sample(host_id, "AA:BB")).unwrap();
+
+        host_repo::delete(&conn, host_id).unwrap();
+        assert!(get_by_host(&conn, host_id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,122 @@
+use rusqlite::{params, Connection};
+
+use crate::models::host_key::KnownHostKey;
+
+const KEY_COLUMNS: &str = "id, host, port, key_type, fingerprint, key, trusted_at";
+
+/// 同一地址和端口只保存一把密钥，替换时覆盖旧记录
+pub fn upsert(conn: &Connection, key: &KnownHostKey) -> Result<KnownHostKey, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO known_host_keys (host, port, key_type, fingerprint, key) \
+         VALUES (?1, ?2, ?3, ?4, ?5) \
+         ON CONFLICT(host, port) DO UPDATE SET key_type = excluded.key_type, \
+         fingerprint = excluded.fingerprint, key = excluded.key, \
+         trusted_at = datetime('now')",
+        params![key.host, key.port, key.key_type, key.fingerprint, key.key],
+    )?;
+    get(conn, &key.host, key.port)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+pub fn get(
+    conn: &Connection,
+    host: &str,
+    port: u16,
+) -> Result<Option<KnownHostKey>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT {} FROM known_host_keys WHERE host = ?1 AND port = ?2",
+        KEY_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query_map(params![host, port], row_to_key)?;
+    match rows.next() {
+        Some(row) => Ok(Some(row?)),
+        None => Ok(None),
+    }
+}
+
+pub fn get_all(conn: &Connection) -> Result<Vec<KnownHostKey>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT {} FROM known_host_keys ORDER BY host, port",
+        KEY_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], row_to_key)?;
+    rows.collect()
+}
+
+pub fn delete(conn: &Connection, id: i64) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute("DELETE FROM known_host_keys WHERE id = ?1", params![id])?;
+    Ok(changed > 0)
+}
+
+fn row_to_key(row: &rusqlite::Row) -> Result<KnownHostKey, rusqlite::Error> {
+    Ok(KnownHostKey {
+        id: row.get(0)?,
+        host: row.get(1)?,
+        port: row.get(2)?,
+        key_type: row.get(3)?,
+        fingerprint: row.get(4)?,
+        key: row.get(5)?,
+        trusted_at: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations;
+    use crate::models::host_key::HostKeyInfo;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    fn sample(host: &str, port: u16, key: &str) -> KnownHostKey {
+        KnownHostKey::new(HostKeyInfo {
+            host: host.into(),
+            port,
+            key_type: "ssh-ed25519".into(),
+            fingerprint: format!("SHA256:{}", key),
+            key: key.into(),
+        })
+    }
+
+    #[test]
+    fn test_upsert_and_get() {
+        let conn = setup_db();
+        let saved = upsert(&conn, &sample("10.0.0.5", 22, "AAAA")).unwrap();
+        assert!(saved.id.is_some());
+        assert!(saved.trusted_at.is_some());
+
+        let fetched = get(&conn, "10.0.0.5", 22).unwrap().unwrap();
+        assert_eq!(fetched.key, "AAAA");
+        assert!(get(&conn, "10.0.0.5", 2222).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_upsert_replaces_existing() {
+        let conn = setup_db();
+        let first = upsert(&conn, &sample("10.0.0.5", 22, "AAAA")).unwrap();
+        let second = upsert(&conn, &sample("10.0.0.5", 22, "BBBB")).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(get_all(&conn).unwrap().len(), 1);
+        assert_eq!(second.fingerprint, "SHA256:BBBB");
+    }
+
+    #[test]
+    fn test_get_all_and_delete() {
+        let conn = setup_db();
+        upsert(&conn, &sample("b.example.com", 22, "AAAA")).unwrap();
+        let a = upsert(&conn, &sample("a.example.com", 22, "BBBB")).unwrap();
+
+        let all = get_all(&conn).unwrap();
+        assert_eq!(all[0].host, "a.example.com");
+        assert_eq!(all.len(), 2);
+
+        assert!(delete(&conn, a.id.unwrap()).unwrap());
+        assert!(!delete(&conn, a.id.unwrap()).unwrap());
+        assert_eq!(get_all(&conn).unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,17 @@
 use rusqlite::{params, Connection};
 
 use crate::crypto::{decrypt, encrypt};
-use crate::models::host::{Host, Protocol};
+use crate::models::host::{
+    FtpDataMode, FtpsMode, Host, Protocol, ProxyMode, SshAuthMethod, SshTransferMode,
+};
+
+/// 跳板机最多层数，防止配置错误时无限跟随
+const MAX_JUMP_HOPS: usize = 8;
+
+const HOST_COLUMNS: &str = "id, name, host, port, protocol, username, password, key_path, \
+     ftps_mode, timezone_offset, ftp_data_mode, ftp_active_address, ftp_active_port_min, \
+     ftp_active_port_max, ftp_ignore_pasv_address, ssh_auth_order, jump_host_id, proxy_mode, \
+     s3_region, s3_bucket, s3_use_http, ssh_transfer_mode, created_at, updated_at";
 
 pub fn insert(
     conn: &Connection,
@@ -9,17 +19,37 @@ pub fn insert(
     encryption_key: Option<&[u8; 32]>,
 ) -> Result<Host, String> {
     let (password, key_path) = encrypt_fields(host, encryption_key)?;
+    let key_path = ssh_only(host, key_path);
+    let s3 = seal_s3_fields(host, encryption_key)?;
     conn.execute(
-        "INSERT INTO hosts (name, host, port, protocol, username, password, key_path) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO hosts (name, host, port, protocol, username, password, key_path, ftps_mode, \
+         timezone_offset, ftp_data_mode, ftp_active_address, ftp_active_port_min, \
+         ftp_active_port_max, ftp_ignore_pasv_address, ssh_auth_order, jump_host_id, proxy_mode, \
+         s3_region, s3_bucket, s3_use_http, ssh_transfer_mode) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, \
+         ?18, ?19, ?20, ?21)",
         params![
             host.name,
-            host.host,
+            s3.host,
             host.port,
             host.protocol.as_str(),
-            host.username,
+            s3.username,
             password,
             key_path,
+            host.ftps_mode.as_str(),
+            host.timezone_offset,
+            host.ftp_data_mode.as_str(),
+            host.ftp_active_address,
+            host.ftp_active_port_min,
+            host.ftp_active_port_max,
+            host.ftp_ignore_pasv_address,
+            SshAuthMethod::join(&host.ssh_auth_order),
+            host.jump_host_id,
+            host.proxy_mode.as_str(),
+            s3.region,
+            s3.bucket,
+            host.s3_use_http,
+            host.ssh_transfer_mode.as_str(),
         ],
     )
     .map_err(|e| e.to_string())?;
@@ -34,116 +64,148 @@ pub fn get_by_id(
     encryption_key: Option<&[u8; 32]>,
 ) -> Result<Option<Host>, String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, host, port, protocol, username, password, key_path, \
-             created_at, updated_at FROM hosts WHERE id = ?1",
-        )
+        .prepare(&format!("SELECT {} FROM hosts WHERE id = ?1", HOST_COLUMNS))
         .map_err(|e| e.to_string())?;
-    let mut rows = stmt
-        .query_map(params![id], |row| {
-            let id: i64 = row.get(0)?;
-            let name: String = row.get(1)?;
-            let host: String = row.get(2)?;
-            let port: u16 = row.get(3)?;
-            let protocol_str: String = row.get(4)?;
-            let username: String = row.get(5)?;
-            let password: Option<String> = row.get(6)?;
-            let key_path: Option<String> = row.get(7)?;
-            let created_at: Option<String> = row.get(8)?;
-            let updated_at: Option<String> = row.get(9)?;
-            Ok((
-                id,
-                name,
-                host,
-                port,
-                protocol_str,
-                username,
-                password,
-                key_path,
-                created_at,
-                updated_at,
-            ))
-        })
+    let row = stmt
+        .query_map(params![id], row_to_host)
+        .map_err(|e| e.to_string())?
+        .next()
+        .transpose()
         .map_err(|e| e.to_string())?;
-    match rows.next() {
-        Some(Ok(row)) => {
-            let (password, key_path) =
-                decrypt_fields(row.6, row.7, encryption_key, conn, row.0)?;
-            let protocol = Protocol::from_str(&row.4).map_err(|e| e.to_string())?;
-            Ok(Some(Host {
-                id: Some(row.0),
-                name: row.1,
-                host: row.2,
-                port: row.3,
-                protocol,
-                username: row.5,
-                password,
-                key_path,
-                created_at: row.8,
-                updated_at: row.9,
-            }))
-        }
-        Some(Err(e)) => Err(e.to_string()),
+    match row {
+        Some(host) => Ok(Some(decrypt_host(host, encryption_key, conn)?)),
         None => Ok(None),
     }
 }
 
 pub fn get_all(conn: &Connection, encryption_key: Option<&[u8; 32]>) -> Result<Vec<Host>, String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, host, port, protocol, username, password, key_path, \
-             created_at, updated_at FROM hosts ORDER BY updated_at DESC",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM hosts ORDER BY updated_at DESC",
+            HOST_COLUMNS
+        ))
         .map_err(|e| e.to_string())?;
-    let rows: Vec<_> = stmt
-        .query_map([], |row| {
-            let id: i64 = row.get(0)?;
-            let name: String = row.get(1)?;
-            let host: String = row.get(2)?;
-            let port: u16 = row.get(3)?;
-            let protocol_str: String = row.get(4)?;
-            let username: String = row.get(5)?;
-            let password: Option<String> = row.get(6)?;
-            let key_path: Option<String> = row.get(7)?;
-            let created_at: Option<String> = row.get(8)?;
-            let updated_at: Option<String> = row.get(9)?;
-            Ok((
-                id,
-                name,
-                host,
-                port,
-                protocol_str,
-                username,
-                password,
-                key_path,
-                created_at,
-                updated_at,
-            ))
-        })
+    let rows: Vec<Host> = stmt
+        .query_map([], row_to_host)
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
     let mut hosts = Vec::new();
-    for row in rows {
-        let (password, key_path) =
-            decrypt_fields(row.6, row.7, encryption_key, conn, row.0)?;
-        let protocol = Protocol::from_str(&row.4).map_err(|e| e.to_string())?;
-        hosts.push(Host {
-            id: Some(row.0),
-            name: row.1,
-            host: row.2,
-            port: row.3,
-            protocol,
-            username: row.5,
-            password,
-            key_path,
-            created_at: row.8,
-            updated_at: row.9,
-        });
+    for host in rows {
+        hosts.push(decrypt_host(host, encryption_key, conn)?);
     }
     Ok(hosts)
 }
 
+/// 解析主机的跳板机链，按连接顺序返回：第一个可直接连接，最后一个紧邻目标主机
+pub fn get_jump_chain(
+    conn: &Connection,
+    host: &Host,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Vec<Host>, String> {
+    let mut chain: Vec<Host> = Vec::new();
+    let mut visited: Vec<i64> = host.id.into_iter().collect();
+    let mut next = host.jump_host_id;
+    while let Some(id) = next {
+        if visited.contains(&id) {
+            return Err("跳板机配置存在循环引用".to_string());
+        }
+        if chain.len() >= MAX_JUMP_HOPS {
+            return Err(format!("跳板机不能超过 {} 层", MAX_JUMP_HOPS));
+        }
+        let jump = get_by_id(conn, id, encryption_key)?
+            .ok_or_else(|| format!("跳板机 {} 不存在", id))?;
+        if jump.protocol != Protocol::Sftp {
+            return Err(format!("跳板机 {} 不是 SFTP 主机", jump.name));
+        }
+        visited.push(id);
+        next = jump.jump_host_id;
+        chain.push(jump);
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+/// 按 HOST_COLUMNS 的列顺序解析一行，password/key_path 及 S3 字段仍为数据库中的密文
+fn row_to_host(row: &rusqlite::Row) -> Result<Host, rusqlite::Error> {
+    let protocol_str: String = row.get(4)?;
+    let ftps_mode_str: String = row.get(8)?;
+    let protocol =
+        Protocol::from_str(&protocol_str).map_err(|e| text_conversion_error(4, e))?;
+    let ftps_mode =
+        FtpsMode::from_str(&ftps_mode_str).map_err(|e| text_conversion_error(8, e))?;
+    let data_mode_str: String = row.get(10)?;
+    let ftp_data_mode =
+        FtpDataMode::from_str(&data_mode_str).map_err(|e| text_conversion_error(10, e))?;
+    let auth_order_str: String = row.get(15)?;
+    let ssh_auth_order =
+        SshAuthMethod::parse_list(&auth_order_str).map_err(|e| text_conversion_error(15, e))?;
+    let proxy_mode_str: String = row.get(17)?;
+    let proxy_mode =
+        ProxyMode::from_str(&proxy_mode_str).map_err(|e| text_conversion_error(17, e))?;
+    let transfer_mode_str: String = row.get(21)?;
+    let ssh_transfer_mode = SshTransferMode::from_str(&transfer_mode_str)
+        .map_err(|e| text_conversion_error(21, e))?;
+    Ok(Host {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        host: row.get(2)?,
+        port: row.get(3)?,
+        protocol,
+        username: row.get(5)?,
+        password: row.get(6)?,
+        key_path: row.get(7)?,
+        ftps_mode,
+        timezone_offset: row.get(9)?,
+        ftp_data_mode,
+        ftp_active_address: row.get(11)?,
+        ftp_active_port_min: row.get(12)?,
+        ftp_active_port_max: row.get(13)?,
+        ftp_ignore_pasv_address: row.get(14)?,
+        ssh_auth_order,
+        jump_host_id: row.get(16)?,
+        ssh_transfer_mode,
+        proxy_mode,
+        s3_region: row.get(18)?,
+        s3_bucket: row.get(19)?,
+        s3_use_http: row.get(20)?,
+        created_at: row.get(22)?,
+        updated_at: row.get(23)?,
+    })
+}
+
+fn text_conversion_error(column: usize, message: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        column,
+        rusqlite::types::Type::Text,
+        Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, message)),
+    )
+}
+
+fn decrypt_host(
+    mut host: Host,
+    encryption_key: Option<&[u8; 32]>,
+    conn: &Connection,
+) -> Result<Host, String> {
+    let host_id = host.id.ok_or("Host 缺少 id")?;
+    let (password, key_path) = decrypt_fields(
+        host.password.take(),
+        host.key_path.take(),
+        encryption_key,
+        conn,
+        host_id,
+    )?;
+    host.password = password;
+    host.key_path = key_path;
+    if let (Protocol::S3, Some(k)) = (&host.protocol, encryption_key) {
+        host.host = decrypt(&host.host, k)?;
+        host.username = decrypt(&host.username, k)?;
+        host.s3_region = host.s3_region.map(|r| decrypt(&r, k)).transpose()?;
+        host.s3_bucket = host.s3_bucket.map(|b| decrypt(&b, k)).transpose()?;
+    }
+    Ok(host)
+}
+
 pub fn update(
     conn: &Connection,
     host: &Host,
@@ -161,19 +223,39 @@ pub fn update(
     } else {
         encrypt_fields(host, encryption_key)?
     };
+    let key_path = ssh_only(host, key_path);
+    let s3 = seal_s3_fields(host, encryption_key)?;
     let changed = conn
         .execute(
             "UPDATE hosts SET name = ?1, host = ?2, port = ?3, protocol = ?4, \
-             username = ?5, password = ?6, key_path = ?7, updated_at = datetime('now') \
-             WHERE id = ?8",
+             username = ?5, password = ?6, key_path = ?7, ftps_mode = ?8, \
+             timezone_offset = ?9, ftp_data_mode = ?10, ftp_active_address = ?11, \
+             ftp_active_port_min = ?12, ftp_active_port_max = ?13, ftp_ignore_pasv_address = ?14, \
+             ssh_auth_order = ?15, jump_host_id = ?16, proxy_mode = ?17, s3_region = ?18, \
+             s3_bucket = ?19, s3_use_http = ?20, ssh_transfer_mode = ?21, \
+             updated_at = datetime('now') WHERE id = ?22",
             params![
                 host.name,
-                host.host,
+                s3.host,
                 host.port,
                 host.protocol.as_str(),
-                host.username,
+                s3.username,
                 password,
                 key_path,
+                host.ftps_mode.as_str(),
+                host.timezone_offset,
+                host.ftp_data_mode.as_str(),
+                host.ftp_active_address,
+                host.ftp_active_port_min,
+                host.ftp_active_port_max,
+                host.ftp_ignore_pasv_address,
+                SshAuthMethod::join(&host.ssh_auth_order),
+                host.jump_host_id,
+                host.proxy_mode.as_str(),
+                s3.region,
+                s3.bucket,
+                host.s3_use_http,
+                host.ssh_transfer_mode.as_str(),
                 id,
             ],
         )
@@ -186,6 +268,32 @@ pub fn delete(conn: &Connection, id: i64) -> Result<bool, rusqlite::Error> {
     Ok(changed > 0)
 }
 
+/// 写入数据库的地址、用户名和 S3 字段；S3 主机的端点、访问密钥 ID、区域和存储桶同样加密保存
+struct SealedS3Fields {
+    host: String,
+    username: String,
+    region: Option<String>,
+    bucket: Option<String>,
+}
+
+fn seal_s3_fields(host: &Host, key: Option<&[u8; 32]>) -> Result<SealedS3Fields, String> {
+    let seal = |value: &str| match (&host.protocol, key) {
+        (Protocol::S3, Some(k)) => encrypt(value, k),
+        _ => Ok(value.to_string()),
+    };
+    Ok(SealedS3Fields {
+        host: seal(&host.host)?,
+        username: seal(&host.username)?,
+        region: host.s3_region.as_deref().map(seal).transpose()?,
+        bucket: host.s3_bucket.as_deref().map(seal).transpose()?,
+    })
+}
+
+/// 密钥文件只用于 SFTP，其他协议保存时清空，避免切换协议后残留旧值
+fn ssh_only(host: &Host, key_path: Option<String>) -> Option<String> {
+    key_path.filter(|_| host.protocol == Protocol::Sftp)
+}
+
 fn encrypt_fields(
     host: &Host,
     key: Option<&[u8; 32]>,
@@ -325,6 +433,39 @@ mod tests {
         assert_eq!(fetched.host, "192.168.1.100");
     }
 
+    #[test]
+    fn test_s3_fields_encrypted() {
+        let conn = setup_db();
+        let key = [3u8; 32];
+        let mut h = Host::new(
+            "artifacts".into(),
+            "minio.corp".into(),
+            9000,
+            Protocol::S3,
+            "AKIAEXAMPLE".into(),
+        );
+        h.password = Some("secret-key".into());
+        h.s3_region = Some("eu-west-1".into());
+        h.s3_bucket = Some("builds".into());
+        h.s3_use_http = true;
+        let created = insert(&conn, &h, Some(&key)).unwrap();
+        assert_eq!(created.host, "minio.corp");
+        assert_eq!(created.username, "AKIAEXAMPLE");
+        assert_eq!(created.s3_bucket.as_deref(), Some("builds"));
+        assert!(created.s3_use_http);
+
+        let (host, username, region, bucket): (String, String, String, String) = conn
+            .query_row(
+                "SELECT host, username, s3_region, s3_bucket FROM hosts",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        for stored in [host, username, region, bucket] {
+            assert!(stored.starts_with("enc:"));
+        }
+    }
+
     #[test]
     fn test_get_by_id_not_found() {
         let conn = setup_db();
@@ -361,6 +502,21 @@ mod tests {
         assert_eq!(fetched.port, 2222);
     }
 
+    #[test]
+    fn test_key_path_cleared_for_non_ssh() {
+        let conn = setup_db();
+        let mut host = sample_host();
+        host.key_path = Some("/home/admin/.ssh/id_ed25519".into());
+        let mut created = insert(&conn, &host, None).unwrap();
+        assert!(created.key_path.is_some());
+
+        created.protocol = Protocol::Ftp;
+        created.port = 21;
+        update(&conn, &created, None).unwrap();
+        let fetched = get_by_id(&conn, created.id.unwrap(), None).unwrap().unwrap();
+        assert_eq!(fetched.key_path, None);
+    }
+
     #[test]
     fn test_update_nonexistent() {
         let conn = setup_db();
@@ -410,4 +566,125 @@ mod tests {
         assert_eq!(fetched.password, Some("secret".into()));
         assert_eq!(fetched.name, "My Server");
     }
+
+    #[test]
+    fn test_ftps_mode_roundtrip() {
+        let conn = setup_db();
+        let mut host =
+            Host::new("Legacy".into(), "10.0.0.9".into(), 990, Protocol::Ftps, "ftpuser".into());
+        host.ftps_mode = FtpsMode::Implicit;
+        let created = insert(&conn, &host, None).unwrap();
+        assert_eq!(created.ftps_mode, FtpsMode::Implicit);
+
+        let mut updated = created.clone();
+        updated.ftps_mode = FtpsMode::Explicit;
+        update(&conn, &updated, None).unwrap();
+        let fetched = get_by_id(&conn, created.id.unwrap(), None).unwrap().unwrap();
+        assert_eq!(fetched.ftps_mode, FtpsMode::Explicit);
+    }
+
+    #[test]
+    fn test_timezone_offset_roundtrip() {
+        let conn = setup_db();
+        let host = Host::new("Old".into(), "10.0.0.5".into(), 21, Protocol::Ftp, "ftp".into());
+        let created = insert(&conn, &host, None).unwrap();
+        assert_eq!(created.timezone_offset, 0);
+
+        let mut updated = created.clone();
+        updated.timezone_offset = 8 * 60;
+        update(&conn, &updated, None).unwrap();
+        let fetched = get_by_id(&conn, created.id.unwrap(), None).unwrap().unwrap();
+        assert_eq!(fetched.timezone_offset, 480);
+    }
+
+    #[test]
+    fn test_ftp_data_settings_roundtrip() {
+        let conn = setup_db();
+        let mut host =
+            Host::new("Behind NAT".into(), "ftp.example.com".into(), 21, Protocol::Ftp, "u".into());
+        host.ftp_data_mode = FtpDataMode::Active;
+        host.ftp_active_address = Some("203.0.113.7".into());
+        host.ftp_active_port_min = Some(50000);
+        host.ftp_active_port_max = Some(50100);
+        let created = insert(&conn, &host, None).unwrap();
+        assert_eq!(created.ftp_data_mode, FtpDataMode::Active);
+        assert_eq!(created.ftp_active_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(created.ftp_active_port_min, Some(50000));
+        assert_eq!(created.ftp_active_port_max, Some(50100));
+
+        let mut updated = created.clone();
+        updated.ftp_data_mode = FtpDataMode::Passive;
+        updated.ftp_active_address = None;
+        updated.ftp_ignore_pasv_address = true;
+        update(&conn, &updated, None).unwrap();
+        let fetched = get_by_id(&conn, created.id.unwrap(), None).unwrap().unwrap();
+        assert_eq!(fetched.ftp_data_mode, FtpDataMode::Passive);
+        assert!(fetched.ftp_active_address.is_none());
+        assert!(fetched.ftp_ignore_pasv_address);
+    }
+
+    #[test]
+    fn test_ssh_auth_order_roundtrip() {
+        let conn = setup_db();
+        let mut host =
+            Host::new("Bastion".into(), "10.0.0.2".into(), 22, Protocol::Sftp, "ops".into());
+        host.ssh_auth_order = vec![SshAuthMethod::Agent, SshAuthMethod::Password];
+        let created = insert(&conn, &host, None).unwrap();
+        assert_eq!(
+            created.ssh_auth_order,
+            vec![SshAuthMethod::Agent, SshAuthMethod::Password]
+        );
+
+        let mut updated = created.clone();
+        updated.ssh_auth_order.clear();
+        update(&conn, &updated, None).unwrap();
+        let fetched = get_by_id(&conn, created.id.unwrap(), None).unwrap().unwrap();
+        assert!(fetched.ssh_auth_order.is_empty());
+    }
+
+    #[test]
+    fn test_jump_chain() {
+        let conn = setup_db();
+        let outer = insert(&conn, &sample_host(), None).unwrap();
+        let mut inner = sample_host();
+        inner.jump_host_id = outer.id;
+        let inner = insert(&conn, &inner, None).unwrap();
+        let mut target = sample_host();
+        target.jump_host_id = inner.id;
+        let target = insert(&conn, &target, None).unwrap();
+
+        let chain = get_jump_chain(&conn, &target, None).unwrap();
+        let ids: Vec<_> = chain.iter().map(|h| h.id).collect();
+        assert_eq!(ids, vec![outer.id, inner.id]);
+        assert!(get_jump_chain(&conn, &outer, None).unwrap().is_empty());
+
+        // 外层跳板机再指回目标主机形成循环
+        let mut looped = outer.clone();
+        looped.jump_host_id = target.id;
+        update(&conn, &looped, None).unwrap();
+        assert!(get_jump_chain(&conn, &target, None).is_err());
+    }
+
+    #[test]
+    fn test_jump_chain_rejects_non_sftp() {
+        let conn = setup_db();
+        let ftp = Host::new("ftp".into(), "10.0.0.3".into(), 21, Protocol::Ftp, "u".into());
+        let ftp = insert(&conn, &ftp, None).unwrap();
+        let mut target = sample_host();
+        target.jump_host_id = ftp.id;
+        assert!(get_jump_chain(&conn, &target, None).is_err());
+    }
+
+    #[test]
+    fn test_jump_host_cleared_on_delete() {
+        let conn = setup_db();
+        let bastion = insert(&conn, &sample_host(), None).unwrap();
+        let mut target = sample_host();
+        target.jump_host_id = bastion.id;
+        let target = insert(&conn, &target, None).unwrap();
+
+        delete(&conn, bastion.id.unwrap()).unwrap();
+        let fetched = get_by_id(&conn, target.id.unwrap(), None).unwrap().unwrap();
+        assert_eq!(fetched.jump_host_id, None);
+    }
 }
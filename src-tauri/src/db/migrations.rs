@@ -7,10 +7,134 @@ pub fn run_all(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(schema::CREATE_TRANSFER_HISTORY_TABLE)?;
     conn.execute_batch(schema::CREATE_DIRECTORY_BOOKMARKS_TABLE)?;
     conn.execute_batch(schema::CREATE_RESUME_RECORDS_TABLE)?;
+    conn.execute_batch(schema::CREATE_TRUSTED_CERTIFICATES_TABLE)?;
+    conn.execute_batch(schema::CREATE_KNOWN_HOST_KEYS_TABLE)?;
+    conn.execute_batch(schema::CREATE_PROXY_SETTINGS_TABLE)?;
+    conn.execute_batch(schema::CREATE_PORT_FORWARDS_TABLE)?;
+    upgrade_table(conn, "hosts", schema::CREATE_HOSTS_TABLE, &["CHECK(protocol"])?;
+    upgrade_table(
+        conn,
+        "transfer_history",
+        schema::CREATE_TRANSFER_HISTORY_TABLE,
+        &["CHECK(direction", "FOREIGN KEY (dest_host_id)"],
+    )?;
+    upgrade_table(
+        conn,
+        "resume_records",
+        schema::CREATE_RESUME_RECORDS_TABLE,
+        &["CHECK(direction", "FOREIGN KEY (dest_host_id)"],
+    )?;
     conn.execute_batch(schema::CREATE_INDICES)?;
+    // 早期版本允许非 SFTP 主机保存密钥路径，清掉这些用不到的残留值
+    conn.execute(
+        "UPDATE hosts SET key_path = NULL WHERE protocol <> 'sftp' AND key_path IS NOT NULL",
+        [],
+    )?;
     Ok(())
 }
 
+/// 建表语句中包含 constraints 之一的约束（如 CHECK 新增了协议或传输方向、外键改了删除行为）
+/// 在旧库中不存在时，按 SQLite 推荐流程重建表，否则只补齐新增的列
+fn upgrade_table(
+    conn: &Connection,
+    table: &str,
+    create_sql: &str,
+    constraints: &[&str],
+) -> Result<(), rusqlite::Error> {
+    let current_sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    let outdated = create_sql
+        .lines()
+        .map(|l| l.trim().trim_end_matches(','))
+        .filter(|l| constraints.iter().any(|c| l.contains(c)))
+        .any(|l| !current_sql.contains(l));
+    if outdated {
+        rebuild_table(conn, table, create_sql)
+    } else {
+        add_missing_columns(conn, table, create_sql)
+    }
+}
+
+/// 对照建表语句，为旧库补齐新增的列（新增列必须带常量默认值或允许 NULL）
+fn add_missing_columns(
+    conn: &Connection,
+    table: &str,
+    create_sql: &str,
+) -> Result<(), rusqlite::Error> {
+    let existing = table_columns(conn, table)?;
+    for line in create_sql.lines() {
+        let def = line.trim().trim_end_matches(',');
+        let column = match def.split_whitespace().next() {
+            Some(c) => c,
+            None => continue,
+        };
+        let is_column = column.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            && !existing.iter().any(|e| e == column);
+        if is_column {
+            conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {};", table, def))?;
+        }
+    }
+    Ok(())
+}
+
+/// 用最新的建表语句重建表并拷贝两边共有的列。
+/// 重建期间关闭外键，避免 DROP TABLE 级联删除子表数据。
+fn rebuild_table(conn: &Connection, table: &str, create_sql: &str) -> Result<(), rusqlite::Error> {
+    let fk_enabled: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.execute_batch("PRAGMA foreign_keys=OFF;")?;
+
+    let result = (|| {
+        let tmp_table = format!("{}_new", table);
+        let old_columns = table_columns(conn, table)?;
+        let create_tmp = create_sql.replacen(
+            &format!("CREATE TABLE IF NOT EXISTS {} (", table),
+            &format!("CREATE TABLE {} (", tmp_table),
+            1,
+        );
+
+        conn.execute_batch("BEGIN;")?;
+        conn.execute_batch(&create_tmp)?;
+        let new_columns = table_columns(conn, &tmp_table)?;
+        let shared: Vec<&String> = old_columns
+            .iter()
+            .filter(|c| new_columns.contains(c))
+            .collect();
+        let column_list = shared
+            .iter()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        conn.execute_batch(&format!(
+            "INSERT INTO {tmp} ({cols}) SELECT {cols} FROM {table}; \
+             DROP TABLE {table}; \
+             ALTER TABLE {tmp} RENAME TO {table};",
+            tmp = tmp_table,
+            cols = column_list,
+            table = table,
+        ))?;
+        conn.execute_batch("COMMIT;")
+    })();
+
+    if result.is_err() {
+        let _ = conn.execute_batch("ROLLBACK;");
+    }
+    if fk_enabled {
+        conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+    }
+    result
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,6 +156,9 @@ mod tests {
         assert!(tables.contains(&"transfer_history".to_string()));
         assert!(tables.contains(&"directory_bookmarks".to_string()));
         assert!(tables.contains(&"resume_records".to_string()));
+        assert!(tables.contains(&"known_host_keys".to_string()));
+        assert!(tables.contains(&"proxy_settings".to_string()));
+        assert!(tables.contains(&"port_forwards".to_string()));
     }
 
     #[test]
@@ -40,4 +167,194 @@ mod tests {
         run_all(&conn).unwrap();
         run_all(&conn).unwrap();
     }
+
+    #[test]
+    fn test_stale_key_path_cleared() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_all(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO hosts (name, host, port, protocol, username, key_path) \
+             VALUES ('ftp', 'h', 21, 'ftp', 'u', '/k'), ('ssh', 'h', 22, 'sftp', 'u', '/k')",
+            [],
+        )
+        .unwrap();
+        run_all(&conn).unwrap();
+        let remaining: Vec<String> = conn
+            .prepare("SELECT protocol FROM hosts WHERE key_path IS NOT NULL")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["sftp".to_string()]);
+    }
+
+    #[test]
+    fn test_upgrade_legacy_hosts_protocol_check() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        conn.execute_batch(
+            "CREATE TABLE hosts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL DEFAULT 22,
+                protocol TEXT NOT NULL CHECK(protocol IN ('ftp', 'sftp')),
+                username TEXT NOT NULL,
+                password TEXT,
+                key_path TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );",
+        )
+        .unwrap();
+        conn.execute_batch(schema::CREATE_TRANSFER_HISTORY_TABLE).unwrap();
+        conn.execute(
+            "INSERT INTO hosts (name, host, port, protocol, username) VALUES ('old', 'h', 21, 'ftp', 'u')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO transfer_history (host_id, filename, remote_path, local_path, direction, status) \
+             VALUES (1, 'a.txt', '/a.txt', '/tmp/a.txt', 'upload', 'success')",
+            [],
+        )
+        .unwrap();
+
+        run_all(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO hosts (name, host, port, protocol, username) VALUES ('new', 'h', 443, 'webdavs', 'u')",
+            [],
+        )
+        .unwrap();
+        let hosts: i64 = conn
+            .query_row("SELECT COUNT(*) FROM hosts", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(hosts, 2);
+        let history: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transfer_history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(history, 1, "重建 hosts 表不应级联删除传输历史");
+        let fk_enabled: i32 = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(fk_enabled, 1);
+    }
+
+    #[test]
+    fn test_upgrade_legacy_transfer_direction_check() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        conn.execute_batch(schema::CREATE_HOSTS_TABLE).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transfer_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                host_id INTEGER NOT NULL,
+                filename TEXT NOT NULL,
+                remote_path TEXT NOT NULL,
+                local_path TEXT NOT NULL,
+                direction TEXT NOT NULL CHECK(direction IN ('upload', 'download')),
+                file_size INTEGER NOT NULL DEFAULT 0,
+                transferred_size INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL CHECK(status IN ('pending', 'transferring', 'success', 'failed', 'cancelled')),
+                error_message TEXT,
+                started_at TEXT,
+                finished_at TEXT,
+                FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE
+            );
+            INSERT INTO hosts (name, host, port, protocol, username) VALUES ('a', 'h', 21, 'ftp', 'u');
+            INSERT INTO hosts (name, host, port, protocol, username) VALUES ('b', 'h', 22, 'sftp', 'u');
+            INSERT INTO transfer_history (host_id, filename, remote_path, local_path, direction, status) \
+             VALUES (1, 'a.txt', '/a.txt', '/tmp/a.txt', 'upload', 'success');",
+        )
+        .unwrap();
+
+        run_all(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO transfer_history (host_id, filename, remote_path, local_path, direction, status, dest_host_id) \
+             VALUES (1, 'a.txt', '/a.txt', '/srv/a.txt', 'remote', 'success', 2)",
+            [],
+        )
+        .unwrap();
+        let history: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transfer_history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(history, 2);
+        assert!(conn
+            .execute(
+                "INSERT INTO resume_records (transfer_id, host_id, remote_path, local_path, direction, dest_host_id) \
+                 VALUES ('t', 1, '/a.txt', '/srv/a.txt', 'remote', 2)",
+                [],
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_upgrade_dest_host_cascade_to_set_null() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        conn.execute_batch(schema::CREATE_HOSTS_TABLE).unwrap();
+        for create_sql in [
+            schema::CREATE_TRANSFER_HISTORY_TABLE,
+            schema::CREATE_RESUME_RECORDS_TABLE,
+        ] {
+            conn.execute_batch(&create_sql.replace("ON DELETE SET NULL", "ON DELETE CASCADE"))
+                .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO hosts (name, host, port, protocol, username) VALUES ('a', 'h', 22, 'sftp', 'u');
+            INSERT INTO hosts (name, host, port, protocol, username) VALUES ('b', 'h', 22, 'sftp', 'u');
+            INSERT INTO transfer_history (host_id, filename, remote_path, local_path, direction, status, dest_host_id) \
+             VALUES (1, 'a.txt', '/a.txt', '/srv/a.txt', 'remote', 'failed', 2);
+            INSERT INTO resume_records (transfer_id, host_id, remote_path, local_path, direction, dest_host_id) \
+             VALUES ('t', 1, '/a.txt', '/srv/a.txt', 'remote', 2);",
+        )
+        .unwrap();
+
+        run_all(&conn).unwrap();
+
+        // 删除目标主机只清空引用，源主机的记录保留
+        conn.execute("DELETE FROM hosts WHERE id = 2", []).unwrap();
+        for table in ["transfer_history", "resume_records"] {
+            let dest: Option<i64> = conn
+                .query_row(&format!("SELECT dest_host_id FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            assert_eq!(dest, None);
+        }
+    }
+
+    #[test]
+    fn test_add_missing_host_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE hosts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                host TEXT NOT NULL,
+                port INTEGER NOT NULL DEFAULT 22,
+                protocol TEXT NOT NULL CHECK(protocol IN ('ftp', 'ftps', 'sftp', 'webdav', 'webdavs', 's3', 'local')),
+                username TEXT NOT NULL,
+                password TEXT,
+                key_path TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            INSERT INTO hosts (name, host, port, protocol, username) VALUES ('old', 'h', 21, 'ftps', 'u');",
+        )
+        .unwrap();
+
+        run_all(&conn).unwrap();
+
+        let mode: String = conn
+            .query_row("SELECT ftps_mode FROM hosts WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "explicit");
+        assert!(conn
+            .execute("UPDATE hosts SET ftps_mode = 'bogus' WHERE id = 1", [])
+            .is_err());
+    }
 }
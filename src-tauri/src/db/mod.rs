@@ -1,6 +1,10 @@
 pub mod bookmark_repo;
+pub mod certificate_repo;
+pub mod host_key_repo;
 pub mod host_repo;
 pub mod migrations;
+pub mod port_forward_repo;
+pub mod proxy_repo;
 pub mod schema;
 pub mod transfer_repo;
 
@@ -85,6 +89,7 @@ mod tests {
         assert!(tables.contains(&"transfer_history".to_string()));
         assert!(tables.contains(&"directory_bookmarks".to_string()));
         assert!(tables.contains(&"resume_records".to_string()));
+        assert!(tables.contains(&"trusted_certificates".to_string()));
     }
 
     #[test]
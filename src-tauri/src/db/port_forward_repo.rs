@@ -0,0 +1,155 @@
+use rusqlite::{params, Connection};
+
+use crate::models::port_forward::{ForwardKind, PortForward};
+
+const FORWARD_COLUMNS: &str = "id, host_id, name, kind, bind_host, bind_port, \
+     target_host, target_port, created_at";
+
+pub fn insert(conn: &Connection, forward: &PortForward) -> Result<PortForward, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO port_forwards \
+         (host_id, name, kind, bind_host, bind_port, target_host, target_port) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            forward.host_id,
+            forward.name,
+            forward.kind.as_str(),
+            forward.bind_host,
+            forward.bind_port,
+            forward.target_host,
+            forward.target_port,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    get_by_id(conn, id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// 所属主机不可修改
+pub fn update(conn: &Connection, forward: &PortForward) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute(
+        "UPDATE port_forwards SET name = ?1, kind = ?2, bind_host = ?3, bind_port = ?4, \
+         target_host = ?5, target_port = ?6 WHERE id = ?7",
+        params![
+            forward.name,
+            forward.kind.as_str(),
+            forward.bind_host,
+            forward.bind_port,
+            forward.target_host,
+            forward.target_port,
+            forward.id,
+        ],
+    )?;
+    Ok(changed > 0)
+}
+
+pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<PortForward>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM port_forwards WHERE id = ?1",
+        FORWARD_COLUMNS
+    ))?;
+    let mut rows = stmt.query_map(params![id], row_to_forward)?;
+    match rows.next() {
+        Some(row) => Ok(Some(row?)),
+        None => Ok(None),
+    }
+}
+
+pub fn get_by_host(conn: &Connection, host_id: i64) -> Result<Vec<PortForward>, rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM port_forwards WHERE host_id = ?1 ORDER BY id",
+        FORWARD_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![host_id], row_to_forward)?;
+    rows.collect()
+}
+
+pub fn delete(conn: &Connection, id: i64) -> Result<bool, rusqlite::Error> {
+    let changed = conn.execute("DELETE FROM port_forwards WHERE id = ?1", params![id])?;
+    Ok(changed > 0)
+}
+
+fn row_to_forward(row: &rusqlite::Row) -> Result<PortForward, rusqlite::Error> {
+    let kind: String = row.get(3)?;
+    Ok(PortForward {
+        id: row.get(0)?,
+        host_id: row.get(1)?,
+        name: row.get(2)?,
+        kind: ForwardKind::from_str(&kind).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                3,
+                rusqlite::types::Type::Text,
+                Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            )
+        })?,
+        bind_host: row.get(4)?,
+        bind_port: row.get(5)?,
+        target_host: row.get(6)?,
+        target_port: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{host_repo, migrations};
+    use crate::models::host::{Host, Protocol};
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    fn db_forward(conn: &Connection) -> PortForward {
+        let h = Host::new(
+            "db-host".into(),
+            "10.0.0.1".into(),
+            22,
+            Protocol::Sftp,
+            "u".into(),
+        );
+        let host = host_repo::insert(conn, &h, None).unwrap();
+        PortForward {
+            id: None,
+            host_id: host.id.unwrap(),
+            name: "postgres".into(),
+            kind: ForwardKind::Local,
+            bind_host: "127.0.0.1".into(),
+            bind_port: 15432,
+            target_host: "db.internal".into(),
+            target_port: 5432,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_update_and_get() {
+        let conn = setup_db();
+        let created = insert(&conn, &db_forward(&conn)).unwrap();
+        assert!(created.id.is_some());
+        assert!(created.created_at.is_some());
+        assert_eq!(created.kind, ForwardKind::Local);
+
+        let mut changed = created.clone();
+        changed.kind = ForwardKind::Remote;
+        changed.bind_port = 0;
+        assert!(update(&conn, &changed).unwrap());
+        let fetched = get_by_id(&conn, created.id.unwrap()).unwrap().unwrap();
+        assert_eq!(fetched, changed);
+        assert_eq!(get_by_host(&conn, created.host_id).unwrap(), vec![changed]);
+    }
+
+    #[test]
+    fn test_delete_and_cascade() {
+        let conn = setup_db();
+        let a = insert(&conn, &db_forward(&conn)).unwrap();
+        assert!(delete(&conn, a.id.unwrap()).unwrap());
+        assert!(!delete(&conn, a.id.unwrap()).unwrap());
+
+        let b = insert(&conn, &db_forward(&conn)).unwrap();
+        host_repo::delete(&conn, b.host_id).unwrap();
+        assert!(get_by_host(&conn, b.host_id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::crypto::{decrypt, encrypt};
+use crate::models::host::Host;
+use crate::models::proxy::{ProxyConfig, ProxyMode, ProxyType};
+
+/// 全局代理，未配置时返回 None
+pub fn get_global(
+    conn: &Connection,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Option<ProxyConfig>, String> {
+    query_one(
+        conn,
+        "SELECT proxy_type, host, port, username, password FROM proxy_settings \
+         WHERE host_id IS NULL",
+        [],
+        encryption_key,
+    )
+}
+
+pub fn get_for_host(
+    conn: &Connection,
+    host_id: i64,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Option<ProxyConfig>, String> {
+    query_one(
+        conn,
+        "SELECT proxy_type, host, port, username, password FROM proxy_settings \
+         WHERE host_id = ?1",
+        params![host_id],
+        encryption_key,
+    )
+}
+
+/// 保存全局代理；proxy 为 None 时清除。未填写密码时沿用已保存的密码
+pub fn set_global(
+    conn: &Connection,
+    proxy: Option<&ProxyConfig>,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let existing = get_global(conn, encryption_key)?;
+    replace(conn, None, proxy, existing.as_ref(), encryption_key)
+}
+
+/// 保存主机自定义代理；proxy 为 None 时清除。未填写密码时沿用已保存的密码
+pub fn set_for_host(
+    conn: &Connection,
+    host_id: i64,
+    proxy: Option<&ProxyConfig>,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let existing = get_for_host(conn, host_id, encryption_key)?;
+    replace(conn, Some(host_id), proxy, existing.as_ref(), encryption_key)
+}
+
+/// 按主机的代理模式取实际生效的代理
+pub fn resolve(
+    conn: &Connection,
+    host: &Host,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Option<ProxyConfig>, String> {
+    match host.proxy_mode {
+        ProxyMode::Direct => Ok(None),
+        ProxyMode::Global => get_global(conn, encryption_key),
+        ProxyMode::Custom => {
+            let host_id = host.id.ok_or("Host 缺少 id")?;
+            get_for_host(conn, host_id, encryption_key)?
+                .map(Some)
+                .ok_or_else(|| format!("主机 {} 未配置自定义代理", host.name))
+        }
+    }
+}
+
+/// 删除旧配置并写入新配置，两步在同一事务中完成，写入失败时保留原配置
+fn replace(
+    conn: &Connection,
+    host_id: Option<i64>,
+    proxy: Option<&ProxyConfig>,
+    existing: Option<&ProxyConfig>,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "DELETE FROM proxy_settings WHERE host_id IS ?1",
+        params![host_id],
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(p) = proxy {
+        insert(&tx, host_id, p, existing, encryption_key)?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn insert(
+    conn: &Connection,
+    host_id: Option<i64>,
+    proxy: &ProxyConfig,
+    existing: Option<&ProxyConfig>,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let password = match proxy.password.as_deref() {
+        Some(p) if !p.is_empty() => Some(p),
+        _ if proxy.username.is_some() => existing.and_then(|e| e.password.as_deref()),
+        _ => None,
+    };
+    conn.execute(
+        "INSERT INTO proxy_settings (host_id, proxy_type, host, port, username, password) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            host_id,
+            proxy.proxy_type.as_str(),
+            proxy.host.trim(),
+            proxy.port,
+            encrypt_field(proxy.username.as_deref(), encryption_key)?,
+            encrypt_field(password, encryption_key)?,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn encrypt_field(value: Option<&str>, key: Option<&[u8; 32]>) -> Result<Option<String>, String> {
+    match (value, key) {
+        (Some(""), _) => Ok(None),
+        (Some(v), Some(k)) => encrypt(v, k).map(Some),
+        (v, _) => Ok(v.map(str::to_string)),
+    }
+}
+
+fn decrypt_field(value: Option<String>, key: Option<&[u8; 32]>) -> Result<Option<String>, String> {
+    match (value, key) {
+        (Some(v), Some(k)) => decrypt(&v, k).map(Some),
+        (v, _) => Ok(v),
+    }
+}
+
+fn query_one<P: rusqlite::Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Option<ProxyConfig>, String> {
+    let row = conn
+        .query_row(sql, params, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u16>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((proxy_type, host, port, username, password)) = row else {
+        return Ok(None);
+    };
+    Ok(Some(ProxyConfig {
+        proxy_type: ProxyType::from_str(&proxy_type)?,
+        host,
+        port,
+        username: decrypt_field(username, encryption_key)?,
+        password: decrypt_field(password, encryption_key)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{host_repo, migrations};
+    use crate::models::host::Protocol;
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    fn socks(password: Option<&str>) -> ProxyConfig {
+        ProxyConfig {
+            proxy_type: ProxyType::Socks5,
+            host: "proxy.corp".into(),
+            port: 1080,
+            username: Some("alice".into()),
+            password: password.map(Into::into),
+        }
+    }
+
+    fn insert_host(conn: &Connection, mode: ProxyMode) -> Host {
+        let mut h = Host::new(
+            "h".into(),
+            "10.0.0.9".into(),
+            22,
+            Protocol::Sftp,
+            "u".into(),
+        );
+        h.proxy_mode = mode;
+        host_repo::insert(conn, &h, None).unwrap()
+    }
+
+    #[test]
+    fn test_global_proxy_encrypted() {
+        let conn = setup_db();
+        set_global(&conn, Some(&socks(Some("s3cret"))), Some(&KEY)).unwrap();
+
+        let stored: String = conn
+            .query_row("SELECT password FROM proxy_settings", [], |row| row.get(0))
+            .unwrap();
+        assert!(stored.starts_with("enc:"));
+        assert_eq!(
+            get_global(&conn, Some(&KEY)).unwrap(),
+            Some(socks(Some("s3cret")))
+        );
+
+        set_global(&conn, None, Some(&KEY)).unwrap();
+        assert_eq!(get_global(&conn, Some(&KEY)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_keeps_password_when_omitted() {
+        let conn = setup_db();
+        set_global(&conn, Some(&socks(Some("s3cret"))), None).unwrap();
+        set_global(&conn, Some(&socks(None)), None).unwrap();
+        assert_eq!(
+            get_global(&conn, None).unwrap(),
+            Some(socks(Some("s3cret")))
+        );
+    }
+
+    #[test]
+    fn test_failed_save_keeps_previous_proxy() {
+        let conn = setup_db();
+        set_global(&conn, Some(&socks(Some("s3cret"))), None).unwrap();
+        conn.execute_batch(
+            "CREATE TEMP TRIGGER reject_insert BEFORE INSERT ON proxy_settings \
+             BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+        )
+        .unwrap();
+
+        let mut changed = socks(Some("other"));
+        changed.port = 1081;
+        assert!(set_global(&conn, Some(&changed), None).is_err());
+        assert_eq!(
+            get_global(&conn, None).unwrap(),
+            Some(socks(Some("s3cret")))
+        );
+    }
+
+    #[test]
+    fn test_resolve_by_mode() {
+        let conn = setup_db();
+        set_global(&conn, Some(&socks(None)), None).unwrap();
+
+        let global = insert_host(&conn, ProxyMode::Global);
+        assert_eq!(resolve(&conn, &global, None).unwrap(), Some(socks(None)));
+
+        let direct = insert_host(&conn, ProxyMode::Direct);
+        assert_eq!(resolve(&conn, &direct, None).unwrap(), None);
+
+        let custom = insert_host(&conn, ProxyMode::Custom);
+        assert!(resolve(&conn, &custom, None).is_err());
+        let http = ProxyConfig {
+            proxy_type: ProxyType::Http,
+            host: "10.1.1.1".into(),
+            port: 3128,
+            username: None,
+            password: None,
+        };
+        set_for_host(&conn, custom.id.unwrap(), Some(&http), None).unwrap();
+        assert_eq!(resolve(&conn, &custom, None).unwrap(), Some(http));
+
+        // 删除主机时一并删除其代理
+        host_repo::delete(&conn, custom.id.unwrap()).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM proxy_settings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+}
@@ -4,10 +4,24 @@ CREATE TABLE IF NOT EXISTS hosts (
     name TEXT NOT NULL,
     host TEXT NOT NULL,
     port INTEGER NOT NULL DEFAULT 22,
-    protocol TEXT NOT NULL CHECK(protocol IN ('ftp', 'sftp')),
+    protocol TEXT NOT NULL CHECK(protocol IN ('ftp', 'ftps', 'sftp', 'webdav', 'webdavs', 's3', 'local')),
     username TEXT NOT NULL,
     password TEXT,
     key_path TEXT,
+    ftps_mode TEXT NOT NULL DEFAULT 'explicit' CHECK(ftps_mode IN ('explicit', 'implicit')),
+    timezone_offset INTEGER NOT NULL DEFAULT 0,
+    ftp_data_mode TEXT NOT NULL DEFAULT 'passive' CHECK(ftp_data_mode IN ('passive', 'epsv', 'active')),
+    ftp_active_address TEXT,
+    ftp_active_port_min INTEGER,
+    ftp_active_port_max INTEGER,
+    ftp_ignore_pasv_address INTEGER NOT NULL DEFAULT 0,
+    ssh_auth_order TEXT NOT NULL DEFAULT '',
+    jump_host_id INTEGER REFERENCES hosts(id) ON DELETE SET NULL,
+    proxy_mode TEXT NOT NULL DEFAULT 'global' CHECK(proxy_mode IN ('global', 'direct', 'custom')),
+    s3_region TEXT,
+    s3_bucket TEXT,
+    s3_use_http INTEGER NOT NULL DEFAULT 0,
+    ssh_transfer_mode TEXT NOT NULL DEFAULT 'auto' CHECK(ssh_transfer_mode IN ('auto', 'sftp', 'scp')),
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now'))
 )";
@@ -19,14 +33,16 @@ CREATE TABLE IF NOT EXISTS transfer_history (
     filename TEXT NOT NULL,
     remote_path TEXT NOT NULL,
     local_path TEXT NOT NULL,
-    direction TEXT NOT NULL CHECK(direction IN ('upload', 'download')),
+    direction TEXT NOT NULL CHECK(direction IN ('upload', 'download', 'remote')),
     file_size INTEGER NOT NULL DEFAULT 0,
     transferred_size INTEGER NOT NULL DEFAULT 0,
     status TEXT NOT NULL CHECK(status IN ('pending', 'transferring', 'success', 'failed', 'cancelled')),
     error_message TEXT,
     started_at TEXT,
     finished_at TEXT,
-    FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE
+    dest_host_id INTEGER,
+    FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE,
+    FOREIGN KEY (dest_host_id) REFERENCES hosts(id) ON DELETE SET NULL
 )";
 
 pub const CREATE_DIRECTORY_BOOKMARKS_TABLE: &str = "
@@ -47,11 +63,65 @@ CREATE TABLE IF NOT EXISTS resume_records (
     host_id INTEGER NOT NULL,
     remote_path TEXT NOT NULL,
     local_path TEXT NOT NULL,
-    direction TEXT NOT NULL CHECK(direction IN ('upload', 'download')),
+    direction TEXT NOT NULL CHECK(direction IN ('upload', 'download', 'remote')),
     file_size INTEGER NOT NULL DEFAULT 0,
     transferred_bytes INTEGER NOT NULL DEFAULT 0,
     checksum TEXT,
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    dest_host_id INTEGER,
+    FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE,
+    FOREIGN KEY (dest_host_id) REFERENCES hosts(id) ON DELETE SET NULL
+)";
+
+pub const CREATE_TRUSTED_CERTIFICATES_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS trusted_certificates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host_id INTEGER NOT NULL UNIQUE,
+    fingerprint TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    certificate TEXT NOT NULL,
+    trusted_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE
+)";
+
+/// 应用内的 known_hosts，按服务器地址和端口保存，与主机配置无关
+pub const CREATE_KNOWN_HOST_KEYS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS known_host_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    key_type TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    key TEXT NOT NULL,
+    trusted_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (host, port)
+)";
+
+/// 代理设置：host_id 为空的一行是全局代理，其余为主机自定义代理
+pub const CREATE_PROXY_SETTINGS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS proxy_settings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host_id INTEGER UNIQUE,
+    proxy_type TEXT NOT NULL CHECK(proxy_type IN ('socks5', 'http')),
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    username TEXT,
+    password TEXT,
+    FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE
+)";
+
+/// 主机的端口转发定义，运行状态不落库
+pub const CREATE_PORT_FORWARDS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS port_forwards (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    host_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK(kind IN ('local', 'remote')),
+    bind_host TEXT NOT NULL,
+    bind_port INTEGER NOT NULL,
+    target_host TEXT NOT NULL,
+    target_port INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
     FOREIGN KEY (host_id) REFERENCES hosts(id) ON DELETE CASCADE
 )";
 
@@ -59,7 +129,9 @@ pub const CREATE_INDICES: &str = "
 CREATE INDEX IF NOT EXISTS idx_transfer_history_host_id ON transfer_history(host_id);
 CREATE INDEX IF NOT EXISTS idx_transfer_history_status ON transfer_history(status);
 CREATE INDEX IF NOT EXISTS idx_transfer_history_started_at ON transfer_history(started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_transfer_history_dest_host_id ON transfer_history(dest_host_id);
 CREATE INDEX IF NOT EXISTS idx_directory_bookmarks_host_id ON directory_bookmarks(host_id);
 CREATE INDEX IF NOT EXISTS idx_resume_records_host_id ON resume_records(host_id);
 CREATE INDEX IF NOT EXISTS idx_resume_records_transfer_id ON resume_records(transfer_id);
+CREATE INDEX IF NOT EXISTS idx_port_forwards_host_id ON port_forwards(host_id);
 ";
@@ -13,8 +13,8 @@ pub fn insert_history(
     conn.execute(
         "INSERT INTO transfer_history \
          (host_id, filename, remote_path, local_path, direction, file_size, \
-          transferred_size, status, error_message, started_at, finished_at) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+          transferred_size, status, error_message, started_at, finished_at, dest_host_id) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
         params![
             record.host_id,
             record.filename,
@@ -27,6 +27,7 @@ pub fn insert_history(
             record.error_message,
             record.started_at,
             record.finished_at,
+            record.dest_host_id,
         ],
     )?;
     let id = conn.last_insert_rowid();
@@ -39,7 +40,7 @@ pub fn get_history_by_id(
 ) -> Result<Option<TransferHistory>, rusqlite::Error> {
     let mut stmt = conn.prepare(
         "SELECT id, host_id, filename, remote_path, local_path, direction, \
-         file_size, transferred_size, status, error_message, started_at, finished_at \
+         file_size, transferred_size, status, error_message, started_at, finished_at, dest_host_id \
          FROM transfer_history WHERE id = ?1",
     )?;
     let mut rows = stmt.query_map(params![id], row_to_history)?;
@@ -55,8 +56,8 @@ pub fn get_history_by_host(
 ) -> Result<Vec<TransferHistory>, rusqlite::Error> {
     let mut stmt = conn.prepare(
         "SELECT id, host_id, filename, remote_path, local_path, direction, \
-         file_size, transferred_size, status, error_message, started_at, finished_at \
-         FROM transfer_history WHERE host_id = ?1 ORDER BY id DESC",
+         file_size, transferred_size, status, error_message, started_at, finished_at, dest_host_id \
+         FROM transfer_history WHERE host_id = ?1 OR dest_host_id = ?1 ORDER BY id DESC",
     )?;
     let rows = stmt.query_map(params![host_id], row_to_history)?;
     rows.collect()
@@ -65,7 +66,7 @@ pub fn get_history_by_host(
 pub fn get_all_history(conn: &Connection) -> Result<Vec<TransferHistory>, rusqlite::Error> {
     let mut stmt = conn.prepare(
         "SELECT id, host_id, filename, remote_path, local_path, direction, \
-         file_size, transferred_size, status, error_message, started_at, finished_at \
+         file_size, transferred_size, status, error_message, started_at, finished_at, dest_host_id \
          FROM transfer_history ORDER BY id DESC",
     )?;
     let rows = stmt.query_map([], row_to_history)?;
@@ -96,7 +97,10 @@ pub fn clear_history_by_host(
     conn: &Connection,
     host_id: i64,
 ) -> Result<usize, rusqlite::Error> {
-    conn.execute("DELETE FROM transfer_history WHERE host_id = ?1", params![host_id])
+    conn.execute(
+        "DELETE FROM transfer_history WHERE host_id = ?1 OR dest_host_id = ?1",
+        params![host_id],
+    )
 }
 
 fn row_to_history(row: &rusqlite::Row) -> Result<TransferHistory, rusqlite::Error> {
@@ -131,6 +135,7 @@ fn row_to_history(row: &rusqlite::Row) -> Result<TransferHistory, rusqlite::Erro
         error_message: row.get(9)?,
         started_at: row.get(10)?,
         finished_at: row.get(11)?,
+        dest_host_id: row.get(12)?,
     })
 }
 
@@ -143,8 +148,8 @@ pub fn insert_resume(
     conn.execute(
         "INSERT INTO resume_records \
          (transfer_id, host_id, remote_path, local_path, direction, \
-          file_size, transferred_bytes, checksum) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+          file_size, transferred_bytes, checksum, dest_host_id) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             record.transfer_id,
             record.host_id,
@@ -154,6 +159,7 @@ pub fn insert_resume(
             record.file_size,
             record.transferred_bytes,
             record.checksum,
+            record.dest_host_id,
         ],
     )?;
     let id = conn.last_insert_rowid();
@@ -166,7 +172,7 @@ pub fn get_resume_by_id(
 ) -> Result<Option<ResumeRecord>, rusqlite::Error> {
     let mut stmt = conn.prepare(
         "SELECT id, transfer_id, host_id, remote_path, local_path, direction, \
-         file_size, transferred_bytes, checksum, created_at \
+         file_size, transferred_bytes, checksum, created_at, dest_host_id \
          FROM resume_records WHERE id = ?1",
     )?;
     let mut rows = stmt.query_map(params![id], row_to_resume)?;
@@ -182,16 +188,18 @@ pub fn find_resume(
     remote_path: &str,
     local_path: &str,
     direction: &TransferDirection,
+    dest_host_id: Option<i64>,
 ) -> Result<Option<ResumeRecord>, rusqlite::Error> {
     let mut stmt = conn.prepare(
         "SELECT id, transfer_id, host_id, remote_path, local_path, direction, \
-         file_size, transferred_bytes, checksum, created_at \
+         file_size, transferred_bytes, checksum, created_at, dest_host_id \
          FROM resume_records \
          WHERE host_id = ?1 AND remote_path = ?2 AND local_path = ?3 AND direction = ?4 \
+         AND dest_host_id IS ?5 \
          ORDER BY created_at DESC LIMIT 1",
     )?;
     let mut rows = stmt.query_map(
-        params![host_id, remote_path, local_path, direction.as_str()],
+        params![host_id, remote_path, local_path, direction.as_str(), dest_host_id],
         row_to_resume,
     )?;
     match rows.next() {
@@ -250,6 +258,7 @@ fn row_to_resume(row: &rusqlite::Row) -> Result<ResumeRecord, rusqlite::Error> {
         transferred_bytes: row.get(7)?,
         checksum: row.get(8)?,
         created_at: row.get(9)?,
+        dest_host_id: row.get(10)?,
     })
 }
 
@@ -314,6 +323,55 @@ mod tests {
         assert_eq!(results.len(), 3);
     }
 
+    #[test]
+    fn test_remote_history_listed_for_both_hosts() {
+        let conn = setup_db();
+        let source = insert_test_host(&conn).id.unwrap();
+        let dest = insert_test_host(&conn).id.unwrap();
+
+        let mut th = TransferHistory::new(
+            source,
+            "site.tar".into(),
+            "/staging/site.tar".into(),
+            "/prod/site.tar".into(),
+            TransferDirection::Remote,
+            2048,
+        );
+        th.dest_host_id = Some(dest);
+        let created = insert_history(&conn, &th).unwrap();
+        assert_eq!(created.direction, TransferDirection::Remote);
+        assert_eq!(created.dest_host_id, Some(dest));
+        assert_eq!(get_history_by_host(&conn, source).unwrap().len(), 1);
+        assert_eq!(get_history_by_host(&conn, dest).unwrap().len(), 1);
+
+        let mut rr = ResumeRecord::new(
+            "tid-remote".into(),
+            source,
+            "/staging/site.tar".into(),
+            "/prod/site.tar".into(),
+            TransferDirection::Remote,
+            2048,
+        );
+        rr.dest_host_id = Some(dest);
+        insert_resume(&conn, &rr).unwrap();
+        let find = |dest_host_id| {
+            find_resume(
+                &conn,
+                source,
+                "/staging/site.tar",
+                "/prod/site.tar",
+                &TransferDirection::Remote,
+                dest_host_id,
+            )
+            .unwrap()
+        };
+        assert!(find(Some(dest)).is_some());
+        assert!(find(None).is_none());
+
+        assert_eq!(clear_history_by_host(&conn, dest).unwrap(), 1);
+        assert!(get_history_by_host(&conn, source).unwrap().is_empty());
+    }
+
     #[test]
     fn test_update_history_status() {
         let conn = setup_db();
@@ -501,6 +559,7 @@ mod tests {
             "/r/data.bin",
             "/l/data.bin",
             &TransferDirection::Upload,
+            None,
         )
         .unwrap();
         assert!(found.is_some());
@@ -512,6 +571,7 @@ mod tests {
             "/r/other.bin",
             "/l/other.bin",
             &TransferDirection::Upload,
+            None,
         )
         .unwrap();
         assert!(not_found.is_none());
@@ -628,6 +688,7 @@ mod tests {
             "/r/f.txt",
             "/l/f.txt",
             &TransferDirection::Upload,
+            None,
         )
         .unwrap();
         assert!(resume.is_none());
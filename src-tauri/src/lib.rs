@@ -7,7 +7,10 @@ pub mod utils;
 pub mod validation;
 
 use db::Database;
+use services::auth_prompt::AuthPromptBroker;
 use services::connection::ConnectionManager;
+use services::port_forward::PortForwardManager;
+use services::terminal::TerminalManager;
 use services::transfer_engine::TransferEngine;
 use std::sync::Arc;
 use tauri::Manager;
@@ -40,10 +43,18 @@ pub fn run() {
             let conn_manager = ConnectionManager::new();
             let engine = TransferEngine::new(conn_manager.clone(), db_arc.clone());
             engine.set_app_handle(app.handle().clone());
+            let auth_prompts = AuthPromptBroker::new();
+            auth_prompts.set_app_handle(app.handle().clone());
+            let terminals = TerminalManager::new();
+            terminals.set_app_handle(app.handle().clone());
+            let port_forwards = PortForwardManager::new();
 
             app.manage(SharedDatabase(db_arc));
             app.manage(conn_manager);
             app.manage(engine);
+            app.manage(auth_prompts);
+            app.manage(terminals);
+            app.manage(port_forwards);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -56,6 +67,7 @@ pub fn run() {
             commands::transfer::clear_transfer_history_by_host,
             commands::transfer::start_upload,
             commands::transfer::start_download,
+            commands::transfer::start_remote_transfer,
             commands::transfer::cancel_transfer,
             commands::transfer::retry_transfer,
             commands::transfer::get_resume_records,
@@ -69,10 +81,26 @@ pub fn run() {
             commands::bookmark::create_bookmark,
             commands::bookmark::delete_bookmark,
             commands::bookmark::touch_bookmark,
+            commands::certificate::fetch_host_certificate,
+            commands::certificate::trust_host_certificate,
+            commands::certificate::get_trusted_certificate,
+            commands::certificate::remove_trusted_certificate,
+            commands::host_key::fetch_host_key,
+            commands::host_key::trust_host_key,
+            commands::host_key::get_known_host_keys,
+            commands::host_key::remove_known_host_key,
+            commands::proxy::get_global_proxy,
+            commands::proxy::set_global_proxy,
+            commands::proxy::get_host_proxy,
+            commands::proxy::set_host_proxy,
+            commands::host_import::preview_ssh_config_import,
+            commands::host_import::preview_site_import,
+            commands::host_import::import_hosts,
             commands::connection::connect_host,
             commands::connection::disconnect_host,
             commands::connection::test_connection,
             commands::connection::test_connection_by_id,
+            commands::connection::respond_auth_prompt,
             commands::connection::connection_status,
             commands::connection::active_connections,
             commands::connection::list_remote_dir,
@@ -80,8 +108,26 @@ pub fn run() {
             commands::connection::delete_remote_file,
             commands::connection::delete_remote_dir,
             commands::connection::rename_remote,
+            commands::connection::copy_remote_items,
+            commands::connection::move_remote_items,
             commands::connection::remote_file_exists,
             commands::connection::remote_file_size,
+            commands::connection::stat_remote,
+            commands::connection::set_remote_permissions,
+            commands::connection::set_remote_owner,
+            commands::remote_exec::run_remote_command,
+            commands::terminal::open_terminal,
+            commands::terminal::write_terminal,
+            commands::terminal::resize_terminal,
+            commands::terminal::close_terminal,
+            commands::terminal::list_terminals,
+            commands::port_forward::get_port_forwards,
+            commands::port_forward::create_port_forward,
+            commands::port_forward::update_port_forward,
+            commands::port_forward::delete_port_forward,
+            commands::port_forward::start_port_forward,
+            commands::port_forward::stop_port_forward,
+            commands::port_forward::get_port_forward_statuses,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
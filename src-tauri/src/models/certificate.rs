@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// 服务器出示的 TLS 证书，供前端展示并由用户确认是否信任
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    /// SHA-256 指纹，大写十六进制，冒号分隔
+    pub fingerprint: String,
+    pub subject: String,
+    /// base64 编码的 DER 证书
+    pub certificate: String,
+}
+
+/// 用户已确认信任的主机证书（证书固定）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustedCertificate {
+    pub id: Option<i64>,
+    pub host_id: i64,
+    pub fingerprint: String,
+    pub subject: String,
+    pub certificate: String,
+    pub trusted_at: Option<String>,
+}
+
+impl TrustedCertificate {
+    pub fn new(host_id: i64, info: CertificateInfo) -> Self {
+        Self {
+            id: None,
+            host_id,
+            fingerprint: info.fingerprint,
+            subject: info.subject,
+            certificate: info.certificate,
+            trusted_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trusted_certificate_new() {
+        let info = CertificateInfo {
+            fingerprint: "AA:BB".into(),
+            subject: "CN=ftp.example.com".into(),
+            certificate: "MIIB".into(),
+        };
+        let tc = TrustedCertificate::new(7, info);
+        assert_eq!(tc.id, None);
+        assert_eq!(tc.host_id, 7);
+        assert_eq!(tc.fingerprint, "AA:BB");
+        assert_eq!(tc.subject, "CN=ftp.example.com");
+        assert!(tc.trusted_at.is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+/// 批量复制、移动时目标目录中已有同名条目的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    /// 覆盖同名文件；任一方是目录时不覆盖，该项记为失败
+    #[serde(rename = "overwrite")]
+    Overwrite,
+    /// 保留已有条目，跳过该项
+    #[default]
+    #[serde(rename = "skip")]
+    Skip,
+    /// 改用“名称 (1).扩展名”这样未被占用的名称
+    #[serde(rename = "rename")]
+    Rename,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FileOpStatus {
+    #[serde(rename = "done")]
+    Done,
+    #[serde(rename = "skipped")]
+    Skipped,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+/// 批量操作中一项的结果，与提交的路径一一对应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOpResult {
+    pub source: String,
+    /// 实际写入的路径，按冲突策略改名时与目标目录下的原名称不同
+    pub target: Option<String>,
+    pub status: FileOpStatus,
+    pub error: Option<String>,
+}
+
+impl FileOpResult {
+    pub fn done(source: String, target: String) -> Self {
+        Self {
+            source,
+            target: Some(target),
+            status: FileOpStatus::Done,
+            error: None,
+        }
+    }
+
+    pub fn skipped(source: String) -> Self {
+        Self {
+            source,
+            target: None,
+            status: FileOpStatus::Skipped,
+            error: None,
+        }
+    }
+
+    pub fn failed(source: String, error: String) -> Self {
+        Self {
+            source,
+            target: None,
+            status: FileOpStatus::Failed,
+            error: Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflict_policy_serde() {
+        let parsed: ConflictPolicy = serde_json::from_str("\"rename\"").unwrap();
+        assert_eq!(parsed, ConflictPolicy::Rename);
+        assert_eq!(
+            serde_json::to_string(&ConflictPolicy::Overwrite).unwrap(),
+            "\"overwrite\""
+        );
+        assert_eq!(ConflictPolicy::default(), ConflictPolicy::Skip);
+        assert_eq!(
+            serde_json::to_string(&FileOpStatus::Done).unwrap(),
+            "\"done\""
+        );
+    }
+}
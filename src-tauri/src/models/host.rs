@@ -1,30 +1,206 @@
 use serde::{Deserialize, Serialize};
 
+pub use super::proxy::ProxyMode;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Protocol {
     #[serde(rename = "ftp")]
     Ftp,
+    #[serde(rename = "ftps")]
+    Ftps,
     #[serde(rename = "sftp")]
     Sftp,
+    /// WebDAV over HTTP
+    #[serde(rename = "webdav")]
+    Webdav,
+    /// WebDAV over HTTPS
+    #[serde(rename = "webdavs")]
+    Webdavs,
+    /// S3 兼容的对象存储（AWS S3、MinIO 等）
+    #[serde(rename = "s3")]
+    S3,
+    /// 本机上的目录（如挂载的网络共享），主机地址字段保存根目录
+    #[serde(rename = "local")]
+    Local,
 }
 
 impl Protocol {
     pub fn as_str(&self) -> &'static str {
         match self {
             Protocol::Ftp => "ftp",
+            Protocol::Ftps => "ftps",
             Protocol::Sftp => "sftp",
+            Protocol::Webdav => "webdav",
+            Protocol::Webdavs => "webdavs",
+            Protocol::S3 => "s3",
+            Protocol::Local => "local",
         }
     }
 
     pub fn from_str(s: &str) -> Result<Self, String> {
         match s {
             "ftp" => Ok(Protocol::Ftp),
+            "ftps" => Ok(Protocol::Ftps),
             "sftp" => Ok(Protocol::Sftp),
+            "webdav" => Ok(Protocol::Webdav),
+            "webdavs" => Ok(Protocol::Webdavs),
+            "s3" => Ok(Protocol::S3),
+            "local" => Ok(Protocol::Local),
             _ => Err(format!("Unknown protocol: {}", s)),
         }
     }
 }
 
+/// FTPS 的 TLS 建立方式，仅对 `Protocol::Ftps` 生效
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FtpsMode {
+    /// 先明文连接，再通过 AUTH TLS 升级（通常为 21 端口）
+    #[default]
+    #[serde(rename = "explicit")]
+    Explicit,
+    /// 建立 TCP 连接后立即进行 TLS 握手（通常为 990 端口）
+    #[serde(rename = "implicit")]
+    Implicit,
+}
+
+impl FtpsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FtpsMode::Explicit => "explicit",
+            FtpsMode::Implicit => "implicit",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "explicit" => Ok(FtpsMode::Explicit),
+            "implicit" => Ok(FtpsMode::Implicit),
+            _ => Err(format!("Unknown FTPS mode: {}", s)),
+        }
+    }
+}
+
+/// FTP 数据连接方式，对 FTP/FTPS 生效
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FtpDataMode {
+    /// PASV：服务器在应答中给出 IPv4 地址和端口，由客户端发起连接
+    #[default]
+    #[serde(rename = "passive")]
+    Passive,
+    /// EPSV（RFC 2428）：只给出端口，地址沿用控制连接，支持 IPv6
+    #[serde(rename = "epsv")]
+    ExtendedPassive,
+    /// PORT/EPRT：客户端监听端口，由服务器发起连接
+    #[serde(rename = "active")]
+    Active,
+}
+
+impl FtpDataMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FtpDataMode::Passive => "passive",
+            FtpDataMode::ExtendedPassive => "epsv",
+            FtpDataMode::Active => "active",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "passive" => Ok(FtpDataMode::Passive),
+            "epsv" => Ok(FtpDataMode::ExtendedPassive),
+            "active" => Ok(FtpDataMode::Active),
+            _ => Err(format!("Unknown FTP data mode: {}", s)),
+        }
+    }
+}
+
+/// SFTP 认证方式，按主机配置的顺序依次尝试
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SshAuthMethod {
+    /// 私钥文件（key_path），密码字段作为私钥口令
+    #[serde(rename = "key")]
+    Key,
+    /// ssh-agent（SSH_AUTH_SOCK）或 Windows 上的 Pageant 提供的身份
+    #[serde(rename = "agent")]
+    Agent,
+    #[serde(rename = "password")]
+    Password,
+    /// 键盘交互：服务器下发提示（如动态验证码），由用户在界面中回答
+    #[serde(rename = "keyboard-interactive")]
+    KeyboardInteractive,
+}
+
+impl SshAuthMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SshAuthMethod::Key => "key",
+            SshAuthMethod::Agent => "agent",
+            SshAuthMethod::Password => "password",
+            SshAuthMethod::KeyboardInteractive => "keyboard-interactive",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "key" => Ok(SshAuthMethod::Key),
+            "agent" => Ok(SshAuthMethod::Agent),
+            "password" => Ok(SshAuthMethod::Password),
+            "keyboard-interactive" => Ok(SshAuthMethod::KeyboardInteractive),
+            _ => Err(format!("Unknown SSH auth method: {}", s)),
+        }
+    }
+
+    /// 数据库中以逗号分隔保存，空串表示未配置
+    pub fn join(methods: &[SshAuthMethod]) -> String {
+        methods
+            .iter()
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn parse_list(s: &str) -> Result<Vec<SshAuthMethod>, String> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(SshAuthMethod::from_str)
+            .collect()
+    }
+}
+
+/// SSH 主机的文件传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SshTransferMode {
+    /// 优先使用 SFTP，服务器没有 SFTP 子系统时自动改用 SCP
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "sftp")]
+    Sftp,
+    /// SCP 传输文件，列目录等操作通过远程 shell 命令完成（如只有 Dropbear 的嵌入式设备）
+    #[serde(rename = "scp")]
+    Scp,
+}
+
+impl SshTransferMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SshTransferMode::Auto => "auto",
+            SshTransferMode::Sftp => "sftp",
+            SshTransferMode::Scp => "scp",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "auto" => Ok(SshTransferMode::Auto),
+            "sftp" => Ok(SshTransferMode::Sftp),
+            "scp" => Ok(SshTransferMode::Scp),
+            _ => Err(format!("Unknown SSH transfer mode: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Host {
     pub id: Option<i64>,
@@ -35,6 +211,46 @@ pub struct Host {
     pub username: String,
     pub password: Option<String>,
     pub key_path: Option<String>,
+    #[serde(default)]
+    pub ftps_mode: FtpsMode,
+    /// 服务器时区相对 UTC 的偏移（分钟，东正西负），用于换算 FTP LIST 中的本地时间
+    #[serde(default)]
+    pub timezone_offset: i32,
+    #[serde(default)]
+    pub ftp_data_mode: FtpDataMode,
+    /// 主动模式下 PORT 通告的地址（如 NAT 后的公网 IP），为空时使用本机地址
+    #[serde(default)]
+    pub ftp_active_address: Option<String>,
+    /// 主动模式监听端口范围，便于在防火墙上放行；为空时由系统分配
+    #[serde(default)]
+    pub ftp_active_port_min: Option<u16>,
+    #[serde(default)]
+    pub ftp_active_port_max: Option<u16>,
+    /// 被动模式忽略 PASV 应答中的地址（常见为内网 IP），改用控制连接的服务器地址
+    #[serde(default)]
+    pub ftp_ignore_pasv_address: bool,
+    /// SFTP 认证顺序；为空时沿用默认：有密钥用密钥、有密码用密码，都没有时使用 ssh-agent，
+    /// 之后服务器仍要求键盘交互（如验证码）时再向用户询问
+    #[serde(default)]
+    pub ssh_auth_order: Vec<SshAuthMethod>,
+    /// SFTP 跳板机（ProxyJump），引用另一台已保存的 SFTP 主机，跳板机自身也可以再配置跳板机
+    #[serde(default)]
+    pub jump_host_id: Option<i64>,
+    /// SSH 主机使用 SFTP 还是 SCP 传输
+    #[serde(default)]
+    pub ssh_transfer_mode: SshTransferMode,
+    /// 代理：跟随全局、直连或使用主机自定义代理
+    #[serde(default)]
+    pub proxy_mode: ProxyMode,
+    /// S3 签名使用的区域，为空时按 us-east-1 处理（MinIO 默认值）
+    #[serde(default)]
+    pub s3_region: Option<String>,
+    /// S3 存储桶，主机地址和端口为服务端点，以路径形式访问存储桶
+    #[serde(default)]
+    pub s3_bucket: Option<String>,
+    /// S3 端点使用 HTTP 而非 HTTPS，适用于内网未配置证书的 MinIO
+    #[serde(default)]
+    pub s3_use_http: bool,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
@@ -56,6 +272,20 @@ impl Host {
             username,
             password: None,
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: None,
             updated_at: None,
         }
@@ -69,10 +299,16 @@ mod tests {
     #[test]
     fn test_protocol_roundtrip() {
         assert_eq!(Protocol::from_str("ftp").unwrap(), Protocol::Ftp);
+        assert_eq!(Protocol::from_str("ftps").unwrap(), Protocol::Ftps);
         assert_eq!(Protocol::from_str("sftp").unwrap(), Protocol::Sftp);
+        assert_eq!(Protocol::from_str("webdavs").unwrap(), Protocol::Webdavs);
+        assert_eq!(Protocol::from_str("s3").unwrap(), Protocol::S3);
+        assert_eq!(Protocol::from_str("local").unwrap(), Protocol::Local);
         assert!(Protocol::from_str("http").is_err());
         assert_eq!(Protocol::Ftp.as_str(), "ftp");
+        assert_eq!(Protocol::Ftps.as_str(), "ftps");
         assert_eq!(Protocol::Sftp.as_str(), "sftp");
+        assert_eq!(Protocol::Webdav.as_str(), "webdav");
     }
 
     #[test]
@@ -81,6 +317,55 @@ mod tests {
         assert_eq!(json, "\"ftp\"");
         let parsed: Protocol = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, Protocol::Ftp);
+
+        let json = serde_json::to_string(&Protocol::Ftps).unwrap();
+        assert_eq!(json, "\"ftps\"");
+    }
+
+    #[test]
+    fn test_ftps_mode_roundtrip() {
+        assert_eq!(FtpsMode::from_str("explicit").unwrap(), FtpsMode::Explicit);
+        assert_eq!(FtpsMode::from_str("implicit").unwrap(), FtpsMode::Implicit);
+        assert!(FtpsMode::from_str("auto").is_err());
+        assert_eq!(FtpsMode::Implicit.as_str(), "implicit");
+    }
+
+    #[test]
+    fn test_ftp_data_mode_roundtrip() {
+        for mode in [FtpDataMode::Passive, FtpDataMode::ExtendedPassive, FtpDataMode::Active] {
+            assert_eq!(FtpDataMode::from_str(mode.as_str()).unwrap(), mode);
+        }
+        assert!(FtpDataMode::from_str("pasv").is_err());
+        let json = serde_json::to_string(&FtpDataMode::ExtendedPassive).unwrap();
+        assert_eq!(json, "\"epsv\"");
+    }
+
+    #[test]
+    fn test_ssh_auth_order_roundtrip() {
+        let order = vec![
+            SshAuthMethod::Agent,
+            SshAuthMethod::Key,
+            SshAuthMethod::Password,
+            SshAuthMethod::KeyboardInteractive,
+        ];
+        let joined = SshAuthMethod::join(&order);
+        assert_eq!(joined, "agent,key,password,keyboard-interactive");
+        assert_eq!(SshAuthMethod::parse_list(&joined).unwrap(), order);
+        assert!(SshAuthMethod::parse_list("").unwrap().is_empty());
+        assert!(SshAuthMethod::parse_list("agent,gssapi").is_err());
+    }
+
+    #[test]
+    fn test_host_ftps_mode_defaults_when_missing() {
+        let json = r#"{"id":null,"name":"n","host":"h","port":21,"protocol":"ftps",
+            "username":"u","password":null,"key_path":null,"created_at":null,"updated_at":null}"#;
+        let host: Host = serde_json::from_str(json).unwrap();
+        assert_eq!(host.ftps_mode, FtpsMode::Explicit);
+        assert_eq!(host.ftp_data_mode, FtpDataMode::Passive);
+        assert!(!host.ftp_ignore_pasv_address);
+        assert!(host.ssh_auth_order.is_empty());
+        assert_eq!(host.proxy_mode, ProxyMode::Global);
+        assert_eq!(host.ssh_transfer_mode, SshTransferMode::Auto);
     }
 
     #[test]
@@ -111,6 +396,20 @@ mod tests {
             username: "user".into(),
             password: Some("pass".into()),
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 480,
+            ftp_data_mode: FtpDataMode::Active,
+            ftp_active_address: Some("203.0.113.7".into()),
+            ftp_active_port_min: Some(50000),
+            ftp_active_port_max: Some(50100),
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: vec![SshAuthMethod::Agent, SshAuthMethod::Key],
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::Scp,
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: Some("2025-01-01 00:00:00".into()),
             updated_at: Some("2025-01-01 00:00:00".into()),
         };
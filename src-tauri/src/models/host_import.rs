@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use super::host::Host;
+
+/// 导入预览中的一条主机记录，前端勾选后原样提交给导入命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostImportEntry {
+    /// 来源中的名称，如 ssh_config 的 Host 别名、站点管理器中带文件夹的站点路径
+    pub source: String,
+    pub host: Host,
+    /// 跳板机是同一批导入中的另一条记录（按 source 引用），导入时换成新主机的 id
+    #[serde(default)]
+    pub jump_source: Option<String>,
+    /// 站点的默认远程目录和本地目录，导入后保存为该主机的目录书签
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+    #[serde(default)]
+    pub local_dir: Option<String>,
+    /// 与已保存主机的地址、端口、用户名、协议相同时为该主机的 id
+    #[serde(default)]
+    pub duplicate_of: Option<i64>,
+    /// 来源使用了本工具不支持的协议或认证方式，这类记录不能导入
+    #[serde(default)]
+    pub unsupported: Option<String>,
+    /// `validate_host` 未通过的原因，有错误的记录不能导入
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl HostImportEntry {
+    pub fn new(source: String, host: Host) -> Self {
+        Self {
+            source,
+            host,
+            jump_source: None,
+            remote_dir: None,
+            local_dir: None,
+            duplicate_of: None,
+            unsupported: None,
+            error: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HostImportStatus {
+    #[serde(rename = "imported")]
+    Imported,
+    #[serde(rename = "skipped")]
+    Skipped,
+    #[serde(rename = "unsupported")]
+    Unsupported,
+}
+
+/// 导入结果中的一条，与提交的记录一一对应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostImportResult {
+    pub source: String,
+    pub status: HostImportStatus,
+    /// 导入成功时为新主机的 id
+    pub host_id: Option<i64>,
+    /// 跳过或不支持的原因
+    pub message: Option<String>,
+}
+
+impl HostImportResult {
+    pub fn imported(source: String, host_id: Option<i64>) -> Self {
+        Self {
+            source,
+            status: HostImportStatus::Imported,
+            host_id,
+            message: None,
+        }
+    }
+
+    pub fn skipped(source: String, status: HostImportStatus, message: String) -> Self {
+        Self {
+            source,
+            status,
+            host_id: None,
+            message: Some(message),
+        }
+    }
+}
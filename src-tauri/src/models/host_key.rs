@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// SSH 服务器出示的主机密钥，供前端展示并由用户确认是否信任
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostKeyInfo {
+    pub host: String,
+    pub port: u16,
+    /// 密钥算法，如 ssh-ed25519、ecdsa-sha2-nistp256
+    pub key_type: String,
+    /// OpenSSH 格式的 SHA-256 指纹：`SHA256:` 加无填充的 base64
+    pub fingerprint: String,
+    /// base64 编码的公钥（SSH 线格式）
+    pub key: String,
+}
+
+/// 应用内保存的已信任主机密钥，与 ~/.ssh/known_hosts 一并用于校验
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownHostKey {
+    pub id: Option<i64>,
+    pub host: String,
+    pub port: u16,
+    pub key_type: String,
+    pub fingerprint: String,
+    pub key: String,
+    pub trusted_at: Option<String>,
+}
+
+impl KnownHostKey {
+    pub fn new(info: HostKeyInfo) -> Self {
+        Self {
+            id: None,
+            host: info.host,
+            port: info.port,
+            key_type: info.key_type,
+            fingerprint: info.fingerprint,
+            key: info.key,
+            trusted_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_host_key_new() {
+        let info = HostKeyInfo {
+            host: "sftp.example.com".into(),
+            port: 2222,
+            key_type: "ssh-ed25519".into(),
+            fingerprint: "SHA256:abc".into(),
+            key: "AAAA".into(),
+        };
+        let known = KnownHostKey::new(info);
+        assert_eq!(known.id, None);
+        assert_eq!(known.host, "sftp.example.com");
+        assert_eq!(known.port, 2222);
+        assert_eq!(known.key_type, "ssh-ed25519");
+        assert!(known.trusted_at.is_none());
+    }
+}
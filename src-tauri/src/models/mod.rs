@@ -1,3 +1,9 @@
 pub mod bookmark;
+pub mod certificate;
+pub mod file_op;
 pub mod host;
+pub mod host_import;
+pub mod host_key;
+pub mod port_forward;
+pub mod proxy;
 pub mod transfer;
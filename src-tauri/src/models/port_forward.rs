@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ForwardKind {
+    /// 本机监听，经 SSH 服务器连接目标（ssh -L）
+    #[serde(rename = "local")]
+    Local,
+    /// SSH 服务器监听，连接转回本机一侧的目标（ssh -R）
+    #[serde(rename = "remote")]
+    Remote,
+}
+
+impl ForwardKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForwardKind::Local => "local",
+            ForwardKind::Remote => "remote",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "local" => Ok(ForwardKind::Local),
+            "remote" => Ok(ForwardKind::Remote),
+            _ => Err(format!("Unknown forward kind: {}", s)),
+        }
+    }
+}
+
+/// 主机上保存的端口转发定义。bind 为监听地址（本地转发在本机，远程转发在服务器），
+/// target 为连接目标（本地转发由服务器连接，远程转发由本机连接）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortForward {
+    pub id: Option<i64>,
+    pub host_id: i64,
+    pub name: String,
+    pub kind: ForwardKind,
+    pub bind_host: String,
+    /// 远程转发可为 0，由服务器分配端口
+    pub bind_port: u16,
+    pub target_host: String,
+    pub target_port: u16,
+    pub created_at: Option<String>,
+}
+
+/// 转发的运行状态与流量统计，bytes_sent 为本机一侧发往对端的字节数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortForwardStatus {
+    pub forward_id: i64,
+    pub running: bool,
+    /// 实际监听的端口，远程转发 bind_port 为 0 时由服务器分配
+    pub bound_port: Option<u16>,
+    pub active_connections: u32,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_kind_roundtrip() {
+        for kind in [ForwardKind::Local, ForwardKind::Remote] {
+            assert_eq!(ForwardKind::from_str(kind.as_str()).unwrap(), kind);
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.as_str()));
+        }
+        assert!(ForwardKind::from_str("dynamic").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProxyType {
+    #[serde(rename = "socks5")]
+    Socks5,
+    /// HTTP CONNECT 隧道
+    #[serde(rename = "http")]
+    Http,
+}
+
+impl ProxyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyType::Socks5 => "socks5",
+            ProxyType::Http => "http",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "socks5" => Ok(ProxyType::Socks5),
+            "http" => Ok(ProxyType::Http),
+            _ => Err(format!("Unknown proxy type: {}", s)),
+        }
+    }
+}
+
+/// 代理服务器设置，全局设置与主机自定义设置共用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    pub proxy_type: ProxyType,
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// 主机使用哪一份代理设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ProxyMode {
+    /// 跟随全局代理，未配置全局代理时直连
+    #[default]
+    #[serde(rename = "global")]
+    Global,
+    /// 不使用代理，适合内网主机
+    #[serde(rename = "direct")]
+    Direct,
+    /// 使用该主机单独保存的代理
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+impl ProxyMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyMode::Global => "global",
+            ProxyMode::Direct => "direct",
+            ProxyMode::Custom => "custom",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "global" => Ok(ProxyMode::Global),
+            "direct" => Ok(ProxyMode::Direct),
+            "custom" => Ok(ProxyMode::Custom),
+            _ => Err(format!("Unknown proxy mode: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_type_roundtrip() {
+        for t in [ProxyType::Socks5, ProxyType::Http] {
+            assert_eq!(ProxyType::from_str(t.as_str()).unwrap(), t);
+        }
+        assert!(ProxyType::from_str("socks4").is_err());
+    }
+
+    #[test]
+    fn test_proxy_mode_roundtrip() {
+        for m in [ProxyMode::Global, ProxyMode::Direct, ProxyMode::Custom] {
+            assert_eq!(ProxyMode::from_str(m.as_str()).unwrap(), m);
+        }
+        assert_eq!(
+            serde_json::to_string(&ProxyMode::Direct).unwrap(),
+            "\"direct\""
+        );
+    }
+
+    #[test]
+    fn test_proxy_config_credentials_default() {
+        let json = r#"{"proxy_type":"http","host":"proxy.corp","port":3128}"#;
+        let config: ProxyConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.proxy_type, ProxyType::Http);
+        assert!(config.username.is_none());
+        assert!(config.password.is_none());
+    }
+}
@@ -6,6 +6,9 @@ pub enum TransferDirection {
     Upload,
     #[serde(rename = "download")]
     Download,
+    /// 主机间传输：host_id、remote_path 为源，dest_host_id、local_path 为目标主机及其上的路径
+    #[serde(rename = "remote")]
+    Remote,
 }
 
 impl TransferDirection {
@@ -13,6 +16,7 @@ impl TransferDirection {
         match self {
             TransferDirection::Upload => "upload",
             TransferDirection::Download => "download",
+            TransferDirection::Remote => "remote",
         }
     }
 
@@ -20,11 +24,27 @@ impl TransferDirection {
         match s {
             "upload" => Ok(TransferDirection::Upload),
             "download" => Ok(TransferDirection::Download),
+            "remote" => Ok(TransferDirection::Remote),
             _ => Err(format!("Unknown direction: {}", s)),
         }
     }
 }
 
+/// 目录传输遇到符号链接时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SymlinkPolicy {
+    /// 按链接目标传输内容，跳过指向自身祖先目录的循环链接
+    #[default]
+    #[serde(rename = "follow")]
+    Follow,
+    /// 忽略所有符号链接
+    #[serde(rename = "skip")]
+    Skip,
+    /// 在目标端重建同样的符号链接，不传输内容
+    #[serde(rename = "preserve")]
+    Preserve,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TransferStatus {
     #[serde(rename = "pending")]
@@ -76,6 +96,9 @@ pub struct TransferHistory {
     pub error_message: Option<String>,
     pub started_at: Option<String>,
     pub finished_at: Option<String>,
+    /// 主机间传输的目标主机
+    #[serde(default)]
+    pub dest_host_id: Option<i64>,
 }
 
 impl TransferHistory {
@@ -100,6 +123,7 @@ impl TransferHistory {
             error_message: None,
             started_at: Some(chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()),
             finished_at: None,
+            dest_host_id: None,
         }
     }
 }
@@ -116,6 +140,9 @@ pub struct ResumeRecord {
     pub transferred_bytes: u64,
     pub checksum: Option<String>,
     pub created_at: Option<String>,
+    /// 主机间传输的目标主机
+    #[serde(default)]
+    pub dest_host_id: Option<i64>,
 }
 
 impl ResumeRecord {
@@ -138,6 +165,7 @@ impl ResumeRecord {
             transferred_bytes: 0,
             checksum: None,
             created_at: None,
+            dest_host_id: None,
         }
     }
 }
@@ -167,6 +195,10 @@ mod tests {
             TransferDirection::from_str("download").unwrap(),
             TransferDirection::Download
         );
+        assert_eq!(
+            TransferDirection::from_str("remote").unwrap(),
+            TransferDirection::Remote
+        );
         assert!(TransferDirection::from_str("sync").is_err());
         assert_eq!(TransferDirection::Upload.as_str(), "upload");
         assert_eq!(TransferDirection::Download.as_str(), "download");
@@ -188,6 +220,14 @@ mod tests {
         assert!(TransferStatus::from_str("unknown").is_err());
     }
 
+    #[test]
+    fn test_symlink_policy_serde() {
+        let parsed: SymlinkPolicy = serde_json::from_str("\"preserve\"").unwrap();
+        assert_eq!(parsed, SymlinkPolicy::Preserve);
+        assert_eq!(serde_json::to_string(&SymlinkPolicy::Skip).unwrap(), "\"skip\"");
+        assert_eq!(SymlinkPolicy::default(), SymlinkPolicy::Follow);
+    }
+
     #[test]
     fn test_transfer_history_new() {
         let th = TransferHistory::new(
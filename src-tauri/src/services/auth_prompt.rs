@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 等待用户输入验证码的最长时间，超时按取消处理
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthPrompt {
+    pub text: String,
+    /// 为 false 时输入内容不应回显（密码、验证码）
+    pub echo: bool,
+}
+
+/// 发给前端的 `ssh-auth-prompt` 事件，前端通过 `respond_auth_prompt` 回答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthPromptRequest {
+    pub request_id: String,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub instructions: String,
+    pub prompts: Vec<AuthPrompt>,
+}
+
+/// 向用户索取键盘交互认证的回答，返回 None 表示用户取消或超时
+pub trait AuthPrompter: Send + Sync + std::fmt::Debug {
+    fn prompt(&self, request: AuthPromptRequest) -> Option<Vec<String>>;
+}
+
+/// 按请求 ID 等待前端回答的通道
+type PendingPrompts = HashMap<String, Sender<Option<Vec<String>>>>;
+
+/// 把 SSH 服务器的提示转发到前端，并在连接线程中阻塞等待回答
+#[derive(Clone, Default)]
+pub struct AuthPromptBroker {
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
+    pending: Arc<Mutex<PendingPrompts>>,
+}
+
+impl std::fmt::Debug for AuthPromptBroker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthPromptBroker").finish_non_exhaustive()
+    }
+}
+
+impl AuthPromptBroker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        let mut h = self.app_handle.lock().unwrap();
+        *h = Some(handle);
+    }
+
+    /// 前端提交回答；answers 为 None 表示用户取消
+    pub fn respond(&self, request_id: &str, answers: Option<Vec<String>>) -> Result<(), String> {
+        let sender = self
+            .pending
+            .lock()
+            .map_err(|e| e.to_string())?
+            .remove(request_id)
+            .ok_or_else(|| "认证请求不存在或已超时".to_string())?;
+        sender
+            .send(answers)
+            .map_err(|_| "认证请求已结束".to_string())
+    }
+
+    fn register(&self, request_id: &str) -> Option<Receiver<Option<Vec<String>>>> {
+        let (tx, rx) = mpsc::channel();
+        self.pending
+            .lock()
+            .ok()?
+            .insert(request_id.to_string(), tx);
+        Some(rx)
+    }
+
+    fn unregister(&self, request_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(request_id);
+        }
+    }
+}
+
+impl AuthPrompter for AuthPromptBroker {
+    fn prompt(&self, request: AuthPromptRequest) -> Option<Vec<String>> {
+        let rx = self.register(&request.request_id)?;
+        let emitted = self
+            .app_handle
+            .lock()
+            .ok()
+            .and_then(|h| {
+                h.as_ref()
+                    .map(|handle| handle.emit("ssh-auth-prompt", &request).is_ok())
+            })
+            .unwrap_or(false);
+        let answers = if emitted {
+            rx.recv_timeout(PROMPT_TIMEOUT).ok().flatten()
+        } else {
+            None
+        };
+        self.unregister(&request.request_id);
+        answers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> AuthPromptRequest {
+        AuthPromptRequest {
+            request_id: "req-1".into(),
+            host: "bastion".into(),
+            port: 22,
+            username: "ops".into(),
+            instructions: String::new(),
+            prompts: vec![AuthPrompt {
+                text: "Verification code: ".into(),
+                echo: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_respond_delivers_answers() {
+        let broker = AuthPromptBroker::new();
+        let rx = broker.register("req-1").unwrap();
+        broker.respond("req-1", Some(vec!["123456".into()])).unwrap();
+        assert_eq!(rx.recv().unwrap(), Some(vec!["123456".to_string()]));
+        // 每个请求只能回答一次
+        assert!(broker.respond("req-1", None).is_err());
+    }
+
+    #[test]
+    fn test_respond_unknown_request() {
+        let broker = AuthPromptBroker::new();
+        assert!(broker.respond("missing", Some(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_prompt_without_frontend_is_cancelled() {
+        let broker = AuthPromptBroker::new();
+        assert_eq!(broker.prompt(request()), None);
+        assert!(broker.pending.lock().unwrap().is_empty());
+    }
+}
@@ -1,11 +1,20 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::sync::{Arc, Mutex};
 
-use crate::models::host::{Host, Protocol};
+use crate::models::certificate::TrustedCertificate;
+use crate::models::host::{FtpsMode, Host, Protocol};
+use crate::models::host_key::{HostKeyInfo, KnownHostKey};
+use crate::models::proxy::ProxyConfig;
 
-use super::ftp_client::FtpClient;
+use super::auth_prompt::AuthPrompter;
+use super::ftp_client::{FtpClient, FtpDataConnection, FtpTls};
 use super::sftp_client::SftpClient;
+use super::local_client::LocalClient;
+use super::s3_client::S3Client;
+use super::webdav_client::WebdavClient;
 
 pub const CHUNK_SIZE: usize = 32768;
 
@@ -15,7 +24,28 @@ pub struct FileEntry {
     pub path: String,
     pub is_dir: bool,
     pub size: u64,
-    pub modified: Option<String>,
+    /// 最后修改时间，统一为 UTC，序列化为 RFC 3339
+    pub modified: Option<DateTime<Utc>>,
+    /// 服务器提供的权限描述：MLSD 的 perm 事实或 LIST 的权限列
+    #[serde(default)]
+    pub permissions: Option<String>,
+    /// 是否为符号链接；此时 is_dir 表示链接目标的类型，目标未知时为 false
+    #[serde(default)]
+    pub is_symlink: bool,
+    #[serde(default)]
+    pub link_target: Option<String>,
+    /// Unix 权限位（如 0o755），不含文件类型位
+    #[serde(default)]
+    pub mode: Option<u32>,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub gid: Option<u32>,
+    /// 属主、属组名称，仅部分服务器在列表中提供
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 pub trait ConnectionTrait: Send {
@@ -51,27 +81,284 @@ pub trait ConnectionTrait: Send {
     fn remove_file(&mut self, path: &str) -> Result<(), String>;
     fn remove_dir(&mut self, path: &str) -> Result<(), String>;
     fn rename(&mut self, from: &str, to: &str) -> Result<(), String>;
+    /// 在 path 处创建指向 target 的符号链接
+    fn create_symlink(&mut self, target: &str, path: &str) -> Result<(), String>;
+
+    fn stat(&mut self, path: &str) -> Result<FileEntry, String>;
+    /// 修改权限位，mode 为八进制权限（如 0o644）
+    fn set_permissions(&mut self, path: &str, mode: u32) -> Result<(), String>;
+    /// 修改属主/属组，传 None 的一方保持不变
+    fn set_owner(&mut self, path: &str, uid: Option<u32>, gid: Option<u32>)
+        -> Result<(), String>;
+
+    /// 已认证的 SSH 会话，供远程命令等在同一连接上另开通道；非 SSH 协议为 None
+    fn ssh_session(&self) -> Option<ssh2::Session> {
+        None
+    }
+
+    /// 主机间传输的读取端：从 offset 起把远程文件内容写入 writer，进度回调同 download。
+    /// 返回本次写入的字节数
+    fn download_to(
+        &mut self,
+        _remote_path: &str,
+        _writer: &mut dyn Write,
+        _offset: u64,
+        _progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        Err("该协议不支持主机间传输".to_string())
+    }
+
+    /// 主机间传输的写入端：reader 给出文件 offset 之后的内容，total 为完整文件大小；
+    /// offset > 0 时保留远程文件已有的前 offset 字节。返回本次写入的字节数
+    fn upload_from(
+        &mut self,
+        _reader: &mut dyn Read,
+        _remote_path: &str,
+        _offset: u64,
+        _total: u64,
+        _progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        Err("该协议不支持主机间传输".to_string())
+    }
+
+    /// upload_from 能否接在远程已有的部分文件之后续写
+    fn can_append(&self) -> bool {
+        false
+    }
+
+    /// 两台 FTP 服务器之间可用 FXP 直接传输，需要拿到各自的控制连接；非 FTP 为 None
+    fn as_ftp(&mut self) -> Option<&mut FtpClient> {
+        None
+    }
+
+    /// 在同一主机上复制单个文件，目标已存在时覆盖。
+    /// 默认经本地临时文件中转，支持服务器端复制的协议应覆盖此方法
+    fn copy(&mut self, from: &str, to: &str) -> Result<(), String> {
+        copy_via_temp_file(self, from, to)
+    }
 }
 
-fn create_client(host: &Host) -> Box<dyn ConnectionTrait> {
+/// 先把文件下载到本地临时文件，再上传到同一主机的目标路径。
+/// 同一条连接不能同时读写两个文件（如 FTP 的单个控制连接），因此不能像主机间传输那样边读边写
+pub(super) fn copy_via_temp_file<C: ConnectionTrait + ?Sized>(
+    conn: &mut C,
+    from: &str,
+    to: &str,
+) -> Result<(), String> {
+    let temp = std::env::temp_dir().join(format!("ftx-copy-{}", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let mut options = std::fs::File::options();
+        options.read(true).write(true).create_new(true);
+        // 临时文件只允许当前用户读写，其他用户看不到复制中的内容
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp).map_err(|e| e.to_string())?;
+        let total = conn.download_to(from, &mut file, 0, None)?;
+        file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+        conn.upload_from(&mut file, to, 0, total, None).map(|_| ())
+    })();
+    let _ = std::fs::remove_file(&temp);
+    result
+}
+
+/// 把下载数据写入本地文件或主机间传输的管道并汇报进度，返回本次写入的字节数
+pub fn copy_download(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    offset: u64,
+    total_size: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> std::io::Result<u64> {
+    let mut buf = [0u8; CHUNK_SIZE];
+    let mut transferred: u64 = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        transferred += n as u64;
+        if let Some(cb) = progress {
+            cb(offset + transferred, total_size);
+        }
+    }
+    Ok(transferred)
+}
+
+pub struct ProgressReader<'a, R: Read> {
+    pub inner: R,
+    pub transferred: u64,
+    pub total: u64,
+    pub callback: Option<&'a dyn Fn(u64, u64)>,
+}
+
+impl<'a, R: Read> Read for ProgressReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            self.transferred += n as u64;
+            if let Some(cb) = self.callback {
+                cb(self.transferred, self.total);
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// 对目录及其下所有条目依次执行 op，返回处理的条目数。
+/// 先处理目录自身再列出内容，授予权限时才能进入原本无权访问的目录；
+/// 符号链接本身不处理也不进入，避免修改到目录树以外的文件。
+pub fn apply_recursive(
+    conn: &mut dyn ConnectionTrait,
+    dir: &str,
+    op: &mut dyn FnMut(&mut dyn ConnectionTrait, &str) -> Result<(), String>,
+) -> Result<u64, String> {
+    op(conn, dir).map_err(|e| format!("{}: {}", dir, e))?;
+    let mut count = 1;
+    for entry in conn.list_dir(dir)? {
+        if entry.is_symlink || entry.name == "." || entry.name == ".." {
+            continue;
+        }
+        if entry.is_dir {
+            count += apply_recursive(conn, &entry.path, op)?;
+        } else {
+            op(conn, &entry.path).map_err(|e| format!("{}: {}", entry.path, e))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// 建立连接时需要、但不保存在 Host 上的附加信息，由命令层从数据库加载
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    /// FTPS 主机已固定的服务器证书
+    pub trusted_certificate: Option<TrustedCertificate>,
+    /// SFTP 服务器在应用内已信任的主机密钥
+    pub trusted_host_key: Option<KnownHostKey>,
+    /// SFTP 跳板机链，按连接顺序排列
+    pub jump_hosts: Vec<JumpHost>,
+    /// SFTP 键盘交互认证时向用户询问验证码等信息
+    pub auth_prompter: Option<Arc<dyn AuthPrompter>>,
+    /// 按主机代理模式解析出的代理，None 表示直连
+    pub proxy: Option<ProxyConfig>,
+}
+
+/// 跳板机及其已信任的主机密钥
+#[derive(Debug, Clone)]
+pub struct JumpHost {
+    pub host: Host,
+    pub trusted_host_key: Option<KnownHostKey>,
+}
+
+fn sftp_client(
+    host: &Host,
+    trusted_host_key: Option<KnownHostKey>,
+    options: &ConnectOptions,
+) -> SftpClient {
+    SftpClient::new(
+        host.host.clone(),
+        host.port,
+        host.username.clone(),
+        host.password.clone(),
+        host.key_path.clone(),
+    )
+    .with_auth_order(host.ssh_auth_order.clone())
+    .with_trusted_host_key(trusted_host_key)
+    .with_prompter(options.auth_prompter.clone())
+}
+
+/// 目标 SFTP 主机的客户端，带上跳板机链和代理
+fn sftp_chain_client(host: &Host, options: &ConnectOptions) -> SftpClient {
+    let jump_hosts = options
+        .jump_hosts
+        .iter()
+        .map(|j| sftp_client(&j.host, j.trusted_host_key.clone(), options))
+        .collect();
+    sftp_client(host, options.trusted_host_key.clone(), options)
+        .with_jump_hosts(jump_hosts)
+        .with_proxy(options.proxy.clone())
+}
+
+fn create_client(host: &Host, options: &ConnectOptions) -> Box<dyn ConnectionTrait> {
     match host.protocol {
-        Protocol::Ftp => Box::new(FtpClient::new(
-            host.host.clone(),
-            host.port,
-            host.username.clone(),
-            host.password.clone().unwrap_or_default(),
-        )),
-        Protocol::Sftp => Box::new(SftpClient::new(
-            host.host.clone(),
-            host.port,
-            host.username.clone(),
-            host.password.clone(),
-            host.key_path.clone(),
-        )),
+        Protocol::Ftp => Box::new(
+            FtpClient::new(
+                host.host.clone(),
+                host.port,
+                host.username.clone(),
+                host.password.clone().unwrap_or_default(),
+            )
+            .with_timezone_offset(host.timezone_offset)
+            .with_data_connection(ftp_data_connection(host))
+            .with_proxy(options.proxy.clone()),
+        ),
+        Protocol::Ftps => Box::new(
+            FtpClient::new(
+                host.host.clone(),
+                host.port,
+                host.username.clone(),
+                host.password.clone().unwrap_or_default(),
+            )
+            .with_tls(match host.ftps_mode {
+                FtpsMode::Explicit => FtpTls::Explicit,
+                FtpsMode::Implicit => FtpTls::Implicit,
+            })
+            .with_trusted_certificate(options.trusted_certificate.clone())
+            .with_timezone_offset(host.timezone_offset)
+            .with_data_connection(ftp_data_connection(host))
+            .with_proxy(options.proxy.clone()),
+        ),
+        Protocol::Sftp => Box::new(
+            sftp_chain_client(host, options).with_transfer_mode(host.ssh_transfer_mode),
+        ),
+        Protocol::Webdav | Protocol::Webdavs => Box::new(
+            WebdavClient::new(
+                host.host.clone(),
+                host.port,
+                host.username.clone(),
+                host.password.clone().unwrap_or_default(),
+            )
+            .with_tls(host.protocol == Protocol::Webdavs)
+            .with_trusted_certificate(options.trusted_certificate.clone())
+            .with_proxy(options.proxy.clone()),
+        ),
+        Protocol::S3 => Box::new(
+            S3Client::new(
+                host.host.clone(),
+                host.port,
+                host.s3_bucket.clone().unwrap_or_default(),
+                host.username.clone(),
+                host.password.clone().unwrap_or_default(),
+            )
+            .with_region(host.s3_region.clone())
+            .with_http(host.s3_use_http)
+            .with_proxy(options.proxy.clone()),
+        ),
+        Protocol::Local => Box::new(LocalClient::new(host.host.trim())),
+    }
+}
+
+/// 主机上的数据连接设置已在保存时校验，这里无法解析的地址按未设置处理
+fn ftp_data_connection(host: &Host) -> FtpDataConnection {
+    FtpDataConnection {
+        mode: host.ftp_data_mode,
+        active_address: host
+            .ftp_active_address
+            .as_deref()
+            .and_then(|a| a.trim().parse().ok()),
+        active_ports: match (host.ftp_active_port_min, host.ftp_active_port_max) {
+            (Some(min), Some(max)) if min <= max => Some(min..=max),
+            _ => None,
+        },
+        ignore_pasv_address: host.ftp_ignore_pasv_address,
     }
 }
 
-/// Thread-safe connection pool that manages active FTP/SFTP connections keyed by host ID.
+/// Thread-safe connection pool that manages active FTP/FTPS/SFTP/WebDAV/S3 connections keyed by host ID.
 /// Each connection is independently locked so operations on different hosts don't block each other.
 #[derive(Clone)]
 pub struct ConnectionManager {
@@ -85,7 +372,7 @@ impl ConnectionManager {
         }
     }
 
-    pub fn connect(&self, host: &Host) -> Result<(), String> {
+    pub fn connect(&self, host: &Host, options: &ConnectOptions) -> Result<(), String> {
         let host_id = host.id.ok_or("Host has no ID")?;
 
         {
@@ -95,7 +382,7 @@ impl ConnectionManager {
             }
         }
 
-        let mut client = create_client(host);
+        let mut client = create_client(host, options);
         client.connect()?;
 
         let mut conns = self.connections.lock().map_err(|e| e.to_string())?;
@@ -133,13 +420,18 @@ impl ConnectionManager {
             .unwrap_or(false)
     }
 
-    pub fn test_connection(host: &Host) -> Result<(), String> {
-        let mut client = create_client(host);
+    pub fn test_connection(host: &Host, options: &ConnectOptions) -> Result<(), String> {
+        let mut client = create_client(host, options);
         client.connect()?;
         client.disconnect()?;
         Ok(())
     }
 
+    /// 取回 SFTP 主机出示的主机密钥，与正式连接一样经代理和跳板机链
+    pub fn fetch_host_key(host: &Host, options: &ConnectOptions) -> Result<HostKeyInfo, String> {
+        sftp_chain_client(host, options).fetch_host_key()
+    }
+
     pub fn disconnect_all(&self) -> Result<(), String> {
         let clients: Vec<_> = {
             let mut conns = self.connections.lock().map_err(|e| e.to_string())?;
@@ -173,6 +465,7 @@ impl ConnectionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::host::{FtpDataMode, ProxyMode, SshTransferMode};
 
     struct MockClient {
         connected: bool,
@@ -216,6 +509,14 @@ mod tests {
                 is_dir: false,
                 size: 100,
                 modified: None,
+                permissions: None,
+                is_symlink: false,
+                link_target: None,
+                mode: None,
+                uid: None,
+                gid: None,
+                owner: None,
+                group: None,
             }])
         }
 
@@ -262,6 +563,23 @@ mod tests {
         fn rename(&mut self, _from: &str, _to: &str) -> Result<(), String> {
             Ok(())
         }
+        fn create_symlink(&mut self, _target: &str, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn stat(&mut self, _path: &str) -> Result<FileEntry, String> {
+            Err("not supported".to_string())
+        }
+        fn set_permissions(&mut self, _path: &str, _mode: u32) -> Result<(), String> {
+            Ok(())
+        }
+        fn set_owner(
+            &mut self,
+            _path: &str,
+            _uid: Option<u32>,
+            _gid: Option<u32>,
+        ) -> Result<(), String> {
+            Ok(())
+        }
     }
 
     #[test]
@@ -270,6 +588,35 @@ mod tests {
         assert!(manager.active_connections().unwrap().is_empty());
     }
 
+    #[test]
+    fn test_apply_recursive_visits_dir_then_entries() {
+        let mut client = MockClient::new(false);
+        client.connect().unwrap();
+        let mut visited = Vec::new();
+        let count = apply_recursive(&mut client, "/", &mut |_, path| {
+            visited.push(path.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(visited, vec!["/", "/test.txt"]);
+    }
+
+    #[test]
+    fn test_apply_recursive_reports_failed_path() {
+        let mut client = MockClient::new(false);
+        client.connect().unwrap();
+        let err = apply_recursive(&mut client, "/", &mut |_, path| {
+            if path == "/test.txt" {
+                Err("Permission denied".to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap_err();
+        assert_eq!(err, "/test.txt: Permission denied");
+    }
+
     #[test]
     fn test_insert_and_get_connection() {
         let manager = ConnectionManager::new();
@@ -320,11 +667,25 @@ mod tests {
             username: "user".into(),
             password: Some("pass".into()),
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: None,
             updated_at: None,
         };
 
-        assert!(manager.connect(&host).is_ok());
+        assert!(manager.connect(&host, &ConnectOptions::default()).is_ok());
         assert!(manager.is_connected(1));
     }
 
@@ -420,13 +781,143 @@ mod tests {
             username: "user".into(),
             password: Some("pass".into()),
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: None,
             updated_at: None,
         };
-        let client = create_client(&host);
+        let client = create_client(&host, &ConnectOptions::default());
         assert!(!client.is_connected());
     }
 
+    #[test]
+    fn test_create_client_ftps() {
+        let host = Host {
+            id: Some(3),
+            name: "test".into(),
+            host: "127.0.0.1".into(),
+            port: 21,
+            protocol: Protocol::Ftps,
+            username: "user".into(),
+            password: Some("pass".into()),
+            key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
+            created_at: None,
+            updated_at: None,
+        };
+        let client = create_client(&host, &ConnectOptions::default());
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_create_client_ftps_implicit() {
+        let mut host = Host::new(
+            "legacy".into(),
+            "127.0.0.1".into(),
+            990,
+            Protocol::Ftps,
+            "user".into(),
+        );
+        host.id = Some(4);
+        host.ftps_mode = FtpsMode::Implicit;
+        let client = create_client(&host, &ConnectOptions::default());
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_create_client_webdav() {
+        let mut host = Host::new(
+            "dav".into(),
+            "127.0.0.1".into(),
+            443,
+            Protocol::Webdavs,
+            "user".into(),
+        );
+        host.id = Some(5);
+        let client = create_client(&host, &ConnectOptions::default());
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_create_client_s3() {
+        let mut host = Host::new(
+            "minio".into(),
+            "127.0.0.1".into(),
+            9000,
+            Protocol::S3,
+            "AKID".into(),
+        );
+        host.id = Some(6);
+        host.s3_bucket = Some("backups".into());
+        host.s3_use_http = true;
+        let client = create_client(&host, &ConnectOptions::default());
+        assert!(!client.is_connected());
+    }
+
+    #[test]
+    fn test_connect_local_host() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hi").unwrap();
+        let mut host = Host::new(
+            "share".into(),
+            dir.path().to_string_lossy().to_string(),
+            0,
+            Protocol::Local,
+            String::new(),
+        );
+        host.id = Some(7);
+        let manager = ConnectionManager::new();
+        manager.connect(&host, &ConnectOptions::default()).unwrap();
+        let conn = manager.get_connection(7).unwrap();
+        let entries = conn.lock().unwrap().list_dir("/").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "/a.txt");
+    }
+
+    #[test]
+    fn test_ftp_data_connection_from_host() {
+        let mut host =
+            Host::new("nat".into(), "ftp.example.com".into(), 21, Protocol::Ftp, "u".into());
+        assert_eq!(ftp_data_connection(&host), FtpDataConnection::default());
+
+        host.ftp_data_mode = FtpDataMode::Active;
+        host.ftp_active_address = Some(" 203.0.113.7 ".into());
+        host.ftp_active_port_min = Some(50000);
+        host.ftp_active_port_max = Some(50010);
+        let data = ftp_data_connection(&host);
+        assert_eq!(data.mode, FtpDataMode::Active);
+        assert_eq!(data.active_address, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(data.active_ports, Some(50000..=50010));
+
+        host.ftp_active_port_max = None;
+        assert!(ftp_data_connection(&host).active_ports.is_none());
+    }
+
     #[test]
     fn test_create_client_sftp() {
         let host = Host {
@@ -438,10 +929,40 @@ mod tests {
             username: "user".into(),
             password: Some("pass".into()),
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: None,
             updated_at: None,
         };
-        let client = create_client(&host);
+        let client = create_client(&host, &ConnectOptions::default());
         assert!(!client.is_connected());
     }
+
+    #[test]
+    fn test_copy_via_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = LocalClient::new(dir.path());
+        client.connect().unwrap();
+        std::fs::write(dir.path().join("a.txt"), b"copied through temp").unwrap();
+
+        copy_via_temp_file(&mut client, "/a.txt", "/b.txt").unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join("b.txt")).unwrap(),
+            b"copied through temp"
+        );
+        assert!(copy_via_temp_file(&mut client, "/missing", "/c.txt").is_err());
+        assert!(!dir.path().join("c.txt").exists());
+    }
 }
@@ -0,0 +1,472 @@
+//! 同一主机内的批量复制与移动。每项单独处理并记录结果，一项失败不影响其余各项；
+//! 目标目录中已有同名条目时按冲突策略处理
+
+use super::connection::{ConnectionTrait, FileEntry};
+use crate::models::file_op::{ConflictPolicy, FileOpResult};
+
+/// 按冲突策略改名时最多尝试的序号
+const MAX_RENAME_ATTEMPTS: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    Copy,
+    Move,
+}
+
+/// 把 paths 中的文件或目录复制到 dest_dir 下，目录连同其内容一起复制
+pub fn copy_items(
+    conn: &mut dyn ConnectionTrait,
+    paths: &[String],
+    dest_dir: &str,
+    policy: ConflictPolicy,
+) -> Vec<FileOpResult> {
+    run_batch(conn, paths, dest_dir, policy, Operation::Copy)
+}
+
+/// 把 paths 中的文件或目录移动到 dest_dir 下
+pub fn move_items(
+    conn: &mut dyn ConnectionTrait,
+    paths: &[String],
+    dest_dir: &str,
+    policy: ConflictPolicy,
+) -> Vec<FileOpResult> {
+    run_batch(conn, paths, dest_dir, policy, Operation::Move)
+}
+
+fn run_batch(
+    conn: &mut dyn ConnectionTrait,
+    paths: &[String],
+    dest_dir: &str,
+    policy: ConflictPolicy,
+    operation: Operation,
+) -> Vec<FileOpResult> {
+    paths
+        .iter()
+        .map(
+            |path| match process(conn, path, dest_dir, policy, operation) {
+                Ok(Some(target)) => FileOpResult::done(path.clone(), target),
+                Ok(None) => FileOpResult::skipped(path.clone()),
+                Err(e) => FileOpResult::failed(path.clone(), e),
+            },
+        )
+        .collect()
+}
+
+fn child_path(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+fn is_same_or_ancestor(ancestor: &str, path: &str) -> bool {
+    path == ancestor
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 处理一项，返回实际写入的路径；被跳过时返回 None
+fn process(
+    conn: &mut dyn ConnectionTrait,
+    source: &str,
+    dest_dir: &str,
+    policy: ConflictPolicy,
+    operation: Operation,
+) -> Result<Option<String>, String> {
+    let source = source.trim_end_matches('/');
+    let name = match source.rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => name,
+        _ => return Err("不能复制或移动根目录".to_string()),
+    };
+    let entry = conn.stat(source)?;
+    let source_is_dir = entry.is_dir && !entry.is_symlink;
+    if source_is_dir && is_same_or_ancestor(source, dest_dir.trim_end_matches('/')) {
+        return Err("不能复制或移动到自身或其子目录".to_string());
+    }
+
+    let mut target = child_path(dest_dir, name);
+    let mut replaced = None;
+    if conn.file_exists(&target)? {
+        if target == source && operation == Operation::Move {
+            // 已在目标目录中，无需移动
+            return Ok(None);
+        }
+        match policy {
+            ConflictPolicy::Skip => return Ok(None),
+            ConflictPolicy::Rename => target = unused_name(conn, dest_dir, name, source_is_dir)?,
+            ConflictPolicy::Overwrite => {
+                if target == source {
+                    return Err("源与目标相同".to_string());
+                }
+                let existing = conn.stat(&target)?;
+                if source_is_dir || (existing.is_dir && !existing.is_symlink) {
+                    return Err("目标已存在同名条目，只有文件可以覆盖".to_string());
+                }
+                // 先把目标改名留作备份，成功后删除、失败时还原；
+                // 目标是符号链接时改名的是链接本身，复制不会写到链接指向的文件
+                replaced = Some(set_aside(conn, dest_dir, name, &target)?);
+            }
+        }
+    }
+
+    let result = match operation {
+        Operation::Move => conn.rename(source, &target),
+        Operation::Copy => copy_entry(conn, &entry, source, &target),
+    };
+    if let Some(backup) = replaced {
+        match result {
+            Ok(()) => {
+                let _ = conn.remove_file(&backup);
+            }
+            Err(e) => {
+                // 复制失败时目标处可能留有写了一半的文件，还原前先删除
+                if operation == Operation::Copy {
+                    let _ = conn.remove_file(&target);
+                }
+                return Err(match conn.rename(&backup, &target) {
+                    Ok(()) => e,
+                    Err(restore) => {
+                        format!("{}；原文件已改名为 {}，还原失败: {}", e, backup, restore)
+                    }
+                });
+            }
+        }
+    }
+    result.map(|_| Some(target))
+}
+
+/// 把将被覆盖的目标改为目标目录中的隐藏备份名，返回备份路径
+fn set_aside(
+    conn: &mut dyn ConnectionTrait,
+    dest_dir: &str,
+    name: &str,
+    target: &str,
+) -> Result<String, String> {
+    for n in 1..=MAX_RENAME_ATTEMPTS {
+        let backup = child_path(dest_dir, &format!(".{}.ftx-old-{}", name, n));
+        if !conn.file_exists(&backup)? {
+            conn.rename(target, &backup)?;
+            return Ok(backup);
+        }
+    }
+    Err(format!("目标目录中找不到可用的备份名称: {}", name))
+}
+
+/// 生成目标目录中未被占用的名称：文件在扩展名前加序号，目录直接在末尾加序号
+fn unused_name(
+    conn: &mut dyn ConnectionTrait,
+    dest_dir: &str,
+    name: &str,
+    is_dir: bool,
+) -> Result<String, String> {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !is_dir && !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    for n in 1..=MAX_RENAME_ATTEMPTS {
+        let candidate = child_path(dest_dir, &format!("{} ({}){}", stem, n, ext));
+        if !conn.file_exists(&candidate)? {
+            return Ok(candidate);
+        }
+    }
+    Err(format!("目标目录中找不到可用的名称: {}", name))
+}
+
+/// 复制一个条目；目录逐层重建后复制其中的文件，符号链接在目标处重建而不复制链接目标
+fn copy_entry(
+    conn: &mut dyn ConnectionTrait,
+    entry: &FileEntry,
+    from: &str,
+    to: &str,
+) -> Result<(), String> {
+    if let (true, Some(link_target)) = (entry.is_symlink, &entry.link_target) {
+        return conn
+            .create_symlink(link_target, to)
+            .map_err(|e| format!("{}: {}", from, e));
+    }
+    if !entry.is_dir {
+        return conn.copy(from, to).map_err(|e| format!("{}: {}", from, e));
+    }
+    conn.mkdir(to).map_err(|e| format!("{}: {}", to, e))?;
+    for child in conn.list_dir(from)? {
+        if child.name == "." || child.name == ".." {
+            continue;
+        }
+        copy_entry(conn, &child, &child.path, &child_path(to, &child.name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::file_op::FileOpStatus;
+    use crate::services::local_client::LocalClient;
+
+    fn connected_client(root: &std::path::Path) -> LocalClient {
+        let mut client = LocalClient::new(root);
+        client.connect().unwrap();
+        client
+    }
+
+    /// 委托给 LocalClient，对 broken 路径的存在性检查、复制和改名返回错误
+    struct FaultyClient {
+        inner: LocalClient,
+        broken: String,
+    }
+
+    impl FaultyClient {
+        fn check(&self, path: &str) -> Result<(), String> {
+            if path == self.broken {
+                return Err(format!("I/O error: {}", path));
+            }
+            Ok(())
+        }
+    }
+
+    impl ConnectionTrait for FaultyClient {
+        fn connect(&mut self) -> Result<(), String> {
+            self.inner.connect()
+        }
+        fn disconnect(&mut self) -> Result<(), String> {
+            self.inner.disconnect()
+        }
+        fn is_connected(&self) -> bool {
+            self.inner.is_connected()
+        }
+        fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+            self.inner.list_dir(path)
+        }
+        fn file_size(&mut self, path: &str) -> Result<u64, String> {
+            self.inner.file_size(path)
+        }
+        fn file_exists(&mut self, path: &str) -> Result<bool, String> {
+            self.check(path)?;
+            self.inner.file_exists(path)
+        }
+        fn upload(
+            &mut self,
+            local_path: &str,
+            remote_path: &str,
+            offset: u64,
+            progress: Option<&dyn Fn(u64, u64)>,
+        ) -> Result<u64, String> {
+            self.inner.upload(local_path, remote_path, offset, progress)
+        }
+        fn download(
+            &mut self,
+            remote_path: &str,
+            local_path: &str,
+            offset: u64,
+            progress: Option<&dyn Fn(u64, u64)>,
+        ) -> Result<u64, String> {
+            self.inner
+                .download(remote_path, local_path, offset, progress)
+        }
+        fn mkdir(&mut self, path: &str) -> Result<(), String> {
+            self.inner.mkdir(path)
+        }
+        fn remove_file(&mut self, path: &str) -> Result<(), String> {
+            self.inner.remove_file(path)
+        }
+        fn remove_dir(&mut self, path: &str) -> Result<(), String> {
+            self.inner.remove_dir(path)
+        }
+        fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+            self.check(from)?;
+            self.inner.rename(from, to)
+        }
+        fn create_symlink(&mut self, target: &str, path: &str) -> Result<(), String> {
+            self.inner.create_symlink(target, path)
+        }
+        fn stat(&mut self, path: &str) -> Result<FileEntry, String> {
+            self.inner.stat(path)
+        }
+        fn set_permissions(&mut self, path: &str, mode: u32) -> Result<(), String> {
+            self.inner.set_permissions(path, mode)
+        }
+        fn set_owner(
+            &mut self,
+            path: &str,
+            uid: Option<u32>,
+            gid: Option<u32>,
+        ) -> Result<(), String> {
+            self.inner.set_owner(path, uid, gid)
+        }
+        /// 出错前先在目标处写入部分内容，模拟中途断开的复制
+        fn copy(&mut self, from: &str, to: &str) -> Result<(), String> {
+            if from == self.broken {
+                self.inner
+                    .upload_from(&mut &b"partial"[..], to, 0, 7, None)?;
+            }
+            self.check(from)?;
+            self.inner.copy(from, to)
+        }
+    }
+
+    #[test]
+    fn test_failed_overwrite_keeps_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("dst")).unwrap();
+        std::fs::write(root.join("src/a.txt"), "new").unwrap();
+        std::fs::write(root.join("dst/a.txt"), "old").unwrap();
+        let mut client = FaultyClient {
+            inner: connected_client(root),
+            broken: "/src/a.txt".to_string(),
+        };
+        let paths = vec!["/src/a.txt".to_string()];
+
+        let results = copy_items(&mut client, &paths, "/dst", ConflictPolicy::Overwrite);
+        assert_eq!(results[0].status, FileOpStatus::Failed);
+        let results = move_items(&mut client, &paths, "/dst", ConflictPolicy::Overwrite);
+        assert_eq!(results[0].status, FileOpStatus::Failed);
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/a.txt")).unwrap(),
+            "old"
+        );
+        assert_eq!(std::fs::read_dir(root.join("dst")).unwrap().count(), 1);
+        assert!(root.join("src/a.txt").exists());
+
+        // 无法判断目标是否存在时该项失败，不当作不存在
+        client.broken = "/dst/a.txt".to_string();
+        let results = copy_items(&mut client, &paths, "/dst", ConflictPolicy::Skip);
+        assert_eq!(results[0].status, FileOpStatus::Failed);
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/a.txt")).unwrap(),
+            "old"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_overwrite_replaces_symlink_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("dst")).unwrap();
+        std::fs::write(root.join("src/a.txt"), "new").unwrap();
+        std::fs::write(root.join("outside.txt"), "outside").unwrap();
+        std::os::unix::fs::symlink("../outside.txt", root.join("dst/a.txt")).unwrap();
+        let mut client = connected_client(root);
+
+        let paths = vec!["/src/a.txt".to_string()];
+        let results = copy_items(&mut client, &paths, "/dst", ConflictPolicy::Overwrite);
+        assert_eq!(results[0].status, FileOpStatus::Done);
+        // 替换链接本身，链接指向的文件保持不变
+        let meta = std::fs::symlink_metadata(root.join("dst/a.txt")).unwrap();
+        assert!(meta.file_type().is_file());
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/a.txt")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("outside.txt")).unwrap(),
+            "outside"
+        );
+        assert_eq!(std::fs::read_dir(root.join("dst")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_copy_items_with_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/site/css")).unwrap();
+        std::fs::write(root.join("src/site/index.html"), "<html>").unwrap();
+        std::fs::write(root.join("src/site/css/main.css"), "body{}").unwrap();
+        std::fs::write(root.join("src/notes.txt"), "new").unwrap();
+        std::fs::create_dir(root.join("dst")).unwrap();
+        std::fs::write(root.join("dst/notes.txt"), "old").unwrap();
+        let mut client = connected_client(root);
+
+        let paths = vec![
+            "/src/site".to_string(),
+            "/src/notes.txt".to_string(),
+            "/src/missing".to_string(),
+        ];
+        let results = copy_items(&mut client, &paths, "/dst", ConflictPolicy::Skip);
+        assert_eq!(results[0].status, FileOpStatus::Done);
+        assert_eq!(results[0].target.as_deref(), Some("/dst/site"));
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/site/css/main.css")).unwrap(),
+            "body{}"
+        );
+        assert_eq!(results[1].status, FileOpStatus::Skipped);
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/notes.txt")).unwrap(),
+            "old"
+        );
+        assert_eq!(results[2].status, FileOpStatus::Failed);
+        assert!(results[2].error.is_some());
+
+        let results = copy_items(&mut client, &paths[..2], "/dst", ConflictPolicy::Rename);
+        assert_eq!(results[0].target.as_deref(), Some("/dst/site (1)"));
+        assert_eq!(results[1].target.as_deref(), Some("/dst/notes (1).txt"));
+        assert!(root.join("dst/site (1)/index.html").exists());
+
+        let results = copy_items(&mut client, &paths[..2], "/dst", ConflictPolicy::Overwrite);
+        assert_eq!(results[0].status, FileOpStatus::Failed);
+        assert_eq!(results[1].status, FileOpStatus::Done);
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/notes.txt")).unwrap(),
+            "new"
+        );
+        assert!(root.join("src/notes.txt").exists());
+
+        // 在同一目录中复制出副本
+        let results = copy_items(
+            &mut client,
+            &["/src/notes.txt".to_string()],
+            "/src",
+            ConflictPolicy::Rename,
+        );
+        assert_eq!(results[0].target.as_deref(), Some("/src/notes (1).txt"));
+    }
+
+    #[test]
+    fn test_move_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("a/inner")).unwrap();
+        std::fs::write(root.join("a.txt"), "one").unwrap();
+        std::fs::write(root.join("b.txt"), "two").unwrap();
+        std::fs::create_dir(root.join("dst")).unwrap();
+        std::fs::write(root.join("dst/b.txt"), "old").unwrap();
+        let mut client = connected_client(root);
+
+        let paths = vec!["/a.txt".to_string(), "/b.txt".to_string(), "/a".to_string()];
+        let results = move_items(&mut client, &paths, "/dst", ConflictPolicy::Overwrite);
+        assert!(results.iter().all(|r| r.status == FileOpStatus::Done));
+        assert!(!root.join("a.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join("dst/b.txt")).unwrap(),
+            "two"
+        );
+        assert!(root.join("dst/a/inner").is_dir());
+
+        let results = move_items(
+            &mut client,
+            &[
+                "/dst/a".to_string(),
+                "/dst/a.txt".to_string(),
+                "/".to_string(),
+            ],
+            "/dst/a/inner",
+            ConflictPolicy::Skip,
+        );
+        assert_eq!(results[0].status, FileOpStatus::Failed);
+        assert!(results[0].error.as_deref().unwrap().contains("子目录"));
+        assert_eq!(results[1].status, FileOpStatus::Done);
+        assert!(root.join("dst/a/inner/a.txt").exists());
+        assert_eq!(results[2].status, FileOpStatus::Failed);
+
+        // 已在目标目录中的条目原样跳过
+        let results = move_items(
+            &mut client,
+            &["/dst/b.txt".to_string()],
+            "/dst/",
+            ConflictPolicy::Rename,
+        );
+        assert_eq!(results[0].status, FileOpStatus::Skipped);
+        assert!(root.join("dst/b.txt").exists());
+    }
+}
@@ -0,0 +1,311 @@
+//! FileZilla 站点管理器（sitemanager.xml）解析
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+use crate::models::host::{FtpDataMode, FtpsMode, Host, Protocol};
+use crate::models::host_import::HostImportEntry;
+
+type Node<'a, 'input> = roxmltree::Node<'a, 'input>;
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|c| c.is_element() && c.tag_name().name() == name)
+}
+
+/// 元素自身的文本，不含子元素；文件夹名与子元素混排在一起
+fn own_text(node: Node) -> String {
+    node.children()
+        .filter(|c| c.is_text())
+        .filter_map(|c| c.text())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name).map(own_text).filter(|t| !t.is_empty())
+}
+
+/// FileZilla 的远程路径编码：`类型 前缀长度 [前缀] (段长度 段名)*`，段名可含空格
+fn decode_remote_dir(encoded: &str) -> Option<String> {
+    fn take_number(s: &str) -> Option<(usize, &str)> {
+        let s = s.trim_start();
+        let end = s.find(' ').unwrap_or(s.len());
+        Some((s[..end].parse().ok()?, &s[end..]))
+    }
+    fn take_chars(s: &str, n: usize) -> Option<(String, &str)> {
+        let s = s.strip_prefix(' ')?;
+        let end = s.char_indices().nth(n).map_or(s.len(), |(i, _)| i);
+        (s[..end].chars().count() == n).then(|| (s[..end].to_string(), &s[end..]))
+    }
+    let (_server_type, rest) = take_number(encoded)?;
+    let (prefix_len, mut rest) = take_number(rest)?;
+    let mut prefix = String::new();
+    if prefix_len > 0 {
+        (prefix, rest) = take_chars(rest, prefix_len)?;
+    }
+    let mut segments = Vec::new();
+    while !rest.trim().is_empty() {
+        let (len, after) = take_number(rest)?;
+        let (segment, after) = take_chars(after, len)?;
+        segments.push(segment);
+        rest = after;
+    }
+    Some(format!("{}/{}", prefix, segments.join("/")))
+}
+
+/// 解析 sitemanager.xml，文件夹层级拼入 source，如 `客户/生产/web`
+pub fn parse_site_manager(xml: &str) -> Result<Vec<HostImportEntry>, String> {
+    let doc = roxmltree::Document::parse(xml.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("无法解析站点管理器文件: {}", e))?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "FileZilla3" {
+        return Err("不是 FileZilla 站点管理器文件".to_string());
+    }
+    let servers = child(root, "Servers").ok_or("站点管理器文件中没有 Servers 节点")?;
+    let mut entries = Vec::new();
+    collect_sites(servers, "", &mut entries);
+    Ok(entries)
+}
+
+fn collect_sites(folder: Node, path: &str, entries: &mut Vec<HostImportEntry>) {
+    for node in folder.children().filter(|c| c.is_element()) {
+        match node.tag_name().name() {
+            "Folder" => {
+                let name = own_text(node);
+                collect_sites(node, &format!("{}{}/", path, name), entries);
+            }
+            "Server" => entries.push(site_entry(node, path)),
+            _ => {}
+        }
+    }
+}
+
+fn site_entry(server: Node, path: &str) -> HostImportEntry {
+    let address = child_text(server, "Host").unwrap_or_default();
+    let name = child_text(server, "Name")
+        .or_else(|| Some(own_text(server)).filter(|t| !t.is_empty()))
+        .unwrap_or_else(|| address.clone());
+    let protocol_code: u32 = child_text(server, "Protocol")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(0);
+    let (protocol, ftps_mode) = match protocol_code {
+        0 | 6 => (Some(Protocol::Ftp), FtpsMode::Explicit),
+        1 => (Some(Protocol::Sftp), FtpsMode::Explicit),
+        3 => (Some(Protocol::Ftps), FtpsMode::Implicit),
+        4 => (Some(Protocol::Ftps), FtpsMode::Explicit),
+        _ => (None, FtpsMode::Explicit),
+    };
+    let default_port = match (&protocol, ftps_mode) {
+        (Some(Protocol::Sftp), _) => 22,
+        (Some(Protocol::Ftps), FtpsMode::Implicit) => 990,
+        _ => 21,
+    };
+    let port = child_text(server, "Port")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(default_port);
+    let logon_type: u32 = child_text(server, "Logontype")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(1);
+    let username = match logon_type {
+        0 => "anonymous".to_string(),
+        _ => child_text(server, "User").unwrap_or_default(),
+    };
+
+    let mut host = Host::new(
+        name.clone(),
+        address,
+        port,
+        protocol.clone().unwrap_or(Protocol::Ftp),
+        username,
+    );
+    host.ftps_mode = ftps_mode;
+    let mut entry = HostImportEntry::new(format!("{}{}", path, name), host);
+    let Some(protocol) = protocol else {
+        entry.unsupported = Some(format!(
+            "不支持该协议（FileZilla 协议编号 {}）",
+            protocol_code
+        ));
+        return entry;
+    };
+
+    match logon_type {
+        0 => entry.host.password = Some("anonymous@".to_string()),
+        1 | 4 => {
+            entry.host.password = site_password(server, &mut entry.warnings);
+            if logon_type == 4 {
+                entry.warnings.push("未导入 FTP Account 字段".to_string());
+            }
+        }
+        2 | 3 => entry
+            .warnings
+            .push("FileZilla 中设置为每次询问密码，导入后请填写密码".to_string()),
+        5 if protocol == Protocol::Sftp => {
+            entry.host.key_path = child_text(server, "Keyfile");
+        }
+        _ => {
+            entry.unsupported = Some(format!(
+                "不支持该登录类型（FileZilla 登录类型 {}）",
+                logon_type
+            ));
+            return entry;
+        }
+    }
+
+    if protocol != Protocol::Sftp {
+        if child_text(server, "PasvMode").as_deref() == Some("MODE_ACTIVE") {
+            entry.host.ftp_data_mode = FtpDataMode::Active;
+        }
+        if let Some(offset) = child_text(server, "TimezoneOffset").and_then(|t| t.parse().ok()) {
+            entry.host.timezone_offset = offset;
+        }
+    }
+    entry.remote_dir = child_text(server, "RemoteDir").and_then(|d| decode_remote_dir(&d));
+    entry.local_dir = child_text(server, "LocalDir");
+    entry
+}
+
+fn site_password(server: Node, warnings: &mut Vec<String>) -> Option<String> {
+    let pass = child(server, "Pass")?;
+    let text = own_text(pass);
+    match pass.attribute("encoding") {
+        Some("base64") => match BASE64
+            .decode(&text)
+            .ok()
+            .and_then(|b| String::from_utf8(b).ok())
+        {
+            Some(p) => Some(p),
+            None => {
+                warnings.push("密码无法解码，导入后请重新填写".to_string());
+                None
+            }
+        },
+        Some("crypt") => {
+            warnings.push("密码已用 FileZilla 主密码加密，导入后请重新填写".to_string());
+            None
+        }
+        _ => Some(text).filter(|t| !t.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SITES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<FileZilla3 version="3.66.4" platform="windows">
+    <Servers>
+        <Server>
+            <Host>ftp.example.com</Host>
+            <Port>2121</Port>
+            <Protocol>0</Protocol>
+            <Type>0</Type>
+            <User>bob</User>
+            <Pass encoding="base64">cEA1czx3MHJk</Pass>
+            <Logontype>1</Logontype>
+            <PasvMode>MODE_ACTIVE</PasvMode>
+            <TimezoneOffset>-300</TimezoneOffset>
+            <Name>Tom &amp; Bob</Name>
+            <RemoteDir>1 0 3 var 7 my site</RemoteDir>
+            <LocalDir>C:\Users\bob</LocalDir>
+        </Server>
+        <Folder expanded="1">客户
+            <Folder>生产
+                <Server>
+                    <Host>10.0.0.8</Host>
+                    <Protocol>1</Protocol>
+                    <User>deploy</User>
+                    <Logontype>5</Logontype>
+                    <Keyfile>/home/me/.ssh/id_ed25519</Keyfile>
+                    <Name>web</Name>
+                </Server>
+            </Folder>
+            <Server>
+                <Host>secure.example.com</Host>
+                <Protocol>3</Protocol>
+                <User>carol</User>
+                <Pass encoding="crypt" pubkey="abc">xyz</Pass>
+                <Logontype>1</Logontype>
+                <Name>vault</Name>
+            </Server>
+            <Server>
+                <Host>bucket.s3.amazonaws.com</Host>
+                <Protocol>7</Protocol>
+                <Logontype>1</Logontype>
+                <Name>s3</Name>
+            </Server>
+        </Folder>
+    </Servers>
+</FileZilla3>
+"#;
+
+    #[test]
+    fn test_parse_site_manager() {
+        let entries = parse_site_manager(SITES).unwrap();
+        let sources: Vec<&str> = entries.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            vec!["Tom & Bob", "客户/生产/web", "客户/vault", "客户/s3"]
+        );
+
+        let ftp = &entries[0];
+        assert_eq!(ftp.host.protocol, Protocol::Ftp);
+        assert_eq!(ftp.host.port, 2121);
+        assert_eq!(ftp.host.password.as_deref(), Some("p@5s<w0rd"));
+        assert_eq!(ftp.host.ftp_data_mode, FtpDataMode::Active);
+        assert_eq!(ftp.host.timezone_offset, -300);
+        assert_eq!(ftp.remote_dir.as_deref(), Some("/var/my site"));
+        assert_eq!(ftp.local_dir.as_deref(), Some(r"C:\Users\bob"));
+
+        let sftp = &entries[1];
+        assert_eq!(sftp.host.protocol, Protocol::Sftp);
+        assert_eq!(sftp.host.port, 22);
+        assert_eq!(
+            sftp.host.key_path.as_deref(),
+            Some("/home/me/.ssh/id_ed25519")
+        );
+
+        let ftps = &entries[2];
+        assert_eq!(ftps.host.protocol, Protocol::Ftps);
+        assert_eq!(ftps.host.ftps_mode, FtpsMode::Implicit);
+        assert_eq!(ftps.host.port, 990);
+        assert_eq!(ftps.host.password, None);
+        assert_eq!(ftps.warnings.len(), 1);
+
+        assert!(entries[3].unsupported.is_some());
+    }
+
+    #[test]
+    fn test_parse_site_manager_rejects_other_xml() {
+        assert!(parse_site_manager("<Settings></Settings>").is_err());
+        assert!(parse_site_manager("<FileZilla3><Servers>").is_err());
+    }
+
+    #[test]
+    fn test_parse_site_manager_cdata_and_entities() {
+        let xml = r#"<FileZilla3><Servers>
+            <Folder expanded='1'>A &#x26; B<Server>
+                <Host>h.example.com</Host>
+                <Protocol>0</Protocol>
+                <User><![CDATA[dev<ops>]]></User>
+                <Pass encoding='plain'>a&#60;b&gt;c</Pass>
+                <Logontype>1</Logontype>
+                <Name>ci &apos;main&apos;</Name>
+            </Server></Folder>
+        </Servers></FileZilla3>"#;
+        let entries = parse_site_manager(xml).unwrap();
+        assert_eq!(entries[0].source, "A & B/ci 'main'");
+        assert_eq!(entries[0].host.username, "dev<ops>");
+        assert_eq!(entries[0].host.password.as_deref(), Some("a<b>c"));
+    }
+
+    #[test]
+    fn test_decode_remote_dir() {
+        assert_eq!(
+            decode_remote_dir("1 0 4 home 3 bob").as_deref(),
+            Some("/home/bob")
+        );
+        assert_eq!(decode_remote_dir("1 0").as_deref(), Some("/"));
+        assert_eq!(decode_remote_dir("1 0 9 short"), None);
+    }
+}
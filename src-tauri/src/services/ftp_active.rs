@@ -0,0 +1,103 @@
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener, TcpStream};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+/// 主动模式下等待服务器连入的最长时间
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 按通告地址的协议族监听所有网卡；指定端口范围时取第一个空闲端口
+pub fn bind_listener(
+    advertised: IpAddr,
+    ports: Option<&RangeInclusive<u16>>,
+) -> Result<TcpListener, String> {
+    let any = match advertised {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    match ports {
+        None => TcpListener::bind((any, 0)).map_err(|e| format!("主动模式监听失败: {}", e)),
+        Some(range) => range
+            .clone()
+            .find_map(|port| TcpListener::bind((any, port)).ok())
+            .ok_or_else(|| {
+                format!(
+                    "主动模式端口 {}-{} 均不可用",
+                    range.start(),
+                    range.end()
+                )
+            }),
+    }
+}
+
+/// IPv4 使用 PORT，IPv6 只能使用 EPRT（RFC 2428）
+pub fn port_command(addr: IpAddr, port: u16) -> String {
+    match addr {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("PORT {},{},{},{},{},{}", a, b, c, d, port >> 8, port & 0xff)
+        }
+        IpAddr::V6(v6) => format!("EPRT |2|{}|{}|", v6, port),
+    }
+}
+
+/// 等待服务器建立数据连接，超时多半是被本机或中间防火墙拦截
+pub fn accept(listener: &TcpListener) -> Result<TcpStream, String> {
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let start = Instant::now();
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // BSD 系统上 accept 得到的套接字会继承非阻塞标志
+                stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+                return Ok(stream);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                if start.elapsed() > ACCEPT_TIMEOUT {
+                    return Err(
+                        "等待服务器建立数据连接超时，请检查防火墙设置或改用被动模式".to_string(),
+                    );
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_port_command() {
+        let addr: IpAddr = "192.168.1.20".parse().unwrap();
+        assert_eq!(port_command(addr, 50001), "PORT 192,168,1,20,195,81");
+        let addr: IpAddr = "2001:db8::1".parse().unwrap();
+        assert_eq!(port_command(addr, 50001), "EPRT |2|2001:db8::1|50001|");
+    }
+
+    #[test]
+    fn test_bind_listener_uses_port_range() {
+        let first = TcpListener::bind("0.0.0.0:0").unwrap();
+        let busy = first.local_addr().unwrap().port();
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        // 范围内唯一的端口已被占用
+        let range = busy..=busy;
+        assert!(bind_listener(ip, Some(&range)).is_err());
+
+        let listener = bind_listener(ip, None).unwrap();
+        assert!(listener.local_addr().unwrap().ip().is_unspecified());
+    }
+
+    #[test]
+    fn test_accept_returns_blocking_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || TcpStream::connect(addr).unwrap());
+        let stream = accept(&listener).unwrap();
+        client.join().unwrap();
+        assert!(stream.peer_addr().is_ok());
+    }
+}
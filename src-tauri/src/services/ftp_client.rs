@@ -1,13 +1,68 @@
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr};
+use std::ops::RangeInclusive;
 
-use super::connection::{ConnectionTrait, FileEntry, CHUNK_SIZE};
+use chrono::{DateTime, FixedOffset, Utc};
+
+use suppaftp::native_tls::TlsConnector;
+use suppaftp::types::{FtpError, FtpResult, Mode};
+use suppaftp::{NativeTlsConnector, NativeTlsFtpStream, Status};
+
+use crate::models::certificate::TrustedCertificate;
+use crate::models::host::{FtpDataMode, FtpsMode};
+use crate::models::proxy::ProxyConfig;
+
+use super::certificate;
+use super::ftp_active;
+use super::connection::{copy_download, ConnectionTrait, FileEntry, ProgressReader};
+use super::ftp_list::{self, join_remote_path, ListClock, ListFormat};
+use super::proxy;
+
+/// FTP 连接的 TLS 模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FtpTls {
+    /// 明文 FTP
+    None,
+    /// 显式 FTPS：AUTH TLS 升级控制通道，PROT P 保护数据通道
+    Explicit,
+    /// 隐式 FTPS：连接建立后立即 TLS 握手，随后 PROT P 保护数据通道
+    Implicit,
+}
+
+/// 数据连接设置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FtpDataConnection {
+    pub mode: FtpDataMode,
+    /// 主动模式在 PORT/EPRT 中通告的地址，未设置时使用控制连接的本地地址
+    pub active_address: Option<IpAddr>,
+    /// 主动模式的监听端口范围，未设置时由系统分配
+    pub active_ports: Option<RangeInclusive<u16>>,
+    /// 被动模式忽略 PASV 应答中的地址，改用控制连接的服务器地址
+    pub ignore_pasv_address: bool,
+}
+
+/// 主动模式下自行建立的数据连接，FTPS 时包裹一层 TLS
+trait DataChannel: Read + Write {}
+
+impl<T: Read + Write> DataChannel for T {}
 
 pub struct FtpClient {
     host: String,
     port: u16,
     username: String,
     password: String,
-    stream: Option<suppaftp::FtpStream>,
+    tls: FtpTls,
+    trusted_certificate: Option<TrustedCertificate>,
+    stream: Option<NativeTlsFtpStream>,
+    /// 服务器在 FEAT 中声明了 MLST/MLSD，连接后检测
+    mlsd: bool,
+    /// 首次 LIST 时识别出的输出格式，同一连接内复用
+    list_format: Option<ListFormat>,
+    /// 服务器所在时区，LIST 输出的是服务器本地时间
+    timezone: FixedOffset,
+    data: FtpDataConnection,
+    /// 控制连接和被动模式数据连接都经此代理建立
+    proxy: Option<ProxyConfig>,
 }
 
 impl FtpClient {
@@ -17,41 +72,420 @@ impl FtpClient {
             port,
             username,
             password,
+            tls: FtpTls::None,
+            trusted_certificate: None,
             stream: None,
+            mlsd: false,
+            list_format: None,
+            timezone: FixedOffset::east_opt(0).unwrap(),
+            data: FtpDataConnection::default(),
+            proxy: None,
+        }
+    }
+
+    pub fn with_tls(mut self, tls: FtpTls) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// 固定服务器证书：不再按系统根证书校验，只接受该证书，控制连接和数据连接都是如此
+    pub fn with_trusted_certificate(mut self, cert: Option<TrustedCertificate>) -> Self {
+        self.trusted_certificate = cert;
+        self
+    }
+
+    /// 服务器时区相对 UTC 的偏移（分钟），超出范围时按 UTC 处理
+    pub fn with_timezone_offset(mut self, minutes: i32) -> Self {
+        self.timezone = FixedOffset::east_opt(minutes * 60).unwrap_or(self.timezone);
+        self
+    }
+
+    pub fn with_data_connection(mut self, data: FtpDataConnection) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    fn tls_connector(&self) -> Result<NativeTlsConnector, String> {
+        self.native_tls_connector().map(NativeTlsConnector::from)
+    }
+
+    /// 固定证书时只信任该证书，否则按系统根证书校验服务器证书
+    fn native_tls_connector(&self) -> Result<TlsConnector, String> {
+        match self.pinned_certificate() {
+            Some(trusted) => certificate::pinned_connector(trusted),
+            None => TlsConnector::new().map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 仅 FTPS 连接使用固定证书
+    fn pinned_certificate(&self) -> Option<&TrustedCertificate> {
+        self.trusted_certificate
+            .as_ref()
+            .filter(|_| self.tls != FtpTls::None)
+    }
+
+    fn open_stream(&mut self) -> Result<NativeTlsFtpStream, String> {
+        if self.proxy.is_some() && self.is_active_mode() {
+            return Err("通过代理连接时不支持主动模式，请改用被动模式".to_string());
+        }
+        match self.tls {
+            FtpTls::None => self.connect_control(),
+            FtpTls::Explicit => {
+                let connector = self.tls_connector()?;
+                let stream = self.connect_control()?;
+                stream
+                    .into_secure(connector, &self.host)
+                    .map_err(|e| self.tls_error(e))
+            }
+            FtpTls::Implicit => {
+                let connector = self.tls_connector()?;
+                let stream = match &self.proxy {
+                    None => NativeTlsFtpStream::connect_secure_implicit(
+                        (self.host.as_str(), self.port),
+                        connector,
+                        &self.host,
+                    ),
+                    // suppaftp 只接受地址并自行连接：先经代理连上服务器，再在本机回环上转发密文。
+                    // 连接失败（包括转发端口被其他程序抢先连入）时断开代理连接，不留给别人使用
+                    Some(proxy_config) => {
+                        let tcp = proxy::connect(&self.host, self.port, Some(proxy_config))?;
+                        let remote = tcp.try_clone().map_err(|e| e.to_string())?;
+                        let local = proxy::loopback_forward(tcp)?;
+                        NativeTlsFtpStream::connect_secure_implicit(local, connector, &self.host)
+                            .inspect_err(|_| {
+                                let _ = remote.shutdown(Shutdown::Both);
+                            })
+                    }
+                };
+                let stream = stream.map_err(|e| self.tls_error(e))?;
+                let mut stream = self.route_passive_data(stream);
+                protect_data_channel(&mut stream)?;
+                Ok(stream)
+            }
+        }
+    }
+
+    /// TLS 握手失败时的错误信息。固定证书时另取一次服务器出示的证书，
+    /// 证书确已更换则给出两个指纹
+    fn tls_error(&self, e: FtpError) -> String {
+        let Some(trusted) = self.pinned_certificate() else {
+            return unverified_certificate_error(e);
+        };
+        if !matches!(e, FtpError::SecureError(_)) {
+            return e.to_string();
+        }
+        let mode = match self.tls {
+            FtpTls::Implicit => FtpsMode::Implicit,
+            _ => FtpsMode::Explicit,
+        };
+        match certificate::fetch_ftps_certificate(&self.host, self.port, mode, self.proxy.as_ref())
+        {
+            Ok(presented) if presented.fingerprint != trusted.fingerprint => {
+                certificate::mismatch_error(trusted, &presented)
+            }
+            _ => format!("TLS 握手失败: {}", e),
+        }
+    }
+
+    /// 建立明文控制连接，配置了代理时经代理转发
+    fn connect_control(&self) -> Result<NativeTlsFtpStream, String> {
+        let tcp = proxy::connect(&self.host, self.port, self.proxy.as_ref())?;
+        let stream = NativeTlsFtpStream::connect_with_stream(tcp).map_err(|e| e.to_string())?;
+        Ok(self.route_passive_data(stream))
+    }
+
+    /// 被动模式的数据连接同样经代理建立，TLS 仍由 suppaftp 在其上握手。
+    /// EPSV 和忽略 PASV 地址时，suppaftp 用控制连接的对端地址拼出数据地址，
+    /// 经代理（或隐式 FTPS 的本机回环转发）时那不是服务器地址，需要换回服务器地址
+    fn route_passive_data(&self, stream: NativeTlsFtpStream) -> NativeTlsFtpStream {
+        let Some(proxy_config) = self.proxy.clone() else {
+            return stream;
+        };
+        let control_peer = stream.get_ref().peer_addr().ok().map(|a| a.ip());
+        let server = self.host.clone();
+        stream.passive_stream_builder(move |addr: SocketAddr| {
+            let target = if Some(addr.ip()) == control_peer {
+                server.clone()
+            } else {
+                addr.ip().to_string()
+            };
+            proxy::connect(&target, addr.port(), Some(&proxy_config)).map_err(data_error)
+        })
+    }
+
+    /// 登录后按主机设置切换数据连接方式；主动模式由本客户端自行处理，
+    /// suppaftp 保持被动模式即可
+    fn apply_data_mode(&self, stream: &mut NativeTlsFtpStream) {
+        match self.data.mode {
+            FtpDataMode::ExtendedPassive => stream.set_mode(Mode::ExtendedPassive),
+            FtpDataMode::Passive | FtpDataMode::Active => {
+                stream.set_mode(Mode::Passive);
+                stream.set_passive_nat_workaround(self.data.ignore_pasv_address);
+            }
+        }
+    }
+
+    fn is_active_mode(&self) -> bool {
+        self.data.mode == FtpDataMode::Active
+    }
+
+    /// 主动模式：本地监听并发送 PORT/EPRT，再发送传输命令，等待服务器连入。
+    /// suppaftp 自带的主动模式只能监听随机端口并通告控制连接的本地地址，
+    /// 无法满足 NAT 后的公网地址和防火墙端口范围。
+    fn open_active_data(&mut self, command: &str) -> FtpResult<Box<dyn DataChannel>> {
+        let tls = match self.tls {
+            FtpTls::None => None,
+            _ => Some(self.native_tls_connector().map_err(FtpError::SecureError)?),
+        };
+        let stream = self.stream.as_mut().ok_or_else(not_connected)?;
+        let local_ip = stream
+            .get_ref()
+            .local_addr()
+            .map_err(FtpError::ConnectionError)?
+            .ip();
+        let advertised = self.data.active_address.unwrap_or(local_ip);
+        let listener = ftp_active::bind_listener(advertised, self.data.active_ports.as_ref())
+            .map_err(data_error)?;
+        let port = listener
+            .local_addr()
+            .map_err(FtpError::ConnectionError)?
+            .port();
+        stream.custom_command(ftp_active::port_command(advertised, port), &[Status::CommandOk])?;
+        stream.custom_command(command, &[Status::AboutToSend, Status::AlreadyOpen])?;
+        let data = ftp_active::accept(&listener).map_err(data_error)?;
+        // 数据通道的 TLS 仍由客户端发起握手（RFC 4217）
+        match tls {
+            None => Ok(Box::new(data)),
+            Some(connector) => connector
+                .connect(&self.host, data)
+                .map(|s| Box::new(s) as Box<dyn DataChannel>)
+                .map_err(|e| FtpError::SecureError(e.to_string())),
+        }
+    }
+
+    /// 主动模式下执行 LIST/MLSD 这类以文本行返回结果的命令
+    fn active_lines(&mut self, command: &str) -> FtpResult<Vec<String>> {
+        let mut data = self.open_active_data(command)?;
+        let mut bytes = Vec::new();
+        let read = data.read_to_end(&mut bytes);
+        self.stream
+            .as_mut()
+            .ok_or_else(not_connected)?
+            .finalize_retr_stream(data)?;
+        read.map_err(FtpError::ConnectionError)?;
+        Ok(String::from_utf8_lossy(&bytes)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
+
+    fn mlsd_lines(&mut self, path: &str) -> FtpResult<Vec<String>> {
+        if self.is_active_mode() {
+            return self.active_lines(&format!("MLSD {}", path));
         }
+        self.stream
+            .as_mut()
+            .ok_or_else(not_connected)?
+            .mlsd(Some(path))
+    }
+
+    fn list_lines(&mut self, path: &str) -> FtpResult<Vec<String>> {
+        if self.is_active_mode() {
+            return self.active_lines(&format!("LIST {}", path));
+        }
+        self.stream
+            .as_mut()
+            .ok_or_else(not_connected)?
+            .list(Some(path))
+    }
+
+    /// FXP 的数据连接在两台服务器之间直接建立，只对不经代理的明文 FTP 尝试
+    fn fxp_capable(&self) -> bool {
+        self.tls == FtpTls::None && self.proxy.is_none() && self.stream.is_some()
+    }
+
+    /// 让服务器进入被动模式并返回其数据端口地址，按主机设置替换应答中的内网地址
+    fn passive_address(&mut self) -> FtpResult<SocketAddr> {
+        let ignore_address = self.data.ignore_pasv_address;
+        let stream = self.stream.as_mut().ok_or_else(not_connected)?;
+        let response = stream.custom_command("PASV", &[Status::PassiveMode])?;
+        let text = response.as_string().map_err(|_| FtpError::BadResponse)?;
+        let mut addr = parse_pasv_address(&text).ok_or(FtpError::BadResponse)?;
+        if ignore_address {
+            let peer = stream
+                .get_ref()
+                .peer_addr()
+                .map_err(FtpError::ConnectionError)?;
+            addr.set_ip(peer.ip());
+        }
+        Ok(addr)
     }
 }
 
-struct ProgressReader<'a, R: Read> {
-    inner: R,
-    transferred: u64,
-    total: u64,
-    callback: Option<&'a dyn Fn(u64, u64)>,
+/// 解析 `227 Entering Passive Mode (h1,h2,h3,h4,p1,p2)` 中的地址
+fn parse_pasv_address(response: &str) -> Option<SocketAddr> {
+    let start = response.find('(')? + 1;
+    let end = start + response[start..].find(')')?;
+    let fields = response[start..end]
+        .split(',')
+        .map(|f| f.trim().parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let [a, b, c, d, p1, p2] = fields[..] else {
+        return None;
+    };
+    Some(SocketAddr::from((
+        [a, b, c, d],
+        u16::from(p1) << 8 | u16::from(p2),
+    )))
 }
 
-impl<'a, R: Read> Read for ProgressReader<'a, R> {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let n = self.inner.read(buf)?;
-        if n > 0 {
-            self.transferred += n as u64;
-            if let Some(cb) = self.callback {
-                cb(self.transferred, self.total);
-            }
+/// 源服务器 RETR 失败时目标服务器仍在等待数据连接，最多花这么久替它连上再断开
+const FXP_UNBLOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// FXP：目标服务器 PASV 监听，源服务器按 PORT 连过去发送文件，数据不经过本机。
+/// 多数服务器默认拒绝 PORT 到控制连接以外的地址，传输开始前的失败都返回 Ok(None)，
+/// 由调用方改为经本机中转；传输期间拿不到进度，完成后一次性汇报
+pub(super) fn fxp_transfer(
+    source: &mut FtpClient,
+    dest: &mut FtpClient,
+    source_path: &str,
+    dest_path: &str,
+    offset: u64,
+    total: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<Option<u64>, String> {
+    if !source.fxp_capable() || !dest.fxp_capable() {
+        return Ok(None);
+    }
+    let Ok(addr) = dest.passive_address() else {
+        return Ok(None);
+    };
+    let (Some(src), Some(dst)) = (source.stream.as_mut(), dest.stream.as_mut()) else {
+        return Ok(None);
+    };
+    if src
+        .custom_command(
+            ftp_active::port_command(addr.ip(), addr.port()),
+            &[Status::CommandOk],
+        )
+        .is_err()
+    {
+        return Ok(None);
+    }
+    // REST 必须紧挨着 STOR/RETR 发送
+    if offset > 0
+        && (dst.resume_transfer(offset as usize).is_err()
+            || src.resume_transfer(offset as usize).is_err())
+    {
+        return Ok(None);
+    }
+
+    // 被动一方要等数据连接建立后才应答 STOR，必须在等待应答的同时向源服务器发出 RETR
+    let (stored, retrieved) = std::thread::scope(|s| {
+        let store = s.spawn(|| {
+            dst.custom_command(
+                format!("STOR {}", dest_path),
+                &[Status::AboutToSend, Status::AlreadyOpen],
+            )
+        });
+        let retrieved = src.custom_command(
+            format!("RETR {}", source_path),
+            &[Status::AboutToSend, Status::AlreadyOpen],
+        );
+        if retrieved.is_err() {
+            let _ = std::net::TcpStream::connect_timeout(&addr, FXP_UNBLOCK_TIMEOUT);
+        }
+        (store.join(), retrieved)
+    });
+    let stored = stored.map_err(|_| "FXP 传输线程异常退出".to_string())?;
+    match (stored.is_ok(), retrieved.is_ok()) {
+        (true, true) => {}
+        (true, false) => {
+            let _ = dst.finalize_put_stream(std::io::sink());
+            return Ok(None);
         }
-        Ok(n)
+        (false, true) => {
+            let _ = src.finalize_retr_stream(std::io::empty());
+            return Ok(None);
+        }
+        (false, false) => return Ok(None),
+    }
+
+    let sent = src.finalize_retr_stream(std::io::empty());
+    let received = dst.finalize_put_stream(std::io::sink());
+    sent.map_err(|e| format!("源服务器发送失败: {}", e))?;
+    received.map_err(|e| format!("目标服务器接收失败: {}", e))?;
+    if let Some(cb) = progress {
+        cb(total, total);
+    }
+    Ok(Some(total.saturating_sub(offset)))
+}
+
+fn not_connected() -> FtpError {
+    FtpError::ConnectionError(std::io::Error::new(
+        std::io::ErrorKind::NotConnected,
+        "Not connected",
+    ))
+}
+
+fn data_error(message: String) -> FtpError {
+    FtpError::ConnectionError(std::io::Error::other(message))
+}
+
+/// RFC 3659 中 MLSD 不单独出现在 FEAT 里，由 MLST 一并声明
+fn supports_mlsd(stream: &mut NativeTlsFtpStream) -> bool {
+    match stream.feat() {
+        Ok(features) => features
+            .keys()
+            .any(|k| k.eq_ignore_ascii_case("MLST") || k.eq_ignore_ascii_case("MLSD")),
+        Err(_) => false,
     }
 }
 
+/// 服务器未实现 MLSD 时返回的错误码，此时应退回 LIST
+fn is_not_implemented(e: &suppaftp::FtpError) -> bool {
+    matches!(
+        e,
+        suppaftp::FtpError::UnexpectedResponse(r)
+            if matches!(r.status, suppaftp::Status::BadCommand | suppaftp::Status::NotImplemented)
+    )
+}
+
+fn unverified_certificate_error(e: impl std::fmt::Display) -> String {
+    format!(
+        "TLS 握手失败: {}。如服务器使用自签名证书，请先获取并信任该证书",
+        e
+    )
+}
+
+/// 隐式模式下 suppaftp 不会发送 PBSZ/PROT，需手动要求数据通道也走 TLS
+fn protect_data_channel(stream: &mut NativeTlsFtpStream) -> Result<(), String> {
+    stream
+        .custom_command("PBSZ 0", &[Status::CommandOk])
+        .map_err(|e| e.to_string())?;
+    stream
+        .custom_command("PROT P", &[Status::CommandOk])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 impl ConnectionTrait for FtpClient {
     fn connect(&mut self) -> Result<(), String> {
-        let addr = format!("{}:{}", self.host, self.port);
-        let mut stream = suppaftp::FtpStream::connect(&addr).map_err(|e| e.to_string())?;
+        let mut stream = self.open_stream()?;
         stream
             .login(&self.username, &self.password)
             .map_err(|e| e.to_string())?;
         stream
             .transfer_type(suppaftp::types::FileType::Binary)
             .map_err(|e| e.to_string())?;
+        self.apply_data_mode(&mut stream);
+        self.mlsd = supports_mlsd(&mut stream);
+        self.list_format = None;
         self.stream = Some(stream);
         Ok(())
     }
@@ -69,16 +503,34 @@ impl ConnectionTrait for FtpClient {
     }
 
     fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
-        let stream = self.stream.as_mut().ok_or("Not connected")?;
-        let entries = stream.list(Some(path)).map_err(|e| e.to_string())?;
-
-        let mut files = Vec::new();
-        for entry in entries {
-            if let Some(file_entry) = parse_ftp_list_entry(&entry, path) {
-                files.push(file_entry);
+        if self.mlsd {
+            match self.mlsd_lines(path) {
+                Ok(lines) => {
+                    return Ok(lines
+                        .iter()
+                        .filter_map(|line| parse_mlsd_entry(line, path))
+                        .collect());
+                }
+                Err(e) if is_not_implemented(&e) => self.mlsd = false,
+                Err(e) => return Err(e.to_string()),
             }
         }
-        Ok(files)
+
+        let lines = self.list_lines(path).map_err(|e| e.to_string())?;
+        let clock = ListClock::new(self.timezone);
+        let cached = self
+            .list_format
+            .map(|f| (f, f.parse_listing(&lines, path, &clock)));
+        match cached {
+            Some((_, files)) if !files.is_empty() => Ok(files),
+            _ => match ftp_list::detect(&lines) {
+                Some(format) => {
+                    self.list_format = Some(format);
+                    Ok(format.parse_listing(&lines, path, &clock))
+                }
+                None => Ok(Vec::new()),
+            },
+        }
     }
 
     fn file_size(&mut self, path: &str) -> Result<u64, String> {
@@ -93,7 +545,13 @@ impl ConnectionTrait for FtpClient {
         let stream = self.stream.as_mut().ok_or("Not connected")?;
         match stream.size(path) {
             Ok(_) => Ok(true),
-            Err(_) => Ok(false),
+            // SIZE 对目录和不存在的路径都返回 550，再到上级目录的列表中查找；上级目录不存在时同样不存在
+            Err(suppaftp::FtpError::UnexpectedResponse(r))
+                if r.status == Status::FileUnavailable =>
+            {
+                Ok(self.stat(path).is_ok())
+            }
+            Err(e) => Err(e.to_string()),
         }
     }
 
@@ -104,7 +562,6 @@ impl ConnectionTrait for FtpClient {
         offset: u64,
         progress: Option<&dyn Fn(u64, u64)>,
     ) -> Result<u64, String> {
-        let stream = self.stream.as_mut().ok_or("Not connected")?;
         let metadata = std::fs::metadata(local_path).map_err(|e| e.to_string())?;
         let total_size = metadata.len();
 
@@ -112,22 +569,8 @@ impl ConnectionTrait for FtpClient {
         if offset > 0 {
             file.seek(SeekFrom::Start(offset))
                 .map_err(|e| e.to_string())?;
-            stream
-                .resume_transfer(offset as usize)
-                .map_err(|e| e.to_string())?;
         }
-
-        let mut reader = ProgressReader {
-            inner: file,
-            transferred: offset,
-            total: total_size,
-            callback: progress,
-        };
-
-        let _ = stream
-            .put_file(remote_path, &mut reader)
-            .map_err(|e| e.to_string())?;
-        Ok(reader.transferred - offset)
+        self.upload_from(&mut file, remote_path, offset, total_size, progress)
     }
 
     fn download(
@@ -137,6 +580,28 @@ impl ConnectionTrait for FtpClient {
         offset: u64,
         progress: Option<&dyn Fn(u64, u64)>,
     ) -> Result<u64, String> {
+        let mut local_file = if offset > 0 {
+            let mut f = std::fs::OpenOptions::new()
+                .write(true)
+                .open(local_path)
+                .map_err(|e| e.to_string())?;
+            f.seek(SeekFrom::Start(offset))
+                .map_err(|e| e.to_string())?;
+            f
+        } else {
+            std::fs::File::create(local_path).map_err(|e| e.to_string())?
+        };
+        self.download_to(remote_path, &mut local_file, offset, progress)
+    }
+
+    fn download_to(
+        &mut self,
+        remote_path: &str,
+        writer: &mut dyn Write,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let active = self.is_active_mode();
         let stream = self.stream.as_mut().ok_or("Not connected")?;
         let total_size = stream
             .size(remote_path)
@@ -149,43 +614,78 @@ impl ConnectionTrait for FtpClient {
                 .map_err(|e| e.to_string())?;
         }
 
-        let mut local_file = if offset > 0 {
-            let mut f = std::fs::OpenOptions::new()
-                .write(true)
-                .open(local_path)
+        if active {
+            let mut data = self
+                .open_active_data(&format!("RETR {}", remote_path))
                 .map_err(|e| e.to_string())?;
-            f.seek(SeekFrom::Start(offset))
+            let copied = copy_download(&mut data, writer, offset, total_size, progress);
+            self.stream
+                .as_mut()
+                .ok_or("Not connected")?
+                .finalize_retr_stream(data)
                 .map_err(|e| e.to_string())?;
-            f
-        } else {
-            std::fs::File::create(local_path).map_err(|e| e.to_string())?
-        };
-
-        let mut transferred: u64 = 0;
+            return copied.map_err(|e| e.to_string());
+        }
 
         stream
             .retr(remote_path, |reader| {
-                let mut buf = [0u8; CHUNK_SIZE];
-                loop {
-                    let n = reader
-                        .read(&mut buf)
-                        .map_err(suppaftp::types::FtpError::ConnectionError)?;
-                    if n == 0 {
-                        break;
-                    }
-                    local_file
-                        .write_all(&buf[..n])
-                        .map_err(suppaftp::types::FtpError::ConnectionError)?;
-                    transferred += n as u64;
-                    if let Some(ref cb) = progress {
-                        cb(offset + transferred, total_size);
-                    }
-                }
-                Ok(transferred)
+                copy_download(reader, writer, offset, total_size, progress)
+                    .map_err(FtpError::ConnectionError)
             })
             .map_err(|e| e.to_string())
     }
 
+    fn upload_from(
+        &mut self,
+        reader: &mut dyn Read,
+        remote_path: &str,
+        offset: u64,
+        total: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let active = self.is_active_mode();
+        let stream = self.stream.as_mut().ok_or("Not connected")?;
+        if offset > 0 {
+            stream
+                .resume_transfer(offset as usize)
+                .map_err(|e| e.to_string())?;
+        }
+
+        let mut reader = ProgressReader {
+            inner: reader,
+            transferred: offset,
+            total,
+            callback: progress,
+        };
+
+        if active {
+            let mut data = self
+                .open_active_data(&format!("STOR {}", remote_path))
+                .map_err(|e| e.to_string())?;
+            let copied = std::io::copy(&mut reader, &mut data);
+            self.stream
+                .as_mut()
+                .ok_or("Not connected")?
+                .finalize_put_stream(data)
+                .map_err(|e| e.to_string())?;
+            copied.map_err(|e| e.to_string())?;
+        } else {
+            let _ = stream
+                .put_file(remote_path, &mut reader)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(reader.transferred - offset)
+    }
+
+    /// REST 后 STOR 从 offset 处续写
+    fn can_append(&self) -> bool {
+        true
+    }
+
+    fn as_ftp(&mut self) -> Option<&mut FtpClient> {
+        Some(self)
+    }
+
     fn mkdir(&mut self, path: &str) -> Result<(), String> {
         let stream = self.stream.as_mut().ok_or("Not connected")?;
         stream.mkdir(path).map_err(|e| e.to_string())?;
@@ -209,34 +709,184 @@ impl ConnectionTrait for FtpClient {
         stream.rename(from, to).map_err(|e| e.to_string())?;
         Ok(())
     }
+
+    fn stat(&mut self, path: &str) -> Result<FileEntry, String> {
+        let stream = self.stream.as_mut().ok_or("Not connected")?;
+        if self.mlsd {
+            let line = stream.mlst(Some(path)).map_err(|e| e.to_string())?;
+            return parse_mlst_entry(&line, path)
+                .ok_or_else(|| format!("无法解析 MLST 应答: {}", line));
+        }
+
+        // 不支持 MLST 时列出上级目录并按名称查找
+        let trimmed = path.trim_end_matches('/');
+        let (parent, name) = match trimmed.rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((parent, name)) => (parent, name),
+            None => (".", trimmed),
+        };
+        if name.is_empty() {
+            return Err("无法获取根目录的属性".to_string());
+        }
+        self.list_dir(parent)?
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| format!("文件不存在: {}", path))
+    }
+
+    fn set_permissions(&mut self, path: &str, mode: u32) -> Result<(), String> {
+        let stream = self.stream.as_mut().ok_or("Not connected")?;
+        stream
+            .site(format!("CHMOD {:o} {}", mode & 0o7777, path))
+            .map_err(|e| format!("修改权限失败: {}", e))?;
+        Ok(())
+    }
+
+    fn set_owner(
+        &mut self,
+        _path: &str,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+    ) -> Result<(), String> {
+        Err("FTP 协议不支持修改属主".to_string())
+    }
+
+    /// FTP 没有标准的符号链接命令，仅 ProFTPD 等服务器提供 SITE SYMLINK 扩展
+    fn create_symlink(&mut self, target: &str, path: &str) -> Result<(), String> {
+        // SITE SYMLINK 按空格拆分参数，不支持引号
+        if target.contains(char::is_whitespace) || path.contains(char::is_whitespace) {
+            return Err(format!(
+                "FTP 服务器无法创建含空格的符号链接: {} -> {}",
+                path, target
+            ));
+        }
+        let stream = self.stream.as_mut().ok_or("Not connected")?;
+        stream
+            .site(format!("SYMLINK {} {}", target, path))
+            .map_err(|e| format!("服务器不支持创建符号链接: {}", e))?;
+        Ok(())
+    }
 }
 
-fn parse_ftp_list_entry(line: &str, parent_path: &str) -> Option<FileEntry> {
-    let parts: Vec<&str> = line.split_whitespace().collect();
-    if parts.len() < 9 {
+/// 解析一行 MLSD 输出，格式为 `fact=value;fact=value; name`。
+/// 事实之后紧跟一个空格，其余部分原样作为文件名，可包含连续空格。
+fn parse_mlsd_entry(line: &str, parent_path: &str) -> Option<FileEntry> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (facts, name) = line.split_once(' ')?;
+    if name.is_empty() || name == "." || name == ".." {
         return None;
     }
-
-    let name = parts[8..].join(" ");
-    if name == "." || name == ".." {
+    // 列表中的 cdir/pdir 是当前目录与上级目录本身
+    if matches!(mlsd_type(facts).as_deref(), Some("cdir" | "pdir")) {
         return None;
     }
+    Some(mlsd_facts_to_entry(
+        facts,
+        name.to_string(),
+        join_remote_path(parent_path, name),
+    ))
+}
 
-    let is_dir = line.starts_with('d');
-    let size: u64 = parts[4].parse().unwrap_or(0);
-    let path = if parent_path.ends_with('/') {
-        format!("{}{}", parent_path, name)
-    } else {
-        format!("{}/{}", parent_path, name)
-    };
+/// 解析 MLST 的应答行，行首带一个空格，文件名部分为服务器返回的完整路径
+fn parse_mlst_entry(line: &str, path: &str) -> Option<FileEntry> {
+    let (facts, _) = line.trim().split_once(' ')?;
+    Some(mlsd_facts_to_entry(facts, remote_basename(path), path.to_string()))
+}
+
+fn remote_basename(path: &str) -> String {
+    match path.trim_end_matches('/').rsplit('/').next() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+fn mlsd_type(facts: &str) -> Option<String> {
+    facts.split(';').find_map(|fact| {
+        let (key, value) = fact.split_once('=')?;
+        key.eq_ignore_ascii_case("type")
+            .then(|| value.to_ascii_lowercase())
+    })
+}
+
+fn mlsd_facts_to_entry(facts: &str, name: String, path: String) -> FileEntry {
+    let mut is_dir = false;
+    let mut size = 0;
+    let mut modified = None;
+    let mut permissions = None;
+    let mut is_symlink = false;
+    let mut link_target = None;
+    let mut mode = None;
+    let mut uid = None;
+    let mut gid = None;
+    let mut owner = None;
+    let mut group = None;
+    for fact in facts.split(';') {
+        let (key, value) = match fact.split_once('=') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "type" => {
+                let kind = value.to_ascii_lowercase();
+                match kind.as_str() {
+                    "dir" | "cdir" | "pdir" => is_dir = true,
+                    // 常见写法：OS.unix=slink:/target 或 OS.unix=symlink
+                    _ if kind.starts_with("os.unix=slink")
+                        || kind.starts_with("os.unix=symlink") =>
+                    {
+                        is_symlink = true;
+                        link_target = value
+                            .split_once(':')
+                            .map(|(_, t)| t.to_string())
+                            .filter(|t| !t.is_empty());
+                    }
+                    _ => {}
+                }
+            }
+            "size" | "sizd" => size = value.parse().unwrap_or(0),
+            "modify" => modified = parse_mlsd_time(value),
+            "perm" => permissions = Some(value.to_string()),
+            "unix.mode" => mode = u32::from_str_radix(value, 8).ok(),
+            "unix.uid" => uid = value.parse().ok(),
+            "unix.gid" => gid = value.parse().ok(),
+            // ProFTPD 的 UNIX.owner/UNIX.group 为数字 ID，名称在 *name 事实中
+            "unix.owner" => match value.parse() {
+                Ok(id) => uid = Some(id),
+                Err(_) => owner = Some(value.to_string()),
+            },
+            "unix.group" => match value.parse() {
+                Ok(id) => gid = Some(id),
+                Err(_) => group = Some(value.to_string()),
+            },
+            "unix.ownername" => owner = Some(value.to_string()),
+            "unix.groupname" => group = Some(value.to_string()),
+            _ => {}
+        }
+    }
 
-    Some(FileEntry {
+    FileEntry {
         name,
         path,
         is_dir,
         size,
-        modified: Some(format!("{} {} {}", parts[5], parts[6], parts[7])),
-    })
+        modified,
+        permissions,
+        is_symlink,
+        link_target,
+        mode,
+        uid,
+        gid,
+        owner,
+        group,
+    }
+}
+
+/// MLSD 时间固定为 UTC 的 `YYYYMMDDHHMMSS[.sss]`，不受服务器时区设置影响，精确到秒
+fn parse_mlsd_time(value: &str) -> Option<DateTime<Utc>> {
+    let whole = value.split('.').next()?;
+    chrono::NaiveDateTime::parse_from_str(whole, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|t| t.and_utc())
 }
 
 #[cfg(test)]
@@ -254,49 +904,157 @@ mod tests {
         assert!(!client.is_connected());
         assert_eq!(client.host, "127.0.0.1");
         assert_eq!(client.port, 21);
+        assert_eq!(client.tls, FtpTls::None);
     }
 
     #[test]
-    fn test_parse_ftp_list_entry_file() {
-        let line = "-rw-r--r--   1 user group   1024 Jan 01 12:00 test.txt";
-        let entry = parse_ftp_list_entry(line, "/home").unwrap();
-        assert_eq!(entry.name, "test.txt");
-        assert_eq!(entry.path, "/home/test.txt");
+    fn test_ftp_client_with_tls() {
+        let client = FtpClient::new(
+            "ftps.example.com".into(),
+            21,
+            "user".into(),
+            "pass".into(),
+        )
+        .with_tls(FtpTls::Explicit);
+        assert!(!client.is_connected());
+        assert_eq!(client.tls, FtpTls::Explicit);
+        assert!(client.trusted_certificate.is_none());
+    }
+
+    #[test]
+    fn test_ftp_client_with_timezone_offset() {
+        let client = FtpClient::new("127.0.0.1".into(), 21, "user".into(), "pass".into())
+            .with_timezone_offset(-300);
+        assert_eq!(client.timezone.local_minus_utc(), -300 * 60);
+        let client = client.with_timezone_offset(24 * 60);
+        assert_eq!(client.timezone.local_minus_utc(), -300 * 60);
+    }
+
+    #[test]
+    fn test_create_symlink_rejects_whitespace() {
+        let mut client = FtpClient::new("127.0.0.1".into(), 21, "user".into(), "pass".into());
+        let err = client.create_symlink("my docs", "/srv/docs").unwrap_err();
+        assert!(err.contains("空格"));
+        let err = client.create_symlink("docs", "/srv/a\r\nDELE x").unwrap_err();
+        assert!(err.contains("空格"));
+    }
+
+    #[test]
+    fn test_pinned_certificate_only_for_ftps() {
+        let pinned = TrustedCertificate {
+            id: None,
+            host_id: 1,
+            fingerprint: "AA".into(),
+            subject: "CN=x".into(),
+            certificate: "bm90IGEgY2VydA==".into(),
+            trusted_at: None,
+        };
+        let client = FtpClient::new("127.0.0.1".into(), 21, "user".into(), "pass".into())
+            .with_trusted_certificate(Some(pinned));
+        assert!(client.pinned_certificate().is_none());
+        let client = client.with_tls(FtpTls::Explicit);
+        assert_eq!(client.pinned_certificate().unwrap().fingerprint, "AA");
+    }
+
+    #[test]
+    fn test_parse_mlsd_entry_file() {
+        let line = "type=file;size=1024;modify=20240115103045;perm=adfrw; report.csv";
+        let entry = parse_mlsd_entry(line, "/data").unwrap();
+        assert_eq!(entry.name, "report.csv");
+        assert_eq!(entry.path, "/data/report.csv");
         assert!(!entry.is_dir);
         assert_eq!(entry.size, 1024);
+        assert_eq!(entry.modified.unwrap().to_rfc3339(), "2024-01-15T10:30:45+00:00");
+        assert_eq!(entry.permissions.as_deref(), Some("adfrw"));
     }
 
     #[test]
-    fn test_parse_ftp_list_entry_dir() {
-        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 subdir";
-        let entry = parse_ftp_list_entry(line, "/home/").unwrap();
-        assert_eq!(entry.name, "subdir");
-        assert_eq!(entry.path, "/home/subdir");
+    fn test_parse_mlsd_entry_dir_and_case_insensitive_facts() {
+        let line = "Type=DIR;Modify=20231231235959.123;Perm=flcdmpe; backups\r\n";
+        let entry = parse_mlsd_entry(line, "/").unwrap();
+        assert_eq!(entry.name, "backups");
+        assert_eq!(entry.path, "/backups");
         assert!(entry.is_dir);
+        assert_eq!(entry.size, 0);
+        assert_eq!(entry.modified.unwrap().timestamp(), 1704067199);
     }
 
     #[test]
-    fn test_parse_ftp_list_entry_skips_dots() {
-        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 .";
-        assert!(parse_ftp_list_entry(line, "/").is_none());
+    fn test_parse_mlsd_entry_preserves_spaces_in_name() {
+        let line = "type=file;size=7; two  spaces  .txt";
+        let entry = parse_mlsd_entry(line, "/data/").unwrap();
+        assert_eq!(entry.name, "two  spaces  .txt");
+        assert_eq!(entry.path, "/data/two  spaces  .txt");
+    }
+
+    #[test]
+    fn test_parse_mlsd_entry_skips_cdir_and_pdir() {
+        assert!(parse_mlsd_entry("type=cdir;perm=el; /data", "/data").is_none());
+        assert!(parse_mlsd_entry("type=pdir;perm=el; ..", "/data").is_none());
+        assert!(parse_mlsd_entry("type=file;size=1;", "/data").is_none());
+    }
+
+    #[test]
+    fn test_parse_mlsd_entry_symlink() {
+        let line = "type=OS.unix=slink:/srv/releases/3;modify=20240101000000; current";
+        let entry = parse_mlsd_entry(line, "/srv").unwrap();
+        assert_eq!(entry.name, "current");
+        assert!(entry.is_symlink);
+        assert!(!entry.is_dir);
+        assert_eq!(entry.link_target.as_deref(), Some("/srv/releases/3"));
+
+        let entry = parse_mlsd_entry("type=OS.unix=symlink;size=4; link", "/").unwrap();
+        assert!(entry.is_symlink);
+        assert!(entry.link_target.is_none());
+    }
+
+    #[test]
+    fn test_parse_mlsd_entry_unix_facts() {
+        let line = "type=file;size=5;UNIX.mode=0640;UNIX.owner=1001;UNIX.group=100;\
+                    UNIX.ownername=deploy;UNIX.groupname=users; app.log";
+        let entry = parse_mlsd_entry(line, "/var/log").unwrap();
+        assert_eq!(entry.mode, Some(0o640));
+        assert_eq!(entry.uid, Some(1001));
+        assert_eq!(entry.gid, Some(100));
+        assert_eq!(entry.owner.as_deref(), Some("deploy"));
+        assert_eq!(entry.group.as_deref(), Some("users"));
+
+        let entry = parse_mlsd_entry("type=dir;unix.owner=www; html", "/").unwrap();
+        assert!(entry.uid.is_none());
+        assert_eq!(entry.owner.as_deref(), Some("www"));
+    }
+
+    #[test]
+    fn test_parse_mlst_entry() {
+        let line = " type=cdir;modify=20240101000000;UNIX.mode=0755; /srv/www";
+        let entry = parse_mlst_entry(line, "/srv/www/").unwrap();
+        assert_eq!(entry.name, "www");
+        assert_eq!(entry.path, "/srv/www/");
+        assert!(entry.is_dir);
+        assert_eq!(entry.mode, Some(0o755));
 
-        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 ..";
-        assert!(parse_ftp_list_entry(line, "/").is_none());
+        let entry = parse_mlst_entry(" type=file;size=3; /a.txt", "/a.txt").unwrap();
+        assert_eq!(entry.name, "a.txt");
+        assert_eq!(entry.size, 3);
+        assert!(parse_mlst_entry("", "/a.txt").is_none());
     }
 
     #[test]
-    fn test_parse_ftp_list_entry_invalid() {
-        let line = "short line";
-        assert!(parse_ftp_list_entry(line, "/").is_none());
+    fn test_parse_mlsd_entry_ignores_bad_modify() {
+        let line = "type=file;size=1;modify=yesterday; a.txt";
+        let entry = parse_mlsd_entry(line, "/").unwrap();
+        assert!(entry.modified.is_none());
     }
 
     #[test]
-    fn test_parse_ftp_list_entry_filename_with_spaces() {
-        let line = "-rw-r--r--   1 user group   2048 Feb 15 09:30 my file name.txt";
-        let entry = parse_ftp_list_entry(line, "/data").unwrap();
-        assert_eq!(entry.name, "my file name.txt");
-        assert_eq!(entry.path, "/data/my file name.txt");
-        assert_eq!(entry.size, 2048);
+    fn test_parse_pasv_address() {
+        let addr = parse_pasv_address("227 Entering Passive Mode (192,168,1,20,195,80).").unwrap();
+        assert_eq!(addr, "192.168.1.20:50000".parse().unwrap());
+        let addr = parse_pasv_address("227 =(10, 0, 0, 1, 0, 21)").unwrap();
+        assert_eq!(addr, "10.0.0.1:21".parse().unwrap());
+        assert!(parse_pasv_address("227 Entering Passive Mode").is_none());
+        assert!(parse_pasv_address("227 (1,2,3,4,5)").is_none());
+        assert!(parse_pasv_address("227 (1,2,3,256,0,21)").is_none());
     }
 
     #[test]
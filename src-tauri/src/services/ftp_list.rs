@@ -0,0 +1,789 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use super::connection::FileEntry;
+
+/// LIST 中的时间是服务器本地时间，近期文件还省略了年份，
+/// 换算成 UTC 需要服务器时区和当前时间
+#[derive(Debug, Clone, Copy)]
+pub struct ListClock {
+    pub offset: FixedOffset,
+    pub now: DateTime<Utc>,
+}
+
+impl ListClock {
+    pub fn new(offset: FixedOffset) -> Self {
+        Self {
+            offset,
+            now: Utc::now(),
+        }
+    }
+
+    fn local_to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.offset
+            .from_local_datetime(&local)
+            .single()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// 省略年份的日期属于最近半年：落在服务器“今天”之后（留一天误差）的算作去年
+    fn infer_year(&self, month: u32, day: u32) -> Option<NaiveDate> {
+        let today = self.now.with_timezone(&self.offset).date_naive();
+        let this_year = NaiveDate::from_ymd_opt(today.year(), month, day);
+        match this_year {
+            Some(date) if date <= today + Duration::days(1) => Some(date),
+            _ => NaiveDate::from_ymd_opt(today.year() - 1, month, day),
+        }
+    }
+
+    /// `Jan 15 10:30` 或 `Jan 15 2023`
+    fn parse_month_day(
+        &self,
+        month: &str,
+        day: &str,
+        year_or_time: &str,
+    ) -> Option<DateTime<Utc>> {
+        let month = month_number(month)?;
+        let day: u32 = day.parse().ok()?;
+        let local = match year_or_time.split_once(':') {
+            Some((h, m)) => self
+                .infer_year(month, day)?
+                .and_hms_opt(h.parse().ok()?, m.parse().ok()?, 0)?,
+            None => NaiveDate::from_ymd_opt(year_or_time.parse().ok()?, month, day)?
+                .and_hms_opt(0, 0, 0)?,
+        };
+        self.local_to_utc(local)
+    }
+}
+
+/// FTP LIST 输出格式。LIST 没有标准格式，各服务器实现差异很大，
+/// 连接后根据第一次返回的内容自动识别，之后沿用同一解析器。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListFormat {
+    /// Unix `ls -l` 风格，绝大多数服务器
+    Unix,
+    /// Windows IIS / DOS 风格：`01-15-24  10:30AM  <DIR>  name`
+    Dos,
+    /// Easily Parsed LIST Format：`+i8388621.48594,m825718503,r,s280,\tname`
+    Eplf,
+    /// OpenVMS：`NAME.EXT;1  213/216  29-JAN-1996 03:33:12  [GRP,OWNER]  (RWED,RWED,,)`
+    Vms,
+    /// Novell NetWare：`d [R----F--] supervisor  512  Jan 16 18:53  name`
+    NetWare,
+}
+
+impl ListFormat {
+    /// 自动识别时的尝试顺序，特征越明确的格式越靠前
+    pub const ALL: [ListFormat; 5] = [
+        ListFormat::Eplf,
+        ListFormat::Unix,
+        ListFormat::Dos,
+        ListFormat::Vms,
+        ListFormat::NetWare,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListFormat::Unix => "unix",
+            ListFormat::Dos => "dos",
+            ListFormat::Eplf => "eplf",
+            ListFormat::Vms => "vms",
+            ListFormat::NetWare => "netware",
+        }
+    }
+
+    /// 解析单行，无法识别或应忽略的行（汇总行、`.`、`..`）返回 None
+    pub fn parse_line(
+        &self,
+        line: &str,
+        parent_path: &str,
+        clock: &ListClock,
+    ) -> Option<FileEntry> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let entry = match self {
+            ListFormat::Unix => parse_unix(line, parent_path, clock),
+            ListFormat::Dos => parse_dos(line, parent_path, clock),
+            ListFormat::Eplf => parse_eplf(line, parent_path),
+            ListFormat::Vms => parse_vms(line, parent_path, clock),
+            ListFormat::NetWare => parse_netware(line, parent_path, clock),
+        }?;
+        if entry.name.is_empty() || entry.name == "." || entry.name == ".." {
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// 按当前格式解析完整的 LIST 输出
+    pub fn parse_listing(
+        &self,
+        lines: &[String],
+        parent_path: &str,
+        clock: &ListClock,
+    ) -> Vec<FileEntry> {
+        let lines = match self {
+            ListFormat::Vms => join_wrapped_vms_lines(lines),
+            _ => lines.to_vec(),
+        };
+        lines
+            .iter()
+            .filter_map(|line| self.parse_line(line, parent_path, clock))
+            .collect()
+    }
+
+    fn recognized_lines(&self, lines: &[String]) -> usize {
+        let clock = ListClock::new(FixedOffset::east_opt(0).unwrap());
+        let lines = match self {
+            ListFormat::Vms => join_wrapped_vms_lines(lines),
+            _ => lines.to_vec(),
+        };
+        lines
+            .iter()
+            .filter(|line| self.parse_line(line, "/", &clock).is_some())
+            .count()
+    }
+}
+
+/// 选出能识别最多行的格式；没有任何格式能识别时返回 None
+pub fn detect(lines: &[String]) -> Option<ListFormat> {
+    let mut best: Option<(ListFormat, usize)> = None;
+    for format in ListFormat::ALL {
+        let count = format.recognized_lines(lines);
+        if count > 0 && best.is_none_or(|(_, c)| count > c) {
+            best = Some((format, count));
+        }
+    }
+    best.map(|(format, _)| format)
+}
+
+pub(crate) fn join_remote_path(parent_path: &str, name: &str) -> String {
+    if parent_path.ends_with('/') {
+        format!("{}{}", parent_path, name)
+    } else {
+        format!("{}/{}", parent_path, name)
+    }
+}
+
+/// 跳过前 n 个以空白分隔的字段，返回剩余部分（保留文件名中的连续空格）
+fn rest_after_fields(line: &str, n: usize) -> Option<&str> {
+    let mut rest = line.trim_start();
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace)?;
+        rest = rest[end..].trim_start();
+    }
+    if rest.is_empty() {
+        None
+    } else {
+        Some(rest)
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+fn is_month(s: &str) -> bool {
+    month_number(s).is_some()
+}
+
+fn month_number(s: &str) -> Option<u32> {
+    MONTHS
+        .iter()
+        .position(|m| s.eq_ignore_ascii_case(m))
+        .map(|i| i as u32 + 1)
+}
+
+/// 两位年份按 POSIX strptime 的规则：69-99 为 19xx，00-68 为 20xx
+fn expand_year(year: &str) -> Option<i32> {
+    let value: i32 = year.parse().ok()?;
+    match year.len() {
+        2 if value >= 69 => Some(1900 + value),
+        2 => Some(2000 + value),
+        4 => Some(value),
+        _ => None,
+    }
+}
+
+fn is_unix_mode(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some('-' | 'd' | 'l' | 'b' | 'c' | 'p' | 's'))
+        && chars
+            .take(9)
+            .filter(|c| matches!(c, 'r' | 'w' | 'x' | 's' | 'S' | 't' | 'T' | 'l' | '-'))
+            .count()
+            == 9
+}
+
+/// 把 `-rwxr-sr-t` 形式的权限串转换为权限位
+pub(crate) fn parse_mode_string(s: &str) -> Option<u32> {
+    let chars: Vec<char> = s.chars().skip(1).take(9).collect();
+    if chars.len() != 9 {
+        return None;
+    }
+    let mut mode = 0;
+    for (i, c) in chars.iter().enumerate() {
+        let shift = 8 - i as u32;
+        let special = match i {
+            2 => 0o4000,
+            5 => 0o2000,
+            8 => 0o1000,
+            _ => 0,
+        };
+        match c {
+            'r' | 'w' | 'x' => mode |= 1 << shift,
+            // s/t 表示同时带执行位，S/T 表示只有特殊位
+            's' | 't' => mode |= (1 << shift) | special,
+            'S' | 'T' => mode |= special,
+            '-' => {}
+            _ => return None,
+        }
+    }
+    Some(mode)
+}
+
+/// Unix `ls -l`：以月份字段定位日期，兼容缺少属组列的服务器
+fn parse_unix(line: &str, parent_path: &str, clock: &ListClock) -> Option<FileEntry> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 8 || !is_unix_mode(parts[0]) {
+        return None;
+    }
+    let month_idx = (3..parts.len() - 3).find(|&i| {
+        is_month(parts[i])
+            && parts[i - 1].parse::<u64>().is_ok()
+            && parts[i + 1].parse::<u8>().is_ok()
+    })?;
+
+    let rest = rest_after_fields(line, month_idx + 3)?;
+    let is_symlink = line.starts_with('l');
+    let (name, link_target) = match rest.split_once(" -> ") {
+        Some((name, target)) if is_symlink => (name.to_string(), Some(target.to_string())),
+        _ => (rest.to_string(), None),
+    };
+    let size: u64 = parts[month_idx - 1].parse().unwrap_or(0);
+    let path = join_remote_path(parent_path, &name);
+    // 权限、链接数之后依次为属主、属组，部分服务器不输出属组
+    let owner = (month_idx >= 4).then(|| parts[2].to_string());
+    let group = (month_idx >= 5).then(|| parts[3].to_string());
+
+    Some(FileEntry {
+        name,
+        path,
+        is_dir: line.starts_with('d'),
+        size,
+        modified: clock.parse_month_day(
+            parts[month_idx],
+            parts[month_idx + 1],
+            parts[month_idx + 2],
+        ),
+        permissions: Some(parts[0].to_string()),
+        is_symlink,
+        link_target,
+        mode: parse_mode_string(parts[0]),
+        uid: None,
+        gid: None,
+        owner,
+        group,
+    })
+}
+
+fn is_dos_date(s: &str) -> bool {
+    let fields: Vec<&str> = s.split(['-', '/']).collect();
+    fields.len() == 3
+        && fields
+            .iter()
+            .all(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_dos_time(s: &str) -> bool {
+    let upper = s.to_ascii_uppercase();
+    let clock = upper.trim_end_matches("AM").trim_end_matches("PM");
+    match clock.split_once(':') {
+        Some((h, m)) => {
+            !h.is_empty()
+                && h.chars().all(|c| c.is_ascii_digit())
+                && !m.is_empty()
+                && m.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// IIS 默认输出 `MM-DD-YY hh:mmAM`，开启四位年份后为 `MM-DD-YYYY HH:mm`
+fn parse_dos_time(date: &str, time: &str, clock: &ListClock) -> Option<DateTime<Utc>> {
+    let fields: Vec<&str> = date.split(['-', '/']).collect();
+    let (year, month, day) = if fields[0].len() == 4 {
+        (fields[0], fields[1], fields[2])
+    } else {
+        (fields[2], fields[0], fields[1])
+    };
+    let date =
+        NaiveDate::from_ymd_opt(expand_year(year)?, month.parse().ok()?, day.parse().ok()?)?;
+
+    let upper = time.to_ascii_uppercase();
+    let (clock_part, pm) = match (upper.strip_suffix("AM"), upper.strip_suffix("PM")) {
+        (Some(t), _) => (t.to_string(), Some(false)),
+        (_, Some(t)) => (t.to_string(), Some(true)),
+        _ => (upper, None),
+    };
+    let (h, m) = clock_part.split_once(':')?;
+    let mut hour: u32 = h.parse().ok()?;
+    match pm {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(pm) => hour = hour % 12 + if pm { 12 } else { 0 },
+        None => {}
+    }
+    clock.local_to_utc(date.and_hms_opt(hour, m.parse().ok()?, 0)?)
+}
+
+/// DOS / IIS：日期、时间、`<DIR>` 或文件大小、文件名
+fn parse_dos(line: &str, parent_path: &str, clock: &ListClock) -> Option<FileEntry> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 4 || !is_dos_date(parts[0]) || !is_dos_time(parts[1]) {
+        return None;
+    }
+
+    let is_dir = parts[2].eq_ignore_ascii_case("<DIR>");
+    let size = if is_dir {
+        0
+    } else {
+        parts[2].replace(',', "").parse::<u64>().ok()?
+    };
+    let name = rest_after_fields(line, 3)?.to_string();
+    let path = join_remote_path(parent_path, &name);
+
+    Some(FileEntry {
+        name,
+        path,
+        is_dir,
+        size,
+        modified: parse_dos_time(parts[0], parts[1], clock),
+        permissions: None,
+        is_symlink: false,
+        link_target: None,
+        mode: None,
+        uid: None,
+        gid: None,
+        owner: None,
+        group: None,
+    })
+}
+
+/// EPLF：`+` 开头，逗号分隔的事实，制表符后为文件名
+fn parse_eplf(line: &str, parent_path: &str) -> Option<FileEntry> {
+    let body = line.strip_prefix('+')?;
+    let (facts, name) = body.split_once('\t')?;
+
+    let mut is_dir = false;
+    let mut size = 0;
+    let mut modified = None;
+    let mut permissions = None;
+    for fact in facts.split(',') {
+        match fact.chars().next() {
+            Some('/') => is_dir = true,
+            Some('s') => size = fact[1..].parse().unwrap_or(0),
+            Some('m') => {
+                modified = fact[1..]
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|t| DateTime::from_timestamp(t, 0));
+            }
+            Some('u') if fact.starts_with("up") => permissions = Some(fact[2..].to_string()),
+            _ => {}
+        }
+    }
+
+    Some(FileEntry {
+        name: name.to_string(),
+        path: join_remote_path(parent_path, name),
+        is_dir,
+        size,
+        modified,
+        permissions,
+        is_symlink: false,
+        link_target: None,
+        mode: None,
+        uid: None,
+        gid: None,
+        owner: None,
+        group: None,
+    })
+}
+
+/// VMS 文件名过长时，其余字段会折到下一行，解析前先拼回一行
+fn join_wrapped_vms_lines(lines: &[String]) -> Vec<String> {
+    let mut joined = Vec::with_capacity(lines.len());
+    let mut pending: Option<String> = None;
+    for line in lines {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if let Some(name) = pending.take() {
+            joined.push(format!("{} {}", name, line.trim_start()));
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next()) {
+            (Some(name), None) if name.contains(';') => pending = Some(name.to_string()),
+            _ => joined.push(line.to_string()),
+        }
+    }
+    joined.extend(pending);
+    joined
+}
+
+/// VMS 的块大小固定为 512 字节
+const VMS_BLOCK_SIZE: u64 = 512;
+
+/// OpenVMS：`NAME.EXT;版本  已用块/分配块  日期 时间  [属主]  (权限)`
+fn parse_vms(line: &str, parent_path: &str, clock: &ListClock) -> Option<FileEntry> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 4 {
+        return None;
+    }
+    let (file, version) = parts[0].rsplit_once(';')?;
+    if file.is_empty() || version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let blocks: u64 = parts[1].split('/').next()?.parse().ok()?;
+    let date: Vec<&str> = parts[2].split('-').collect();
+    if date.len() != 3 {
+        return None;
+    }
+
+    let (name, is_dir) = match file.len().checked_sub(4) {
+        Some(i) if file[i..].eq_ignore_ascii_case(".DIR") => (&file[..i], true),
+        _ => (file, false),
+    };
+    // 时间可能带百分之一秒：`03:33:12.34`
+    let time = parts[3].split('.').next().unwrap_or(parts[3]);
+    let modified = NaiveDate::from_ymd_opt(
+        date[2].parse().ok()?,
+        month_number(date[1])?,
+        date[0].parse().ok()?,
+    )
+    .and_then(|d| {
+        let mut hms = time.split(':').map(|f| f.parse::<u32>().ok());
+        let (h, m, s) = (hms.next()??, hms.next()??, hms.next().flatten().unwrap_or(0));
+        d.and_hms_opt(h, m, s)
+    })
+    .and_then(|local| clock.local_to_utc(local));
+    let permissions = parts
+        .iter()
+        .find(|p| p.starts_with('(') && p.ends_with(')'))
+        .map(|p| p.to_string());
+
+    Some(FileEntry {
+        name: name.to_string(),
+        path: join_remote_path(parent_path, name),
+        is_dir,
+        size: if is_dir { 0 } else { blocks * VMS_BLOCK_SIZE },
+        modified,
+        permissions,
+        is_symlink: false,
+        link_target: None,
+        mode: None,
+        uid: None,
+        gid: None,
+        owner: None,
+        group: None,
+    })
+}
+
+/// NetWare：类型、`[权限]`、属主、大小、月 日 时间/年份、文件名
+fn parse_netware(line: &str, parent_path: &str, clock: &ListClock) -> Option<FileEntry> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 8 || !matches!(parts[0], "d" | "-") {
+        return None;
+    }
+    if !(parts[1].starts_with('[') && parts[1].ends_with(']')) {
+        return None;
+    }
+    let size: u64 = parts[3].parse().ok()?;
+    if !is_month(parts[4]) {
+        return None;
+    }
+
+    let name = rest_after_fields(line, 7)?.to_string();
+    let path = join_remote_path(parent_path, &name);
+
+    Some(FileEntry {
+        name,
+        path,
+        is_dir: parts[0] == "d",
+        size,
+        modified: clock.parse_month_day(parts[4], parts[5], parts[6]),
+        permissions: Some(parts[1].to_string()),
+        is_symlink: false,
+        link_target: None,
+        mode: None,
+        uid: None,
+        gid: None,
+        owner: None,
+        group: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(corpus: &str) -> Vec<String> {
+        corpus.lines().map(|l| l.to_string()).collect()
+    }
+
+    /// 固定“当前时间”为 2024-06-01，使省略年份的推断结果稳定
+    fn clock() -> ListClock {
+        ListClock {
+            offset: FixedOffset::east_opt(0).unwrap(),
+            now: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    fn utc(s: &str) -> Option<DateTime<Utc>> {
+        Some(DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc))
+    }
+
+    /// vsftpd / ProFTPD 实际输出
+    const UNIX_LISTING: &str = "\
+total 24
+drwxr-xr-x    2 ftp      ftp          4096 Mar 03 09:12 incoming
+-rw-r--r--    1 ftp      ftp       1048576 Jan 15  2023 release-1.0.tar.gz
+-rw-r--r--    1 ftp      ftp           512 Feb 29 23:59 two  spaces.txt
+lrwxrwxrwx    1 ftp      ftp            11 Jul 07 07:07 latest -> release-1.0
+drwxr-xr-x    2 ftp      ftp          4096 Mar 03 09:12 .
+drwxr-xr-x    2 ftp      ftp          4096 Mar 03 09:12 ..";
+
+    /// 不带属组列的服务器（部分嵌入式设备）
+    const UNIX_NO_GROUP_LISTING: &str = "\
+-rw-r--r--   1 owner     2048 Dec 31 2022 no-group.bin
+drwxr-xr-x   3 owner     4096 Nov 11 11:11 docs";
+
+    /// Windows IIS 10 默认（MS-DOS）目录格式
+    const IIS_LISTING: &str = "\
+01-15-24  10:30AM       <DIR>          Reports 2024
+01-16-24  02:05PM              1234567 annual report.pdf
+12-01-2023  23:15                  42 notes.txt
+03-04-24  09:00AM            1,024,000 big.iso";
+
+    /// 来自 D. J. Bernstein 的 EPLF 规范示例
+    const EPLF_LISTING: &str = "\
++i8388621.29609,m824255902,/,\tdev
++i8388621.44468,m839956783,r,s10376,\tRFCEPLF
++i8388621.48594,m825718503,r,s280,up644,\tdjb.html";
+
+    /// OpenVMS FTP 服务器输出，含头尾汇总行和折行的长文件名
+    const VMS_LISTING: &str = "\
+Directory DISK$USER:[ANONYMOUS]
+
+CII-MANUAL.TEX;1  213/216  29-JAN-1996 03:33:12  [ANONYMOU,ANONYMOUS]   (RWED,RWED,,)
+DOCS.DIR;1          1/3     7-FEB-2001 10:01:02.55  [SYSTEM]   (RWE,RWE,RE,RE)
+A_VERY_LONG_FILE_NAME_THAT_WRAPS.TXT;12
+                    4/6    17-MAR-2020 18:00:00  [ANONYMOU,ANONYMOUS]   (RWED,RWED,RE,)
+
+Total of 3 files, 218/225 blocks.";
+
+    /// Novell NetWare 4.x / 5.x 输出
+    const NETWARE_LISTING: &str = "\
+total 0
+d [R----F--] supervisor            512       Jan 16 18:53    login
+- [RWCEAFMS] rhesus             214059       Oct 20 15:27    cx.exe
+- [RWCEAFMS] rhesus                 12       Oct 20  1999    read me.txt";
+
+    #[test]
+    fn test_detect_formats() {
+        assert_eq!(detect(&lines(UNIX_LISTING)), Some(ListFormat::Unix));
+        assert_eq!(detect(&lines(UNIX_NO_GROUP_LISTING)), Some(ListFormat::Unix));
+        assert_eq!(detect(&lines(IIS_LISTING)), Some(ListFormat::Dos));
+        assert_eq!(detect(&lines(EPLF_LISTING)), Some(ListFormat::Eplf));
+        assert_eq!(detect(&lines(VMS_LISTING)), Some(ListFormat::Vms));
+        assert_eq!(detect(&lines(NETWARE_LISTING)), Some(ListFormat::NetWare));
+        assert_eq!(detect(&[]), None);
+        assert_eq!(detect(&lines("550 no files\ngarbage")), None);
+    }
+
+    #[test]
+    fn test_parse_unix_listing() {
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_LISTING), "/pub", &clock());
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["incoming", "release-1.0.tar.gz", "two  spaces.txt", "latest"]
+        );
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].size, 1048576);
+        assert_eq!(entries[1].modified, utc("2023-01-15T00:00:00Z"));
+        assert_eq!(entries[2].path, "/pub/two  spaces.txt");
+        assert_eq!(entries[2].permissions.as_deref(), Some("-rw-r--r--"));
+        assert!(!entries[2].is_symlink);
+        assert!(entries[3].is_symlink);
+        assert_eq!(entries[3].path, "/pub/latest");
+        assert_eq!(entries[3].link_target.as_deref(), Some("release-1.0"));
+    }
+
+    #[test]
+    fn test_unix_time_infers_year_and_applies_offset() {
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_LISTING), "/", &clock());
+        assert_eq!(entries[0].modified, utc("2024-03-03T09:12:00Z"));
+        assert_eq!(entries[2].modified, utc("2024-02-29T23:59:00Z"));
+        // 晚于“今天”的月日属于去年
+        assert_eq!(entries[3].modified, utc("2023-07-07T07:07:00Z"));
+
+        let east8 = ListClock {
+            offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+            ..clock()
+        };
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_LISTING), "/", &east8);
+        assert_eq!(entries[0].modified, utc("2024-03-03T01:12:00Z"));
+        assert_eq!(entries[1].modified, utc("2023-01-14T16:00:00Z"));
+    }
+
+    #[test]
+    fn test_parse_mode_string() {
+        assert_eq!(parse_mode_string("-rw-r--r--"), Some(0o644));
+        assert_eq!(parse_mode_string("drwxr-xr-x"), Some(0o755));
+        assert_eq!(parse_mode_string("-rwsr-sr-t"), Some(0o7755));
+        assert_eq!(parse_mode_string("drwxrwx--T"), Some(0o1770));
+        assert_eq!(parse_mode_string("-rwS------"), Some(0o4600));
+        assert_eq!(parse_mode_string("-rw-"), None);
+        assert_eq!(parse_mode_string("-rw-r--r-Q"), None);
+    }
+
+    #[test]
+    fn test_parse_unix_owner_and_group() {
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_LISTING), "/pub", &clock());
+        assert_eq!(entries[0].mode, Some(0o755));
+        assert_eq!(entries[0].owner.as_deref(), Some("ftp"));
+        assert_eq!(entries[0].group.as_deref(), Some("ftp"));
+
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_NO_GROUP_LISTING), "/", &clock());
+        assert_eq!(entries[0].owner.as_deref(), Some("owner"));
+        assert!(entries[0].group.is_none());
+    }
+
+    #[test]
+    fn test_parse_unix_arrow_in_regular_file_name() {
+        let line = "-rw-r--r--   1 user group   10 Jan 01 12:00 a -> b.txt";
+        let entry = ListFormat::Unix.parse_line(line, "/", &clock()).unwrap();
+        assert_eq!(entry.name, "a -> b.txt");
+        assert!(!entry.is_symlink);
+        assert!(entry.link_target.is_none());
+    }
+
+    #[test]
+    fn test_parse_unix_without_group() {
+        let entries = ListFormat::Unix.parse_listing(&lines(UNIX_NO_GROUP_LISTING), "/", &clock());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "no-group.bin");
+        assert_eq!(entries[0].size, 2048);
+        assert!(entries[1].is_dir);
+        assert_eq!(entries[1].path, "/docs");
+    }
+
+    #[test]
+    fn test_parse_unix_file() {
+        let line = "-rw-r--r--   1 user group   1024 Jan 01 12:00 test.txt";
+        let entry = ListFormat::Unix.parse_line(line, "/home", &clock()).unwrap();
+        assert_eq!(entry.name, "test.txt");
+        assert_eq!(entry.path, "/home/test.txt");
+        assert!(!entry.is_dir);
+        assert_eq!(entry.size, 1024);
+        assert_eq!(entry.permissions.as_deref(), Some("-rw-r--r--"));
+    }
+
+    #[test]
+    fn test_parse_unix_dir() {
+        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 subdir";
+        let entry = ListFormat::Unix.parse_line(line, "/home/", &clock()).unwrap();
+        assert_eq!(entry.name, "subdir");
+        assert_eq!(entry.path, "/home/subdir");
+        assert!(entry.is_dir);
+    }
+
+    #[test]
+    fn test_parse_unix_skips_dots_and_invalid() {
+        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 .";
+        assert!(ListFormat::Unix.parse_line(line, "/", &clock()).is_none());
+        let line = "drwxr-xr-x   2 user group   4096 Jan 01 12:00 ..";
+        assert!(ListFormat::Unix.parse_line(line, "/", &clock()).is_none());
+        assert!(ListFormat::Unix.parse_line("short line", "/", &clock()).is_none());
+    }
+
+    #[test]
+    fn test_parse_unix_filename_with_spaces() {
+        let line = "-rw-r--r--   1 user group   2048 Feb 15 09:30 my file name.txt";
+        let entry = ListFormat::Unix.parse_line(line, "/data", &clock()).unwrap();
+        assert_eq!(entry.name, "my file name.txt");
+        assert_eq!(entry.path, "/data/my file name.txt");
+        assert_eq!(entry.size, 2048);
+    }
+
+    #[test]
+    fn test_parse_iis_listing() {
+        let entries = ListFormat::Dos.parse_listing(&lines(IIS_LISTING), "/", &clock());
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].name, "Reports 2024");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].size, 0);
+        assert_eq!(entries[0].modified, utc("2024-01-15T10:30:00Z"));
+        assert_eq!(entries[1].modified, utc("2024-01-16T14:05:00Z"));
+        assert_eq!(entries[1].name, "annual report.pdf");
+        assert_eq!(entries[1].size, 1234567);
+        assert_eq!(entries[2].name, "notes.txt");
+        assert_eq!(entries[2].modified, utc("2023-12-01T23:15:00Z"));
+        assert_eq!(entries[3].size, 1024000);
+    }
+
+    #[test]
+    fn test_parse_eplf_listing() {
+        let entries = ListFormat::Eplf.parse_listing(&lines(EPLF_LISTING), "/pub", &clock());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "dev");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].modified, utc("1996-02-13T23:58:22Z"));
+        assert_eq!(entries[1].size, 10376);
+        assert_eq!(entries[2].path, "/pub/djb.html");
+        assert_eq!(entries[2].permissions.as_deref(), Some("644"));
+    }
+
+    #[test]
+    fn test_parse_vms_listing() {
+        let entries = ListFormat::Vms.parse_listing(&lines(VMS_LISTING), "/", &clock());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "CII-MANUAL.TEX");
+        assert_eq!(entries[0].size, 213 * 512);
+        assert_eq!(entries[0].modified, utc("1996-01-29T03:33:12Z"));
+        assert_eq!(entries[0].permissions.as_deref(), Some("(RWED,RWED,,)"));
+        assert_eq!(entries[1].name, "DOCS");
+        assert!(entries[1].is_dir);
+        assert_eq!(entries[1].modified, utc("2001-02-07T10:01:02Z"));
+        assert_eq!(entries[2].name, "A_VERY_LONG_FILE_NAME_THAT_WRAPS.TXT");
+        assert_eq!(entries[2].size, 4 * 512);
+    }
+
+    #[test]
+    fn test_parse_netware_listing() {
+        let entries =
+            ListFormat::NetWare.parse_listing(&lines(NETWARE_LISTING), "/sys", &clock());
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "login");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].size, 214059);
+        assert_eq!(entries[1].permissions.as_deref(), Some("[RWCEAFMS]"));
+        assert_eq!(entries[2].name, "read me.txt");
+        assert_eq!(entries[2].path, "/sys/read me.txt");
+        assert_eq!(entries[2].modified, utc("1999-10-20T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_parsers_reject_other_formats() {
+        for (format, corpus) in [
+            (ListFormat::Unix, IIS_LISTING),
+            (ListFormat::Dos, UNIX_LISTING),
+            (ListFormat::Eplf, NETWARE_LISTING),
+            (ListFormat::NetWare, UNIX_LISTING),
+            (ListFormat::Vms, IIS_LISTING),
+        ] {
+            assert!(
+                format.parse_listing(&lines(corpus), "/", &clock()).is_empty(),
+                "{} 不应识别其他格式",
+                format.as_str()
+            );
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! 批量导入主机：校验、查重，并按跳板机依赖顺序写入数据库
+
+use rusqlite::Connection;
+
+use crate::db::{bookmark_repo, host_repo};
+use crate::models::bookmark::DirectoryBookmark;
+use crate::models::host::Host;
+use crate::models::host_import::{HostImportEntry, HostImportResult, HostImportStatus};
+use crate::validation::host::validate_host;
+
+/// 地址（不区分大小写）、端口、用户名和协议都相同即视为同一台主机
+fn same_host(a: &Host, b: &Host) -> bool {
+    a.host.trim().eq_ignore_ascii_case(b.host.trim())
+        && a.port == b.port
+        && a.username.trim() == b.username.trim()
+        && a.protocol == b.protocol
+}
+
+/// 站点管理器允许同名站点，给重名的记录加上序号，保证 source 可作为唯一标识
+pub fn dedupe_sources(entries: &mut [HostImportEntry]) {
+    for i in 1..entries.len() {
+        let base = entries[i].source.clone();
+        let mut n = 1;
+        while entries[..i].iter().any(|e| e.source == entries[i].source) {
+            n += 1;
+            entries[i].source = format!("{} ({})", base, n);
+        }
+    }
+}
+
+/// 逐条校验并查重；跳板机指向重复记录时直接改用已保存主机的 id
+pub fn review(entries: &mut [HostImportEntry], existing: &[Host]) {
+    for entry in entries.iter_mut() {
+        if entry.unsupported.is_some() {
+            continue;
+        }
+        entry.error = validate_host(&entry.host).err();
+        entry.duplicate_of = existing
+            .iter()
+            .find(|h| same_host(h, &entry.host))
+            .and_then(|h| h.id);
+    }
+    for i in 0..entries.len() {
+        let Some(ref source) = entries[i].jump_source else {
+            continue;
+        };
+        match entries.iter().find(|e| &e.source == source) {
+            Some(jump) => {
+                if let Some(id) = jump.duplicate_of {
+                    entries[i].host.jump_host_id = Some(id);
+                    entries[i].jump_source = None;
+                }
+            }
+            None => {
+                if entries[i].error.is_none() {
+                    entries[i].error = Some(format!("跳板机 {} 未一起导入", source));
+                }
+            }
+        }
+    }
+}
+
+/// 逐条导入选中的记录并返回每条的结果；跳板机先于引用它的主机写入，
+/// 站点自带的默认目录保存为目录书签
+pub fn import_entries(
+    conn: &Connection,
+    mut entries: Vec<HostImportEntry>,
+    encryption_key: Option<&[u8; 32]>,
+) -> Result<Vec<HostImportResult>, String> {
+    let existing = host_repo::get_all(conn, encryption_key)?;
+    review(&mut entries, &existing);
+
+    let mut results: Vec<Option<HostImportResult>> = vec![None; entries.len()];
+    for i in 0..entries.len() {
+        let entry = &entries[i];
+        let skip = |message: String| {
+            HostImportResult::skipped(entry.source.clone(), HostImportStatus::Skipped, message)
+        };
+        results[i] = if let Some(ref reason) = entry.unsupported {
+            Some(HostImportResult::skipped(
+                entry.source.clone(),
+                HostImportStatus::Unsupported,
+                reason.clone(),
+            ))
+        } else if let Some(ref error) = entry.error {
+            Some(skip(error.clone()))
+        } else if entry.duplicate_of.is_some() {
+            Some(skip("与已保存的主机重复".to_string()))
+        } else if (0..i).any(|j| results[j].is_none() && same_host(&entries[j].host, &entry.host)) {
+            Some(skip("与本次导入的其他记录重复".to_string()))
+        } else {
+            None
+        };
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    loop {
+        let mut progressed = false;
+        for i in 0..entries.len() {
+            if results[i].is_some() {
+                continue;
+            }
+            let mut host = entries[i].host.clone();
+            host.id = None;
+            if let Some(ref source) = entries[i].jump_source {
+                let jump = entries.iter().position(|e| &e.source == source);
+                match jump.and_then(|j| results[j].as_ref()) {
+                    None => continue,
+                    Some(r) if r.status == HostImportStatus::Imported => {
+                        host.jump_host_id = r.host_id
+                    }
+                    Some(_) => {
+                        results[i] = Some(HostImportResult::skipped(
+                            entries[i].source.clone(),
+                            HostImportStatus::Skipped,
+                            format!("跳板机 {} 未导入", source),
+                        ));
+                        progressed = true;
+                        continue;
+                    }
+                }
+            }
+            progressed = true;
+            results[i] = Some(match host_repo::insert(&tx, &host, encryption_key) {
+                Ok(created) => {
+                    insert_bookmark(&tx, &entries[i], created.id)?;
+                    HostImportResult::imported(entries[i].source.clone(), created.id)
+                }
+                Err(e) => HostImportResult::skipped(
+                    entries[i].source.clone(),
+                    HostImportStatus::Skipped,
+                    e,
+                ),
+            });
+        }
+        if !progressed {
+            break;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(entries
+        .iter()
+        .zip(results)
+        .map(|(entry, result)| {
+            result.unwrap_or_else(|| {
+                HostImportResult::skipped(
+                    entry.source.clone(),
+                    HostImportStatus::Skipped,
+                    "跳板机配置存在循环引用".to_string(),
+                )
+            })
+        })
+        .collect())
+}
+
+fn insert_bookmark(
+    conn: &Connection,
+    entry: &HostImportEntry,
+    host_id: Option<i64>,
+) -> Result<(), String> {
+    let Some(host_id) = host_id else {
+        return Ok(());
+    };
+    if entry.remote_dir.is_none() && entry.local_dir.is_none() {
+        return Ok(());
+    }
+    let mut bookmark = DirectoryBookmark::new(host_id, "默认目录".to_string());
+    bookmark.remote_dir = entry.remote_dir.clone();
+    bookmark.local_dir = entry.local_dir.clone();
+    bookmark_repo::insert(conn, &bookmark).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations;
+    use crate::models::host::Protocol;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys=ON;").unwrap();
+        migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    fn entry(source: &str, address: &str) -> HostImportEntry {
+        let host = Host::new(
+            source.into(),
+            address.into(),
+            22,
+            Protocol::Sftp,
+            "ops".into(),
+        );
+        HostImportEntry::new(source.into(), host)
+    }
+
+    #[test]
+    fn test_review_detects_duplicates_and_errors() {
+        let conn = setup_db();
+        let saved = host_repo::insert(&conn, &entry("old", "10.0.0.1").host, None).unwrap();
+
+        let mut app = entry("app", "10.0.0.2");
+        app.jump_source = Some("bastion".into());
+        let mut broken = entry("broken", "10.0.0.3");
+        broken.host.username = String::new();
+        let mut orphan = entry("orphan", "10.0.0.4");
+        orphan.jump_source = Some("missing".into());
+        let mut entries = vec![entry("bastion", "10.0.0.1"), app, broken, orphan];
+
+        review(&mut entries, std::slice::from_ref(&saved));
+        assert_eq!(entries[0].duplicate_of, saved.id);
+        // 跳板机已保存过，直接引用已有主机
+        assert_eq!(entries[1].host.jump_host_id, saved.id);
+        assert_eq!(entries[1].jump_source, None);
+        assert!(entries[2].error.is_some());
+        assert!(entries[3].error.as_deref().unwrap().contains("missing"));
+    }
+
+    #[test]
+    fn test_import_entries_inserts_jump_hosts_first() {
+        let conn = setup_db();
+        let mut app = entry("app", "10.0.0.2");
+        app.jump_source = Some("bastion".into());
+        app.remote_dir = Some("/srv/app".into());
+        let results = import_entries(&conn, vec![app, entry("bastion", "10.0.0.1")], None).unwrap();
+        assert!(results
+            .iter()
+            .all(|r| r.status == HostImportStatus::Imported));
+        let app = host_repo::get_by_id(&conn, results[0].host_id.unwrap(), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(app.jump_host_id, results[1].host_id);
+
+        let bookmarks = bookmark_repo::get_by_host(&conn, app.id.unwrap()).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].remote_dir.as_deref(), Some("/srv/app"));
+    }
+
+    #[test]
+    fn test_import_entries_reports_each_entry() {
+        let conn = setup_db();
+        host_repo::insert(&conn, &entry("old", "10.0.0.1").host, None).unwrap();
+        let mut s3 = entry("s3", "s3.amazonaws.com");
+        s3.unsupported = Some("不支持 S3 协议".into());
+        let mut a = entry("a", "10.0.0.5");
+        a.jump_source = Some("b".into());
+        let mut b = entry("b", "10.0.0.6");
+        b.jump_source = Some("a".into());
+        let mut behind_dup = entry("behind", "10.0.0.7");
+        behind_dup.jump_source = Some("again".into());
+
+        let results = import_entries(
+            &conn,
+            vec![
+                entry("new", "10.0.0.9"),
+                entry("dup", "10.0.0.1"),
+                s3,
+                a,
+                b,
+                entry("again", "10.0.0.9"),
+                behind_dup,
+            ],
+            None,
+        )
+        .unwrap();
+        let statuses: Vec<HostImportStatus> = results.iter().map(|r| r.status).collect();
+        use HostImportStatus::*;
+        assert_eq!(
+            statuses,
+            vec![
+                Imported,
+                Skipped,
+                Unsupported,
+                Skipped,
+                Skipped,
+                Skipped,
+                Skipped
+            ]
+        );
+        assert!(results[3].message.as_deref().unwrap().contains("循环"));
+        assert!(results[6].message.as_deref().unwrap().contains("again"));
+        assert_eq!(host_repo::get_all(&conn, None).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,172 @@
+//! SSH 主机密钥的指纹计算、首次连接信任（TOFU）与校验
+
+use base64::{
+    engine::general_purpose::{STANDARD as BASE64, STANDARD_NO_PAD},
+    Engine,
+};
+use sha2::{Digest, Sha256};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+use std::net::TcpStream;
+
+use crate::models::host_key::{HostKeyInfo, KnownHostKey};
+
+const PROBE_TIMEOUT_MS: u32 = 15_000;
+
+/// 与 `ssh-keygen -l` 一致的 SHA-256 指纹
+pub fn fingerprint_sha256(key: &[u8]) -> String {
+    format!("SHA256:{}", STANDARD_NO_PAD.encode(Sha256::digest(key)))
+}
+
+/// 公钥线格式以算法名开头（4 字节大端长度 + 名称）
+pub fn key_type_name(key: &[u8]) -> Result<String, String> {
+    let invalid = || "主机密钥格式无效".to_string();
+    let len_bytes: [u8; 4] = key
+        .get(..4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(invalid)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    let name = key.get(4..4 + len).ok_or_else(invalid)?;
+    let name = std::str::from_utf8(name).map_err(|_| invalid())?;
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    Ok(name.to_string())
+}
+
+/// 由服务器出示的公钥构造展示信息
+pub fn inspect_host_key(host: &str, port: u16, key: &[u8]) -> Result<HostKeyInfo, String> {
+    Ok(HostKeyInfo {
+        host: host.to_string(),
+        port,
+        key_type: key_type_name(key)?,
+        fingerprint: fingerprint_sha256(key),
+        key: BASE64.encode(key),
+    })
+}
+
+/// 解析前端回传的 base64 公钥
+pub fn decode_host_key(encoded: &str) -> Result<Vec<u8>, String> {
+    BASE64
+        .decode(encoded.trim())
+        .map_err(|e| format!("主机密钥解码失败: {}", e))
+}
+
+/// 在已建立的连接上完成 SSH 握手后取回 host:port 的主机密钥，不做认证，仅用于展示与信任
+pub fn fetch_ssh_host_key(tcp: TcpStream, host: &str, port: u16) -> Result<HostKeyInfo, String> {
+    let mut session = Session::new().map_err(|e| e.to_string())?;
+    session.set_timeout(PROBE_TIMEOUT_MS);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(|e| e.to_string())?;
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| "无法获取主机密钥".to_string())?;
+    let info = inspect_host_key(host, port, key);
+    let _ = session.disconnect(None, "bye", None);
+    info
+}
+
+/// known_hosts 中非 22 端口的条目写作 `[host]:port`
+fn known_hosts_name(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{}]:{}", host, port)
+    }
+}
+
+fn check_openssh_known_hosts(
+    session: &Session,
+    host: &str,
+    port: u16,
+    key: &[u8],
+) -> Result<CheckResult, String> {
+    let mut known_hosts = session.known_hosts().map_err(|e| e.to_string())?;
+    if let Some(home) = dirs::home_dir() {
+        let path = home.join(".ssh").join("known_hosts");
+        if path.exists() {
+            let _ = known_hosts.read_file(&path, KnownHostFileKind::OpenSSH);
+        }
+    }
+    Ok(known_hosts.check(&known_hosts_name(host, port), key))
+}
+
+/// 校验服务器主机密钥。应用内已保存该地址的密钥时以它为准（便于密钥轮换后替换），
+/// 否则查 ~/.ssh/known_hosts；两处都没有记录时返回指纹，由用户确认后调用信任命令
+pub fn verify_host_key(
+    session: &Session,
+    host: &str,
+    port: u16,
+    trusted: Option<&KnownHostKey>,
+) -> Result<(), String> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| "无法获取主机密钥".to_string())?;
+    let presented = inspect_host_key(host, port, key)?;
+
+    if let Some(trusted) = trusted {
+        if trusted.key == presented.key {
+            return Ok(());
+        }
+        return Err(format!(
+            "主机密钥与已信任的密钥不一致，可能存在中间人攻击。已信任指纹: {} {}，服务器出示指纹: {} {}",
+            trusted.key_type, trusted.fingerprint, presented.key_type, presented.fingerprint
+        ));
+    }
+
+    match check_openssh_known_hosts(session, host, port, key)? {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(format!(
+            "主机密钥与 ~/.ssh/known_hosts 中 {} 的记录不一致，可能存在中间人攻击。服务器出示指纹: {} {}",
+            known_hosts_name(host, port),
+            presented.key_type,
+            presented.fingerprint
+        )),
+        CheckResult::NotFound => Err(format!(
+            "主机 {} 的密钥尚未受信任（{} {}），请核对指纹后信任该主机",
+            known_hosts_name(host, port),
+            presented.key_type,
+            presented.fingerprint
+        )),
+        CheckResult::Failure => Err("主机密钥验证失败".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ssh-ed25519 公钥，公钥部分为 0..32
+    const SAMPLE_KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f";
+
+    #[test]
+    fn test_fingerprint_matches_ssh_keygen() {
+        let key = decode_host_key(SAMPLE_KEY).unwrap();
+        assert_eq!(
+            fingerprint_sha256(&key),
+            "SHA256:ZkAslGjFiUHdGf/WUL8rQvkib4PTvQatUV0OUQSncCA"
+        );
+    }
+
+    #[test]
+    fn test_inspect_host_key() {
+        let key = decode_host_key(SAMPLE_KEY).unwrap();
+        let info = inspect_host_key("sftp.example.com", 2222, &key).unwrap();
+        assert_eq!(info.key_type, "ssh-ed25519");
+        assert_eq!(info.key, SAMPLE_KEY);
+        assert_eq!(info.port, 2222);
+    }
+
+    #[test]
+    fn test_key_type_name_rejects_truncated() {
+        assert!(key_type_name(&[]).is_err());
+        assert!(key_type_name(&[0, 0, 0, 11, b's', b's', b'h']).is_err());
+        assert!(key_type_name(&[0, 0, 0, 0]).is_err());
+        assert!(decode_host_key("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_known_hosts_name() {
+        assert_eq!(known_hosts_name("example.com", 22), "example.com");
+        assert_eq!(known_hosts_name("example.com", 2222), "[example.com]:2222");
+    }
+}
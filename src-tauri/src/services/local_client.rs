@@ -0,0 +1,428 @@
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+
+use super::connection::{copy_download, ConnectionTrait, FileEntry};
+
+#[cfg(unix)]
+fn unix_ownership(metadata: &std::fs::Metadata) -> (Option<u32>, Option<u32>, Option<u32>) {
+    use std::os::unix::fs::MetadataExt;
+    (
+        Some(metadata.mode() & 0o7777),
+        Some(metadata.uid()),
+        Some(metadata.gid()),
+    )
+}
+
+#[cfg(not(unix))]
+fn unix_ownership(_metadata: &std::fs::Metadata) -> (Option<u32>, Option<u32>, Option<u32>) {
+    (None, None, None)
+}
+
+/// 由本地路径构造条目；链接按目标展示类型和大小，悬空链接保留链接自身的信息
+fn local_entry(path: &Path, name: String) -> Result<FileEntry, String> {
+    let link_metadata = std::fs::symlink_metadata(path).map_err(|e| e.to_string())?;
+    let is_symlink = link_metadata.file_type().is_symlink();
+    let link_target = if is_symlink {
+        std::fs::read_link(path)
+            .ok()
+            .map(|t| t.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    let metadata = if is_symlink {
+        std::fs::metadata(path).unwrap_or(link_metadata)
+    } else {
+        link_metadata
+    };
+    let (mode, uid, gid) = unix_ownership(&metadata);
+
+    Ok(FileEntry {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_dir: metadata.is_dir(),
+        size: metadata.len(),
+        modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+        permissions: None,
+        is_symlink,
+        link_target,
+        mode,
+        uid,
+        gid,
+        owner: None,
+        group: None,
+    })
+}
+
+/// 列出本地目录，条目路径为完整的本地路径
+pub fn read_dir_entries(dir: &Path) -> Result<Vec<FileEntry>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        files.push(local_entry(&entry.path(), name)?);
+    }
+    Ok(files)
+}
+
+/// 按偏移续传复制文件，进度回调与其他客户端一致为 (offset + 已复制, 总大小)
+fn copy_file(
+    src: &Path,
+    dst: &Path,
+    offset: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<u64, String> {
+    let total_size = std::fs::metadata(src).map_err(|e| e.to_string())?.len();
+    let mut reader = open_at(src, offset)?;
+    let mut writer = create_at(dst, offset)?;
+    copy_download(&mut reader, &mut writer, offset, total_size, progress).map_err(|e| e.to_string())
+}
+
+/// 打开文件并定位到 offset 处读取
+fn open_at(path: &Path, offset: u64) -> Result<File, String> {
+    let mut f = File::open(path).map_err(|e| e.to_string())?;
+    if offset > 0 {
+        f.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    }
+    Ok(f)
+}
+
+/// 续传时打开已有文件定位到 offset 处写入，否则新建（截断）文件
+fn create_at(path: &Path, offset: u64) -> Result<File, String> {
+    if offset == 0 {
+        return File::create(path).map_err(|e| e.to_string());
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    f.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    Ok(f)
+}
+
+/// 本地磁盘上的目录（如挂载的网络共享）作为“远程”端：远程路径 `/` 对应 root，
+/// 与其他协议共用传输队列、进度、续传和历史记录
+pub struct LocalClient {
+    root: PathBuf,
+    connected: bool,
+}
+
+impl LocalClient {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            connected: false,
+        }
+    }
+
+    /// 远程路径映射到 root 下的本地路径，不允许通过 `..` 或盘符跳出 root
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.connected {
+            return Err("Not connected".to_string());
+        }
+        let mut full = self.root.clone();
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(part) => full.push(part),
+                Component::RootDir | Component::CurDir => {}
+                _ => return Err(format!("路径超出本地目录范围: {}", path)),
+            }
+        }
+        Ok(full)
+    }
+
+    fn remote_entry(&self, path: &str) -> Result<FileEntry, String> {
+        let full = self.resolve(path)?;
+        let name = full
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        Ok(FileEntry {
+            path: path.to_string(),
+            ..local_entry(&full, name)?
+        })
+    }
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+impl ConnectionTrait for LocalClient {
+    fn connect(&mut self) -> Result<(), String> {
+        if !self.root.is_dir() {
+            return Err(format!("本地目录不存在: {}", self.root.display()));
+        }
+        self.connected = true;
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<(), String> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+        let mut entries = read_dir_entries(&self.resolve(path)?)?;
+        for entry in &mut entries {
+            entry.path = join_path(path, &entry.name);
+        }
+        Ok(entries)
+    }
+
+    fn file_size(&mut self, path: &str) -> Result<u64, String> {
+        Ok(std::fs::metadata(self.resolve(path)?)
+            .map_err(|e| e.to_string())?
+            .len())
+    }
+
+    fn file_exists(&mut self, path: &str) -> Result<bool, String> {
+        match std::fs::symlink_metadata(self.resolve(path)?) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn upload(
+        &mut self,
+        local_path: &str,
+        remote_path: &str,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        copy_file(
+            Path::new(local_path),
+            &self.resolve(remote_path)?,
+            offset,
+            progress,
+        )
+    }
+
+    fn download(
+        &mut self,
+        remote_path: &str,
+        local_path: &str,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        copy_file(
+            &self.resolve(remote_path)?,
+            Path::new(local_path),
+            offset,
+            progress,
+        )
+    }
+
+    fn download_to(
+        &mut self,
+        remote_path: &str,
+        writer: &mut dyn Write,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let path = self.resolve(remote_path)?;
+        let total_size = std::fs::metadata(&path).map_err(|e| e.to_string())?.len();
+        let mut reader = open_at(&path, offset)?;
+        copy_download(&mut reader, writer, offset, total_size, progress).map_err(|e| e.to_string())
+    }
+
+    fn upload_from(
+        &mut self,
+        reader: &mut dyn Read,
+        remote_path: &str,
+        offset: u64,
+        total: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let mut writer = create_at(&self.resolve(remote_path)?, offset)?;
+        copy_download(reader, &mut writer, offset, total, progress).map_err(|e| e.to_string())
+    }
+
+    fn can_append(&self) -> bool {
+        true
+    }
+
+    fn copy(&mut self, from: &str, to: &str) -> Result<(), String> {
+        std::fs::copy(self.resolve(from)?, self.resolve(to)?)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), String> {
+        std::fs::create_dir(self.resolve(path)?).map_err(|e| e.to_string())
+    }
+
+    fn remove_file(&mut self, path: &str) -> Result<(), String> {
+        std::fs::remove_file(self.resolve(path)?).map_err(|e| e.to_string())
+    }
+
+    fn remove_dir(&mut self, path: &str) -> Result<(), String> {
+        std::fs::remove_dir(self.resolve(path)?).map_err(|e| e.to_string())
+    }
+
+    /// std::fs::rename 在 Unix 上会覆盖已存在的目标，先检查以与 SFTP 行为一致
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let to = self.resolve(to)?;
+        if std::fs::symlink_metadata(&to).is_ok() {
+            return Err("目标已存在".to_string());
+        }
+        std::fs::rename(self.resolve(from)?, to).map_err(|e| e.to_string())
+    }
+
+    /// 链接目标原样写入，相对路径相对于链接所在目录
+    fn create_symlink(&mut self, target: &str, path: &str) -> Result<(), String> {
+        let link = self.resolve(path)?;
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+        }
+        #[cfg(windows)]
+        {
+            let target_is_dir = link
+                .parent()
+                .map(|p| p.join(target).is_dir())
+                .unwrap_or(false);
+            if target_is_dir {
+                std::os::windows::fs::symlink_dir(target, link)
+            } else {
+                std::os::windows::fs::symlink_file(target, link)
+            }
+            .map_err(|e| e.to_string())
+        }
+    }
+
+    fn stat(&mut self, path: &str) -> Result<FileEntry, String> {
+        self.remote_entry(path)
+    }
+
+    #[cfg(unix)]
+    fn set_permissions(&mut self, path: &str, mode: u32) -> Result<(), String> {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(
+            self.resolve(path)?,
+            std::fs::Permissions::from_mode(mode & 0o7777),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(unix))]
+    fn set_permissions(&mut self, _path: &str, _mode: u32) -> Result<(), String> {
+        Err("当前系统不支持修改权限".to_string())
+    }
+
+    #[cfg(unix)]
+    fn set_owner(&mut self, path: &str, uid: Option<u32>, gid: Option<u32>) -> Result<(), String> {
+        std::os::unix::fs::chown(self.resolve(path)?, uid, gid).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(unix))]
+    fn set_owner(
+        &mut self,
+        _path: &str,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+    ) -> Result<(), String> {
+        Err("当前系统不支持修改属主".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connected_client(root: &Path) -> LocalClient {
+        let mut client = LocalClient::new(root);
+        client.connect().unwrap();
+        client
+    }
+
+    #[test]
+    fn test_paths_stay_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = connected_client(dir.path());
+        assert_eq!(
+            client.resolve("/a/./b").unwrap(),
+            dir.path().join("a").join("b")
+        );
+        assert!(client.resolve("/a/../../etc/passwd").is_err());
+        assert!(client.list_dir("/..").is_err());
+
+        client.disconnect().unwrap();
+        assert!(client.resolve("/").is_err());
+        assert!(LocalClient::new(dir.path().join("missing"))
+            .connect()
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_dir_and_file_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = connected_client(dir.path());
+        client.mkdir("/docs").unwrap();
+        std::fs::write(dir.path().join("docs/a.txt"), "hello").unwrap();
+
+        let entries = client.list_dir("/docs").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "/docs/a.txt");
+        assert_eq!(entries[0].size, 5);
+        assert_eq!(client.stat("/docs").unwrap().path, "/docs");
+        assert!(client.stat("/docs").unwrap().is_dir);
+
+        std::fs::write(dir.path().join("docs/b.txt"), "").unwrap();
+        assert!(client.rename("/docs/a.txt", "/docs/b.txt").is_err());
+        client.rename("/docs/a.txt", "/docs/c.txt").unwrap();
+        assert!(client.file_exists("/docs/c.txt").unwrap());
+        assert!(!client.file_exists("/docs/a.txt").unwrap());
+        assert!(client.remove_dir("/docs").is_err());
+
+        client.copy("/docs/c.txt", "/docs/b.txt").unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join("docs/b.txt")).unwrap(),
+            b"hello"
+        );
+        assert!(client.file_exists("/docs/c.txt").unwrap());
+        assert!(client.copy("/docs/missing", "/docs/d.txt").is_err());
+    }
+
+    #[test]
+    fn test_resumed_upload_and_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = connected_client(dir.path());
+        let local = dir.path().join("local.bin");
+        std::fs::write(&local, b"0123456789").unwrap();
+        std::fs::write(dir.path().join("remote.bin"), b"0123").unwrap();
+
+        let reported = std::cell::Cell::new(0);
+        let progress = |done: u64, total: u64| {
+            assert_eq!(total, 10);
+            reported.set(done);
+        };
+        let n = client
+            .upload(local.to_str().unwrap(), "/remote.bin", 4, Some(&progress))
+            .unwrap();
+        assert_eq!(n, 6);
+        assert_eq!(reported.get(), 10);
+        assert_eq!(
+            std::fs::read(dir.path().join("remote.bin")).unwrap(),
+            b"0123456789"
+        );
+
+        let copy = dir.path().join("copy.bin");
+        let n = client
+            .download("/remote.bin", copy.to_str().unwrap(), 0, None)
+            .unwrap();
+        assert_eq!(n, 10);
+        assert_eq!(std::fs::read(copy).unwrap(), b"0123456789");
+    }
+}
@@ -1,5 +1,25 @@
+pub mod auth_prompt;
+pub mod certificate;
 pub mod connection;
+pub mod file_op;
+pub mod filezilla;
+pub mod ftp_active;
 pub mod ftp_client;
+pub mod ftp_list;
+pub mod host_import;
+pub mod host_key;
+pub mod local_client;
+pub mod port_forward;
+pub mod proxy;
+pub mod remote_exec;
+pub mod remote_transfer;
 pub mod resume;
+pub mod s3_client;
+pub mod scp;
 pub mod sftp_client;
+pub mod ssh_config;
+pub mod ssh_tunnel;
+pub mod terminal;
 pub mod transfer_engine;
+pub mod webdav_client;
+pub mod winscp;
@@ -0,0 +1,575 @@
+//! SSH 端口转发：在已连接 SFTP 主机的会话上运行本地转发（direct-tcpip）和远程转发（tcpip-forward）。
+//! 每条转发由一个线程轮询监听端和全部连接，通道读写只在短暂持有连接锁时以非阻塞模式进行，
+//! 与浏览、传输共用同一连接
+
+use ssh2::{Channel, Listener, Session};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::connection::{ConnectionTrait, CHUNK_SIZE};
+use super::remote_exec::with_nonblocking;
+use super::ssh_tunnel::{flush_some, read_some};
+use crate::models::port_forward::{ForwardKind, PortForward, PortForwardStatus};
+
+/// 所有连接都没有数据时的等待间隔
+const IDLE_WAIT: Duration = Duration::from_millis(5);
+/// 远程转发连接本机一侧目标的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type SharedConnection = Arc<Mutex<Box<dyn ConnectionTrait>>>;
+
+#[derive(Default)]
+struct ForwardStats {
+    running: AtomicBool,
+    /// 0 表示尚未监听
+    bound_port: AtomicU32,
+    active_connections: AtomicU32,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    /// 转发终止的原因，或运行中最近一次单个连接失败的原因
+    error: Mutex<Option<String>>,
+}
+
+impl ForwardStats {
+    fn set_error(&self, error: String) {
+        if let Ok(mut e) = self.error.lock() {
+            *e = Some(error);
+        }
+    }
+
+    fn snapshot(&self, forward_id: i64) -> PortForwardStatus {
+        PortForwardStatus {
+            forward_id,
+            running: self.running.load(Ordering::Relaxed),
+            bound_port: match self.bound_port.load(Ordering::Relaxed) {
+                0 => None,
+                port => Some(port as u16),
+            },
+            active_connections: self.active_connections.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            error: self.error.lock().ok().and_then(|e| e.clone()),
+        }
+    }
+}
+
+struct ForwardEntry {
+    host_id: i64,
+    stop: Arc<AtomicBool>,
+    stats: Arc<ForwardStats>,
+}
+
+/// 转发的监听端
+enum Source {
+    Local(TcpListener),
+    Remote(Listener),
+}
+
+/// 被转发连接的服务器一端；测试中以本机套接字代替 SSH 通道
+trait RemoteEnd: Read + Write {
+    /// 读到 0 字节时判断服务器是否已发送 EOF
+    fn eof(&self) -> bool;
+    /// 本机一侧不会再发送数据，把 EOF 转给服务器
+    fn send_eof(&mut self) -> std::io::Result<()>;
+}
+
+impl RemoteEnd for Channel {
+    fn eof(&self) -> bool {
+        Channel::eof(self)
+    }
+
+    fn send_eof(&mut self) -> std::io::Result<()> {
+        Channel::send_eof(self).map_err(std::io::Error::from)
+    }
+}
+
+/// 一条被转发的连接：本机套接字与 SSH 通道之间的双向缓冲。
+/// 一方关闭写入后另一方向照常转发，积压的数据送达后才把关闭传给对方
+struct Pipe<C: RemoteEnd = Channel> {
+    socket: TcpStream,
+    channel: C,
+    to_remote: Vec<u8>,
+    to_local: Vec<u8>,
+    /// 本机一侧已关闭写入
+    local_eof: bool,
+    /// 服务器一侧已关闭写入
+    remote_eof: bool,
+    /// 已把本机一侧的关闭转给通道
+    remote_eof_sent: bool,
+    /// 已把服务器一侧的关闭转给本机套接字
+    local_shutdown: bool,
+    /// 任一端读写出错，连接直接结束
+    broken: bool,
+}
+
+impl<C: RemoteEnd> Pipe<C> {
+    fn new(socket: TcpStream, channel: C) -> Result<Self, String> {
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(Self {
+            socket,
+            channel,
+            to_remote: Vec::new(),
+            to_local: Vec::new(),
+            local_eof: false,
+            remote_eof: false,
+            remote_eof_sent: false,
+            local_shutdown: false,
+            broken: false,
+        })
+    }
+
+    fn finished(&self) -> bool {
+        self.broken || (self.remote_eof_sent && self.local_shutdown)
+    }
+
+    /// 从本机套接字读取要发给通道的数据，返回是否有进展
+    fn read_local(&mut self, buf: &mut [u8]) -> bool {
+        if self.broken || self.local_eof || !self.to_remote.is_empty() {
+            return false;
+        }
+        match read_some(&mut self.socket, buf) {
+            Ok(Some(n)) if n > 0 => self.to_remote.extend_from_slice(&buf[..n]),
+            Ok(Some(_)) => return false,
+            Ok(None) => self.local_eof = true,
+            Err(_) => self.broken = true,
+        }
+        true
+    }
+
+    /// 把数据写给通道并读取通道发来的数据，须在连接锁内以非阻塞模式调用
+    fn exchange_remote(&mut self, buf: &mut [u8], stats: &ForwardStats) -> bool {
+        if self.broken {
+            return false;
+        }
+        let pending = self.to_remote.len();
+        let mut progressed = match flush_some(&mut self.channel, &mut self.to_remote) {
+            Ok(p) => p,
+            Err(_) => {
+                self.broken = true;
+                return true;
+            }
+        };
+        let sent = pending - self.to_remote.len();
+        stats.bytes_sent.fetch_add(sent as u64, Ordering::Relaxed);
+
+        if self.local_eof && self.to_remote.is_empty() && !self.remote_eof_sent {
+            match self.channel.send_eof() {
+                Ok(()) => {
+                    self.remote_eof_sent = true;
+                    progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => {
+                    self.broken = true;
+                    return true;
+                }
+            }
+        }
+
+        if !self.remote_eof && self.to_local.is_empty() {
+            match read_some(&mut self.channel, buf) {
+                Ok(Some(n)) if n > 0 => {
+                    self.to_local.extend_from_slice(&buf[..n]);
+                    stats.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+                    progressed = true;
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    self.remote_eof = self.channel.eof();
+                    progressed |= self.remote_eof;
+                }
+                Err(_) => {
+                    self.broken = true;
+                    return true;
+                }
+            }
+        }
+        progressed
+    }
+
+    /// 把通道发来的数据写给本机套接字，返回是否有进展
+    fn write_local(&mut self) -> bool {
+        if self.broken {
+            return false;
+        }
+        let progressed = match flush_some(&mut self.socket, &mut self.to_local) {
+            Ok(p) => p,
+            Err(_) => {
+                self.broken = true;
+                return true;
+            }
+        };
+        if self.remote_eof && self.to_local.is_empty() && !self.local_shutdown {
+            let _ = self.socket.shutdown(Shutdown::Write);
+            self.local_shutdown = true;
+            return true;
+        }
+        progressed
+    }
+}
+
+/// 管理运行中的端口转发，状态在停止后保留到下次启动，便于查看失败原因
+#[derive(Clone, Default)]
+pub struct PortForwardManager {
+    forwards: Arc<Mutex<HashMap<i64, ForwardEntry>>>,
+}
+
+impl PortForwardManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始监听并在后台转发；监听失败（如端口被占用）时直接返回错误
+    pub fn start(
+        &self,
+        forward: &PortForward,
+        conn: SharedConnection,
+    ) -> Result<PortForwardStatus, String> {
+        let forward_id = forward.id.ok_or("转发定义缺少 id")?;
+        if self.status(forward_id)?.running {
+            return Err("该转发已在运行".to_string());
+        }
+        let session = conn
+            .lock()
+            .map_err(|e| e.to_string())?
+            .ssh_session()
+            .ok_or_else(|| "仅已连接的 SFTP 主机支持端口转发".to_string())?;
+
+        let (source, bound_port) = match forward.kind {
+            ForwardKind::Local => {
+                let listener = TcpListener::bind((forward.bind_host.as_str(), forward.bind_port))
+                    .map_err(|e| {
+                    format!(
+                        "无法监听 {}:{}: {}",
+                        forward.bind_host, forward.bind_port, e
+                    )
+                })?;
+                listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+                let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+                (Source::Local(listener), port)
+            }
+            ForwardKind::Remote => {
+                let _guard = conn.lock().map_err(|e| e.to_string())?;
+                let (listener, port) = session
+                    .channel_forward_listen(forward.bind_port, Some(&forward.bind_host), None)
+                    .map_err(|e| {
+                        format!(
+                            "服务器无法监听 {}:{}: {}",
+                            forward.bind_host, forward.bind_port, e
+                        )
+                    })?;
+                (Source::Remote(listener), port)
+            }
+        };
+
+        let stats = Arc::new(ForwardStats::default());
+        stats.running.store(true, Ordering::Relaxed);
+        stats.bound_port.store(bound_port as u32, Ordering::Relaxed);
+        let stop = Arc::new(AtomicBool::new(false));
+        self.forwards.lock().map_err(|e| e.to_string())?.insert(
+            forward_id,
+            ForwardEntry {
+                host_id: forward.host_id,
+                stop: stop.clone(),
+                stats: stats.clone(),
+            },
+        );
+
+        let forward = forward.clone();
+        let status = stats.snapshot(forward_id);
+        std::thread::spawn(move || {
+            if let Err(e) = run(&conn, &session, &forward, source, &stats, &stop) {
+                stats.set_error(e);
+            }
+            stats.active_connections.store(0, Ordering::Relaxed);
+            stats.running.store(false, Ordering::Relaxed);
+        });
+        Ok(status)
+    }
+
+    /// 通知转发线程停止，已建立的连接随之关闭
+    pub fn stop(&self, forward_id: i64) -> Result<(), String> {
+        let forwards = self.forwards.lock().map_err(|e| e.to_string())?;
+        if let Some(entry) = forwards.get(&forward_id) {
+            entry.stop.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// 未启动过的转发返回空状态
+    pub fn status(&self, forward_id: i64) -> Result<PortForwardStatus, String> {
+        let forwards = self.forwards.lock().map_err(|e| e.to_string())?;
+        Ok(forwards
+            .get(&forward_id)
+            .map(|entry| entry.stats.snapshot(forward_id))
+            .unwrap_or(PortForwardStatus {
+                forward_id,
+                ..Default::default()
+            }))
+    }
+
+    /// 主机下启动过的转发的状态
+    pub fn statuses(&self, host_id: i64) -> Result<Vec<PortForwardStatus>, String> {
+        let forwards = self.forwards.lock().map_err(|e| e.to_string())?;
+        let mut statuses: Vec<_> = forwards
+            .iter()
+            .filter(|(_, entry)| entry.host_id == host_id)
+            .map(|(id, entry)| entry.stats.snapshot(*id))
+            .collect();
+        statuses.sort_by_key(|s| s.forward_id);
+        Ok(statuses)
+    }
+}
+
+/// 接受一个新连接并打开对应的另一端；单个连接失败只记录原因，不终止转发
+fn accept(
+    conn: &SharedConnection,
+    session: &Session,
+    forward: &PortForward,
+    source: &mut Source,
+    stats: &ForwardStats,
+) -> Result<Option<Pipe>, String> {
+    let target = format!("{}:{}", forward.target_host, forward.target_port);
+    match source {
+        Source::Local(listener) => {
+            let socket = match listener.accept() {
+                Ok((socket, _)) => socket,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(e.to_string()),
+            };
+            let channel = {
+                let _guard = conn.lock().map_err(|e| e.to_string())?;
+                session.channel_direct_tcpip(&forward.target_host, forward.target_port, None)
+            };
+            match channel {
+                Ok(channel) => Pipe::new(socket, channel).map(Some),
+                Err(e) => {
+                    stats.set_error(format!("服务器无法连接 {}: {}", target, e));
+                    Ok(None)
+                }
+            }
+        }
+        Source::Remote(listener) => {
+            let accepted = with_nonblocking(conn, session, || {
+                Ok(listener.accept().map_err(std::io::Error::from))
+            })?;
+            let channel = match accepted {
+                Ok(channel) => channel,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(e.to_string()),
+            };
+            let socket = target
+                .to_socket_addrs()
+                .and_then(|mut addrs| {
+                    addrs
+                        .next()
+                        .ok_or_else(|| std::io::Error::from(ErrorKind::AddrNotAvailable))
+                })
+                .and_then(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT));
+            match socket {
+                Ok(socket) => Pipe::new(socket, channel).map(Some),
+                Err(e) => {
+                    stats.set_error(format!("无法连接 {}: {}", target, e));
+                    let _guard = conn.lock().map_err(|e| e.to_string())?;
+                    drop(channel);
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+/// 转发线程主循环，返回 Err 表示转发因错误终止
+fn run(
+    conn: &SharedConnection,
+    session: &Session,
+    forward: &PortForward,
+    mut source: Source,
+    stats: &ForwardStats,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    let mut pipes: Vec<Pipe> = Vec::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let result = loop {
+        if stop.load(Ordering::Relaxed) {
+            break Ok(());
+        }
+        match conn.lock() {
+            Ok(client) if client.is_connected() => {}
+            _ => break Err("连接已断开".to_string()),
+        }
+
+        let mut progressed = match accept(conn, session, forward, &mut source, stats) {
+            Ok(Some(pipe)) => {
+                pipes.push(pipe);
+                true
+            }
+            Ok(None) => false,
+            Err(e) => break Err(e),
+        };
+
+        for pipe in pipes.iter_mut() {
+            progressed |= pipe.read_local(&mut buf);
+        }
+
+        let channel_progress = with_nonblocking(conn, session, || {
+            let mut progressed = false;
+            for pipe in pipes.iter_mut() {
+                progressed |= pipe.exchange_remote(&mut buf, stats);
+            }
+            Ok(progressed)
+        });
+        match channel_progress {
+            Ok(p) => progressed |= p,
+            Err(e) => break Err(e),
+        }
+
+        for pipe in pipes.iter_mut() {
+            progressed |= pipe.write_local();
+        }
+
+        if pipes.iter().any(Pipe::finished) {
+            // 通道在连接锁内释放，libssh2 释放时会收发数据
+            let _guard = conn.lock().map_err(|e| e.to_string())?;
+            pipes.retain(|p| !p.finished());
+        }
+        stats
+            .active_connections
+            .store(pipes.len() as u32, Ordering::Relaxed);
+
+        if !progressed {
+            std::thread::sleep(IDLE_WAIT);
+        }
+    };
+
+    // 远程转发的监听在释放时向服务器取消
+    if let Ok(_guard) = conn.lock() {
+        drop(pipes);
+        drop(source);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::local_client::LocalClient;
+
+    impl RemoteEnd for TcpStream {
+        fn eof(&self) -> bool {
+            true
+        }
+
+        fn send_eof(&mut self) -> std::io::Result<()> {
+            self.shutdown(Shutdown::Write)
+        }
+    }
+
+    fn socket_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let a = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (b, _) = listener.accept().unwrap();
+        (a, b)
+    }
+
+    /// 按转发线程的顺序处理一条连接，直到结束
+    fn relay(mut pipe: Pipe<TcpStream>, stats: &ForwardStats) {
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        while !pipe.finished() {
+            let progressed = pipe.read_local(&mut buf)
+                | pipe.exchange_remote(&mut buf, stats)
+                | pipe.write_local();
+            if !progressed {
+                std::thread::sleep(IDLE_WAIT);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipe_relays_after_half_close() {
+        // client <-> socket 为本机一侧的连接，channel <-> server 模拟 SSH 通道
+        let (mut client, socket) = socket_pair();
+        let (channel, mut server) = socket_pair();
+        channel.set_nonblocking(true).unwrap();
+        let pipe = Pipe::new(socket, channel).unwrap();
+        let stats = Arc::new(ForwardStats::default());
+        let handle = {
+            let stats = stats.clone();
+            std::thread::spawn(move || relay(pipe, &stats))
+        };
+
+        // 客户端发完请求后关闭写入，服务器读到完整请求和 EOF
+        let request = vec![7u8; 100_000];
+        client.write_all(&request).unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).unwrap();
+        assert_eq!(received, request);
+
+        // 之后服务器的应答仍完整送回
+        let response = vec![9u8; 300_000];
+        server.write_all(&response).unwrap();
+        drop(server);
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).unwrap();
+        assert_eq!(reply, response);
+        handle.join().unwrap();
+
+        let status = stats.snapshot(1);
+        assert_eq!(status.bytes_sent, 100_000);
+        assert_eq!(status.bytes_received, 300_000);
+    }
+
+    fn forward(kind: ForwardKind) -> PortForward {
+        PortForward {
+            id: Some(7),
+            host_id: 1,
+            name: "db".into(),
+            kind,
+            bind_host: "127.0.0.1".into(),
+            bind_port: 0,
+            target_host: "127.0.0.1".into(),
+            target_port: 5432,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_start_requires_ssh_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = LocalClient::new(dir.path());
+        client.connect().unwrap();
+        let conn: SharedConnection = Arc::new(Mutex::new(Box::new(client)));
+
+        let manager = PortForwardManager::new();
+        let err = manager
+            .start(&forward(ForwardKind::Local), conn)
+            .unwrap_err();
+        assert!(err.contains("SFTP"));
+        assert!(manager.statuses(1).unwrap().is_empty());
+        let status = manager.status(7).unwrap();
+        assert_eq!(status.forward_id, 7);
+        assert!(!status.running);
+        assert!(manager.stop(7).is_ok());
+    }
+
+    #[test]
+    fn test_stats_snapshot() {
+        let stats = ForwardStats::default();
+        stats.running.store(true, Ordering::Relaxed);
+        stats.bound_port.store(15432, Ordering::Relaxed);
+        stats.bytes_sent.fetch_add(10, Ordering::Relaxed);
+        stats.set_error("服务器无法连接".into());
+
+        let status = stats.snapshot(3);
+        assert!(status.running);
+        assert_eq!(status.bound_port, Some(15432));
+        assert_eq!(status.bytes_sent, 10);
+        assert_eq!(status.error.as_deref(), Some("服务器无法连接"));
+        assert_eq!(ForwardStats::default().snapshot(3).bound_port, None);
+    }
+}
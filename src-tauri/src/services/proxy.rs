@@ -0,0 +1,361 @@
+//! 经 SOCKS5（RFC 1928/1929）或 HTTP CONNECT 代理建立 TCP 连接。
+//! 握手完成后得到的仍是普通 TcpStream，可直接交给 ssh2 或 suppaftp 使用。
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::models::proxy::{ProxyConfig, ProxyType};
+
+/// 代理握手的超时，握手完成后恢复为阻塞读写
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15);
+/// HTTP 代理应答头的最大长度
+const MAX_HTTP_RESPONSE: usize = 8192;
+
+/// 连接 host:port，配置了代理时经代理转发
+pub fn connect(host: &str, port: u16, proxy: Option<&ProxyConfig>) -> Result<TcpStream, String> {
+    let Some(proxy) = proxy else {
+        return TcpStream::connect((host, port)).map_err(|e| e.to_string());
+    };
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port))
+        .map_err(|e| format!("无法连接代理服务器 {}:{}: {}", proxy.host, proxy.port, e))?;
+    stream
+        .set_read_timeout(Some(HANDSHAKE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_write_timeout(Some(HANDSHAKE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    match proxy.proxy_type {
+        ProxyType::Socks5 => socks5_handshake(&mut stream, proxy, host, port)?,
+        ProxyType::Http => http_connect(&mut stream, proxy, host, port)?,
+    }
+    stream.set_read_timeout(None).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(None).map_err(|e| e.to_string())?;
+    Ok(stream)
+}
+
+/// 把已建立的连接原样转发到本机回环地址上的一个端口，供只接受地址、自行建立连接的库
+/// （如 suppaftp 的隐式 FTPS）使用。只搬运字节流，TLS 仍由连入方与服务器端到端完成。
+/// 监听端口只接受一次连接，被其他程序抢先连入时调用方自己的连接会失败，此时应关闭 stream
+pub fn loopback_forward(stream: TcpStream) -> Result<SocketAddr, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        let Ok((local, _)) = listener.accept() else {
+            return;
+        };
+        drop(listener);
+        let (Ok(local_reader), Ok(remote_reader)) = (local.try_clone(), stream.try_clone()) else {
+            return;
+        };
+        let upstream = std::thread::spawn(move || pipe(local_reader, stream));
+        pipe(remote_reader, local);
+        let _ = upstream.join();
+    });
+    Ok(addr)
+}
+
+/// 单向搬运直到读端关闭，随后关闭写端的发送方向，让对方也读到 EOF
+fn pipe(mut from: TcpStream, mut to: TcpStream) {
+    let _ = std::io::copy(&mut from, &mut to);
+    let _ = to.shutdown(Shutdown::Write);
+    let _ = from.shutdown(Shutdown::Read);
+}
+
+fn credentials(proxy: &ProxyConfig) -> Option<(&str, &str)> {
+    proxy
+        .username
+        .as_deref()
+        .filter(|u| !u.is_empty())
+        .map(|u| (u, proxy.password.as_deref().unwrap_or("")))
+}
+
+fn io_err(e: std::io::Error) -> String {
+    format!("代理握手失败: {}", e)
+}
+
+fn socks5_handshake(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let auth = credentials(proxy);
+    // 0x00 无需认证，0x02 用户名/密码
+    let greeting: &[u8] = if auth.is_some() {
+        &[5, 2, 0x00, 0x02]
+    } else {
+        &[5, 1, 0x00]
+    };
+    stream.write_all(greeting).map_err(io_err)?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).map_err(io_err)?;
+    if reply[0] != 5 {
+        return Err("代理服务器不是 SOCKS5 协议".to_string());
+    }
+    match (reply[1], auth) {
+        (0x00, _) => {}
+        (0x02, Some((user, pass))) => {
+            if user.len() > 255 || pass.len() > 255 {
+                return Err("SOCKS5 用户名和密码不能超过 255 字节".to_string());
+            }
+            let mut req = vec![1, user.len() as u8];
+            req.extend_from_slice(user.as_bytes());
+            req.push(pass.len() as u8);
+            req.extend_from_slice(pass.as_bytes());
+            stream.write_all(&req).map_err(io_err)?;
+            stream.read_exact(&mut reply).map_err(io_err)?;
+            if reply[1] != 0 {
+                return Err("SOCKS5 代理认证失败".to_string());
+            }
+        }
+        (0x02, None) => return Err("SOCKS5 代理要求用户名和密码".to_string()),
+        _ => return Err("SOCKS5 代理不支持所需的认证方式".to_string()),
+    }
+
+    let mut req = vec![5, 1, 0];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            req.push(1);
+            req.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            req.push(4);
+            req.extend_from_slice(&ip.octets());
+        }
+        // 域名交给代理解析，内网主机名在本机可能无法解析
+        Err(_) => {
+            if host.len() > 255 {
+                return Err("主机名过长".to_string());
+            }
+            req.push(3);
+            req.push(host.len() as u8);
+            req.extend_from_slice(host.as_bytes());
+        }
+    }
+    req.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&req).map_err(io_err)?;
+
+    let mut head = [0u8; 4];
+    stream.read_exact(&mut head).map_err(io_err)?;
+    if head[1] != 0 {
+        return Err(format!(
+            "SOCKS5 代理拒绝连接 {}:{}: {}",
+            host,
+            port,
+            socks5_reply_message(head[1])
+        ));
+    }
+    // 跳过代理返回的绑定地址和端口
+    let addr_len = match head[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).map_err(io_err)?;
+            len[0] as usize
+        }
+        _ => return Err("SOCKS5 代理应答格式无效".to_string()),
+    };
+    let mut rest = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut rest).map_err(io_err)?;
+    Ok(())
+}
+
+fn socks5_reply_message(code: u8) -> &'static str {
+    match code {
+        1 => "代理服务器内部错误",
+        2 => "代理规则不允许",
+        3 => "网络不可达",
+        4 => "主机不可达",
+        5 => "连接被拒绝",
+        6 => "TTL 超时",
+        7 => "不支持的命令",
+        8 => "不支持的地址类型",
+        _ => "未知错误",
+    }
+}
+
+fn http_connect(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{}]:{}", host, port),
+        _ => format!("{}:{}", host, port),
+    };
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", authority);
+    if let Some((user, pass)) = credentials(proxy) {
+        let token = BASE64.encode(format!("{}:{}", user, pass));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).map_err(io_err)?;
+
+    // 逐字节读取应答头，避免把隧道里服务器先发的数据（如 SSH 版本串）读走
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_HTTP_RESPONSE {
+            return Err("HTTP 代理应答过长".to_string());
+        }
+        let n = stream.read(&mut byte).map_err(io_err)?;
+        if n == 0 {
+            return Err("HTTP 代理提前关闭了连接".to_string());
+        }
+        response.push(byte[0]);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    match status {
+        s if s.starts_with('2') => Ok(()),
+        "407" => Err("HTTP 代理认证失败".to_string()),
+        _ => Err(format!("HTTP 代理拒绝连接 {}: {}", authority, status_line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn proxy(proxy_type: ProxyType, port: u16, user: Option<&str>) -> ProxyConfig {
+        ProxyConfig {
+            proxy_type,
+            host: "127.0.0.1".into(),
+            port,
+            username: user.map(Into::into),
+            password: user.map(|_| "pw".into()),
+        }
+    }
+
+    /// 启动只接受一个连接的代理桩，返回端口和记录下的握手请求
+    fn stub<F>(handler: F) -> (u16, thread::JoinHandle<Vec<u8>>)
+    where
+        F: FnOnce(&mut TcpStream) -> Vec<u8> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            handler(&mut s)
+        });
+        (port, handle)
+    }
+
+    fn read_n(s: &mut TcpStream, n: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; n];
+        s.read_exact(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_socks5_with_auth_and_domain() {
+        let (port, handle) = stub(|s| {
+            let mut seen = read_n(s, 4);
+            s.write_all(&[5, 2]).unwrap();
+            seen.extend(read_n(s, 2 + 5 + 1 + 2));
+            s.write_all(&[1, 0]).unwrap();
+            seen.extend(read_n(s, 4 + 1 + 13 + 2));
+            s.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+            s.write_all(b"SSH-2.0-stub\r\n").unwrap();
+            seen
+        });
+        let mut stream = connect(
+            "sftp.internal",
+            22,
+            Some(&proxy(ProxyType::Socks5, port, Some("alice"))),
+        )
+        .unwrap();
+        let mut banner = [0u8; 14];
+        stream.read_exact(&mut banner).unwrap();
+        assert_eq!(&banner, b"SSH-2.0-stub\r\n");
+
+        let seen = handle.join().unwrap();
+        assert_eq!(&seen[..4], &[5, 2, 0, 2]);
+        assert_eq!(&seen[4..14], b"\x01\x05alice\x02pw");
+        assert_eq!(&seen[14..19], &[5, 1, 0, 3, 13]);
+        assert_eq!(&seen[19..32], b"sftp.internal");
+        assert_eq!(&seen[32..], &[0, 22]);
+    }
+
+    #[test]
+    fn test_socks5_rejected() {
+        let (port, handle) = stub(|s| {
+            read_n(s, 3);
+            s.write_all(&[5, 0]).unwrap();
+            read_n(s, 4 + 4 + 2);
+            s.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+            Vec::new()
+        });
+        let err = connect("10.0.0.1", 21, Some(&proxy(ProxyType::Socks5, port, None))).unwrap_err();
+        assert!(err.contains("连接被拒绝"), "{}", err);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_http_connect_keeps_tunnel_data() {
+        let (port, handle) = stub(|s| {
+            let mut seen = Vec::new();
+            while !seen.ends_with(b"\r\n\r\n") {
+                seen.extend(read_n(s, 1));
+            }
+            // 应答头和隧道数据在同一个包里到达
+            s.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n220 ready\r\n")
+                .unwrap();
+            seen
+        });
+        let mut stream = connect(
+            "ftp.example.com",
+            21,
+            Some(&proxy(ProxyType::Http, port, Some("alice"))),
+        )
+        .unwrap();
+        let mut greeting = [0u8; 11];
+        stream.read_exact(&mut greeting).unwrap();
+        assert_eq!(&greeting, b"220 ready\r\n");
+
+        let seen = String::from_utf8(handle.join().unwrap()).unwrap();
+        assert!(seen.starts_with("CONNECT ftp.example.com:21 HTTP/1.1\r\n"));
+        assert!(seen.contains("Proxy-Authorization: Basic YWxpY2U6cHc=\r\n"));
+    }
+
+    #[test]
+    fn test_http_connect_auth_required() {
+        let (port, handle) = stub(|s| {
+            let mut seen = Vec::new();
+            while !seen.ends_with(b"\r\n\r\n") {
+                seen.extend(read_n(s, 1));
+            }
+            s.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .unwrap();
+            seen
+        });
+        let err = connect("::1", 22, Some(&proxy(ProxyType::Http, port, None))).unwrap_err();
+        assert_eq!(err, "HTTP 代理认证失败");
+        let seen = String::from_utf8(handle.join().unwrap()).unwrap();
+        assert!(seen.starts_with("CONNECT [::1]:22 HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn test_loopback_forward_relays_until_closed() {
+        let (port, handle) = stub(|s| {
+            let seen = read_n(s, 5);
+            s.write_all(b"world").unwrap();
+            s.shutdown(Shutdown::Write).unwrap();
+            seen
+        });
+        let remote = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let addr = loopback_forward(remote).unwrap();
+        let mut local = TcpStream::connect(addr).unwrap();
+        local.write_all(b"hello").unwrap();
+        let mut reply = Vec::new();
+        local.read_to_end(&mut reply).unwrap();
+        assert_eq!(reply, b"world");
+        assert_eq!(handle.join().unwrap(), b"hello");
+    }
+}
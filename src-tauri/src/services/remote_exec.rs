@@ -0,0 +1,198 @@
+//! 远程命令执行：在已连接主机的 SSH 会话上另开 exec 通道。
+//! 输出以非阻塞方式轮询读取，每次只短暂持有连接锁，执行期间浏览目录等操作仍可穿插进行
+
+use serde::Serialize;
+use ssh2::{Channel, Session};
+use std::io::{ErrorKind, Read};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::connection::ConnectionTrait;
+use super::scp::quote;
+
+/// 没有新输出时的轮询间隔
+pub(super) const POLL_INTERVAL: Duration = Duration::from_millis(30);
+const READ_BUFFER_SIZE: usize = 32 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OutputStream {
+    #[serde(rename = "stdout")]
+    Stdout,
+    #[serde(rename = "stderr")]
+    Stderr,
+}
+
+/// 按块解码 UTF-8 输出，块尾不完整的多字节字符留到下一块再解码
+#[derive(Default)]
+pub(super) struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub(super) fn decode(&mut self, chunk: &[u8]) -> String {
+        self.pending.extend_from_slice(chunk);
+        let split = incomplete_tail_start(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..split]).into_owned();
+        self.pending.drain(..split);
+        text
+    }
+
+    /// 输出结束时剩余的不完整字节按替换字符输出
+    pub(super) fn finish(&mut self) -> String {
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        text
+    }
+}
+
+/// 返回末尾不完整 UTF-8 序列的起始位置，没有则返回长度
+fn incomplete_tail_start(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    for i in 1..=len.min(3) {
+        let b = bytes[len - i];
+        if b & 0xC0 == 0x80 {
+            continue;
+        }
+        let needed = match b {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if needed > i { len - i } else { len };
+    }
+    len
+}
+
+/// 非阻塞读取一次；没有数据时返回 0
+pub(super) fn read_nonblocking(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, String> {
+    match reader.read(buf) {
+        Ok(n) => Ok(n),
+        Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(0),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 在连接锁内以非阻塞模式执行 f，结束后恢复阻塞模式供其他操作使用
+pub(super) fn with_nonblocking<T>(
+    conn: &Mutex<Box<dyn ConnectionTrait>>,
+    session: &Session,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = conn.lock().map_err(|e| e.to_string())?;
+    session.set_blocking(false);
+    let result = f();
+    session.set_blocking(true);
+    result
+}
+
+/// 读取一轮 stdout 与 stderr，返回 (stdout, stderr, 是否已到 EOF)
+fn poll_output(channel: &mut Channel, buf: &mut [u8]) -> Result<(Vec<u8>, Vec<u8>, bool), String> {
+    let n = read_nonblocking(channel, buf)?;
+    let stdout = buf[..n].to_vec();
+    let n = read_nonblocking(&mut channel.stderr(), buf)?;
+    let stderr = buf[..n].to_vec();
+    Ok((stdout, stderr, channel.eof()))
+}
+
+/// 执行命令并逐块回调输出，返回退出码。working_dir 不存在时命令不会执行；
+/// 超时后关闭通道并返回错误，远程进程是否随之结束取决于服务器
+pub fn run_command(
+    conn: &Mutex<Box<dyn ConnectionTrait>>,
+    command: &str,
+    working_dir: Option<&str>,
+    timeout: Option<Duration>,
+    on_output: &dyn Fn(OutputStream, String),
+) -> Result<i32, String> {
+    let command = match working_dir.filter(|dir| !dir.is_empty()) {
+        Some(dir) => format!("cd -- {} || exit 1; {}", quote(dir), command),
+        None => command.to_string(),
+    };
+    let (session, mut channel) = {
+        let conn = conn.lock().map_err(|e| e.to_string())?;
+        let session = conn
+            .ssh_session()
+            .ok_or_else(|| "仅已连接的 SFTP 主机支持执行远程命令".to_string())?;
+        let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+        channel.exec(&command).map_err(|e| e.to_string())?;
+        (session, channel)
+    };
+
+    let started = Instant::now();
+    let mut stdout = Utf8Decoder::default();
+    let mut stderr = Utf8Decoder::default();
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let (out, err, eof) =
+            with_nonblocking(conn, &session, || poll_output(&mut channel, &mut buf))?;
+        let idle = out.is_empty() && err.is_empty();
+        for (stream, decoder, bytes) in [
+            (OutputStream::Stdout, &mut stdout, out),
+            (OutputStream::Stderr, &mut stderr, err),
+        ] {
+            let text = decoder.decode(&bytes);
+            if !text.is_empty() {
+                on_output(stream, text);
+            }
+        }
+        if eof && idle {
+            break;
+        }
+        if let Some(limit) = timeout {
+            if started.elapsed() >= limit {
+                if let Ok(_guard) = conn.lock() {
+                    let _ = channel.close();
+                }
+                return Err(format!("命令执行超时（{} 秒）", limit.as_secs()));
+            }
+        }
+        if idle {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+    for (stream, decoder) in [
+        (OutputStream::Stdout, &mut stdout),
+        (OutputStream::Stderr, &mut stderr),
+    ] {
+        let text = decoder.finish();
+        if !text.is_empty() {
+            on_output(stream, text);
+        }
+    }
+
+    let _guard = conn.lock().map_err(|e| e.to_string())?;
+    channel.wait_close().map_err(|e| e.to_string())?;
+    channel.exit_status().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::local_client::LocalClient;
+
+    #[test]
+    fn test_decoder_keeps_split_characters() {
+        let bytes = "中文 ok".as_bytes();
+        let mut decoder = Utf8Decoder::default();
+        let mut text = decoder.decode(&bytes[..1]);
+        assert_eq!(text, "");
+        text += &decoder.decode(&bytes[1..4]);
+        assert_eq!(text, "中");
+        text += &decoder.decode(&bytes[4..]);
+        assert_eq!(text, "中文 ok");
+        assert_eq!(decoder.finish(), "");
+
+        assert_eq!(decoder.decode(b"a\xFFb\xE4\xB8"), "a\u{FFFD}b");
+        assert_eq!(decoder.finish(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_requires_ssh_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = LocalClient::new(dir.path());
+        client.connect().unwrap();
+        let conn: Mutex<Box<dyn ConnectionTrait>> = Mutex::new(Box::new(client));
+        let err = run_command(&conn, "ls", None, None, &|_, _| {}).unwrap_err();
+        assert!(err.contains("SFTP"));
+    }
+}
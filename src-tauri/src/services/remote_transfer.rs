@@ -0,0 +1,250 @@
+//! 主机间传输：源主机读出的内容经有界内存管道直接写入目标主机，不落本地磁盘。
+//! 两台都是 FTP 服务器时先尝试 FXP，让服务器之间直接传输
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Mutex;
+
+use super::connection::ConnectionTrait;
+use super::ftp_client;
+
+/// 管道中最多缓存的数据块数，每块不超过 CHUNK_SIZE，约 2 MiB
+const PIPE_CAPACITY: usize = 64;
+
+/// 不能用 ErrorKind::Interrupted，io::copy 等会把它当作可重试的错误一直重试
+fn cancelled() -> io::Error {
+    io::Error::other("传输已取消")
+}
+
+/// 管道写入端，由读取源文件的线程持有
+struct PipeWriter<'a> {
+    tx: SyncSender<Vec<u8>>,
+    cancel: &'a AtomicBool,
+}
+
+impl Write for PipeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+        self.tx
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "目标主机已停止接收"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 管道读取端。写入端提前关闭时报错而不是返回 EOF，避免目标端把不完整的文件当作传输成功
+struct PipeReader<'a> {
+    rx: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    pos: usize,
+    /// 还应收到的字节数
+    remaining: u64,
+    /// 源端在内容读完前就关闭了管道
+    truncated: bool,
+    cancel: &'a AtomicBool,
+}
+
+impl Read for PipeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+        while self.pos == self.chunk.len() {
+            match self.rx.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                Err(_) if self.remaining == 0 => return Ok(0),
+                Err(_) => {
+                    self.truncated = true;
+                    return Err(io::Error::new(ErrorKind::UnexpectedEof, "源文件读取中断"));
+                }
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        self.remaining = self.remaining.saturating_sub(n as u64);
+        Ok(n)
+    }
+}
+
+/// 目标主机上已有的部分文件可以接着写时返回其大小作为续传起点，否则为 0
+pub fn resume_offset(dest: &mut dyn ConnectionTrait, dest_path: &str, total: u64) -> u64 {
+    if !dest.can_append() {
+        return 0;
+    }
+    match dest.file_size(dest_path) {
+        Ok(size) if size < total => size,
+        _ => 0,
+    }
+}
+
+/// 把源主机上的文件传到目标主机，offset > 0 时接在目标已有的前 offset 字节之后。
+/// 传输期间两边的连接都被占用；按 Mutex 地址顺序加锁，两台主机同时互传时不会死锁。
+/// 进度回调与各客户端一致为 (offset + 已写入, 总大小)，返回本次写入的字节数
+pub fn transfer(
+    source: &Mutex<Box<dyn ConnectionTrait>>,
+    dest: &Mutex<Box<dyn ConnectionTrait>>,
+    source_path: &str,
+    dest_path: &str,
+    offset: u64,
+    progress: Option<&(dyn Fn(u64, u64) + Sync)>,
+    cancel: &AtomicBool,
+) -> Result<u64, String> {
+    if std::ptr::eq(source, dest) {
+        return Err("源主机与目标主机不能相同".to_string());
+    }
+    let (mut src_guard, mut dst_guard) =
+        if (source as *const _ as usize) < (dest as *const _ as usize) {
+            let s = source.lock().map_err(|e| e.to_string())?;
+            (s, dest.lock().map_err(|e| e.to_string())?)
+        } else {
+            let d = dest.lock().map_err(|e| e.to_string())?;
+            (source.lock().map_err(|e| e.to_string())?, d)
+        };
+    let src: &mut dyn ConnectionTrait = &mut **src_guard;
+    let dst: &mut dyn ConnectionTrait = &mut **dst_guard;
+    let progress = progress.map(|cb| cb as &dyn Fn(u64, u64));
+
+    let total = src.file_size(source_path)?;
+    if let (Some(src_ftp), Some(dst_ftp)) = (src.as_ftp(), dst.as_ftp()) {
+        let sent = ftp_client::fxp_transfer(
+            src_ftp,
+            dst_ftp,
+            source_path,
+            dest_path,
+            offset,
+            total,
+            progress,
+        )?;
+        if let Some(sent) = sent {
+            return Ok(sent);
+        }
+    }
+
+    let (tx, rx) = mpsc::sync_channel(PIPE_CAPACITY);
+    let mut writer = PipeWriter { tx, cancel };
+    let mut reader = PipeReader {
+        rx,
+        chunk: Vec::new(),
+        pos: 0,
+        remaining: total.saturating_sub(offset),
+        truncated: false,
+        cancel,
+    };
+    let (read, written, truncated) = std::thread::scope(|s| {
+        let reading = s.spawn(move || src.download_to(source_path, &mut writer, offset, None));
+        let written = dst.upload_from(&mut reader, dest_path, offset, total, progress);
+        let truncated = reader.truncated;
+        // 目标端出错时关闭管道，读取线程随之退出
+        drop(reader);
+        (reading.join(), written, truncated)
+    });
+    let read = read.map_err(|_| "读取线程异常退出".to_string())?;
+
+    if cancel.load(Ordering::Relaxed) {
+        return Err("传输已取消".to_string());
+    }
+    match (read, written) {
+        (Ok(_), Ok(n)) => Ok(n),
+        (Err(e), _) if truncated => Err(format!("读取源文件失败: {}", e)),
+        (_, Err(e)) => Err(e),
+        (Err(e), Ok(_)) => Err(format!("读取源文件失败: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::local_client::LocalClient;
+
+    fn local_conn(dir: &std::path::Path) -> Mutex<Box<dyn ConnectionTrait>> {
+        let mut client = LocalClient::new(dir);
+        client.connect().unwrap();
+        Mutex::new(Box::new(client))
+    }
+
+    #[test]
+    fn test_transfer_streams_between_hosts() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let dst_dir = tempfile::tempdir().unwrap();
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(src_dir.path().join("data.bin"), &content).unwrap();
+        let source = local_conn(src_dir.path());
+        let dest = local_conn(dst_dir.path());
+
+        let last = Mutex::new((0, 0));
+        let progress = |done: u64, total: u64| *last.lock().unwrap() = (done, total);
+        let cancel = AtomicBool::new(false);
+        let sent = transfer(
+            &source,
+            &dest,
+            "/data.bin",
+            "/copy.bin",
+            0,
+            Some(&progress),
+            &cancel,
+        )
+        .unwrap();
+
+        assert_eq!(sent, content.len() as u64);
+        assert_eq!(
+            std::fs::read(dst_dir.path().join("copy.bin")).unwrap(),
+            content
+        );
+        let total = content.len() as u64;
+        assert_eq!(*last.lock().unwrap(), (total, total));
+    }
+
+    #[test]
+    fn test_transfer_resumes_partial_destination() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let dst_dir = tempfile::tempdir().unwrap();
+        std::fs::write(src_dir.path().join("a.txt"), b"hello remote world").unwrap();
+        std::fs::write(dst_dir.path().join("a.txt"), b"hello ").unwrap();
+        let source = local_conn(src_dir.path());
+        let dest = local_conn(dst_dir.path());
+
+        let offset = resume_offset(dest.lock().unwrap().as_mut(), "/a.txt", 18);
+        assert_eq!(offset, 6);
+        assert_eq!(
+            resume_offset(dest.lock().unwrap().as_mut(), "/missing", 18),
+            0
+        );
+
+        let cancel = AtomicBool::new(false);
+        let sent = transfer(&source, &dest, "/a.txt", "/a.txt", offset, None, &cancel).unwrap();
+        assert_eq!(sent, 12);
+        assert_eq!(
+            std::fs::read(dst_dir.path().join("a.txt")).unwrap(),
+            b"hello remote world"
+        );
+    }
+
+    #[test]
+    fn test_transfer_errors() {
+        let src_dir = tempfile::tempdir().unwrap();
+        let dst_dir = tempfile::tempdir().unwrap();
+        std::fs::write(src_dir.path().join("a.txt"), b"data").unwrap();
+        let source = local_conn(src_dir.path());
+        let dest = local_conn(dst_dir.path());
+        let cancel = AtomicBool::new(false);
+
+        let err = transfer(&source, &source, "/a.txt", "/b.txt", 0, None, &cancel).unwrap_err();
+        assert!(err.contains("不能相同"));
+        assert!(transfer(&source, &dest, "/missing", "/b.txt", 0, None, &cancel).is_err());
+
+        cancel.store(true, Ordering::Relaxed);
+        let err = transfer(&source, &dest, "/a.txt", "/b.txt", 0, None, &cancel).unwrap_err();
+        assert_eq!(err, "传输已取消");
+    }
+}
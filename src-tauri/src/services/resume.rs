@@ -7,21 +7,23 @@ pub fn find_resume_record(
     remote_path: &str,
     local_path: &str,
     direction: &str,
+    dest_host_id: Option<i64>,
 ) -> Result<Option<ResumeRecord>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let mut stmt = conn
         .prepare(
             "SELECT id, transfer_id, host_id, remote_path, local_path, direction,
-                    file_size, transferred_bytes, checksum, created_at
+                    file_size, transferred_bytes, checksum, created_at, dest_host_id
              FROM resume_records
              WHERE host_id = ?1 AND remote_path = ?2 AND local_path = ?3 AND direction = ?4
+               AND dest_host_id IS ?5
              ORDER BY created_at DESC LIMIT 1",
         )
         .map_err(|e| e.to_string())?;
 
     let result = stmt
         .query_row(
-            rusqlite::params![host_id, remote_path, local_path, direction],
+            rusqlite::params![host_id, remote_path, local_path, direction, dest_host_id],
             |row| {
                 Ok(ResumeRecord {
                     id: row.get(0)?,
@@ -37,6 +39,7 @@ pub fn find_resume_record(
                     transferred_bytes: row.get(7)?,
                     checksum: row.get(8)?,
                     created_at: row.get(9)?,
+                    dest_host_id: row.get(10)?,
                 })
             },
         )
@@ -49,8 +52,8 @@ pub fn find_resume_record(
 pub fn save_resume_record(db: &Database, record: &ResumeRecord) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     conn.execute(
-        "INSERT INTO resume_records (transfer_id, host_id, remote_path, local_path, direction, file_size, transferred_bytes, checksum)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO resume_records (transfer_id, host_id, remote_path, local_path, direction, file_size, transferred_bytes, checksum, dest_host_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         rusqlite::params![
             record.transfer_id,
             record.host_id,
@@ -60,6 +63,7 @@ pub fn save_resume_record(db: &Database, record: &ResumeRecord) -> Result<(), St
             record.file_size,
             record.transferred_bytes,
             record.checksum,
+            record.dest_host_id,
         ],
     )
     .map_err(|e| e.to_string())?;
@@ -110,11 +114,19 @@ mod tests {
             transferred_bytes: 512,
             checksum: None,
             created_at: None,
+            dest_host_id: None,
         };
 
         save_resume_record(&db, &record).unwrap();
-        let found = find_resume_record(&db, 1, "/remote/file.txt", "/local/file.txt", "upload")
-            .unwrap();
+        let found = find_resume_record(
+            &db,
+            1,
+            "/remote/file.txt",
+            "/local/file.txt",
+            "upload",
+            None,
+        )
+        .unwrap();
 
         assert!(found.is_some());
         let found = found.unwrap();
@@ -136,13 +148,21 @@ mod tests {
             transferred_bytes: 1024,
             checksum: None,
             created_at: None,
+            dest_host_id: None,
         };
 
         save_resume_record(&db, &record).unwrap();
         delete_resume_record(&db, "test-456").unwrap();
 
-        let found = find_resume_record(&db, 1, "/remote/file.txt", "/local/file.txt", "download")
-            .unwrap();
+        let found = find_resume_record(
+            &db,
+            1,
+            "/remote/file.txt",
+            "/local/file.txt",
+            "download",
+            None,
+        )
+        .unwrap();
         assert!(found.is_none());
     }
 }
@@ -0,0 +1,483 @@
+//! SCP 传输模式：文件内容经 libssh2 的 scp_send/scp_recv 传输，
+//! 列目录、删除、重命名等操作在 exec 通道中执行 POSIX shell 命令（兼容 BusyBox）
+
+use ssh2::{Channel, Session};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::connection::{copy_download, FileEntry, ProgressReader, CHUNK_SIZE};
+use super::remote_exec::{read_nonblocking, POLL_INTERVAL};
+
+/// 定义 shell 函数 e：每个条目输出三行——是否符号链接及 stat 信息、名称、链接目标。
+/// stat 跟随链接，类型与大小取自链接目标；目标不存在时退回链接自身的信息
+const ENTRY_FN: &str = "e() { l=0; [ -L \"$1\" ] && l=1; \
+     s=$(stat -L -c '%f %s %Y %u %g %U %G' -- \"$1\" 2>/dev/null || \
+     stat -c '%f %s %Y %u %g %U %G' -- \"$1\") || return 1; \
+     echo \"$l $s\"; printf '%s\\n' \"$1\"; \
+     if [ $l = 1 ]; then readlink -- \"$1\"; else echo; fi; }";
+
+/// st_mode 中的文件类型位
+const FILE_TYPE_MASK: u32 = 0o170000;
+const DIR_TYPE: u32 = 0o040000;
+const PERMISSION_BITS: u32 = 0o7777;
+
+/// 按 POSIX shell 单引号规则转义参数
+pub(super) fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// 执行命令并返回退出码、标准输出和标准错误
+fn run(session: &Session, command: &str) -> Result<(i32, String, String), String> {
+    let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+    channel.exec(command).map_err(|e| e.to_string())?;
+    // 命令都不读标准输入；提前关闭，服务器强制执行 internal-sftp 等会等待输入的程序时也能结束
+    let _ = channel.send_eof();
+    session.set_blocking(false);
+    let output = read_output(&mut channel);
+    session.set_blocking(true);
+    let (stdout, stderr) = output?;
+    channel.wait_close().map_err(|e| e.to_string())?;
+    let status = channel.exit_status().map_err(|e| e.to_string())?;
+    Ok((
+        status,
+        String::from_utf8_lossy(&stdout).into_owned(),
+        String::from_utf8_lossy(&stderr).into_owned(),
+    ))
+}
+
+/// 交替读取标准输出和标准错误直到通道结束。两路共用通道窗口，
+/// 先读完一路再读另一路时，另一路写满窗口会使命令和读取互相等待
+fn read_output(channel: &mut Channel) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        let n = read_nonblocking(channel, &mut buf)?;
+        stdout.extend_from_slice(&buf[..n]);
+        let m = read_nonblocking(&mut channel.stderr(), &mut buf)?;
+        stderr.extend_from_slice(&buf[..m]);
+        let idle = n == 0 && m == 0;
+        if idle && channel.eof() {
+            return Ok((stdout, stderr));
+        }
+        if idle {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// 执行命令，退出码非 0 时以标准错误作为错误信息
+fn run_checked(session: &Session, command: &str) -> Result<String, String> {
+    let (status, stdout, stderr) = run(session, command)?;
+    if status == 0 {
+        Ok(stdout)
+    } else {
+        Err(command_error(status, &stderr))
+    }
+}
+
+fn command_error(status: i32, stderr: &str) -> String {
+    match stderr.trim() {
+        "" => format!("远程命令执行失败（退出码 {}）", status),
+        message => message.to_string(),
+    }
+}
+
+/// 解析 ENTRY_FN 的输出；name 与 path 暂为输出中的原始名称，由调用方补全
+fn parse_entries(output: &str) -> Result<Vec<FileEntry>, String> {
+    let lines: Vec<&str> = output.lines().collect();
+    lines
+        .chunks(3)
+        .filter(|record| record.len() == 3)
+        .map(|record| {
+            let fields: Vec<&str> = record[0].split_whitespace().collect();
+            let [is_symlink, mode, size, mtime, uid, gid, owner, group] = fields[..] else {
+                return Err(format!("无法解析远程 stat 输出: {}", record[0]));
+            };
+            let mode = u32::from_str_radix(mode, 16)
+                .map_err(|_| format!("无法解析远程 stat 输出: {}", record[0]))?;
+            let is_symlink = is_symlink == "1";
+            let known = |name: &str| (name != "UNKNOWN").then(|| name.to_string());
+            Ok(FileEntry {
+                name: record[1].to_string(),
+                path: record[1].to_string(),
+                is_dir: mode & FILE_TYPE_MASK == DIR_TYPE,
+                size: size.parse().unwrap_or(0),
+                modified: mtime
+                    .parse()
+                    .ok()
+                    .and_then(|t| chrono::DateTime::from_timestamp(t, 0)),
+                permissions: None,
+                is_symlink,
+                link_target: is_symlink.then(|| record[2].to_string()),
+                mode: Some(mode & PERMISSION_BITS),
+                uid: uid.parse().ok(),
+                gid: gid.parse().ok(),
+                owner: known(owner),
+                group: known(group),
+            })
+        })
+        .collect()
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+pub(super) fn list_dir(session: &Session, path: &str) -> Result<Vec<FileEntry>, String> {
+    // 三个通配符分别匹配隐藏文件、以 .. 开头的文件和普通文件，未匹配时原样保留，由 -e/-L 过滤
+    let command = format!(
+        "{}; cd -- {} || exit 1; for f in .[!.]* ..?* *; do \
+         if [ -e \"$f\" ] || [ -L \"$f\" ]; then e \"$f\"; fi; done; exit 0",
+        ENTRY_FN,
+        quote(path)
+    );
+    let output = run_checked(session, &command)?;
+    let mut entries = parse_entries(&output)?;
+    for entry in &mut entries {
+        entry.path = join_path(path, &entry.name);
+    }
+    Ok(entries)
+}
+
+pub(super) fn stat(session: &Session, path: &str) -> Result<FileEntry, String> {
+    let output = run_checked(session, &format!("{}; e {}", ENTRY_FN, quote(path)))?;
+    let mut entry = parse_entries(&output)?
+        .pop()
+        .ok_or_else(|| "文件或目录不存在".to_string())?;
+    entry.name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    entry.path = path.to_string();
+    Ok(entry)
+}
+
+pub(super) fn exists(session: &Session, path: &str) -> Result<bool, String> {
+    // -e 跟随符号链接，目标不存在的链接视为不存在
+    let (status, _, _) = run(session, &format!("[ -e {} ]", quote(path)))?;
+    Ok(status == 0)
+}
+
+/// SCP 不能追加写入；续传且远程文件恰好为 offset 大小时用 `cat >>` 追加，否则整个文件重传。
+/// 返回值扣除 offset，使调用方累计的字节数仍等于文件大小
+pub(super) fn upload(
+    session: &Session,
+    local_path: &str,
+    remote_path: &str,
+    offset: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<u64, String> {
+    let total_size = std::fs::metadata(local_path)
+        .map_err(|e| e.to_string())?
+        .len();
+    let mut local_file = std::fs::File::open(local_path).map_err(|e| e.to_string())?;
+    if offset > 0 && offset <= total_size && remote_size_is(session, remote_path, offset) {
+        local_file
+            .seek(SeekFrom::Start(offset))
+            .map_err(|e| e.to_string())?;
+        append(
+            session,
+            &mut local_file,
+            remote_path,
+            offset,
+            total_size,
+            progress,
+        )?;
+    } else {
+        send(session, &mut local_file, remote_path, total_size, progress)?;
+    }
+    Ok(total_size.saturating_sub(offset))
+}
+
+/// 主机间传输的写入端；reader 已跳过前 offset 字节，无法重传，远程文件大小不符时直接报错
+pub(super) fn upload_from(
+    session: &Session,
+    reader: &mut dyn Read,
+    remote_path: &str,
+    offset: u64,
+    total: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<u64, String> {
+    if offset == 0 {
+        send(session, reader, remote_path, total, progress)?;
+        return Ok(total);
+    }
+    if !remote_size_is(session, remote_path, offset) {
+        return Err("远程文件大小与续传位置不一致，无法续传".to_string());
+    }
+    append(session, reader, remote_path, offset, total, progress)?;
+    Ok(total.saturating_sub(offset))
+}
+
+fn remote_size_is(session: &Session, remote_path: &str, size: u64) -> bool {
+    stat(session, remote_path).is_ok_and(|e| !e.is_dir && e.size == size)
+}
+
+/// 经 scp_send 写入整个文件，reader 须恰好给出 total 字节
+fn send(
+    session: &Session,
+    reader: &mut dyn Read,
+    remote_path: &str,
+    total: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<(), String> {
+    let mut channel = session
+        .scp_send(Path::new(remote_path), 0o644, total, None)
+        .map_err(|e| e.to_string())?;
+    let mut reader = ProgressReader {
+        inner: reader,
+        transferred: 0,
+        total,
+        callback: progress,
+    };
+    std::io::copy(&mut reader, &mut channel).map_err(|e| e.to_string())?;
+    close_scp_channel(channel)
+}
+
+/// 用 `cat >>` 把 reader 的内容追加到远程文件末尾
+fn append(
+    session: &Session,
+    reader: &mut dyn Read,
+    remote_path: &str,
+    offset: u64,
+    total: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<(), String> {
+    let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+    channel
+        .exec(&format!("cat >> {}", quote(remote_path)))
+        .map_err(|e| e.to_string())?;
+    let mut reader = ProgressReader {
+        inner: reader,
+        transferred: offset,
+        total,
+        callback: progress,
+    };
+    std::io::copy(&mut reader, &mut channel).map_err(|e| e.to_string())?;
+    channel.send_eof().map_err(|e| e.to_string())?;
+    let mut stderr = String::new();
+    let _ = channel.stderr().read_to_string(&mut stderr);
+    channel.wait_close().map_err(|e| e.to_string())?;
+    let status = channel.exit_status().map_err(|e| e.to_string())?;
+    if status != 0 {
+        return Err(command_error(status, &stderr));
+    }
+    Ok(())
+}
+
+pub(super) fn download(
+    session: &Session,
+    remote_path: &str,
+    local_path: &str,
+    offset: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<u64, String> {
+    let mut local_file = if offset > 0 {
+        let mut f = std::fs::OpenOptions::new()
+            .write(true)
+            .open(local_path)
+            .map_err(|e| e.to_string())?;
+        f.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+        f
+    } else {
+        std::fs::File::create(local_path).map_err(|e| e.to_string())?
+    };
+    download_to(session, remote_path, &mut local_file, offset, progress)
+}
+
+/// 续传时用 `tail -c +N` 读取剩余部分，否则经 scp_recv 读取整个文件
+pub(super) fn download_to(
+    session: &Session,
+    remote_path: &str,
+    writer: &mut dyn Write,
+    offset: u64,
+    progress: Option<&dyn Fn(u64, u64)>,
+) -> Result<u64, String> {
+    if offset == 0 {
+        let (channel, remote_stat) = session
+            .scp_recv(Path::new(remote_path))
+            .map_err(|e| e.to_string())?;
+        let total_size = remote_stat.size();
+        let mut reader = channel.take(total_size);
+        let transferred = copy_download(&mut reader, writer, 0, total_size, progress)
+            .map_err(|e| e.to_string())?;
+        close_scp_channel(reader.into_inner())?;
+        return Ok(transferred);
+    }
+
+    let total_size = stat(session, remote_path)?.size;
+    if offset >= total_size {
+        return Ok(0);
+    }
+    let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+    channel
+        .exec(&format!("tail -c +{} {}", offset + 1, quote(remote_path)))
+        .map_err(|e| e.to_string())?;
+    let transferred = copy_download(&mut channel, writer, offset, total_size, progress)
+        .map_err(|e| e.to_string())?;
+    let mut stderr = String::new();
+    let _ = channel.stderr().read_to_string(&mut stderr);
+    channel.wait_close().map_err(|e| e.to_string())?;
+    let status = channel.exit_status().map_err(|e| e.to_string())?;
+    if status != 0 {
+        return Err(command_error(status, &stderr));
+    }
+    Ok(transferred)
+}
+
+/// 按 libssh2 的要求关闭 SCP 通道，确保对端已收到全部内容
+fn close_scp_channel(mut channel: ssh2::Channel) -> Result<(), String> {
+    channel.send_eof().map_err(|e| e.to_string())?;
+    channel.wait_eof().map_err(|e| e.to_string())?;
+    channel.close().map_err(|e| e.to_string())?;
+    channel.wait_close().map_err(|e| e.to_string())
+}
+
+pub(super) fn mkdir(session: &Session, path: &str) -> Result<(), String> {
+    run_checked(session, &format!("mkdir -- {}", quote(path))).map(|_| ())
+}
+
+pub(super) fn remove_file(session: &Session, path: &str) -> Result<(), String> {
+    run_checked(session, &format!("rm -- {}", quote(path))).map(|_| ())
+}
+
+pub(super) fn remove_dir(session: &Session, path: &str) -> Result<(), String> {
+    run_checked(session, &format!("rmdir -- {}", quote(path))).map(|_| ())
+}
+
+/// mv 会覆盖已存在的目标，先检查以保持与 SFTP 重命名一致
+pub(super) fn rename(session: &Session, from: &str, to: &str) -> Result<(), String> {
+    let to = quote(to);
+    let command = format!(
+        "if [ -e {0} ] || [ -L {0} ]; then echo '目标已存在' >&2; exit 1; fi; mv -- {1} {0}",
+        to,
+        quote(from)
+    );
+    run_checked(session, &command).map(|_| ())
+}
+
+/// cp 执行后输出的标记。服务器用 ForceCommand 把 exec 换成其他程序时退出码可能仍为 0，
+/// 以是否收到标记判断 cp 确实执行了
+const COPY_DONE_MARKER: &str = "ftx-copy-done";
+const COPY_FAILED_MARKER: &str = "ftx-copy-failed";
+
+/// 在服务器上用 cp 复制文件，目标已存在时覆盖。服务器不允许执行命令时返回 Ok(false)，
+/// 由调用方改用其他方式复制；cp 执行失败时返回其错误
+pub(super) fn copy(session: &Session, from: &str, to: &str) -> Result<bool, String> {
+    let command = format!(
+        "if cp -- {} {}; then echo {}; else echo {}; exit 1; fi",
+        quote(from),
+        quote(to),
+        COPY_DONE_MARKER,
+        COPY_FAILED_MARKER
+    );
+    let Ok((status, stdout, stderr)) = run(session, &command) else {
+        return Ok(false);
+    };
+    match stdout.trim() {
+        COPY_DONE_MARKER => Ok(true),
+        COPY_FAILED_MARKER => Err(command_error(status, &stderr)),
+        _ => Ok(false),
+    }
+}
+
+pub(super) fn create_symlink(session: &Session, target: &str, path: &str) -> Result<(), String> {
+    run_checked(
+        session,
+        &format!("ln -s -- {} {}", quote(target), quote(path)),
+    )
+    .map(|_| ())
+}
+
+pub(super) fn set_permissions(session: &Session, path: &str, mode: u32) -> Result<(), String> {
+    run_checked(
+        session,
+        &format!("chmod {:o} -- {}", mode & PERMISSION_BITS, quote(path)),
+    )
+    .map(|_| ())
+}
+
+pub(super) fn set_owner(
+    session: &Session,
+    path: &str,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> Result<(), String> {
+    let owner = match (uid, gid) {
+        (Some(u), Some(g)) => format!("{}:{}", u, g),
+        (Some(u), None) => u.to_string(),
+        (None, Some(g)) => format!(":{}", g),
+        (None, None) => return Ok(()),
+    };
+    run_checked(session, &format!("chown {} -- {}", owner, quote(path))).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("/data/a b"), "'/data/a b'");
+        assert_eq!(quote("it's"), "'it'\\''s'");
+        assert_eq!(quote("$(rm -rf /)"), "'$(rm -rf /)'");
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let output = "0 41ed 4096 1700000000 0 0 root root\n\
+                      etc\n\
+                      \n\
+                      0 81a4 1234 1700000100 1000 100 UNKNOWN users\n\
+                      log file.txt\n\
+                      \n\
+                      1 a1ff 7 1700000300 0 0 root root\n\
+                      stale\n\
+                      missing\n\
+                      1 41ed 4096 1700000200 0 0 root root\n\
+                      www\n\
+                      /var/www\n";
+        let mut entries = parse_entries(output).unwrap();
+        assert_eq!(entries.len(), 4);
+
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].mode, Some(0o755));
+        assert!(!entries[0].is_symlink);
+
+        assert_eq!(entries[1].name, "log file.txt");
+        assert_eq!(entries[1].size, 1234);
+        assert_eq!(entries[1].mode, Some(0o644));
+        assert_eq!(entries[1].uid, Some(1000));
+        assert_eq!(entries[1].owner, None);
+        assert_eq!(entries[1].group.as_deref(), Some("users"));
+        assert_eq!(
+            entries[1].modified.map(|m| m.timestamp()),
+            Some(1_700_000_100)
+        );
+
+        // 指向目录的符号链接按目录展示，大小与权限取自链接目标
+        let link = entries.pop().unwrap();
+        assert!(link.is_symlink && link.is_dir);
+        assert_eq!(link.size, 4096);
+        assert_eq!(link.mode, Some(0o755));
+        assert_eq!(link.link_target.as_deref(), Some("/var/www"));
+
+        // 目标不存在的链接保留链接自身的信息
+        let broken = entries.pop().unwrap();
+        assert!(broken.is_symlink && !broken.is_dir);
+        assert_eq!(broken.link_target.as_deref(), Some("missing"));
+
+        assert!(parse_entries("garbage\nname\n\n").is_err());
+    }
+
+    #[test]
+    fn test_join_path() {
+        assert_eq!(join_path("/", "etc"), "/etc");
+        assert_eq!(join_path("/home/user", "a.txt"), "/home/user/a.txt");
+    }
+}
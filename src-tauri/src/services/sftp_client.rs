@@ -1,42 +1,156 @@
-use ssh2::{CheckResult, KnownHostFileKind, Session};
+use ssh2::{ErrorCode, FileStat, KeyboardInteractivePrompt, Prompt, Session, Sftp};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::net::TcpStream;
 use std::path::Path;
+use std::sync::Arc;
 
+use crate::models::host::{SshAuthMethod, SshTransferMode};
+use crate::models::host_key::{HostKeyInfo, KnownHostKey};
+use crate::models::proxy::ProxyConfig;
+
+use super::auth_prompt::{AuthPrompt, AuthPromptRequest, AuthPrompter};
 use super::connection::{ConnectionTrait, FileEntry, CHUNK_SIZE};
+use super::host_key::{fetch_ssh_host_key, verify_host_key};
+use super::proxy;
+use super::scp;
+use super::ssh_tunnel;
+
+/// 权限位掩码，去掉 st_mode 中的文件类型位
+const PERMISSION_BITS: u32 = 0o7777;
+/// SFTP 状态码 SSH_FX_NO_SUCH_FILE
+const SFTP_NO_SUCH_FILE: i32 = 2;
+
+/// 由 lstat 结果构造条目；符号链接再读取链接目标并按目标类型展示
+fn entry_from_lstat(sftp: &Sftp, path: &Path, name: String, lstat: FileStat) -> FileEntry {
+    let is_symlink = lstat.file_type().is_symlink();
+    let modified = lstat
+        .mtime
+        .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0));
+    let (target_stat, link_target) = if is_symlink {
+        let target = sftp
+            .readlink(path)
+            .ok()
+            .map(|t| t.to_string_lossy().to_string());
+        // 悬空链接 stat 会失败，此时按普通文件展示
+        (sftp.stat(path).ok(), target)
+    } else {
+        (Some(lstat.clone()), None)
+    };
+    FileEntry {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_dir: target_stat.as_ref().map(|s| s.is_dir()).unwrap_or(false),
+        size: target_stat.as_ref().and_then(|s| s.size).unwrap_or(0),
+        modified,
+        permissions: None,
+        is_symlink,
+        link_target,
+        mode: lstat.perm.map(|p| p & PERMISSION_BITS),
+        uid: lstat.uid,
+        gid: lstat.gid,
+        owner: None,
+        group: None,
+    }
+}
 
-fn verify_host_key(session: &mut Session, host: &str, port: u16) -> Result<(), String> {
-    let known_hosts_path = dirs::home_dir()
-        .ok_or_else(|| "无法获取用户主目录".to_string())?
-        .join(".ssh")
-        .join("known_hosts");
+fn auth_method_label(method: SshAuthMethod) -> &'static str {
+    match method {
+        SshAuthMethod::Key => "密钥",
+        SshAuthMethod::Agent => "SSH Agent",
+        SshAuthMethod::Password => "密码",
+        SshAuthMethod::KeyboardInteractive => "键盘交互",
+    }
+}
 
-    let mut known_hosts = session.known_hosts().map_err(|e| e.to_string())?;
-    if known_hosts_path.exists() {
-        let _ = known_hosts.read_file(&known_hosts_path, KnownHostFileKind::OpenSSH);
+/// 服务器是否只是用键盘交互的形式索要登录密码
+fn is_password_prompt(prompts: &[Prompt<'_>]) -> bool {
+    match prompts {
+        [p] if !p.echo => {
+            let text = p.text.to_lowercase();
+            text.contains("password") || text.contains("密码")
+        }
+        _ => false,
     }
+}
 
-    let (key, _key_type) = session
-        .host_key()
-        .ok_or_else(|| "无法获取主机密钥".to_string())?;
-    let host_str = if port == 22 {
-        host.to_string()
-    } else {
-        format!("[{}]:{}", host, port)
-    };
+/// 把 libssh2 的键盘交互回调转给前端；单独的密码提示用已保存的密码回答一次
+struct InteractiveResponder<'a> {
+    client: &'a SftpClient,
+    prompter: &'a dyn AuthPrompter,
+    password_used: bool,
+    cancelled: bool,
+}
+
+impl KeyboardInteractivePrompt for InteractiveResponder<'_> {
+    fn prompt<'a>(
+        &mut self,
+        username: &str,
+        instructions: &str,
+        prompts: &[Prompt<'a>],
+    ) -> Vec<String> {
+        // 服务器可能发送不含提示的轮次，只需空回答
+        if prompts.is_empty() || self.cancelled {
+            return Vec::new();
+        }
+        if !self.password_used && is_password_prompt(prompts) {
+            if let Some(ref password) = self.client.password {
+                self.password_used = true;
+                return vec![password.clone()];
+            }
+        }
+        let request = AuthPromptRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            host: self.client.host.clone(),
+            port: self.client.port,
+            username: username.to_string(),
+            instructions: instructions.to_string(),
+            prompts: prompts
+                .iter()
+                .map(|p| AuthPrompt {
+                    text: p.text.to_string(),
+                    echo: p.echo,
+                })
+                .collect(),
+        };
+        match self.prompter.prompt(request) {
+            Some(answers) => answers,
+            None => {
+                self.cancelled = true;
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// 服务器当前是否接受某种认证方式；查询失败时不做判断，交给实际认证报错
+fn server_offers(session: &Session, username: &str, method: &str) -> bool {
+    session
+        .auth_methods(username)
+        .map(|methods| methods.split(',').any(|m| m == method))
+        .unwrap_or(true)
+}
 
-    match known_hosts.check(&host_str, key) {
-        CheckResult::Match => Ok(()),
-        CheckResult::Mismatch => Err(format!(
-            "主机密钥不匹配，可能存在中间人攻击。请检查 ~/.ssh/known_hosts 中的 {} 条目",
-            host_str
-        )),
-        CheckResult::NotFound => Err(format!(
-            "主机 {} 未在 known_hosts 中。请先使用 ssh 连接一次以信任该主机，或手动添加到 ~/.ssh/known_hosts",
-            host_str
-        )),
-        CheckResult::Failure => Err("主机密钥验证失败".to_string()),
+/// 依次尝试 agent 中的每个身份，硬件密钥等没有私钥文件的场景只能走这里
+fn userauth_agent(session: &Session, username: &str) -> Result<(), String> {
+    let mut agent = session.agent().map_err(|e| e.to_string())?;
+    agent
+        .connect()
+        .map_err(|e| format!("无法连接 SSH Agent，请确认 SSH_AUTH_SOCK 已设置: {}", e))?;
+    agent.list_identities().map_err(|e| e.to_string())?;
+    let identities = agent.identities().map_err(|e| e.to_string())?;
+    if identities.is_empty() {
+        let _ = agent.disconnect();
+        return Err("SSH Agent 中没有可用的密钥".to_string());
     }
+    let mut result = Err("SSH Agent 中的密钥均被服务器拒绝".to_string());
+    for identity in &identities {
+        if agent.userauth(username, identity).is_ok() {
+            result = Ok(());
+            break;
+        }
+    }
+    let _ = agent.disconnect();
+    result
 }
 
 pub struct SftpClient {
@@ -45,6 +159,19 @@ pub struct SftpClient {
     username: String,
     password: Option<String>,
     key_path: Option<String>,
+    /// 认证方式的尝试顺序，为空时按 `default_auth_order` 推断
+    auth_order: Vec<SshAuthMethod>,
+    /// 键盘交互认证时询问用户，未设置时跳过该认证方式
+    prompter: Option<Arc<dyn AuthPrompter>>,
+    /// 应用内已信任的主机密钥，优先于 ~/.ssh/known_hosts
+    trusted_host_key: Option<KnownHostKey>,
+    /// 跳板机链，按连接顺序排列；每一跳各自校验主机密钥和认证
+    jump_hosts: Vec<SftpClient>,
+    /// 到第一跳（没有跳板机时即本主机）的连接经此代理建立
+    proxy: Option<ProxyConfig>,
+    transfer_mode: SshTransferMode,
+    /// 连接时按传输方式确定；为 true 时文件操作改走 SCP 和远程 shell 命令
+    use_scp: bool,
     session: Option<Session>,
 }
 
@@ -62,50 +189,195 @@ impl SftpClient {
             username,
             password,
             key_path,
+            auth_order: Vec::new(),
+            prompter: None,
+            trusted_host_key: None,
+            jump_hosts: Vec::new(),
+            proxy: None,
+            transfer_mode: SshTransferMode::default(),
+            use_scp: false,
             session: None,
         }
     }
 
-    fn sftp(&self) -> Result<ssh2::Sftp, String> {
-        self.session
-            .as_ref()
-            .ok_or_else(|| "Not connected".to_string())?
-            .sftp()
-            .map_err(|e| e.to_string())
+    pub fn with_auth_order(mut self, order: Vec<SshAuthMethod>) -> Self {
+        self.auth_order = order;
+        self
     }
-}
 
-impl ConnectionTrait for SftpClient {
-    fn connect(&mut self) -> Result<(), String> {
-        let addr = format!("{}:{}", self.host, self.port);
-        let tcp = TcpStream::connect(&addr).map_err(|e| e.to_string())?;
+    pub fn with_trusted_host_key(mut self, key: Option<KnownHostKey>) -> Self {
+        self.trusted_host_key = key;
+        self
+    }
+
+    pub fn with_jump_hosts(mut self, jump_hosts: Vec<SftpClient>) -> Self {
+        self.jump_hosts = jump_hosts;
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    pub fn with_transfer_mode(mut self, mode: SshTransferMode) -> Self {
+        self.transfer_mode = mode;
+        self
+    }
+
+    pub fn with_prompter(mut self, prompter: Option<Arc<dyn AuthPrompter>>) -> Self {
+        self.prompter = prompter;
+        self
+    }
+
+    /// 未配置顺序时：有密钥先用密钥，再尝试 ssh-agent（密钥有口令且已载入 agent 的情况），
+    /// 有密码时再用密码；没有密钥时有密码用密码，都没有时才尝试 ssh-agent。
+    /// 最后追加键盘交互，兼容要求“密钥 + 验证码”的服务器
+    fn effective_auth_order(&self) -> Vec<SshAuthMethod> {
+        if !self.auth_order.is_empty() {
+            return self.auth_order.clone();
+        }
+        let mut order = Vec::new();
+        if self.key_path.is_some() {
+            order.push(SshAuthMethod::Key);
+            order.push(SshAuthMethod::Agent);
+            if self.password.is_some() {
+                order.push(SshAuthMethod::Password);
+            }
+        } else if self.password.is_some() {
+            order.push(SshAuthMethod::Password);
+        } else {
+            order.push(SshAuthMethod::Agent);
+        }
+        order.push(SshAuthMethod::KeyboardInteractive);
+        order
+    }
+
+    fn authenticate(&self, session: &Session, method: SshAuthMethod) -> Result<(), String> {
+        match method {
+            SshAuthMethod::Key => {
+                let key_path = self.key_path.as_ref().ok_or("未配置密钥文件")?;
+                session
+                    .userauth_pubkey_file(
+                        &self.username,
+                        None,
+                        Path::new(key_path),
+                        self.password.as_deref(),
+                    )
+                    .map_err(|e| e.to_string())
+            }
+            SshAuthMethod::Agent => userauth_agent(session, &self.username),
+            SshAuthMethod::Password => {
+                let password = self.password.as_ref().ok_or("未配置密码")?;
+                session
+                    .userauth_password(&self.username, password)
+                    .map_err(|e| e.to_string())
+            }
+            SshAuthMethod::KeyboardInteractive => {
+                let prompter = self.prompter.as_deref().ok_or("当前连接无法询问验证码")?;
+                let mut responder = InteractiveResponder {
+                    client: self,
+                    prompter,
+                    password_used: false,
+                    cancelled: false,
+                };
+                let result = session
+                    .userauth_keyboard_interactive(&self.username, &mut responder)
+                    .map_err(|e| e.to_string());
+                if responder.cancelled {
+                    return Err("用户取消了认证".to_string());
+                }
+                result
+            }
+        }
+    }
+
+    /// 经跳板机逐跳建立到本主机的 TCP 流；没有跳板机时直接连接
+    fn open_stream(&self) -> Result<TcpStream, String> {
+        let mut stream: Option<TcpStream> = None;
+        for (i, hop) in self.jump_hosts.iter().enumerate() {
+            let tcp = match stream.take() {
+                Some(s) => s,
+                None => self.connect_tcp(&hop.host, hop.port)?,
+            };
+            let session = hop
+                .open_session(tcp)
+                .map_err(|e| format!("跳板机 {}: {}", hop.host, e))?;
+            let next = self.jump_hosts.get(i + 1).unwrap_or(self);
+            stream = Some(ssh_tunnel::direct_tcpip(session, &next.host, next.port)?);
+        }
+        match stream {
+            Some(s) => Ok(s),
+            None => self.connect_tcp(&self.host, self.port),
+        }
+    }
+
+    /// 取回本主机出示的主机密钥供用户确认：经跳板机时每一跳照常校验主机密钥并认证，
+    /// 到本主机只握手、不校验也不认证
+    pub fn fetch_host_key(&self) -> Result<HostKeyInfo, String> {
+        fetch_ssh_host_key(self.open_stream()?, &self.host, self.port)
+    }
+
+    fn connect_tcp(&self, host: &str, port: u16) -> Result<TcpStream, String> {
+        proxy::connect(host, port, self.proxy.as_ref())
+    }
+
+    /// 在已建立的 TCP 流上完成握手、主机密钥校验和认证
+    fn open_session(&self, tcp: TcpStream) -> Result<Session, String> {
         let mut session = Session::new().map_err(|e| e.to_string())?;
         session.set_tcp_stream(tcp);
         session.handshake().map_err(|e| e.to_string())?;
 
-        verify_host_key(&mut session, &self.host, self.port)?;
-
-        if let Some(ref key_path) = self.key_path {
-            session
-                .userauth_pubkey_file(
-                    &self.username,
-                    None,
-                    Path::new(key_path),
-                    self.password.as_deref(),
-                )
-                .map_err(|e| e.to_string())?;
-        } else if let Some(ref password) = self.password {
-            session
-                .userauth_password(&self.username, password)
-                .map_err(|e| e.to_string())?;
-        } else {
-            return Err("No authentication method provided".to_string());
+        verify_host_key(
+            &session,
+            &self.host,
+            self.port,
+            self.trusted_host_key.as_ref(),
+        )?;
+
+        let mut errors = Vec::new();
+        for method in self.effective_auth_order() {
+            // 前一步部分成功后（如公钥通过仍需验证码）服务器会收窄可用方式，不在列表中的直接跳过
+            if method == SshAuthMethod::KeyboardInteractive
+                && !server_offers(&session, &self.username, "keyboard-interactive")
+            {
+                continue;
+            }
+            if let Err(e) = self.authenticate(&session, method) {
+                errors.push(format!("{}: {}", auth_method_label(method), e));
+            }
+            if session.authenticated() {
+                break;
+            }
         }
 
         if !session.authenticated() {
-            return Err("Authentication failed".to_string());
+            return Err(format!("认证失败（{}）", errors.join("；")));
         }
+        Ok(session)
+    }
+
+    fn session(&self) -> Result<&Session, String> {
+        self.session
+            .as_ref()
+            .ok_or_else(|| "Not connected".to_string())
+    }
 
+    fn sftp(&self) -> Result<ssh2::Sftp, String> {
+        self.session()?.sftp().map_err(|e| e.to_string())
+    }
+}
+
+impl ConnectionTrait for SftpClient {
+    fn connect(&mut self) -> Result<(), String> {
+        let tcp = self.open_stream()?;
+        let session = self.open_session(tcp)?;
+        self.use_scp = match self.transfer_mode {
+            SshTransferMode::Sftp => false,
+            SshTransferMode::Scp => true,
+            // 没有 sftp-server 的服务器（如嵌入式设备上的 Dropbear）打开 SFTP 通道会失败
+            SshTransferMode::Auto => session.sftp().is_err(),
+        };
         self.session = Some(session);
         Ok(())
     }
@@ -128,6 +400,9 @@ impl ConnectionTrait for SftpClient {
     }
 
     fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+        if self.use_scp {
+            return scp::list_dir(self.session()?, path);
+        }
         let sftp = self.sftp()?;
         let entries = sftp
             .readdir(Path::new(path))
@@ -143,18 +418,20 @@ impl ConnectionTrait for SftpClient {
             if name == "." || name == ".." {
                 continue;
             }
-            files.push(FileEntry {
-                name,
-                path: pathbuf.to_string_lossy().to_string(),
-                is_dir: stat.is_dir(),
-                size: stat.size.unwrap_or(0),
-                modified: stat.mtime.map(|t| t.to_string()),
-            });
+            // readdir 的属性在多数服务器上即 lstat 结果，缺少类型信息时再单独 lstat
+            let lstat = match stat.perm {
+                Some(_) => stat,
+                None => sftp.lstat(&pathbuf).unwrap_or(stat),
+            };
+            files.push(entry_from_lstat(&sftp, &pathbuf, name, lstat));
         }
         Ok(files)
     }
 
     fn file_size(&mut self, path: &str) -> Result<u64, String> {
+        if self.use_scp {
+            return Ok(scp::stat(self.session()?, path)?.size);
+        }
         let sftp = self.sftp()?;
         let stat = sftp.stat(Path::new(path)).map_err(|e| e.to_string())?;
         stat.size
@@ -162,10 +439,14 @@ impl ConnectionTrait for SftpClient {
     }
 
     fn file_exists(&mut self, path: &str) -> Result<bool, String> {
+        if self.use_scp {
+            return scp::exists(self.session()?, path);
+        }
         let sftp = self.sftp()?;
         match sftp.stat(Path::new(path)) {
             Ok(_) => Ok(true),
-            Err(_) => Ok(false),
+            Err(e) if e.code() == ErrorCode::SFTP(SFTP_NO_SUCH_FILE) => Ok(false),
+            Err(e) => Err(e.to_string()),
         }
     }
 
@@ -176,126 +457,269 @@ impl ConnectionTrait for SftpClient {
         offset: u64,
         progress: Option<&dyn Fn(u64, u64)>,
     ) -> Result<u64, String> {
-        let sftp = self.sftp()?;
+        if self.use_scp {
+            return scp::upload(self.session()?, local_path, remote_path, offset, progress);
+        }
         let metadata = std::fs::metadata(local_path).map_err(|e| e.to_string())?;
         let total_size = metadata.len();
 
         let mut local_file =
             std::fs::File::open(local_path).map_err(|e| e.to_string())?;
-
-        let mut remote_file = if offset > 0 {
+        if offset > 0 {
             local_file
                 .seek(SeekFrom::Start(offset))
                 .map_err(|e| e.to_string())?;
-            let mut f = sftp
-                .open_mode(
-                    Path::new(remote_path),
-                    ssh2::OpenFlags::WRITE,
-                    0o644,
-                    ssh2::OpenType::File,
-                )
+        }
+        self.upload_from(&mut local_file, remote_path, offset, total_size, progress)
+    }
+
+    fn download(
+        &mut self,
+        remote_path: &str,
+        local_path: &str,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        if self.use_scp {
+            return scp::download(self.session()?, remote_path, local_path, offset, progress);
+        }
+        let mut local_file = if offset > 0 {
+            let mut f = std::fs::OpenOptions::new()
+                .write(true)
+                .open(local_path)
                 .map_err(|e| e.to_string())?;
             f.seek(SeekFrom::Start(offset))
                 .map_err(|e| e.to_string())?;
             f
         } else {
-            sftp.create(Path::new(remote_path))
-                .map_err(|e| e.to_string())?
+            std::fs::File::create(local_path).map_err(|e| e.to_string())?
         };
+        self.download_to(remote_path, &mut local_file, offset, progress)
+    }
+
+    fn download_to(
+        &mut self,
+        remote_path: &str,
+        writer: &mut dyn Write,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        if self.use_scp {
+            return scp::download_to(self.session()?, remote_path, writer, offset, progress);
+        }
+        let sftp = self.sftp()?;
+        let stat = sftp
+            .stat(Path::new(remote_path))
+            .map_err(|e| e.to_string())?;
+        let total_size = stat.size.unwrap_or(0);
+
+        let mut remote_file = sftp
+            .open(Path::new(remote_path))
+            .map_err(|e| e.to_string())?;
+        if offset > 0 {
+            remote_file
+                .seek(SeekFrom::Start(offset))
+                .map_err(|e| e.to_string())?;
+        }
 
         let mut buf = [0u8; CHUNK_SIZE];
-        let mut transferred = offset;
+        let mut transferred: u64 = 0;
 
         loop {
-            let n = local_file.read(&mut buf).map_err(|e| e.to_string())?;
+            let n = remote_file.read(&mut buf).map_err(|e| e.to_string())?;
             if n == 0 {
                 break;
             }
-            remote_file
+            writer
                 .write_all(&buf[..n])
                 .map_err(|e| e.to_string())?;
             transferred += n as u64;
             if let Some(cb) = progress {
-                cb(transferred, total_size);
+                cb(offset + transferred, total_size);
             }
         }
 
-        Ok(transferred - offset)
+        Ok(transferred)
     }
 
-    fn download(
+    fn upload_from(
         &mut self,
+        reader: &mut dyn Read,
         remote_path: &str,
-        local_path: &str,
         offset: u64,
+        total: u64,
         progress: Option<&dyn Fn(u64, u64)>,
     ) -> Result<u64, String> {
+        if self.use_scp {
+            return scp::upload_from(self.session()?, reader, remote_path, offset, total, progress);
+        }
         let sftp = self.sftp()?;
-        let stat = sftp
-            .stat(Path::new(remote_path))
-            .map_err(|e| e.to_string())?;
-        let total_size = stat.size.unwrap_or(0);
-
-        let mut remote_file = sftp
-            .open(Path::new(remote_path))
-            .map_err(|e| e.to_string())?;
-
-        let mut local_file = if offset > 0 {
-            remote_file
-                .seek(SeekFrom::Start(offset))
-                .map_err(|e| e.to_string())?;
-            let mut f = std::fs::OpenOptions::new()
-                .write(true)
-                .open(local_path)
+        let mut remote_file = if offset > 0 {
+            let mut f = sftp
+                .open_mode(
+                    Path::new(remote_path),
+                    ssh2::OpenFlags::WRITE,
+                    0o644,
+                    ssh2::OpenType::File,
+                )
                 .map_err(|e| e.to_string())?;
             f.seek(SeekFrom::Start(offset))
                 .map_err(|e| e.to_string())?;
             f
         } else {
-            std::fs::File::create(local_path).map_err(|e| e.to_string())?
+            sftp.create(Path::new(remote_path))
+                .map_err(|e| e.to_string())?
         };
 
         let mut buf = [0u8; CHUNK_SIZE];
-        let mut transferred: u64 = 0;
+        let mut transferred = offset;
 
         loop {
-            let n = remote_file.read(&mut buf).map_err(|e| e.to_string())?;
+            let n = reader.read(&mut buf).map_err(|e| e.to_string())?;
             if n == 0 {
                 break;
             }
-            local_file
+            remote_file
                 .write_all(&buf[..n])
                 .map_err(|e| e.to_string())?;
             transferred += n as u64;
             if let Some(cb) = progress {
-                cb(offset + transferred, total_size);
+                cb(transferred, total);
             }
         }
 
-        Ok(transferred)
+        Ok(transferred - offset)
+    }
+
+    /// SFTP 可在任意位置写入；SCP 模式下由 `cat >>` 追加
+    fn can_append(&self) -> bool {
+        true
+    }
+
+    /// libssh2 不支持 SFTP 扩展请求，无法使用 copy-data，服务器端复制改由 exec 通道执行 cp；
+    /// 服务器不允许执行命令时在两个 SFTP 句柄间逐块复制，数据仍需往返客户端。
+    /// cp 已执行但失败（如权限不足、磁盘已满）时直接返回其错误
+    fn copy(&mut self, from: &str, to: &str) -> Result<(), String> {
+        if scp::copy(self.session()?, from, to)? {
+            return Ok(());
+        }
+        if self.use_scp {
+            return Err("服务器不允许执行复制命令".to_string());
+        }
+        let sftp = self.sftp()?;
+        let mut source = sftp
+            .open(Path::new(from))
+            .map_err(|e| e.to_string())?;
+        let mut target = sftp
+            .create(Path::new(to))
+            .map_err(|e| e.to_string())?;
+        std::io::copy(&mut source, &mut target)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     }
 
     fn mkdir(&mut self, path: &str) -> Result<(), String> {
+        if self.use_scp {
+            return scp::mkdir(self.session()?, path);
+        }
         let sftp = self.sftp()?;
         sftp.mkdir(Path::new(path), 0o755)
             .map_err(|e| e.to_string())
     }
 
     fn remove_file(&mut self, path: &str) -> Result<(), String> {
+        if self.use_scp {
+            return scp::remove_file(self.session()?, path);
+        }
         let sftp = self.sftp()?;
         sftp.unlink(Path::new(path)).map_err(|e| e.to_string())
     }
 
     fn remove_dir(&mut self, path: &str) -> Result<(), String> {
+        if self.use_scp {
+            return scp::remove_dir(self.session()?, path);
+        }
         let sftp = self.sftp()?;
         sftp.rmdir(Path::new(path)).map_err(|e| e.to_string())
     }
 
     fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        if self.use_scp {
+            return scp::rename(self.session()?, from, to);
+        }
         let sftp = self.sftp()?;
         sftp.rename(Path::new(from), Path::new(to), None)
             .map_err(|e| e.to_string())
     }
+
+    fn create_symlink(&mut self, target: &str, path: &str) -> Result<(), String> {
+        if self.use_scp {
+            return scp::create_symlink(self.session()?, target, path);
+        }
+        let sftp = self.sftp()?;
+        // ssh2 的参数顺序为 (目标, 链接路径)，与 OpenSSH 服务端的实际行为一致
+        sftp.symlink(Path::new(target), Path::new(path))
+            .map_err(|e| e.to_string())
+    }
+
+    fn stat(&mut self, path: &str) -> Result<FileEntry, String> {
+        if self.use_scp {
+            return scp::stat(self.session()?, path);
+        }
+        let sftp = self.sftp()?;
+        let path = Path::new(path);
+        let lstat = sftp.lstat(path).map_err(|e| e.to_string())?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        Ok(entry_from_lstat(&sftp, path, name, lstat))
+    }
+
+    fn set_permissions(&mut self, path: &str, mode: u32) -> Result<(), String> {
+        if self.use_scp {
+            return scp::set_permissions(self.session()?, path, mode);
+        }
+        let sftp = self.sftp()?;
+        let stat = FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: Some(mode & PERMISSION_BITS),
+            atime: None,
+            mtime: None,
+        };
+        sftp.setstat(Path::new(path), stat)
+            .map_err(|e| e.to_string())
+    }
+
+    fn set_owner(
+        &mut self,
+        path: &str,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> Result<(), String> {
+        if self.use_scp {
+            return scp::set_owner(self.session()?, path, uid, gid);
+        }
+        let sftp = self.sftp()?;
+        // SFTP v3 只能同时设置 uid 与 gid，缺省的一方沿用当前值
+        let current = sftp.stat(Path::new(path)).map_err(|e| e.to_string())?;
+        let stat = FileStat {
+            size: None,
+            uid: uid.or(current.uid),
+            gid: gid.or(current.gid),
+            perm: None,
+            atime: None,
+            mtime: None,
+        };
+        sftp.setstat(Path::new(path), stat)
+            .map_err(|e| e.to_string())
+    }
+
+    fn ssh_session(&self) -> Option<Session> {
+        self.session.clone()
+    }
 }
 
 #[cfg(test)]
@@ -330,6 +754,143 @@ mod tests {
         assert!(client.password.is_none());
     }
 
+    #[test]
+    fn test_effective_auth_order() {
+        let new = |password: Option<&str>, key: Option<&str>| {
+            SftpClient::new(
+                "h".into(),
+                22,
+                "u".into(),
+                password.map(Into::into),
+                key.map(Into::into),
+            )
+        };
+        assert_eq!(
+            new(Some("p"), Some("/k")).effective_auth_order(),
+            vec![
+                SshAuthMethod::Key,
+                SshAuthMethod::Agent,
+                SshAuthMethod::Password,
+                SshAuthMethod::KeyboardInteractive
+            ]
+        );
+        assert_eq!(
+            new(None, Some("/k")).effective_auth_order(),
+            vec![
+                SshAuthMethod::Key,
+                SshAuthMethod::Agent,
+                SshAuthMethod::KeyboardInteractive
+            ]
+        );
+        assert_eq!(
+            new(Some("p"), None).effective_auth_order(),
+            vec![SshAuthMethod::Password, SshAuthMethod::KeyboardInteractive]
+        );
+        assert_eq!(
+            new(None, None).effective_auth_order(),
+            vec![SshAuthMethod::Agent, SshAuthMethod::KeyboardInteractive]
+        );
+
+        let order = vec![SshAuthMethod::Agent, SshAuthMethod::Key];
+        let client = new(None, Some("/k")).with_auth_order(order.clone());
+        assert_eq!(client.effective_auth_order(), order);
+    }
+
+    #[test]
+    fn test_fetch_host_key_goes_through_jump_host() {
+        use std::net::TcpListener;
+
+        // 目标主机只能经跳板机到达，不应被直接连接
+        let target = TcpListener::bind("127.0.0.1:0").unwrap();
+        target.set_nonblocking(true).unwrap();
+        let target_port = target.local_addr().unwrap().port();
+
+        // 跳板机桩：发送版本串、读走客户端的握手数据后断开
+        let bastion = TcpListener::bind("127.0.0.1:0").unwrap();
+        let bastion_port = bastion.local_addr().unwrap().port();
+        let handle = std::thread::spawn(move || {
+            let (mut socket, _) = bastion.accept().unwrap();
+            socket.write_all(b"SSH-2.0-bastion\r\n").unwrap();
+            let mut buf = [0u8; 64];
+            socket.read(&mut buf).unwrap()
+        });
+
+        let new = |port| SftpClient::new("127.0.0.1".into(), port, "u".into(), None, None);
+        let client = new(target_port).with_jump_hosts(vec![new(bastion_port)]);
+        let err = client.fetch_host_key().unwrap_err();
+        assert!(err.starts_with("跳板机 127.0.0.1: "), "{}", err);
+        assert!(handle.join().unwrap() > 0);
+        assert!(target.accept().is_err());
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingPrompter {
+        requests: std::sync::Mutex<Vec<AuthPromptRequest>>,
+        answer: Option<Vec<String>>,
+    }
+
+    impl AuthPrompter for RecordingPrompter {
+        fn prompt(&self, request: AuthPromptRequest) -> Option<Vec<String>> {
+            self.requests.lock().unwrap().push(request);
+            self.answer.clone()
+        }
+    }
+
+    fn prompt(text: &str, echo: bool) -> Prompt<'_> {
+        Prompt {
+            text: text.into(),
+            echo,
+        }
+    }
+
+    #[test]
+    fn test_interactive_responder() {
+        let client = SftpClient::new("h".into(), 2222, "u".into(), Some("pass".into()), None);
+        let prompter = RecordingPrompter {
+            answer: Some(vec!["123456".into()]),
+            ..Default::default()
+        };
+        let mut responder = InteractiveResponder {
+            client: &client,
+            prompter: &prompter,
+            password_used: false,
+            cancelled: false,
+        };
+
+        assert!(responder.prompt("u", "", &[]).is_empty());
+        // 密码提示用已保存的密码回答，只回答一次，避免密码错误时反复重试
+        let password = [prompt("Password: ", false)];
+        assert_eq!(responder.prompt("u", "", &password), vec!["pass"]);
+        assert!(prompter.requests.lock().unwrap().is_empty());
+        assert_eq!(responder.prompt("u", "", &password), vec!["123456"]);
+
+        let otp = [prompt("Verification code: ", false)];
+        assert_eq!(responder.prompt("u", "Google Authenticator", &otp), vec!["123456"]);
+        let requests = prompter.requests.lock().unwrap();
+        let last = requests.last().unwrap();
+        assert_eq!(last.port, 2222);
+        assert_eq!(last.instructions, "Google Authenticator");
+        assert_eq!(last.prompts, vec![AuthPrompt { text: "Verification code: ".into(), echo: false }]);
+    }
+
+    #[test]
+    fn test_interactive_responder_cancel() {
+        let client = SftpClient::new("h".into(), 22, "u".into(), None, None);
+        let prompter = RecordingPrompter::default();
+        let mut responder = InteractiveResponder {
+            client: &client,
+            prompter: &prompter,
+            password_used: false,
+            cancelled: false,
+        };
+        let otp = [prompt("Verification code: ", false)];
+        assert!(responder.prompt("u", "", &otp).is_empty());
+        assert!(responder.cancelled);
+        // 取消后不再弹出后续提示
+        responder.prompt("u", "", &otp);
+        assert_eq!(prompter.requests.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_sftp_not_connected_errors() {
         let client = SftpClient::new(
@@ -0,0 +1,582 @@
+//! OpenSSH 客户端配置（~/.ssh/config）解析，用于批量导入主机
+
+use std::path::{Path, PathBuf};
+
+use crate::models::host::{Host, Protocol};
+use crate::models::host_import::HostImportEntry;
+
+/// 与 OpenSSH 一致，Include 最多嵌套 16 层
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// 导入时关心的配置项，其余配置项忽略
+const KNOWN_KEYWORDS: [&str; 5] = ["hostname", "port", "user", "identityfile", "proxyjump"];
+
+/// 一个具体的 Host 别名按 OpenSSH 规则合并后的设置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SshConfigHost {
+    pub alias: String,
+    pub hostname: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub identity_file: Option<String>,
+    /// `[user@]host[:port]`，多跳时以逗号分隔；`none` 已按未设置处理
+    pub proxy_jump: Option<String>,
+}
+
+/// Host 段（Match 段的 patterns 为空，不匹配任何别名）
+#[derive(Debug)]
+struct Block {
+    patterns: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+/// 解析配置文件，返回所有具体别名（不含通配符和否定模式）的合并结果
+pub fn parse_config(path: &Path, home: &Path) -> Result<Vec<SshConfigHost>, String> {
+    // 第一个 Host 之前的配置项对所有主机生效
+    let mut blocks = vec![Block {
+        patterns: vec!["*".to_string()],
+        options: Vec::new(),
+    }];
+    read_file(path, home, &mut blocks, 0)?;
+    Ok(resolve_hosts(&blocks, home))
+}
+
+fn read_file(
+    path: &Path,
+    home: &Path,
+    blocks: &mut Vec<Block>,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(format!("Include 嵌套超过 {} 层", MAX_INCLUDE_DEPTH));
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    for (index, line) in content.lines().enumerate() {
+        let Some((keyword, args)) = split_line(line) else {
+            continue;
+        };
+        let location = || format!("{}:{}", path.display(), index + 1);
+        match keyword.as_str() {
+            "host" => blocks.push(Block {
+                patterns: args,
+                options: Vec::new(),
+            }),
+            "match" => blocks.push(Block {
+                patterns: Vec::new(),
+                options: Vec::new(),
+            }),
+            "include" => {
+                let current = blocks.len() - 1;
+                for pattern in &args {
+                    for file in expand_include(pattern, home) {
+                        read_file(&file, home, blocks, depth + 1)?;
+                    }
+                }
+                // 被包含文件结束后回到 Include 所在的 Host 段
+                if blocks.len() - 1 != current {
+                    let patterns = blocks[current].patterns.clone();
+                    blocks.push(Block {
+                        patterns,
+                        options: Vec::new(),
+                    });
+                }
+            }
+            k if KNOWN_KEYWORDS.contains(&k) => {
+                let value = args
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| format!("{} 缺少参数", location()))?;
+                if k == "port" && value.parse::<u16>().ok().filter(|&p| p != 0).is_none() {
+                    return Err(format!("{} 端口无效: {}", location(), value));
+                }
+                if let Some(block) = blocks.last_mut() {
+                    block.options.push((keyword, value));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 拆出小写的配置项名和参数；支持 `Key value`、`Key=value` 和双引号包裹的参数
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let keyword = line[..end].to_lowercase();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+    Some((keyword, split_args(rest)))
+}
+
+fn split_args(s: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_arg = false;
+    for c in s.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Include 的相对路径相对 ~/.ssh；文件名部分可含 `*`、`?` 通配符，匹配结果按名称排序
+fn expand_include(pattern: &str, home: &Path) -> Vec<PathBuf> {
+    let path = expand_home(pattern, home);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        home.join(".ssh").join(path)
+    };
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return if path.is_file() {
+            vec![path]
+        } else {
+            Vec::new()
+        };
+    }
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_str()
+                .is_some_and(|n| !n.starts_with('.') && wildcard_match(name, n))
+        })
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+fn expand_home(value: &str, home: &Path) -> PathBuf {
+    match value.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None if value == "~" => home.to_path_buf(),
+        None => PathBuf::from(value),
+    }
+}
+
+/// OpenSSH 的模式匹配：`*` 匹配任意字符串，`?` 匹配单个字符，不区分大小写
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// 命中任一否定模式即不匹配，否则命中任一普通模式即匹配
+fn block_matches(patterns: &[String], alias: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(negated, alias) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(pattern, alias),
+        }
+    }
+    matched
+}
+
+fn is_concrete(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '!'])
+}
+
+/// 每个配置项取第一个匹配段中的值，与 ssh 的取值规则一致
+fn resolve_hosts(blocks: &[Block], home: &Path) -> Vec<SshConfigHost> {
+    let mut aliases: Vec<&str> = Vec::new();
+    for block in blocks.iter().skip(1) {
+        for pattern in block.patterns.iter().filter(|p| is_concrete(p)) {
+            if !aliases.contains(&pattern.as_str()) {
+                aliases.push(pattern);
+            }
+        }
+    }
+    aliases
+        .into_iter()
+        .map(|alias| {
+            let mut values: Vec<(&str, &str)> = Vec::new();
+            for block in blocks.iter().filter(|b| block_matches(&b.patterns, alias)) {
+                for (key, value) in &block.options {
+                    if !values.iter().any(|(k, _)| k == key) {
+                        values.push((key, value));
+                    }
+                }
+            }
+            let get = |key: &str| {
+                values
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            };
+            let hostname = get("hostname").map(|h| expand_tokens(&h, alias, alias, home));
+            let target = hostname.as_deref().unwrap_or(alias);
+            SshConfigHost {
+                alias: alias.to_string(),
+                port: get("port").and_then(|p| p.parse().ok()),
+                user: get("user"),
+                identity_file: get("identityfile")
+                    .map(|f| expand_tokens(&f, alias, target, home))
+                    .map(|f| expand_home(&f, home).to_string_lossy().into_owned()),
+                proxy_jump: get("proxyjump").filter(|j| !j.eq_ignore_ascii_case("none")),
+                hostname,
+            }
+        })
+        .collect()
+}
+
+/// 展开 `%%`、`%d`（本机家目录）、`%n`（别名）和 `%h`（目标主机名）
+fn expand_tokens(value: &str, alias: &str, hostname: &str, home: &Path) -> String {
+    let mut out = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => out.push('%'),
+            Some('d') => out.push_str(&home.to_string_lossy()),
+            Some('n') => out.push_str(alias),
+            Some('h') => out.push_str(hostname),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+/// 拆分 ProxyJump 中的一跳：`[ssh://][user@]host[:port]`，IPv6 地址需写成 `[addr]:port`
+fn parse_jump(spec: &str) -> (Option<String>, String, Option<u16>) {
+    let spec = spec.trim().trim_start_matches("ssh://");
+    let (user, rest) = match spec.rsplit_once('@') {
+        Some((u, r)) => (Some(u.to_string()), r),
+        None => (None, spec),
+    };
+    if let Some(inner) = rest.strip_prefix('[') {
+        if let Some((addr, tail)) = inner.split_once(']') {
+            let port = tail.strip_prefix(':').and_then(|p| p.parse().ok());
+            return (user, addr.to_string(), port);
+        }
+    }
+    match rest.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (user, host.to_string(), port.parse().ok()),
+        _ => (user, rest.to_string(), None),
+    }
+}
+
+/// 把 ProxyJump 中的一跳解析为导入记录的 source：指向配置中的别名时引用该记录，
+/// 否则为跳板机单独生成一条记录，方便一起导入或与已保存的主机匹配
+fn jump_entry(
+    entries: &mut Vec<HostImportEntry>,
+    hosts: &[SshConfigHost],
+    hop: &str,
+    default_user: &str,
+) -> String {
+    let (user, jump_host, port) = parse_jump(hop);
+    if user.is_none() && port.is_none() && hosts.iter().any(|other| other.alias == jump_host) {
+        return jump_host;
+    }
+    let source = hop.trim().to_string();
+    if !entries.iter().any(|e| e.source == source) {
+        let host = Host::new(
+            jump_host.clone(),
+            jump_host,
+            port.unwrap_or(22),
+            Protocol::Sftp,
+            user.unwrap_or_else(|| default_user.to_string()),
+        );
+        entries.push(HostImportEntry::new(source.clone(), host));
+    }
+    source
+}
+
+/// 转换为导入预览记录。多跳的 `ProxyJump a,b,c` 按跳板链导入：
+/// 主机经 c 连接，c 经 b，b 经 a
+pub fn to_import_entries(hosts: &[SshConfigHost], default_user: &str) -> Vec<HostImportEntry> {
+    let mut entries: Vec<HostImportEntry> = hosts
+        .iter()
+        .map(|h| {
+            let mut host = Host::new(
+                h.alias.clone(),
+                h.hostname.clone().unwrap_or_else(|| h.alias.clone()),
+                h.port.unwrap_or(22),
+                Protocol::Sftp,
+                h.user.clone().unwrap_or_else(|| default_user.to_string()),
+            );
+            host.key_path = h.identity_file.clone();
+            HostImportEntry::new(h.alias.clone(), host)
+        })
+        .collect();
+
+    // (所属主机, 某一跳, 它的上一跳)，各主机自己的 ProxyJump 设置完后再串联
+    let mut links = Vec::new();
+    for (i, h) in hosts.iter().enumerate() {
+        let Some(ref proxy_jump) = h.proxy_jump else {
+            continue;
+        };
+        let mut previous: Option<String> = None;
+        for hop in proxy_jump.split(',').filter(|hop| !hop.trim().is_empty()) {
+            let source = jump_entry(&mut entries, hosts, hop, default_user);
+            if let Some(prev) = previous {
+                links.push((i, source.clone(), prev));
+            }
+            previous = Some(source);
+        }
+        entries[i].jump_source = previous;
+    }
+
+    for (owner, source, previous) in links {
+        let Some(hop) = entries.iter_mut().find(|e| e.source == source) else {
+            continue;
+        };
+        let conflict = match hop.jump_source {
+            None => {
+                hop.jump_source = Some(previous);
+                None
+            }
+            Some(ref existing) if *existing == previous => None,
+            Some(ref existing) => Some(format!(
+                "跳板机 {} 已配置经 {} 连接，ProxyJump 中的上一跳 {} 未生效",
+                source, existing, previous
+            )),
+        };
+        entries[owner].warnings.extend(conflict);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_config_first_value_wins() {
+        let home = TempDir::new().unwrap();
+        let config = write(
+            home.path(),
+            ".ssh/config",
+            "User fallback\n\
+             # 注释\n\
+             Host web web-backup\n\
+             \x20   HostName=10.0.0.5\n\
+             \x20   Port 2222\n\
+             \x20   IdentityFile \"~/.ssh/id web\"\n\
+             Host *.internal !db.internal\n\
+             \x20   User ops\n\
+             Host db.internal\n\
+             \x20   Port 5022\n\
+             Host *\n\
+             \x20   User ignored\n\
+             \x20   Port 22\n",
+        );
+        let hosts = parse_config(&config, home.path()).unwrap();
+        let aliases: Vec<&str> = hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["web", "web-backup", "db.internal"]);
+
+        assert_eq!(hosts[0].hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(hosts[0].port, Some(2222));
+        // 第一个 Host 之前的 User 优先于后面的 Host *
+        assert_eq!(hosts[0].user.as_deref(), Some("fallback"));
+        let key = home.path().join(".ssh/id web");
+        assert_eq!(hosts[0].identity_file.as_deref(), key.to_str());
+
+        assert_eq!(hosts[2].hostname, None);
+        assert_eq!(hosts[2].port, Some(5022));
+        assert_eq!(hosts[2].user.as_deref(), Some("fallback"));
+    }
+
+    #[test]
+    fn test_parse_config_include() {
+        let home = TempDir::new().unwrap();
+        write(
+            home.path(),
+            ".ssh/config.d/10-staging",
+            "Host staging\n    HostName staging.example.com\n",
+        );
+        write(
+            home.path(),
+            ".ssh/config.d/20-bastion",
+            "Host bastion\n    User jump\n",
+        );
+        let config = write(
+            home.path(),
+            ".ssh/config",
+            "Host app\n    Include config.d/*\n    User deploy\n    ProxyJump none\n",
+        );
+        let hosts = parse_config(&config, home.path()).unwrap();
+        let aliases: Vec<&str> = hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["app", "staging", "bastion"]);
+        // Include 之后的配置项仍属于 Host app
+        assert_eq!(hosts[0].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[0].proxy_jump, None);
+        assert_eq!(hosts[1].user, None);
+        assert_eq!(hosts[2].user.as_deref(), Some("jump"));
+    }
+
+    #[test]
+    fn test_parse_config_invalid_port() {
+        let home = TempDir::new().unwrap();
+        let config = write(home.path(), "config", "Host a\n  Port abc\n");
+        let err = parse_config(&config, home.path()).unwrap_err();
+        assert!(err.contains(":2"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_jump() {
+        assert_eq!(parse_jump("bastion"), (None, "bastion".into(), None));
+        assert_eq!(
+            parse_jump("ops@10.0.0.1:2200"),
+            (Some("ops".into()), "10.0.0.1".into(), Some(2200))
+        );
+        assert_eq!(
+            parse_jump("ssh://[2001:db8::1]:22"),
+            (None, "2001:db8::1".into(), Some(22))
+        );
+    }
+
+    #[test]
+    fn test_to_import_entries_proxy_jump() {
+        let hosts = vec![
+            SshConfigHost {
+                alias: "bastion".into(),
+                hostname: Some("203.0.113.10".into()),
+                ..Default::default()
+            },
+            SshConfigHost {
+                alias: "app".into(),
+                user: Some("deploy".into()),
+                proxy_jump: Some("bastion".into()),
+                ..Default::default()
+            },
+            SshConfigHost {
+                alias: "db".into(),
+                proxy_jump: Some("bastion,ops@gw.example.com:2222".into()),
+                ..Default::default()
+            },
+        ];
+        let entries = to_import_entries(&hosts, "me");
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].host.host, "203.0.113.10");
+        assert_eq!(entries[0].host.username, "me");
+        assert_eq!(entries[1].jump_source.as_deref(), Some("bastion"));
+        assert_eq!(entries[1].host.host, "app");
+
+        assert_eq!(
+            entries[2].jump_source.as_deref(),
+            Some("ops@gw.example.com:2222")
+        );
+        assert!(entries[2].warnings.is_empty());
+        let gateway = &entries[3];
+        assert_eq!(gateway.host.host, "gw.example.com");
+        assert_eq!(gateway.host.port, 2222);
+        assert_eq!(gateway.host.username, "ops");
+        // 多跳时网关经上一跳连接
+        assert_eq!(gateway.jump_source.as_deref(), Some("bastion"));
+    }
+
+    #[test]
+    fn test_to_import_entries_jump_chain() {
+        let hosts = vec![
+            SshConfigHost {
+                alias: "edge".into(),
+                ..Default::default()
+            },
+            SshConfigHost {
+                alias: "core".into(),
+                proxy_jump: Some("edge".into()),
+                ..Default::default()
+            },
+            SshConfigHost {
+                alias: "db".into(),
+                proxy_jump: Some("ops@10.0.0.1, edge ,core".into()),
+                ..Default::default()
+            },
+        ];
+        let entries = to_import_entries(&hosts, "me");
+        let jump_of = |source: &str| {
+            entries
+                .iter()
+                .find(|e| e.source == source)
+                .and_then(|e| e.jump_source.as_deref())
+        };
+        assert_eq!(jump_of("db"), Some("core"));
+        assert_eq!(jump_of("core"), Some("edge"));
+        assert_eq!(jump_of("edge"), Some("ops@10.0.0.1"));
+        assert_eq!(jump_of("ops@10.0.0.1"), None);
+        assert!(entries[2].warnings.is_empty());
+
+        // 与已有的跳板设置冲突时保留原设置并提示
+        let mut conflicting = hosts.clone();
+        conflicting[2].proxy_jump = Some("ops@10.0.0.1,core".into());
+        let entries = to_import_entries(&conflicting, "me");
+        assert_eq!(
+            entries
+                .iter()
+                .find(|e| e.source == "core")
+                .and_then(|e| e.jump_source.as_deref()),
+            Some("edge")
+        );
+        assert_eq!(entries[2].warnings.len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+//! 通过跳板机的 direct-tcpip 通道转发 TCP 连接。
+//! libssh2 只能在真实套接字上握手，因此在本机回环地址上建一对套接字，
+//! 由后台线程在通道和其中一端之间搬运数据，另一端交给下一跳的 Session。
+
+use ssh2::{Channel, Session};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use super::connection::CHUNK_SIZE;
+
+/// 两个方向都没有数据时的等待间隔
+const IDLE_WAIT: Duration = Duration::from_millis(2);
+
+/// 在 session 上打开到 host:port 的通道，返回可交给下一跳 `set_tcp_stream` 的本地套接字。
+/// session 的所有权移交给转发线程，下一跳断开后随线程一起释放
+pub fn direct_tcpip(session: Session, host: &str, port: u16) -> Result<TcpStream, String> {
+    let channel = session
+        .channel_direct_tcpip(host, port, None)
+        .map_err(|e| format!("跳板机无法连接 {}:{}: {}", host, port, e))?;
+
+    let (local, relay) = loopback_pair()?;
+    relay.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+    std::thread::spawn(move || {
+        session.set_blocking(false);
+        relay_loop(ChannelEnd(channel), relay);
+        // 转发结束后再断开跳板机，上一跳的转发线程随之退出
+        let _ = session.disconnect(None, "bye", None);
+    });
+    Ok(local)
+}
+
+/// 在本机回环地址上建立一对相连的套接字，返回（交给调用方的一端，转发线程持有的一端）
+pub(super) fn loopback_pair() -> Result<(TcpStream, TcpStream), String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+    let local = TcpStream::connect(listener.local_addr().map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    let (relay, peer) = listener.accept().map_err(|e| e.to_string())?;
+    // 防止本机其他进程抢先连入监听端口
+    if Some(peer) != local.local_addr().ok() {
+        return Err("本地转发端口被其他程序连接".to_string());
+    }
+    Ok((local, relay))
+}
+
+/// 转发的远端：读到 0 字节时由 `is_closed` 判断对端是否真的关闭
+pub(super) trait TunnelEnd: Read + Write {
+    fn is_closed(&self) -> bool;
+}
+
+/// 非阻塞模式下的 ssh2 通道，释放时关闭通道
+struct ChannelEnd(Channel);
+
+impl Read for ChannelEnd {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for ChannelEnd {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl TunnelEnd for ChannelEnd {
+    fn is_closed(&self) -> bool {
+        self.0.eof()
+    }
+}
+
+impl Drop for ChannelEnd {
+    fn drop(&mut self) {
+        let _ = self.0.close();
+    }
+}
+
+/// 读取一次；WouldBlock 视为暂无数据，Ok(None) 表示对端已关闭
+pub(super) fn read_some(reader: &mut impl Read, buf: &mut [u8]) -> Result<Option<usize>, ()> {
+    match reader.read(buf) {
+        Ok(0) => Ok(None),
+        Ok(n) => Ok(Some(n)),
+        Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(Some(0)),
+        Err(_) => Err(()),
+    }
+}
+
+/// 写出尽量多的待发数据，返回是否有进展
+pub(super) fn flush_some(writer: &mut impl Write, pending: &mut Vec<u8>) -> Result<bool, ()> {
+    if pending.is_empty() {
+        return Ok(false);
+    }
+    match writer.write(pending) {
+        Ok(0) => Err(()),
+        Ok(n) => {
+            pending.drain(..n);
+            Ok(true)
+        }
+        Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+        Err(_) => Err(()),
+    }
+}
+
+/// 在远端与本机套接字之间双向搬运数据，直到任一方关闭；两端都须处于非阻塞模式
+pub(super) fn relay_loop(mut remote: impl TunnelEnd, mut socket: TcpStream) {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut to_remote = Vec::new();
+    let mut to_local = Vec::new();
+    let mut remote_closed = false;
+
+    loop {
+        let mut progressed = false;
+
+        if to_remote.is_empty() {
+            match read_some(&mut socket, &mut buf) {
+                Ok(Some(n)) if n > 0 => {
+                    to_remote.extend_from_slice(&buf[..n]);
+                    progressed = true;
+                }
+                Ok(Some(_)) => {}
+                // 下一跳已断开，没有必要再把剩余数据送回
+                Ok(None) | Err(_) => return,
+            }
+        }
+        match flush_some(&mut remote, &mut to_remote) {
+            Ok(p) => progressed |= p,
+            Err(_) => return,
+        }
+
+        if to_local.is_empty() && !remote_closed {
+            match read_some(&mut remote, &mut buf) {
+                Ok(Some(n)) if n > 0 => {
+                    to_local.extend_from_slice(&buf[..n]);
+                    progressed = true;
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => remote_closed = remote.is_closed(),
+                Err(_) => return,
+            }
+        }
+        match flush_some(&mut socket, &mut to_local) {
+            Ok(p) => progressed |= p,
+            Err(_) => return,
+        }
+
+        if remote_closed && to_local.is_empty() {
+            return;
+        }
+        if !progressed {
+            std::thread::sleep(IDLE_WAIT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl TunnelEnd for TcpStream {
+        fn is_closed(&self) -> bool {
+            true
+        }
+    }
+
+    fn socket_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let a = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (b, _) = listener.accept().unwrap();
+        (a, b)
+    }
+
+    #[test]
+    fn test_relay_loop_forwards_both_directions() {
+        // local <-> relay 模拟交给下一跳的套接字，remote <-> server 模拟跳板机通道
+        let (mut local, relay) = socket_pair();
+        let (remote, mut server) = socket_pair();
+        relay.set_nonblocking(true).unwrap();
+        remote.set_nonblocking(true).unwrap();
+        let handle = std::thread::spawn(move || relay_loop(remote, relay));
+
+        let payload: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        local.write_all(&payload).unwrap();
+        let mut received = vec![0u8; payload.len()];
+        server.read_exact(&mut received).unwrap();
+        assert_eq!(received, payload);
+
+        server.write_all(b"SSH-2.0-test\r\n").unwrap();
+        drop(server);
+        let mut reply = Vec::new();
+        local.read_to_end(&mut reply).unwrap();
+        assert_eq!(reply, b"SSH-2.0-test\r\n");
+        handle.join().unwrap();
+    }
+}
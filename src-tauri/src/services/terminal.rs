@@ -0,0 +1,268 @@
+//! 交互式终端：在已连接 SFTP 主机的 SSH 会话上开 PTY shell 通道，沿用该连接的认证和主机密钥校验。
+//! 每个终端由独立线程轮询输出，按键与窗口大小变化经 mpsc 通道交给该线程写入
+
+use serde::Serialize;
+use ssh2::{Channel, Session};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+use super::connection::ConnectionTrait;
+use super::remote_exec::{read_nonblocking, with_nonblocking, Utf8Decoder, POLL_INTERVAL};
+use super::ssh_tunnel::flush_some;
+
+const TERM: &str = "xterm-256color";
+const READ_BUFFER_SIZE: usize = 32 * 1024;
+
+enum TerminalInput {
+    Data(Vec<u8>),
+    Resize(u32, u32),
+    Close,
+}
+
+struct TerminalHandle {
+    host_id: i64,
+    input: Sender<TerminalInput>,
+}
+
+/// 发给前端的 `terminal-output` 事件
+#[derive(Debug, Clone, Serialize)]
+struct TerminalOutput {
+    terminal_id: String,
+    data: String,
+}
+
+/// 发给前端的 `terminal-exit` 事件；exit_status 为 None 表示由用户关闭或连接中断
+#[derive(Debug, Clone, Serialize)]
+struct TerminalExit {
+    terminal_id: String,
+    exit_status: Option<i32>,
+    error: Option<String>,
+}
+
+/// 管理所有打开的终端，同一主机可同时打开多个
+#[derive(Clone, Default)]
+pub struct TerminalManager {
+    app_handle: Arc<Mutex<Option<AppHandle>>>,
+    terminals: Arc<Mutex<HashMap<String, TerminalHandle>>>,
+}
+
+impl TerminalManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        let mut h = self.app_handle.lock().unwrap();
+        *h = Some(handle);
+    }
+
+    /// 打开终端并返回终端 ID，输出通过 `terminal-output` 事件推送
+    pub fn open(
+        &self,
+        host_id: i64,
+        conn: Arc<Mutex<Box<dyn ConnectionTrait>>>,
+        cols: u32,
+        rows: u32,
+    ) -> Result<String, String> {
+        let (session, channel) = {
+            let client = conn.lock().map_err(|e| e.to_string())?;
+            let session = client
+                .ssh_session()
+                .ok_or_else(|| "仅已连接的 SFTP 主机支持打开终端".to_string())?;
+            let mut channel = session.channel_session().map_err(|e| e.to_string())?;
+            channel
+                .request_pty(TERM, None, Some((cols, rows, 0, 0)))
+                .map_err(|e| e.to_string())?;
+            channel.shell().map_err(|e| e.to_string())?;
+            (session, channel)
+        };
+
+        let terminal_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::channel();
+        self.terminals
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(terminal_id.clone(), TerminalHandle { host_id, input: tx });
+
+        let manager = self.clone();
+        let id = terminal_id.clone();
+        std::thread::spawn(move || {
+            let result = pump(&conn, &session, channel, &rx, &|data| {
+                manager.emit_output(&id, data)
+            });
+            if let Ok(mut terminals) = manager.terminals.lock() {
+                terminals.remove(&id);
+            }
+            manager.emit_exit(&id, result);
+        });
+        Ok(terminal_id)
+    }
+
+    /// 写入按键或粘贴的内容
+    pub fn write(&self, terminal_id: &str, data: &str) -> Result<(), String> {
+        self.send(terminal_id, TerminalInput::Data(data.as_bytes().to_vec()))
+    }
+
+    pub fn resize(&self, terminal_id: &str, cols: u32, rows: u32) -> Result<(), String> {
+        self.send(terminal_id, TerminalInput::Resize(cols, rows))
+    }
+
+    pub fn close(&self, terminal_id: &str) -> Result<(), String> {
+        self.send(terminal_id, TerminalInput::Close)
+    }
+
+    /// 主机当前打开的终端 ID
+    pub fn list(&self, host_id: i64) -> Result<Vec<String>, String> {
+        let terminals = self.terminals.lock().map_err(|e| e.to_string())?;
+        Ok(terminals
+            .iter()
+            .filter(|(_, handle)| handle.host_id == host_id)
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+
+    fn send(&self, terminal_id: &str, input: TerminalInput) -> Result<(), String> {
+        let terminals = self.terminals.lock().map_err(|e| e.to_string())?;
+        let handle = terminals
+            .get(terminal_id)
+            .ok_or_else(|| "终端不存在或已关闭".to_string())?;
+        handle
+            .input
+            .send(input)
+            .map_err(|_| "终端不存在或已关闭".to_string())
+    }
+
+    fn emit_output(&self, terminal_id: &str, data: String) {
+        if let Some(ref handle) = *self.app_handle.lock().unwrap() {
+            let _ = handle.emit(
+                "terminal-output",
+                TerminalOutput {
+                    terminal_id: terminal_id.to_string(),
+                    data,
+                },
+            );
+        }
+    }
+
+    fn emit_exit(&self, terminal_id: &str, result: Result<Option<i32>, String>) {
+        if let Some(ref handle) = *self.app_handle.lock().unwrap() {
+            let (exit_status, error) = match result {
+                Ok(status) => (status, None),
+                Err(e) => (None, Some(e)),
+            };
+            let _ = handle.emit(
+                "terminal-exit",
+                TerminalExit {
+                    terminal_id: terminal_id.to_string(),
+                    exit_status,
+                    error,
+                },
+            );
+        }
+    }
+}
+
+/// 处理一条输入：按键内容放入待发缓冲区，由主循环以非阻塞方式写出，
+/// 服务器暂不接收时不会占住连接锁；窗口大小变化与关闭在连接锁内立即执行。
+/// 返回 false 表示用户关闭了终端
+fn apply_input(
+    conn: &Mutex<Box<dyn ConnectionTrait>>,
+    channel: &mut Channel,
+    outgoing: &mut Vec<u8>,
+    input: TerminalInput,
+) -> Result<bool, String> {
+    match input {
+        TerminalInput::Data(data) => outgoing.extend_from_slice(&data),
+        TerminalInput::Resize(cols, rows) => {
+            let _guard = conn.lock().map_err(|e| e.to_string())?;
+            channel
+                .request_pty_size(cols, rows, None, None)
+                .map_err(|e| e.to_string())?;
+        }
+        TerminalInput::Close => {
+            let _guard = conn.lock().map_err(|e| e.to_string())?;
+            let _ = channel.close();
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// 终端线程主循环：先收取积压的输入，再写出待发内容并读取一轮输出；
+/// 空闲时在输入通道上等待，按键可立即处理。
+/// shell 退出时返回其退出码
+fn pump(
+    conn: &Mutex<Box<dyn ConnectionTrait>>,
+    session: &Session,
+    mut channel: Channel,
+    input: &Receiver<TerminalInput>,
+    on_output: &dyn Fn(String),
+) -> Result<Option<i32>, String> {
+    let mut decoder = Utf8Decoder::default();
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    let mut outgoing = Vec::new();
+    let mut pending = None;
+    loop {
+        while let Some(next) = pending.take().or_else(|| input.try_recv().ok()) {
+            if !apply_input(conn, &mut channel, &mut outgoing, next)? {
+                return Ok(None);
+            }
+        }
+
+        // PTY 下 stderr 通常已并入 stdout，仍读取扩展数据以免占满通道窗口
+        let (wrote, read, eof) = with_nonblocking(conn, session, || {
+            let wrote = flush_some(&mut channel, &mut outgoing)
+                .map_err(|_| "终端连接已断开".to_string())?;
+            let n = read_nonblocking(&mut channel, &mut buf)?;
+            let mut data = buf[..n].to_vec();
+            let n = read_nonblocking(&mut channel.stderr(), &mut buf)?;
+            data.extend_from_slice(&buf[..n]);
+            Ok((wrote, data, channel.eof()))
+        })?;
+        let text = decoder.decode(&read);
+        if !text.is_empty() {
+            on_output(text);
+        }
+        if eof && read.is_empty() {
+            break;
+        }
+        if read.is_empty() && !wrote {
+            pending = match input.recv_timeout(POLL_INTERVAL) {
+                Ok(next) => Some(next),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => Some(TerminalInput::Close),
+            };
+        }
+    }
+
+    let text = decoder.finish();
+    if !text.is_empty() {
+        on_output(text);
+    }
+    let _guard = conn.lock().map_err(|e| e.to_string())?;
+    channel.wait_close().map_err(|e| e.to_string())?;
+    channel.exit_status().map(Some).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::local_client::LocalClient;
+
+    #[test]
+    fn test_open_requires_ssh_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = LocalClient::new(dir.path());
+        client.connect().unwrap();
+        let conn: Arc<Mutex<Box<dyn ConnectionTrait>>> = Arc::new(Mutex::new(Box::new(client)));
+
+        let manager = TerminalManager::new();
+        let err = manager.open(1, conn, 80, 24).unwrap_err();
+        assert!(err.contains("SFTP"));
+        assert!(manager.list(1).unwrap().is_empty());
+        assert!(manager.write("missing", "ls\r").is_err());
+        assert!(manager.close("missing").is_err());
+    }
+}
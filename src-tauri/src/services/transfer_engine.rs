@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -11,7 +11,7 @@ use crate::models::transfer::{
     ResumeRecord, TransferDirection, TransferHistory, TransferProgress, TransferStatus,
 };
 use crate::services::connection::ConnectionManager;
-use crate::services::resume;
+use crate::services::{remote_transfer, resume};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferTask {
@@ -22,6 +22,9 @@ pub struct TransferTask {
     pub remote_path: String,
     pub direction: String,
     pub file_size: u64,
+    /// 主机间传输的目标主机，此时 remote_path 为源路径、local_path 为目标路径
+    #[serde(default)]
+    pub dest_host_id: Option<i64>,
 }
 
 impl TransferTask {
@@ -41,8 +44,36 @@ impl TransferTask {
             remote_path,
             direction,
             file_size,
+            dest_host_id: None,
         }
     }
+
+    /// 主机间传输：把源主机上的 source_path 传到目标主机的 dest_path
+    pub fn remote(
+        source_host_id: i64,
+        filename: String,
+        source_path: String,
+        dest_host_id: i64,
+        dest_path: String,
+        file_size: u64,
+    ) -> Self {
+        let mut task = Self::new(
+            source_host_id,
+            filename,
+            dest_path,
+            source_path,
+            TransferDirection::Remote.as_str().to_string(),
+            file_size,
+        );
+        task.dest_host_id = Some(dest_host_id);
+        task
+    }
+}
+
+/// 一批任务全部结束后执行的回调，由批内最后结束的任务触发
+struct BatchHook {
+    remaining: AtomicUsize,
+    on_finished: Mutex<Option<Box<dyn FnOnce() + Send>>>,
 }
 
 #[derive(Clone)]
@@ -52,6 +83,7 @@ pub struct TransferEngine {
     app_handle: Arc<Mutex<Option<AppHandle>>>,
     active_tasks: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     task_handles: Arc<Mutex<HashMap<String, std::thread::JoinHandle<()>>>>,
+    batch_hooks: Arc<Mutex<HashMap<String, Arc<BatchHook>>>>,
 }
 
 impl TransferEngine {
@@ -62,6 +94,7 @@ impl TransferEngine {
             app_handle: Arc::new(Mutex::new(None)),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
             task_handles: Arc::new(Mutex::new(HashMap::new())),
+            batch_hooks: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -95,6 +128,41 @@ impl TransferEngine {
         Ok(task_id)
     }
 
+    /// 提交一批任务，全部结束（无论成功、失败还是取消）后执行 on_finished；
+    /// 批次为空时立即执行
+    pub fn submit_batch(
+        &self,
+        tasks: Vec<TransferTask>,
+        on_finished: impl FnOnce() + Send + 'static,
+    ) -> Result<Vec<String>, String> {
+        if tasks.is_empty() {
+            on_finished();
+            return Ok(Vec::new());
+        }
+        let ids: Vec<String> = tasks.iter().map(|t| t.id.clone()).collect();
+        let hook = Arc::new(BatchHook {
+            remaining: AtomicUsize::new(ids.len()),
+            on_finished: Mutex::new(Some(Box::new(on_finished))),
+        });
+        {
+            // 先登记再提交，任务结束得再快也能找到回调
+            let mut hooks = self.batch_hooks.lock().map_err(|e| e.to_string())?;
+            for id in &ids {
+                hooks.insert(id.clone(), hook.clone());
+            }
+        }
+        for (i, task) in tasks.into_iter().enumerate() {
+            if let Err(e) = self.submit_task(task) {
+                // 未提交的任务不会结束，代它们计数，保证回调仍会执行
+                for id in &ids[i..] {
+                    self.finish_batch_member(id);
+                }
+                return Err(e);
+            }
+        }
+        Ok(ids)
+    }
+
     pub fn cancel_task(&self, transfer_id: &str) -> Result<(), String> {
         let flag = {
             let active = self.active_tasks.lock().map_err(|e| e.to_string())?;
@@ -114,12 +182,10 @@ impl TransferEngine {
     }
 
     fn execute_task(&self, task: TransferTask) {
-        let direction = match task.direction.as_str() {
-            "upload" => TransferDirection::Upload,
-            _ => TransferDirection::Download,
-        };
+        let direction =
+            TransferDirection::from_str(&task.direction).unwrap_or(TransferDirection::Download);
 
-        let history = TransferHistory::new(
+        let mut history = TransferHistory::new(
             task.host_id,
             task.filename.clone(),
             task.remote_path.clone(),
@@ -127,6 +193,7 @@ impl TransferEngine {
             direction.clone(),
             task.file_size,
         );
+        history.dest_host_id = task.dest_host_id;
 
         let history_id = {
             let conn = match self.db.conn.lock() {
@@ -159,17 +226,6 @@ impl TransferEngine {
             );
         }
 
-        let resume_offset = match resume::find_resume_record(
-            &self.db,
-            task.host_id,
-            &task.remote_path,
-            &task.local_path,
-            direction.as_str(),
-        ) {
-            Ok(Some(r)) => r.transferred_bytes,
-            _ => 0,
-        };
-
         let conn_arc = match self.conn_manager.get_connection(task.host_id) {
             Ok(c) => c,
             Err(e) => {
@@ -177,6 +233,39 @@ impl TransferEngine {
                 return;
             }
         };
+        let dest_conn = match task
+            .dest_host_id
+            .map(|id| self.conn_manager.get_connection(id))
+            .transpose()
+        {
+            Ok(c) => c,
+            Err(e) => {
+                self.finish_task_failed(&task, history_id, &e);
+                return;
+            }
+        };
+
+        let resume_record = resume::find_resume_record(
+            &self.db,
+            task.host_id,
+            &task.remote_path,
+            &task.local_path,
+            direction.as_str(),
+            task.dest_host_id,
+        )
+        .ok()
+        .flatten();
+        // 主机间传输以目标主机上实际已写入的大小为准
+        let resume_offset = match (resume_record, &dest_conn) {
+            (Some(_), Some(dest)) => match dest.lock() {
+                Ok(mut d) => {
+                    remote_transfer::resume_offset(d.as_mut(), &task.local_path, task.file_size)
+                }
+                Err(_) => 0,
+            },
+            (Some(r), None) => r.transferred_bytes,
+            (None, _) => 0,
+        };
 
         let cancel_flag = {
             let active = self.active_tasks.lock().unwrap();
@@ -199,6 +288,7 @@ impl TransferEngine {
         let last_resume_save = Arc::new(Mutex::new(Instant::now()));
         let db_for_progress = self.db.clone();
         let host_id = task.host_id;
+        let dest_host_id = task.dest_host_id;
         let remote_path_c = task.remote_path.clone();
         let local_path_c = task.local_path.clone();
         let direction_c = direction.clone();
@@ -254,11 +344,22 @@ impl TransferEngine {
                 );
                 let mut record = record;
                 record.transferred_bytes = effective_transferred;
+                record.dest_host_id = dest_host_id;
                 let _ = resume::save_resume_record(&db_for_progress, &record);
             }
         };
 
-        let result = {
+        let result = if let Some(ref dest_arc) = dest_conn {
+            remote_transfer::transfer(
+                &conn_arc,
+                dest_arc,
+                &task.remote_path,
+                &task.local_path,
+                resume_offset,
+                Some(&progress_fn),
+                &cancel_flag,
+            )
+        } else {
             let mut conn_guard = match conn_arc.lock() {
                 Ok(g) => g,
                 Err(e) => {
@@ -280,6 +381,7 @@ impl TransferEngine {
                     resume_offset,
                     Some(&progress_fn),
                 ),
+                TransferDirection::Remote => Err("主机间传输缺少目标主机".to_string()),
             }
         };
 
@@ -339,11 +441,9 @@ impl TransferEngine {
         );
         drop(conn);
 
-        let direction = match task.direction.as_str() {
-            "upload" => TransferDirection::Upload,
-            _ => TransferDirection::Download,
-        };
-        let record = ResumeRecord::new(
+        let direction =
+            TransferDirection::from_str(&task.direction).unwrap_or(TransferDirection::Download);
+        let mut record = ResumeRecord::new(
             task.id.clone(),
             task.host_id,
             task.remote_path.clone(),
@@ -351,6 +451,7 @@ impl TransferEngine {
             direction,
             task.file_size,
         );
+        record.dest_host_id = task.dest_host_id;
         let _ = resume::save_resume_record(&self.db, &record);
 
         self.emit_failed(&task.id, &task.filename, error);
@@ -361,6 +462,30 @@ impl TransferEngine {
         if let Ok(mut active) = self.active_tasks.lock() {
             active.remove(task_id);
         }
+        self.finish_batch_member(task_id);
+    }
+
+    /// 任务结束时递减所在批次的计数，最后一个结束时执行批次回调；
+    /// 同一任务重复调用只计一次
+    fn finish_batch_member(&self, task_id: &str) {
+        let hook = match self.batch_hooks.lock() {
+            Ok(mut hooks) => hooks.remove(task_id),
+            Err(_) => None,
+        };
+        let Some(hook) = hook else {
+            return;
+        };
+        if hook.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let on_finished = hook.on_finished.lock().ok().and_then(|mut f| f.take());
+            if let Some(on_finished) = on_finished {
+                on_finished();
+            }
+        }
+    }
+
+    /// 通知前端一项不经传输队列的操作失败（如重建符号链接），沿用 transfer-failed 事件
+    pub fn notify_failure(&self, filename: &str, error: &str) {
+        self.emit_failed(&uuid::Uuid::new_v4().to_string(), filename, error);
     }
 
     fn emit_event(&self, event: &str, transfer_id: &str, filename: &str) {
@@ -405,6 +530,7 @@ mod tests {
     use super::*;
     use crate::db::migrations;
     use crate::services::connection::ConnectionTrait;
+    use crate::services::local_client::LocalClient;
     use rusqlite::Connection;
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -479,6 +605,26 @@ mod tests {
         fn rename(&mut self, _from: &str, _to: &str) -> Result<(), String> {
             Ok(())
         }
+        fn create_symlink(&mut self, _target: &str, _path: &str) -> Result<(), String> {
+            Ok(())
+        }
+        fn stat(
+            &mut self,
+            _path: &str,
+        ) -> Result<crate::services::connection::FileEntry, String> {
+            Err("not supported".to_string())
+        }
+        fn set_permissions(&mut self, _path: &str, _mode: u32) -> Result<(), String> {
+            Ok(())
+        }
+        fn set_owner(
+            &mut self,
+            _path: &str,
+            _uid: Option<u32>,
+            _gid: Option<u32>,
+        ) -> Result<(), String> {
+            Ok(())
+        }
     }
 
     fn setup_test_db() -> Arc<Database> {
@@ -634,6 +780,49 @@ mod tests {
         assert!(engine.get_active_task_ids().unwrap().is_empty());
     }
 
+    #[test]
+    fn test_submit_batch_runs_hook_after_last_task() {
+        let engine = setup_engine();
+        let files: Vec<NamedTempFile> = (0..3).map(|_| create_temp_file()).collect();
+        let tasks: Vec<TransferTask> = files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                TransferTask::new(
+                    1,
+                    format!("file_{}.txt", i),
+                    f.path().to_str().unwrap().to_string(),
+                    format!("/remote/file_{}.txt", i),
+                    "upload".into(),
+                    100,
+                )
+            })
+            .collect();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let probe = engine.clone();
+        let ids = engine
+            .submit_batch(tasks, move || {
+                let _ = tx.send(probe.get_active_task_ids().unwrap());
+            })
+            .unwrap();
+        assert_eq!(ids.len(), 3);
+        // 回调只执行一次，且此时批内任务都已结束
+        let active = rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert!(ids.iter().all(|id| !active.contains(id)));
+        assert!(rx
+            .recv_timeout(std::time::Duration::from_millis(200))
+            .is_err());
+        assert!(engine.batch_hooks.lock().unwrap().is_empty());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ids = engine
+            .submit_batch(Vec::new(), move || tx.send(()).unwrap())
+            .unwrap();
+        assert!(ids.is_empty());
+        assert!(rx.try_recv().is_ok());
+    }
+
     #[test]
     fn test_cancel_task_cleans_up() {
         let engine = setup_engine();
@@ -667,4 +856,126 @@ mod tests {
 
         assert!(engine.get_active_task_ids().unwrap().is_empty());
     }
+
+    /// 用真实的本地目录后端走完整的续传流程：从续传记录的偏移继续写入并记录历史
+    #[test]
+    fn test_resumed_upload_with_local_client() {
+        let root = tempfile::tempdir().unwrap();
+        let db = setup_test_db();
+        let conn_manager = ConnectionManager::new();
+        let mut client = LocalClient::new(root.path());
+        client.connect().unwrap();
+        conn_manager
+            .insert_mock_connection(1, Box::new(client))
+            .unwrap();
+        let engine = TransferEngine::new(conn_manager, db.clone());
+
+        let local = root.path().join("source.bin");
+        std::fs::write(&local, b"0123456789").unwrap();
+        std::fs::write(root.path().join("dest.bin"), b"0123").unwrap();
+        let local_path = local.to_string_lossy().to_string();
+        let mut record = ResumeRecord::new(
+            "earlier".into(),
+            1,
+            "/dest.bin".into(),
+            local_path.clone(),
+            TransferDirection::Upload,
+            10,
+        );
+        record.transferred_bytes = 4;
+        resume::save_resume_record(&db, &record).unwrap();
+
+        let task = TransferTask::new(
+            1,
+            "source.bin".into(),
+            local_path,
+            "/dest.bin".into(),
+            "upload".into(),
+            10,
+        );
+        let task_id = task.id.clone();
+        engine.submit_task(task).unwrap();
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while engine.get_active_task_ids().unwrap().contains(&task_id) {
+            assert!(Instant::now() < deadline, "task not finished within 5s");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert_eq!(
+            std::fs::read(root.path().join("dest.bin")).unwrap(),
+            b"0123456789"
+        );
+        let conn = db.conn.lock().unwrap();
+        let history = transfer_repo::get_history_by_host(&conn, 1).unwrap();
+        assert_eq!(history[0].status, TransferStatus::Success);
+        assert_eq!(history[0].transferred_size, 10);
+    }
+
+    /// 主机间传输接着目标主机上已有的部分写入，历史记录同时带上两台主机
+    #[test]
+    fn test_resumed_remote_transfer_between_local_clients() {
+        let source_root = tempfile::tempdir().unwrap();
+        let dest_root = tempfile::tempdir().unwrap();
+        let db = setup_test_db();
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO hosts (name, host, port, protocol, username) VALUES ('prod', 'localhost', 22, 'sftp', 'user')",
+                [],
+            )
+            .unwrap();
+        let conn_manager = ConnectionManager::new();
+        for (id, root) in [(1, source_root.path()), (2, dest_root.path())] {
+            let mut client = LocalClient::new(root);
+            client.connect().unwrap();
+            conn_manager
+                .insert_mock_connection(id, Box::new(client))
+                .unwrap();
+        }
+        let engine = TransferEngine::new(conn_manager, db.clone());
+
+        std::fs::write(source_root.path().join("site.tar"), b"0123456789").unwrap();
+        std::fs::write(dest_root.path().join("site.tar"), b"012").unwrap();
+        let mut record = ResumeRecord::new(
+            "earlier".into(),
+            1,
+            "/site.tar".into(),
+            "/site.tar".into(),
+            TransferDirection::Remote,
+            10,
+        );
+        record.dest_host_id = Some(2);
+        resume::save_resume_record(&db, &record).unwrap();
+
+        let task = TransferTask::remote(
+            1,
+            "site.tar".into(),
+            "/site.tar".into(),
+            2,
+            "/site.tar".into(),
+            10,
+        );
+        let task_id = task.id.clone();
+        engine.submit_task(task).unwrap();
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while engine.get_active_task_ids().unwrap().contains(&task_id) {
+            assert!(Instant::now() < deadline, "task not finished within 5s");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert_eq!(
+            std::fs::read(dest_root.path().join("site.tar")).unwrap(),
+            b"0123456789"
+        );
+        let conn = db.conn.lock().unwrap();
+        let history = transfer_repo::get_history_by_host(&conn, 2).unwrap();
+        assert_eq!(history[0].status, TransferStatus::Success);
+        assert_eq!(history[0].direction, TransferDirection::Remote);
+        assert_eq!(history[0].host_id, 1);
+        assert_eq!(history[0].dest_host_id, Some(2));
+        assert_eq!(history[0].transferred_size, 10);
+    }
 }
@@ -0,0 +1,742 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use suppaftp::native_tls::TlsConnector;
+
+use crate::models::certificate::TrustedCertificate;
+use crate::models::proxy::{ProxyConfig, ProxyType};
+use crate::utils::percent::{percent_decode, percent_encode};
+
+use super::certificate;
+use super::connection::{copy_download, ConnectionTrait, FileEntry, ProgressReader};
+
+const DAV_NS: &str = "DAV:";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// PROPFIND 只请求列表需要的属性，避免服务器返回全部死属性
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/><d:getcontentlength/><d:getlastmodified/></d:prop></d:propfind>"#;
+
+pub struct WebdavClient {
+    host: String,
+    port: u16,
+    username: String,
+    password: String,
+    /// 使用 HTTPS（WebDAVS）
+    tls: bool,
+    trusted_certificate: Option<TrustedCertificate>,
+    proxy: Option<ProxyConfig>,
+    agent: Option<ureq::Agent>,
+}
+
+impl WebdavClient {
+    pub fn new(host: String, port: u16, username: String, password: String) -> Self {
+        Self {
+            host,
+            port,
+            username,
+            password,
+            tls: false,
+            trusted_certificate: None,
+            proxy: None,
+            agent: None,
+        }
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// 固定服务器证书：只接受指纹与这张证书一致的服务器（可为自签名），不再校验证书链
+    pub fn with_trusted_certificate(mut self, cert: Option<TrustedCertificate>) -> Self {
+        self.trusted_certificate = cert;
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    fn build_agent(&self) -> Result<ureq::Agent, String> {
+        let mut builder = ureq::AgentBuilder::new()
+            .timeout_connect(CONNECT_TIMEOUT)
+            .timeout_read(IO_TIMEOUT)
+            .timeout_write(IO_TIMEOUT)
+            // 跟随重定向会丢失 Authorization，且 MOVE/PUT 被改写为 GET，交给调用方处理
+            .redirects(0);
+        if let Some(ref proxy) = self.proxy {
+            builder = builder.proxy(ureq_proxy(proxy)?);
+        }
+        if self.tls {
+            builder = match self.trusted_certificate {
+                Some(ref trusted) => {
+                    builder.tls_connector(Arc::new(PinnedTlsConnector(trusted.clone())))
+                }
+                None => {
+                    let connector = TlsConnector::new().map_err(|e| e.to_string())?;
+                    builder.tls_connector(Arc::new(connector))
+                }
+            };
+        }
+        Ok(builder.build())
+    }
+
+    fn origin(&self) -> String {
+        let scheme = if self.tls { "https" } else { "http" };
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        format!("{}://{}:{}", scheme, host, self.port)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.origin(), encode_path(path))
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request, String> {
+        let agent = self.agent.as_ref().ok_or("Not connected")?;
+        let mut request = agent.request(method, &self.url(path));
+        if !self.username.is_empty() {
+            request = request.set("Authorization", &basic_auth(&self.username, &self.password));
+        }
+        Ok(request)
+    }
+
+    fn propfind(&self, path: &str, depth: &str) -> Result<Vec<FileEntry>, String> {
+        let response = self
+            .request("PROPFIND", path)?
+            .set("Depth", depth)
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)
+            .map_err(http_error)?;
+        let body = response.into_string().map_err(|e| e.to_string())?;
+        parse_multistatus(&body)
+    }
+
+    /// GET 文件，offset > 0 时带 Range；返回响应及其内容在文件中的起始位置
+    /// （服务器忽略 Range 时为 0），offset 已到文件末尾时返回 None
+    fn get_from(&self, path: &str, offset: u64) -> Result<Option<(ureq::Response, u64)>, String> {
+        let mut request = self.request("GET", path)?;
+        if offset > 0 {
+            request = request.set("Range", &format!("bytes={}-", offset));
+        }
+        let response = match request.call() {
+            Err(ureq::Error::Status(416, _)) if offset > 0 => return Ok(None),
+            result => result.map_err(http_error)?,
+        };
+        let start = if offset > 0 && response.status() == 206 {
+            offset
+        } else {
+            0
+        };
+        Ok(Some((response, start)))
+    }
+}
+
+/// 固定证书时的 TLS 连接器。ureq 每建立一条连接都经过这里，指纹在该连接上核对，
+/// 不一致时在发送 Authorization 之前断开
+struct PinnedTlsConnector(TrustedCertificate);
+
+impl ureq::TlsConnector for PinnedTlsConnector {
+    fn connect(
+        &self,
+        dns_name: &str,
+        io: Box<dyn ureq::ReadWrite>,
+    ) -> Result<Box<dyn ureq::ReadWrite>, ureq::Error> {
+        let stream =
+            certificate::connect_pinned(dns_name, io, &self.0).map_err(std::io::Error::other)?;
+        Ok(Box::new(stream))
+    }
+}
+
+/// 由 Content-Length 推算完整文件大小，未提供时为 0
+fn content_total(response: &ureq::Response, start: u64) -> u64 {
+    response
+        .header("Content-Length")
+        .and_then(|l| l.parse::<u64>().ok())
+        .map(|r| start + r)
+        .unwrap_or(0)
+}
+
+impl ConnectionTrait for WebdavClient {
+    /// HTTP 无状态，连接时只创建 agent 并用一次 PROPFIND 校验地址与凭据
+    fn connect(&mut self) -> Result<(), String> {
+        self.agent = Some(self.build_agent()?);
+        match self.request("PROPFIND", "/")?.set("Depth", "0").call() {
+            // 部分服务（如 Nextcloud）只在子路径下提供 WebDAV，根目录返回 403/404/405 也视为可达
+            Ok(_) | Err(ureq::Error::Status(403..=405, _)) => Ok(()),
+            Err(e) => {
+                self.agent = None;
+                Err(http_error(e))
+            }
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<(), String> {
+        self.agent = None;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.agent.is_some()
+    }
+
+    fn list_dir(&mut self, path: &str) -> Result<Vec<FileEntry>, String> {
+        let dir = path.trim_end_matches('/');
+        let mut entries = self.propfind(&format!("{}/", dir), "1")?;
+        // 结果中包含被列出的目录本身
+        entries.retain(|e| e.path.trim_end_matches('/') != dir);
+        Ok(entries)
+    }
+
+    fn file_size(&mut self, path: &str) -> Result<u64, String> {
+        Ok(self.stat(path)?.size)
+    }
+
+    fn file_exists(&mut self, path: &str) -> Result<bool, String> {
+        match self.request("PROPFIND", path)?.set("Depth", "0").call() {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::Status(404, _)) => Ok(false),
+            Err(e) => Err(http_error(e)),
+        }
+    }
+
+    /// WebDAV 没有标准的追加写入方式，offset > 0 时整个文件重新上传；
+    /// 返回值扣除 offset，使调用方累计的字节数仍等于文件大小
+    fn upload(
+        &mut self,
+        local_path: &str,
+        remote_path: &str,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let total_size = std::fs::metadata(local_path)
+            .map_err(|e| e.to_string())?
+            .len();
+        let file = std::fs::File::open(local_path).map_err(|e| e.to_string())?;
+        let mut reader = ProgressReader {
+            inner: file,
+            transferred: 0,
+            total: total_size,
+            callback: progress,
+        };
+        self.request("PUT", remote_path)?
+            .set("Content-Length", &total_size.to_string())
+            .set("Content-Type", "application/octet-stream")
+            .send(&mut reader)
+            .map_err(http_error)?;
+        Ok(reader.transferred.saturating_sub(offset))
+    }
+
+    /// 续传时用 Range 请求剩余部分；服务器不支持 Range（返回 200）时从头下载
+    fn download(
+        &mut self,
+        remote_path: &str,
+        local_path: &str,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let Some((response, start)) = self.get_from(remote_path, offset)? else {
+            // 本地已是完整文件
+            return Ok(0);
+        };
+        let total_size = content_total(&response, start);
+
+        let mut local_file = if start > 0 {
+            let mut f = std::fs::OpenOptions::new()
+                .write(true)
+                .open(local_path)
+                .map_err(|e| e.to_string())?;
+            f.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+            f
+        } else {
+            std::fs::File::create(local_path).map_err(|e| e.to_string())?
+        };
+
+        let mut reader = response.into_reader();
+        let transferred = copy_download(&mut reader, &mut local_file, start, total_size, progress)
+            .map_err(|e| e.to_string())?;
+        Ok((start + transferred).saturating_sub(offset))
+    }
+
+    /// 服务器不支持 Range 时丢弃前 offset 字节，writer 只收到 offset 之后的内容
+    fn download_to(
+        &mut self,
+        remote_path: &str,
+        writer: &mut dyn Write,
+        offset: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        let Some((response, start)) = self.get_from(remote_path, offset)? else {
+            return Ok(0);
+        };
+        let total_size = content_total(&response, start);
+        let mut reader = response.into_reader();
+        if start < offset {
+            std::io::copy(
+                &mut (&mut reader).take(offset - start),
+                &mut std::io::sink(),
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        copy_download(&mut reader, writer, offset, total_size, progress).map_err(|e| e.to_string())
+    }
+
+    /// 只能整个文件重新上传，调用方须从头提供内容
+    fn upload_from(
+        &mut self,
+        reader: &mut dyn Read,
+        remote_path: &str,
+        offset: u64,
+        total: u64,
+        progress: Option<&dyn Fn(u64, u64)>,
+    ) -> Result<u64, String> {
+        if offset > 0 {
+            return Err("WebDAV 不支持续写已有文件".to_string());
+        }
+        let mut reader = ProgressReader {
+            inner: reader,
+            transferred: 0,
+            total,
+            callback: progress,
+        };
+        self.request("PUT", remote_path)?
+            .set("Content-Length", &total.to_string())
+            .set("Content-Type", "application/octet-stream")
+            .send(&mut reader)
+            .map_err(http_error)?;
+        Ok(reader.transferred)
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<(), String> {
+        let dir = format!("{}/", path.trim_end_matches('/'));
+        match self.request("MKCOL", &dir)?.call() {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(405, _)) => Err("目录已存在".to_string()),
+            Err(e) => Err(http_error(e)),
+        }
+    }
+
+    fn remove_file(&mut self, path: &str) -> Result<(), String> {
+        self.request("DELETE", path)?.call().map_err(http_error)?;
+        Ok(())
+    }
+
+    /// WebDAV 的 DELETE 会递归删除集合，这里先确认目录为空，与其他协议的 rmdir 行为一致
+    fn remove_dir(&mut self, path: &str) -> Result<(), String> {
+        if !self.list_dir(path)?.is_empty() {
+            return Err("目录不为空".to_string());
+        }
+        let dir = format!("{}/", path.trim_end_matches('/'));
+        self.request("DELETE", &dir)?.call().map_err(http_error)?;
+        Ok(())
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let destination = self.url(to);
+        match self
+            .request("MOVE", from)?
+            .set("Destination", &destination)
+            .set("Overwrite", "F")
+            .call()
+        {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(412, _)) => Err("目标已存在".to_string()),
+            Err(e) => Err(http_error(e)),
+        }
+    }
+
+    /// WebDAV 的 COPY 方法由服务器完成复制
+    fn copy(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let destination = self.url(to);
+        self.request("COPY", from)?
+            .set("Destination", &destination)
+            .set("Overwrite", "T")
+            .set("Depth", "0")
+            .call()
+            .map(|_| ())
+            .map_err(http_error)
+    }
+
+    fn create_symlink(&mut self, _target: &str, _path: &str) -> Result<(), String> {
+        Err("WebDAV 协议不支持符号链接".to_string())
+    }
+
+    fn stat(&mut self, path: &str) -> Result<FileEntry, String> {
+        self.propfind(path, "0")?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("服务器未返回 {} 的属性", path))
+    }
+
+    fn set_permissions(&mut self, _path: &str, _mode: u32) -> Result<(), String> {
+        Err("WebDAV 协议不支持修改权限".to_string())
+    }
+
+    fn set_owner(
+        &mut self,
+        _path: &str,
+        _uid: Option<u32>,
+        _gid: Option<u32>,
+    ) -> Result<(), String> {
+        Err("WebDAV 协议不支持修改属主".to_string())
+    }
+}
+
+pub(super) fn ureq_proxy(proxy: &ProxyConfig) -> Result<ureq::Proxy, String> {
+    let scheme = match proxy.proxy_type {
+        ProxyType::Socks5 => "socks5",
+        ProxyType::Http => "http",
+    };
+    // ureq 不对代理 URL 中的凭据做百分号解码，而是按最后一个 `@` 分出主机、按第一个 `:`
+    // 分出用户名后原样发送，因此凭据不能编码，只有用户名中的 `:` 无法表示
+    let credentials = match (&proxy.username, &proxy.password) {
+        (Some(user), _) if user.contains(':') => {
+            return Err("代理用户名不能包含“:”".to_string());
+        }
+        (Some(user), pass) if !user.is_empty() => {
+            format!("{}:{}@", user, pass.as_deref().unwrap_or_default())
+        }
+        _ => String::new(),
+    };
+    ureq::Proxy::new(format!(
+        "{}://{}{}:{}",
+        scheme, credentials, proxy.host, proxy.port
+    ))
+    .map_err(|e| format!("代理设置无效: {}", e))
+}
+
+fn basic_auth(username: &str, password: &str) -> String {
+    use base64::Engine;
+    let token =
+        base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+    format!("Basic {}", token)
+}
+
+fn http_error(e: ureq::Error) -> String {
+    match e {
+        ureq::Error::Status(code, response) => {
+            let reason = match code {
+                401 => "认证失败，请检查用户名和密码",
+                403 => "没有权限",
+                404 => "文件或目录不存在",
+                409 => "上级目录不存在",
+                423 => "资源已被锁定",
+                507 => "服务器存储空间不足",
+                _ => response.status_text(),
+            };
+            format!("HTTP {}: {}", code, reason)
+        }
+        ureq::Error::Transport(t) => t.to_string(),
+    }
+}
+
+/// 对路径逐段做百分号编码，保留分隔符 `/`
+fn encode_path(path: &str) -> String {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    let segments: Vec<String> = path.split('/').map(percent_encode).collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// href 可能是绝对 URL 或绝对路径，统一为解码后的路径，去掉集合末尾的 `/`
+fn href_to_path(href: &str) -> String {
+    let path = match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or("/"),
+        None => href,
+    };
+    let decoded = percent_decode(path);
+    match decoded.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn dav(node: &roxmltree::Node, name: &str) -> bool {
+    node.tag_name().namespace() == Some(DAV_NS) && node.tag_name().name() == name
+}
+
+fn child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|c| dav(c, name))
+}
+
+/// 解析 PROPFIND 返回的 207 Multi-Status，只取状态为 200 的 propstat
+fn parse_multistatus(xml: &str) -> Result<Vec<FileEntry>, String> {
+    let doc =
+        roxmltree::Document::parse(xml).map_err(|e| format!("无法解析 PROPFIND 应答: {}", e))?;
+    let mut entries = Vec::new();
+    for response in doc.descendants().filter(|n| dav(n, "response")) {
+        let Some(href) = child(response, "href").and_then(|h| h.text()) else {
+            continue;
+        };
+        let path = href_to_path(href.trim());
+        let prop = response
+            .children()
+            .filter(|n| dav(n, "propstat"))
+            .filter(|ps| {
+                child(*ps, "status")
+                    .and_then(|s| s.text())
+                    .is_none_or(|s| s.contains(" 200 "))
+            })
+            .find_map(|ps| child(ps, "prop"));
+        let Some(prop) = prop else {
+            continue;
+        };
+        let is_dir = child(prop, "resourcetype")
+            .is_some_and(|rt| rt.children().any(|c| dav(&c, "collection")));
+        let size = child(prop, "getcontentlength")
+            .and_then(|n| n.text())
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(0);
+        let modified = child(prop, "getlastmodified")
+            .and_then(|n| n.text())
+            .and_then(|s| DateTime::parse_from_rfc2822(s.trim()).ok())
+            .map(|d| d.with_timezone(&Utc));
+        let name = path.rsplit('/').next().unwrap_or_default().to_string();
+        entries.push(FileEntry {
+            name,
+            path,
+            is_dir,
+            size,
+            modified,
+            permissions: None,
+            is_symlink: false,
+            link_target: None,
+            mode: None,
+            uid: None,
+            gid: None,
+            owner: None,
+            group: None,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    const LISTING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/docs/</D:href>
+    <D:propstat>
+      <D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>http://dav.example.com/docs/My%20Report.pdf</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:resourcetype/>
+        <D:getcontentlength>1024</D:getcontentlength>
+        <D:getlastmodified>Sat, 14 Oct 2023 08:30:00 GMT</D:getlastmodified>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+    <D:propstat>
+      <D:prop><D:quota-used-bytes/></D:prop>
+      <D:status>HTTP/1.1 404 Not Found</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/docs/%E5%9B%BE%E7%89%87/</D:href>
+    <D:propstat>
+      <D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    /// 依次应答 responses 中的每个请求，并把收到的请求头和请求体发回测试线程
+    fn serve(responses: Vec<String>) -> (u16, mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = v.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                tx.send((head, body)).unwrap();
+                (&stream).write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (port, rx)
+    }
+
+    fn reply(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn test_ureq_proxy_sends_credentials_verbatim() {
+        use base64::Engine;
+        let (port, rx) = serve(vec![reply("407 Proxy Authentication Required", "")]);
+        let mut proxy = ProxyConfig {
+            proxy_type: ProxyType::Http,
+            host: "127.0.0.1".to_string(),
+            port,
+            username: Some("ops@corp".to_string()),
+            password: Some("p@ss:w/rd%41".to_string()),
+        };
+        let agent = ureq::AgentBuilder::new()
+            .proxy(ureq_proxy(&proxy).unwrap())
+            .build();
+        assert!(agent.get("https://dav.example.com/").call().is_err());
+        let (head, _) = rx.recv().unwrap();
+        let token = head
+            .lines()
+            .find_map(|l| l.strip_prefix("Proxy-Authorization: basic "))
+            .unwrap();
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(token.trim())
+            .unwrap();
+        assert_eq!(decoded, b"ops@corp:p@ss:w/rd%41");
+
+        proxy.username = Some("a:b".to_string());
+        assert!(ureq_proxy(&proxy).is_err());
+    }
+
+    fn connected_client(port: u16) -> WebdavClient {
+        let mut client = WebdavClient::new("127.0.0.1".into(), port, "bob".into(), "secret".into());
+        client.connect().unwrap();
+        client
+    }
+
+    #[test]
+    fn test_parse_multistatus() {
+        let entries = parse_multistatus(LISTING).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].path, "/docs");
+
+        let report = &entries[1];
+        assert_eq!(report.name, "My Report.pdf");
+        assert_eq!(report.path, "/docs/My Report.pdf");
+        assert!(!report.is_dir);
+        assert_eq!(report.size, 1024);
+        assert_eq!(
+            report.modified.unwrap().to_rfc3339(),
+            "2023-10-14T08:30:00+00:00"
+        );
+
+        assert_eq!(entries[2].name, "图片");
+        assert!(entries[2].is_dir);
+        assert!(parse_multistatus("not xml").is_err());
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("/docs/My Report.pdf"), "/docs/My%20Report.pdf");
+        assert_eq!(encode_path("a/图"), "/a/%E5%9B%BE");
+        assert_eq!(href_to_path(&encode_path("/a b/c#d/")), "/a b/c#d");
+    }
+
+    #[test]
+    fn test_list_dir_skips_requested_directory() {
+        let (port, rx) = serve(vec![
+            reply("207 Multi-Status", LISTING),
+            reply("207 Multi-Status", LISTING),
+        ]);
+        let mut client = connected_client(port);
+        let (head, _) = rx.recv().unwrap();
+        assert!(head.starts_with("PROPFIND / HTTP/1.1"));
+        assert!(head.contains("Authorization: Basic Ym9iOnNlY3JldA=="));
+
+        let entries = client.list_dir("/docs").unwrap();
+        let (head, _) = rx.recv().unwrap();
+        assert!(head.starts_with("PROPFIND /docs/ HTTP/1.1"));
+        assert!(head.contains("Depth: 1"));
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["My Report.pdf", "图片"]);
+    }
+
+    #[test]
+    fn test_download_resumes_with_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("file.bin");
+        std::fs::write(&local, b"hello ").unwrap();
+
+        let (port, rx) = serve(vec![
+            reply("207 Multi-Status", LISTING),
+            reply("206 Partial Content", "world"),
+        ]);
+        let mut client = connected_client(port);
+        rx.recv().unwrap();
+
+        let n = client
+            .download("/file.bin", local.to_str().unwrap(), 6, None)
+            .unwrap();
+        assert_eq!(n, 5);
+        let (head, _) = rx.recv().unwrap();
+        assert!(head.contains("Range: bytes=6-"));
+        assert_eq!(std::fs::read(&local).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_upload_mkdir_and_move_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("up.txt");
+        std::fs::write(&local, b"payload").unwrap();
+
+        let (port, rx) = serve(vec![
+            reply("207 Multi-Status", LISTING),
+            reply("201 Created", ""),
+            reply("405 Method Not Allowed", ""),
+            reply("412 Precondition Failed", ""),
+        ]);
+        let mut client = connected_client(port);
+        rx.recv().unwrap();
+
+        let n = client
+            .upload(local.to_str().unwrap(), "/in box/up.txt", 0, None)
+            .unwrap();
+        assert_eq!(n, 7);
+        let (head, body) = rx.recv().unwrap();
+        assert!(head.starts_with("PUT /in%20box/up.txt HTTP/1.1"));
+        assert_eq!(body, b"payload");
+
+        assert_eq!(client.mkdir("/in box").unwrap_err(), "目录已存在");
+        let (head, _) = rx.recv().unwrap();
+        assert!(head.starts_with("MKCOL /in%20box/ HTTP/1.1"));
+
+        assert_eq!(client.rename("/a", "/b c").unwrap_err(), "目标已存在");
+        let (head, _) = rx.recv().unwrap();
+        assert!(head.starts_with("MOVE /a HTTP/1.1"));
+        assert!(head.contains(&format!("Destination: http://127.0.0.1:{}/b%20c", port)));
+        assert!(head.contains("Overwrite: F"));
+    }
+}
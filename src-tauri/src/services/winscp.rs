@@ -0,0 +1,283 @@
+//! WinSCP 配置导出文件（WinSCP.ini）解析
+
+use crate::models::host::{FtpDataMode, FtpsMode, Host, Protocol, SshTransferMode};
+use crate::models::host_import::HostImportEntry;
+use crate::utils::percent::percent_decode;
+
+const SESSION_PREFIX: &str = "Sessions\\";
+const DEFAULT_SESSION: &str = "Default Settings";
+
+/// WinSCP 未设置主密码时的密码混淆算法常量
+const PWALG_SIMPLE_MAGIC: u8 = 0xA3;
+const PWALG_SIMPLE_FLAG: u8 = 0xFF;
+
+/// 还原 WinSCP 混淆保存的密码；key 为用户名加主机名，对不上时说明设置了主密码
+fn deobfuscate_password(encoded: &str, key: &str) -> Option<String> {
+    let mut digits = encoded
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()?
+        .into_iter();
+    let mut next = || -> Option<u8> {
+        let hi = digits.next()?;
+        let lo = digits.next()?;
+        Some(!(((hi << 4) + lo) ^ PWALG_SIMPLE_MAGIC))
+    };
+    let flag = next()?;
+    let length = if flag == PWALG_SIMPLE_FLAG {
+        next()?;
+        next()?
+    } else {
+        flag
+    };
+    let shift = next()?;
+    for _ in 0..shift {
+        next()?;
+    }
+    let bytes = (0..length).map(|_| next()).collect::<Option<Vec<u8>>>()?;
+    let password = String::from_utf8(bytes).ok()?;
+    if flag == PWALG_SIMPLE_FLAG {
+        password.strip_prefix(key).map(str::to_string)
+    } else {
+        Some(password)
+    }
+}
+
+/// 解析 ini 中的 `[Sessions\...]` 段；会话名中的 `/` 为文件夹分隔符
+pub fn parse_ini(content: &str) -> Result<Vec<HostImportEntry>, String> {
+    let mut sessions: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    let mut in_session = false;
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_session = false;
+            if let Some(name) = section.strip_prefix(SESSION_PREFIX) {
+                let name = percent_decode(name);
+                if name != DEFAULT_SESSION {
+                    sessions.push((name, Vec::new()));
+                    in_session = true;
+                }
+            }
+            continue;
+        }
+        if !in_session {
+            continue;
+        }
+        if let (Some((key, value)), Some((_, values))) = (line.split_once('='), sessions.last_mut())
+        {
+            values.push((key.trim().to_string(), percent_decode(value.trim())));
+        }
+    }
+    if sessions.is_empty() {
+        return Err("WinSCP 配置中没有保存的会话".to_string());
+    }
+    Ok(sessions
+        .iter()
+        .map(|(name, values)| session_entry(name, values))
+        .collect())
+}
+
+fn session_entry(name: &str, values: &[(String, String)]) -> HostImportEntry {
+    let get = |key: &str| {
+        values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    };
+    let address = get("HostName").unwrap_or_default().to_string();
+    let username = get("UserName").unwrap_or_default().to_string();
+    // 未写出的项为 WinSCP 默认值：SFTP（可回退 SCP）、被动模式
+    let fs_protocol: u32 = get("FSProtocol").and_then(|p| p.parse().ok()).unwrap_or(1);
+    let ftps: u32 = get("Ftps").and_then(|p| p.parse().ok()).unwrap_or(0);
+    let protocol = match (fs_protocol, ftps) {
+        (0..=2, _) => Some(Protocol::Sftp),
+        (5, 0) => Some(Protocol::Ftp),
+        (5, _) => Some(Protocol::Ftps),
+        (6, 0) => Some(Protocol::Webdav),
+        (6, _) => Some(Protocol::Webdavs),
+        _ => None,
+    };
+    let ftps_mode = if ftps == 1 {
+        FtpsMode::Implicit
+    } else {
+        FtpsMode::Explicit
+    };
+    let default_port = match (&protocol, ftps_mode) {
+        (Some(Protocol::Sftp), _) => 22,
+        (Some(Protocol::Ftps), FtpsMode::Implicit) => 990,
+        (Some(Protocol::Webdav), _) => 80,
+        (Some(Protocol::Webdavs), _) => 443,
+        _ => 21,
+    };
+    let port = get("PortNumber")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(default_port);
+    let display_name = name.rsplit('/').next().unwrap_or(name).to_string();
+
+    let mut host = Host::new(
+        display_name,
+        address.clone(),
+        port,
+        protocol.clone().unwrap_or(Protocol::Sftp),
+        username.clone(),
+    );
+    if protocol == Some(Protocol::Ftps) {
+        host.ftps_mode = ftps_mode;
+    }
+    // 0 为 SCP，1 为 SFTP（可回退 SCP），2 为仅 SFTP
+    host.ssh_transfer_mode = match fs_protocol {
+        0 => SshTransferMode::Scp,
+        2 => SshTransferMode::Sftp,
+        _ => SshTransferMode::Auto,
+    };
+    let mut entry = HostImportEntry::new(name.to_string(), host);
+    let Some(protocol) = protocol else {
+        let label = match fs_protocol {
+            7 => "S3".to_string(),
+            other => format!("WinSCP 协议编号 {}", other),
+        };
+        entry.unsupported = Some(format!("不支持 {} 协议", label));
+        return entry;
+    };
+
+    if let Some(encoded) = get("Password") {
+        match deobfuscate_password(encoded, &format!("{}{}", username, address)) {
+            Some(password) => entry.host.password = Some(password),
+            None => entry
+                .warnings
+                .push("密码已用 WinSCP 主密码加密，导入后请重新填写".to_string()),
+        }
+    }
+    if protocol == Protocol::Sftp {
+        if let Some(key) = get("PublicKeyFile") {
+            if key.to_lowercase().ends_with(".ppk") {
+                entry
+                    .warnings
+                    .push("PuTTY 格式（.ppk）的密钥需先转换为 OpenSSH 格式".to_string());
+            }
+            entry.host.key_path = Some(key.to_string());
+        }
+        if get("Tunnel") == Some("1") {
+            entry
+                .warnings
+                .push("未导入 SSH 隧道设置，可导入后为其设置跳板机".to_string());
+        }
+    } else if matches!(protocol, Protocol::Ftp | Protocol::Ftps) && get("FtpPasvMode") == Some("0")
+    {
+        entry.host.ftp_data_mode = FtpDataMode::Active;
+    }
+    entry.remote_dir = get("RemoteDirectory").map(str::to_string);
+    entry.local_dir = get("LocalDirectory").map(str::to_string);
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 与 WinSCP 的混淆算法相同，填充字节固定以便测试
+    fn obfuscate(password: &str, key: &str) -> String {
+        let encode = |b: u8| format!("{:02X}", !b ^ PWALG_SIMPLE_MAGIC);
+        let plain = format!("{}{}", key, password);
+        let mut out = String::new();
+        out += &encode(PWALG_SIMPLE_FLAG);
+        out += &encode(0);
+        out += &encode(plain.len() as u8);
+        out += &encode(3);
+        out += &encode(0x11).repeat(3);
+        for b in plain.bytes() {
+            out += &encode(b);
+        }
+        out
+    }
+
+    #[test]
+    fn test_deobfuscate_password() {
+        let encoded = obfuscate("s3cr3t!", "bobexample.com");
+        assert_eq!(
+            deobfuscate_password(&encoded, "bobexample.com").as_deref(),
+            Some("s3cr3t!")
+        );
+        // 设置了主密码时还原结果对不上 key
+        assert_eq!(deobfuscate_password(&encoded, "aliceexample.com"), None);
+        assert_eq!(deobfuscate_password("XYZ", "k"), None);
+    }
+
+    #[test]
+    fn test_parse_ini() {
+        let ini = format!(
+            "[Configuration\\Interface]\n\
+             Theme=0\n\
+             [Sessions\\Default%20Settings]\n\
+             HostName=ignored\n\
+             [Sessions\\My%20Folder/Web%20Server]\n\
+             HostName=web.example.com\n\
+             UserName=bob\n\
+             Password={}\n\
+             PortNumber=2222\n\
+             PublicKeyFile=C:%5CKeys%5Cbob.ppk\n\
+             RemoteDirectory=/var/www\n\
+             LocalDirectory=C:%5CSites\n\
+             [Sessions\\ftps]\n\
+             HostName=files.example.com\n\
+             UserName=carol\n\
+             FSProtocol=5\n\
+             Ftps=1\n\
+             FtpPasvMode=0\n\
+             [Sessions\\dav]\n\
+             HostName=dav.example.com\n\
+             FSProtocol=6\n\
+             Ftps=1\n\
+             [Sessions\\s3]\n\
+             HostName=s3.amazonaws.com\n\
+             FSProtocol=7\n\
+             [Sessions\\router]\n\
+             HostName=192.168.1.1\n\
+             UserName=root\n\
+             FSProtocol=0\n",
+            obfuscate("pa ss", "bobweb.example.com")
+        );
+        let entries = parse_ini(&ini).unwrap();
+        assert_eq!(entries.len(), 5);
+
+        let web = &entries[0];
+        assert_eq!(web.source, "My Folder/Web Server");
+        assert_eq!(web.host.name, "Web Server");
+        assert_eq!(web.host.protocol, Protocol::Sftp);
+        assert_eq!(web.host.port, 2222);
+        assert_eq!(web.host.ssh_transfer_mode, SshTransferMode::Auto);
+        assert_eq!(web.host.password.as_deref(), Some("pa ss"));
+        assert_eq!(web.host.key_path.as_deref(), Some(r"C:\Keys\bob.ppk"));
+        assert_eq!(web.warnings.len(), 1);
+        assert_eq!(web.remote_dir.as_deref(), Some("/var/www"));
+        assert_eq!(web.local_dir.as_deref(), Some(r"C:\Sites"));
+
+        let ftps = &entries[1];
+        assert_eq!(ftps.host.protocol, Protocol::Ftps);
+        assert_eq!(ftps.host.ftps_mode, FtpsMode::Implicit);
+        assert_eq!(ftps.host.port, 990);
+        assert_eq!(ftps.host.ftp_data_mode, FtpDataMode::Active);
+
+        let dav = &entries[2];
+        assert_eq!(dav.host.protocol, Protocol::Webdavs);
+        assert_eq!(dav.host.port, 443);
+        assert!(dav.unsupported.is_none());
+
+        assert!(entries[3].unsupported.as_deref().unwrap().contains("S3"));
+
+        let router = &entries[4];
+        assert_eq!(router.host.protocol, Protocol::Sftp);
+        assert_eq!(router.host.ssh_transfer_mode, SshTransferMode::Scp);
+        assert_eq!(router.host.port, 22);
+        assert!(router.unsupported.is_none());
+    }
+
+    #[test]
+    fn test_parse_ini_without_sessions() {
+        assert!(parse_ini("[Configuration]\nFoo=1\n").is_err());
+    }
+}
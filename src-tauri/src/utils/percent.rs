@@ -0,0 +1,51 @@
+//! 百分号编码（`%XX`），用于 URL 以及 WinSCP 配置文件中的转义
+
+/// 编码除 RFC 3986 非保留字符（字母、数字、`-._~`）以外的所有字节
+pub fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// 还原 `%XX` 转义，不完整的转义原样保留，解码后不是合法 UTF-8 的字节替换为 U+FFFD
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(b) = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_round_trip() {
+        let encoded = percent_encode("p@ss:w/rd 密");
+        assert_eq!(encoded, "p%40ss%3Aw%2Frd%20%E5%AF%86");
+        assert_eq!(percent_decode(&encoded), "p@ss:w/rd 密");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%41"), "%zzA");
+    }
+}
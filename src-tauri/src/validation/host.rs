@@ -1,7 +1,8 @@
 //! Host 输入校验，防止超长字符串、非法字符、路径遍历
 
-use crate::models::host::Host;
+use crate::models::host::{Host, Protocol, SshTransferMode};
 use crate::utils::path::{normalize_and_validate, normalize_path_for_create};
+use std::net::IpAddr;
 use std::path::Path;
 
 const MAX_NAME_LEN: usize = 128;
@@ -9,19 +10,114 @@ const MAX_HOST_LEN: usize = 256;
 const MAX_USERNAME_LEN: usize = 128;
 const MAX_PASSWORD_LEN: usize = 512;
 const MAX_KEY_PATH_LEN: usize = 1024;
+/// 现实中的时区范围为 UTC-12:00 至 UTC+14:00
+const MIN_TIMEZONE_OFFSET: i32 = -12 * 60;
+const MAX_TIMEZONE_OFFSET: i32 = 14 * 60;
 
 /// 校验 Host 输入
 pub fn validate_host(host: &Host) -> Result<(), String> {
     validate_name(&host.name)?;
     validate_host_address(&host.host)?;
-    validate_port(host.port)?;
-    validate_username(&host.username)?;
+    // 本地目录主机不使用端口和账号
+    if host.protocol != Protocol::Local {
+        validate_port(host.port)?;
+        validate_username(&host.username)?;
+    }
     if let Some(ref p) = host.password {
         validate_password(p)?;
     }
-    if let Some(ref k) = host.key_path {
+    // 其他协议不使用密钥文件，保存时会清空该字段，旧记录中残留的值不影响编辑
+    if let Some(k) = host.key_path.as_ref().filter(|_| host.protocol == Protocol::Sftp) {
         validate_key_path(k)?;
     }
+    validate_timezone_offset(host.timezone_offset)?;
+    validate_ftp_data_connection(host)?;
+    validate_protocol_fields(host)?;
+    Ok(())
+}
+
+/// 校验协议相关字段：认证顺序、跳板机和传输方式仅适用于 SFTP
+fn validate_protocol_fields(host: &Host) -> Result<(), String> {
+    match host.protocol {
+        Protocol::Ftp
+        | Protocol::Ftps
+        | Protocol::Webdav
+        | Protocol::Webdavs
+        | Protocol::S3
+        | Protocol::Local => {
+            if !host.ssh_auth_order.is_empty() {
+                return Err(format!(
+                    "{} 协议不支持设置 SSH 认证方式",
+                    host.protocol.as_str().to_uppercase()
+                ));
+            }
+            if host.jump_host_id.is_some() {
+                return Err(format!(
+                    "{} 协议不支持跳板机",
+                    host.protocol.as_str().to_uppercase()
+                ));
+            }
+            if host.ssh_transfer_mode != SshTransferMode::Auto {
+                return Err(format!(
+                    "{} 协议不支持设置 SSH 传输方式",
+                    host.protocol.as_str().to_uppercase()
+                ));
+            }
+            if host.protocol == Protocol::S3 {
+                validate_s3_fields(host)?;
+            }
+            if host.protocol == Protocol::Local {
+                validate_local_root(&host.host)?;
+            }
+        }
+        Protocol::Sftp => {
+            let order = &host.ssh_auth_order;
+            if order.iter().enumerate().any(|(i, m)| order[..i].contains(m)) {
+                return Err("认证方式不能重复".to_string());
+            }
+            if host.id.is_some() && host.jump_host_id == host.id {
+                return Err("不能把主机自身设为跳板机".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 存储桶名称遵循 S3 命名规则：3–63 个字符，仅小写字母、数字、`.` 和 `-`，首尾为字母或数字
+fn validate_s3_fields(host: &Host) -> Result<(), String> {
+    let bucket = host.s3_bucket.as_deref().map(str::trim).unwrap_or_default();
+    if bucket.is_empty() {
+        return Err("S3 存储桶不能为空".to_string());
+    }
+    let valid_chars = bucket
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-');
+    let valid_edges = bucket.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && bucket.ends_with(|c: char| c.is_ascii_alphanumeric());
+    if !(3..=63).contains(&bucket.len()) || !valid_chars || !valid_edges {
+        return Err("S3 存储桶名称无效".to_string());
+    }
+    if let Some(ref region) = host.s3_region {
+        if !region
+            .trim()
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err("S3 区域名称无效".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 本地目录主机的根目录必须是已存在的绝对路径
+fn validate_local_root(root: &str) -> Result<(), String> {
+    let trimmed = root.trim();
+    if !Path::new(trimmed).is_absolute() {
+        return Err("本地目录必须为绝对路径".to_string());
+    }
+    if !normalize_and_validate(trimmed)?.is_dir() {
+        return Err("本地目录不存在".to_string());
+    }
     Ok(())
 }
 
@@ -63,6 +159,31 @@ fn validate_port(port: u16) -> Result<(), String> {
     Ok(())
 }
 
+fn validate_timezone_offset(minutes: i32) -> Result<(), String> {
+    if !(MIN_TIMEZONE_OFFSET..=MAX_TIMEZONE_OFFSET).contains(&minutes) {
+        return Err("服务器时区必须在 UTC-12:00 到 UTC+14:00 之间".to_string());
+    }
+    Ok(())
+}
+
+fn validate_ftp_data_connection(host: &Host) -> Result<(), String> {
+    if let Some(ref address) = host.ftp_active_address {
+        if address.trim().parse::<IpAddr>().is_err() {
+            return Err("主动模式地址必须是 IPv4 或 IPv6 地址".to_string());
+        }
+    }
+    match (host.ftp_active_port_min, host.ftp_active_port_max) {
+        (None, None) => {}
+        (Some(min), Some(max)) => {
+            if min == 0 || min > max {
+                return Err("主动模式端口范围无效".to_string());
+            }
+        }
+        _ => return Err("主动模式端口范围需同时填写起止端口".to_string()),
+    }
+    Ok(())
+}
+
 fn validate_username(username: &str) -> Result<(), String> {
     let trimmed = username.trim();
     if trimmed.is_empty() {
@@ -116,7 +237,7 @@ fn validate_key_path(key_path: &str) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::host::Protocol;
+    use crate::models::host::{FtpDataMode, FtpsMode, ProxyMode, SshAuthMethod};
 
     fn valid_host() -> Host {
         Host {
@@ -128,6 +249,20 @@ mod tests {
             username: "user".into(),
             password: Some("pass".into()),
             key_path: None,
+            ftps_mode: FtpsMode::default(),
+            timezone_offset: 0,
+            ftp_data_mode: FtpDataMode::default(),
+            ftp_active_address: None,
+            ftp_active_port_min: None,
+            ftp_active_port_max: None,
+            ftp_ignore_pasv_address: false,
+            ssh_auth_order: Vec::new(),
+            jump_host_id: None,
+            ssh_transfer_mode: SshTransferMode::default(),
+            proxy_mode: ProxyMode::default(),
+            s3_region: None,
+            s3_bucket: None,
+            s3_use_http: false,
             created_at: None,
             updated_at: None,
         }
@@ -175,6 +310,67 @@ mod tests {
         assert!(validate_host(&h).is_err());
     }
 
+    #[test]
+    fn test_validate_timezone_offset() {
+        let mut h = valid_host();
+        h.timezone_offset = 14 * 60;
+        assert!(validate_host(&h).is_ok());
+        h.timezone_offset = -12 * 60;
+        assert!(validate_host(&h).is_ok());
+        h.timezone_offset = 15 * 60;
+        assert!(validate_host(&h).is_err());
+        h.timezone_offset = -13 * 60;
+        assert!(validate_host(&h).is_err());
+    }
+
+    #[test]
+    fn test_validate_ftp_data_connection() {
+        let mut h = valid_host();
+        h.protocol = Protocol::Ftp;
+        h.ftp_data_mode = FtpDataMode::Active;
+        h.ftp_active_address = Some("203.0.113.7".into());
+        h.ftp_active_port_min = Some(50000);
+        h.ftp_active_port_max = Some(50100);
+        assert!(validate_host(&h).is_ok());
+
+        h.ftp_active_address = Some("nat.example.com".into());
+        assert!(validate_host(&h).is_err());
+        h.ftp_active_address = Some("::1".into());
+        assert!(validate_host(&h).is_ok());
+
+        h.ftp_active_port_max = Some(40000);
+        assert!(validate_host(&h).is_err());
+        h.ftp_active_port_max = None;
+        assert!(validate_host(&h).is_err());
+        h.ftp_active_port_min = None;
+        assert!(validate_host(&h).is_ok());
+    }
+
+    #[test]
+    fn test_validate_ssh_auth_order() {
+        let mut h = valid_host();
+        h.ssh_auth_order = vec![SshAuthMethod::Agent, SshAuthMethod::Password];
+        assert!(validate_host(&h).is_ok());
+        h.ssh_auth_order = vec![SshAuthMethod::Agent, SshAuthMethod::Agent];
+        assert!(validate_host(&h).is_err());
+        h.ssh_auth_order = vec![SshAuthMethod::Agent];
+        h.protocol = Protocol::Ftp;
+        assert!(validate_host(&h).is_err());
+    }
+
+    #[test]
+    fn test_validate_jump_host() {
+        let mut h = valid_host();
+        h.id = Some(3);
+        h.jump_host_id = Some(1);
+        assert!(validate_host(&h).is_ok());
+        h.jump_host_id = Some(3);
+        assert!(validate_host(&h).is_err());
+        h.jump_host_id = Some(1);
+        h.protocol = Protocol::Ftps;
+        assert!(validate_host(&h).is_err());
+    }
+
     #[test]
     fn test_validate_username_empty() {
         let mut h = valid_host();
@@ -188,4 +384,68 @@ mod tests {
         h.key_path = Some("/home/user/.ssh/../../../etc/passwd".into());
         assert!(validate_host(&h).is_err());
     }
+
+    #[test]
+    fn test_validate_ftps_ok() {
+        let mut h = valid_host();
+        h.protocol = Protocol::Ftps;
+        h.port = 21;
+        assert!(validate_host(&h).is_ok());
+    }
+
+    #[test]
+    fn test_validate_ftp_ignores_key_path() {
+        let mut h = valid_host();
+        h.protocol = Protocol::Ftps;
+        h.key_path = Some("/home/user/.ssh/../../../etc/passwd".into());
+        assert!(validate_host(&h).is_ok());
+        h.protocol = Protocol::Sftp;
+        assert!(validate_host(&h).is_err());
+    }
+
+    #[test]
+    fn test_validate_s3_bucket() {
+        let mut h = valid_host();
+        h.protocol = Protocol::S3;
+        h.port = 9000;
+        assert!(validate_host(&h).is_err());
+        h.s3_bucket = Some("build-artifacts".into());
+        h.s3_region = Some("us-east-1".into());
+        assert!(validate_host(&h).is_ok());
+        h.s3_bucket = Some("Build_Artifacts".into());
+        assert!(validate_host(&h).is_err());
+        h.s3_bucket = Some("-ab".into());
+        assert!(validate_host(&h).is_err());
+        h.s3_bucket = Some("abc".into());
+        h.s3_region = Some("us east".into());
+        assert!(validate_host(&h).is_err());
+    }
+
+    #[test]
+    fn test_validate_webdav_rejects_ssh_fields() {
+        let mut h = valid_host();
+        h.protocol = Protocol::Webdavs;
+        h.port = 443;
+        assert!(validate_host(&h).is_ok());
+        h.jump_host_id = Some(1);
+        assert!(validate_host(&h).is_err());
+        h.jump_host_id = None;
+        h.ssh_transfer_mode = SshTransferMode::Scp;
+        assert!(validate_host(&h).is_err());
+    }
+
+    #[test]
+    fn test_validate_local_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut h = valid_host();
+        h.protocol = Protocol::Local;
+        h.host = dir.path().to_string_lossy().to_string();
+        h.username = String::new();
+        h.port = 0;
+        assert!(validate_host(&h).is_ok());
+        h.host = "relative/dir".into();
+        assert!(validate_host(&h).is_err());
+        h.host = dir.path().join("missing").to_string_lossy().to_string();
+        assert!(validate_host(&h).is_err());
+    }
 }
@@ -1 +1,3 @@
 pub mod host;
+pub mod port_forward;
+pub mod proxy;
@@ -0,0 +1,74 @@
+//! 端口转发定义输入校验
+
+use crate::models::port_forward::{ForwardKind, PortForward};
+
+const MAX_NAME_LEN: usize = 128;
+const MAX_HOST_LEN: usize = 256;
+
+/// 校验端口转发定义
+pub fn validate_port_forward(forward: &PortForward) -> Result<(), String> {
+    let name = forward.name.trim();
+    if name.is_empty() {
+        return Err("转发名称不能为空".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("转发名称不能超过 {} 个字符", MAX_NAME_LEN));
+    }
+    for (label, host) in [
+        ("监听地址", &forward.bind_host),
+        ("目标地址", &forward.target_host),
+    ] {
+        let host = host.trim();
+        if host.is_empty() {
+            return Err(format!("{}不能为空", label));
+        }
+        if host.len() > MAX_HOST_LEN || host.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(format!("{}格式不正确", label));
+        }
+    }
+    // 远程转发的监听端口可为 0，由服务器分配
+    if forward.bind_port == 0 && forward.kind == ForwardKind::Local {
+        return Err("本地转发的监听端口不能为 0".to_string());
+    }
+    if forward.target_port == 0 {
+        return Err("目标端口不能为 0".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_forward() -> PortForward {
+        PortForward {
+            id: None,
+            host_id: 1,
+            name: "mysql".into(),
+            kind: ForwardKind::Local,
+            bind_host: "127.0.0.1".into(),
+            bind_port: 13306,
+            target_host: "127.0.0.1".into(),
+            target_port: 3306,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_port_forward() {
+        let mut f = valid_forward();
+        assert!(validate_port_forward(&f).is_ok());
+        f.bind_port = 0;
+        assert!(validate_port_forward(&f).is_err());
+        f.kind = ForwardKind::Remote;
+        assert!(validate_port_forward(&f).is_ok());
+        f.target_port = 0;
+        assert!(validate_port_forward(&f).is_err());
+        f.target_port = 3306;
+        f.target_host = "db host".into();
+        assert!(validate_port_forward(&f).is_err());
+        f.target_host = "db".into();
+        f.name = " ".into();
+        assert!(validate_port_forward(&f).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! 代理设置输入校验
+
+use crate::models::proxy::ProxyConfig;
+
+const MAX_HOST_LEN: usize = 256;
+/// SOCKS5 用户名/密码认证（RFC 1929）中两者各占一个长度字节
+const MAX_CREDENTIAL_LEN: usize = 255;
+
+/// 校验代理设置
+pub fn validate_proxy(proxy: &ProxyConfig) -> Result<(), String> {
+    let host = proxy.host.trim();
+    if host.is_empty() {
+        return Err("代理地址不能为空".to_string());
+    }
+    if host.len() > MAX_HOST_LEN {
+        return Err(format!("代理地址不能超过 {} 个字符", MAX_HOST_LEN));
+    }
+    if proxy.port == 0 {
+        return Err("代理端口不能为 0".to_string());
+    }
+    let username = proxy.username.as_deref().unwrap_or("");
+    let password = proxy.password.as_deref().unwrap_or("");
+    if username.len() > MAX_CREDENTIAL_LEN || password.len() > MAX_CREDENTIAL_LEN {
+        return Err(format!(
+            "代理用户名和密码不能超过 {} 个字符",
+            MAX_CREDENTIAL_LEN
+        ));
+    }
+    if username.is_empty() && !password.is_empty() {
+        return Err("填写代理密码时需同时填写用户名".to_string());
+    }
+    // HTTP Basic 认证以第一个 `:` 分隔用户名和密码
+    if username.contains(':') {
+        return Err("代理用户名不能包含“:”".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::proxy::ProxyType;
+
+    fn valid_proxy() -> ProxyConfig {
+        ProxyConfig {
+            proxy_type: ProxyType::Socks5,
+            host: "127.0.0.1".into(),
+            port: 1080,
+            username: None,
+            password: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_proxy() {
+        let mut p = valid_proxy();
+        assert!(validate_proxy(&p).is_ok());
+        p.port = 0;
+        assert!(validate_proxy(&p).is_err());
+        p.port = 1080;
+        p.host = "  ".into();
+        assert!(validate_proxy(&p).is_err());
+    }
+
+    #[test]
+    fn test_validate_proxy_credentials() {
+        let mut p = valid_proxy();
+        p.password = Some("secret".into());
+        assert!(validate_proxy(&p).is_err());
+        p.username = Some("alice".into());
+        assert!(validate_proxy(&p).is_ok());
+        p.username = Some("alice:ops".into());
+        assert!(validate_proxy(&p).is_err());
+        p.username = Some("alice".into());
+        p.password = Some("x".repeat(MAX_CREDENTIAL_LEN + 1));
+        assert!(validate_proxy(&p).is_err());
+    }
+}
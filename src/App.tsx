@@ -5,10 +5,18 @@ import FileBrowser from "./components/FileBrowser";
 import TransferQueue from "./components/TransferQueue";
 import TransferHistory from "./components/TransferHistory";
 import OverwriteDialog from "./components/OverwriteDialog";
+import AuthPromptDialog from "./components/AuthPromptDialog";
+import TerminalPanel from "./components/Terminal";
 import { useTransferListener } from "./hooks/useTransfer";
+import { useAuthPromptListener } from "./hooks/useAuthPrompt";
+import { useRemoteExecListener } from "./hooks/useRemoteExec";
+import { useTerminalListener } from "./hooks/useTerminal";
 
 function App() {
   useTransferListener();
+  useAuthPromptListener();
+  useRemoteExecListener();
+  useTerminalListener();
 
   return (
     <AppLayout
@@ -20,9 +28,11 @@ function App() {
       }
     >
       <FileBrowser />
+      <TerminalPanel />
       <TransferQueue />
       <TransferHistory />
       <OverwriteDialog />
+      <AuthPromptDialog />
     </AppLayout>
   );
 }
@@ -0,0 +1,71 @@
+import React, { useEffect, useState } from "react";
+import { Modal, Input, Space, Typography, message } from "antd";
+import { useAuthPromptStore } from "../../stores/authPromptStore";
+
+const AuthPromptDialog: React.FC = () => {
+  const current = useAuthPromptStore((s) => s.queue[0]);
+  const respond = useAuthPromptStore((s) => s.respond);
+  const cancel = useAuthPromptStore((s) => s.cancel);
+  const [answers, setAnswers] = useState<string[]>([]);
+
+  useEffect(() => {
+    setAnswers(current ? current.prompts.map(() => "") : []);
+  }, [current]);
+
+  if (!current) return null;
+
+  const handleError = (e: unknown) => {
+    message.error(`提交失败: ${e}`);
+  };
+
+  const handleOk = () => {
+    respond(answers).catch(handleError);
+  };
+
+  const handleCancel = () => {
+    cancel().catch(handleError);
+  };
+
+  const setAnswer = (index: number, value: string) => {
+    setAnswers((prev) => prev.map((a, i) => (i === index ? value : a)));
+  };
+
+  return (
+    <Modal
+      title="登录验证"
+      open
+      okText="确定"
+      cancelText="取消登录"
+      maskClosable={false}
+      onOk={handleOk}
+      onCancel={handleCancel}
+    >
+      <Space direction="vertical" style={{ width: "100%" }}>
+        <Typography.Text type="secondary">
+          {current.username}@{current.host}:{current.port}
+        </Typography.Text>
+        {current.instructions && (
+          <Typography.Paragraph style={{ whiteSpace: "pre-wrap" }}>
+            {current.instructions}
+          </Typography.Paragraph>
+        )}
+        {current.prompts.map((prompt, index) => {
+          const InputComponent = prompt.echo ? Input : Input.Password;
+          return (
+            <div key={index}>
+              <div>{prompt.text}</div>
+              <InputComponent
+                autoFocus={index === 0}
+                value={answers[index] ?? ""}
+                onChange={(e) => setAnswer(index, e.target.value)}
+                onPressEnter={handleOk}
+              />
+            </div>
+          );
+        })}
+      </Space>
+    </Modal>
+  );
+};
+
+export default AuthPromptDialog;
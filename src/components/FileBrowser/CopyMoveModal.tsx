@@ -0,0 +1,122 @@
+import React, { useEffect, useState } from "react";
+import { Input, Modal, Radio, Space, Typography, message } from "antd";
+import { useFileBrowserStore } from "../../stores/fileBrowserStore";
+import type { ConflictPolicy, FileOpResult } from "../../types";
+
+const { Text } = Typography;
+
+export type CopyMoveOperation = "copy" | "move";
+
+interface CopyMoveModalProps {
+  operation: CopyMoveOperation | null;
+  paths: string[];
+  defaultDir: string;
+  onClose: () => void;
+  onDone: () => void;
+}
+
+const OPERATION_LABELS: Record<CopyMoveOperation, string> = {
+  copy: "复制",
+  move: "移动",
+};
+
+/** 汇总每项结果，失败项逐条列出原因 */
+const reportResults = (label: string, results: FileOpResult[]) => {
+  const done = results.filter((r) => r.status === "done").length;
+  const skipped = results.filter((r) => r.status === "skipped").length;
+  const failed = results.filter((r) => r.status === "failed");
+  const summary = `${label}完成 ${done} 项，跳过 ${skipped} 项，失败 ${failed.length} 项`;
+  if (failed.length === 0) {
+    message.success(summary);
+    return;
+  }
+  Modal.error({
+    title: summary,
+    content: (
+      <div style={{ maxHeight: 240, overflow: "auto" }}>
+        {failed.map((r) => (
+          <div key={r.source}>
+            <Text code>{r.source}</Text> {r.error}
+          </div>
+        ))}
+      </div>
+    ),
+  });
+};
+
+/** 在当前主机上把选中的条目复制或移动到另一个目录，由服务器完成而不经过本地 */
+const CopyMoveModal: React.FC<CopyMoveModalProps> = ({
+  operation,
+  paths,
+  defaultDir,
+  onClose,
+  onDone,
+}) => {
+  const copyRemoteItems = useFileBrowserStore((s) => s.copyRemoteItems);
+  const moveRemoteItems = useFileBrowserStore((s) => s.moveRemoteItems);
+  const [destDir, setDestDir] = useState(defaultDir);
+  const [policy, setPolicy] = useState<ConflictPolicy>("skip");
+  const [submitting, setSubmitting] = useState(false);
+
+  useEffect(() => {
+    if (operation) {
+      setDestDir(defaultDir);
+    }
+  }, [operation, defaultDir]);
+
+  const label = operation ? OPERATION_LABELS[operation] : "";
+
+  const handleOk = async () => {
+    if (!operation || !destDir.trim()) return;
+    setSubmitting(true);
+    try {
+      const run = operation === "copy" ? copyRemoteItems : moveRemoteItems;
+      const results = await run(paths, destDir.trim(), policy);
+      reportResults(label, results);
+      onDone();
+    } catch (err) {
+      message.error(`${label}失败: ${err}`);
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <Modal
+      title={`${label}到…`}
+      open={operation != null}
+      onOk={handleOk}
+      onCancel={onClose}
+      confirmLoading={submitting}
+      okText={label}
+      cancelText="取消"
+    >
+      <Space direction="vertical" style={{ width: "100%" }}>
+        <Text type="secondary">已选择 {paths.length} 个项目</Text>
+        <Input
+          addonBefore="目标目录"
+          value={destDir}
+          onChange={(e) => setDestDir(e.target.value)}
+          onPressEnter={handleOk}
+          autoFocus
+        />
+        <div>
+          <Text style={{ marginRight: 8 }}>同名时</Text>
+          <Radio.Group
+            value={policy}
+            onChange={(e) => setPolicy(e.target.value)}
+            optionType="button"
+            size="small"
+            options={[
+              { label: "跳过", value: "skip" },
+              { label: "重命名", value: "rename" },
+              { label: "覆盖", value: "overwrite" },
+            ]}
+          />
+        </div>
+      </Space>
+    </Modal>
+  );
+};
+
+export default CopyMoveModal;
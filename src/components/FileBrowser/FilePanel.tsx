@@ -6,11 +6,18 @@ import {
   FolderAddOutlined,
   DeleteOutlined,
   StarOutlined,
+  CodeOutlined,
+  DesktopOutlined,
+  ApiOutlined,
+  CopyOutlined,
+  ScissorOutlined,
 } from "@ant-design/icons";
 import { invoke } from "@tauri-apps/api/core";
 import type { FileEntry } from "../../types";
 import PathBreadcrumb from "./PathBreadcrumb";
 import FileTable from "./FileTable";
+import CopyMoveModal from "./CopyMoveModal";
+import type { CopyMoveOperation } from "./CopyMoveModal";
 
 interface FilePanelProps {
   title: string;
@@ -25,6 +32,12 @@ interface FilePanelProps {
   onRefresh: () => void;
   onSelect: (paths: string[]) => void;
   onAddBookmark?: (hostId: number, path: string) => void;
+  /** 仅 SSH 主机提供，打开远程命令窗口 */
+  onRunCommand?: () => void;
+  /** 仅 SSH 主机提供，打开交互式终端 */
+  onOpenTerminal?: () => void;
+  /** 仅 SSH 主机提供，管理端口转发 */
+  onManageForwards?: () => void;
 }
 
 const FilePanel: React.FC<FilePanelProps> = ({
@@ -40,9 +53,13 @@ const FilePanel: React.FC<FilePanelProps> = ({
   onRefresh,
   onSelect,
   onAddBookmark,
+  onRunCommand,
+  onOpenTerminal,
+  onManageForwards,
 }) => {
   const [mkdirVisible, setMkdirVisible] = useState(false);
   const [newDirName, setNewDirName] = useState("");
+  const [copyMove, setCopyMove] = useState<CopyMoveOperation | null>(null);
 
   const handleCreateDir = useCallback(async () => {
     if (!newDirName.trim()) return;
@@ -174,6 +191,54 @@ const FilePanel: React.FC<FilePanelProps> = ({
               />
             </Tooltip>
           )}
+          {mode === "remote" && onRunCommand && (
+            <Button
+              type="text"
+              size="small"
+              icon={<CodeOutlined />}
+              onClick={onRunCommand}
+              disabled={disabled}
+              title="执行命令"
+            />
+          )}
+          {mode === "remote" && onOpenTerminal && (
+            <Button
+              type="text"
+              size="small"
+              icon={<DesktopOutlined />}
+              onClick={onOpenTerminal}
+              disabled={disabled}
+              title="打开终端"
+            />
+          )}
+          {mode === "remote" && onManageForwards && (
+            <Button
+              type="text"
+              size="small"
+              icon={<ApiOutlined />}
+              onClick={onManageForwards}
+              disabled={disabled}
+              title="端口转发"
+            />
+          )}
+          {selectedFiles.length > 0 && mode === "remote" && (
+            <>
+              <Button
+                type="text"
+                size="small"
+                icon={<CopyOutlined />}
+                onClick={() => setCopyMove("copy")}
+                title="复制选中到…"
+              />
+              <Button
+                type="text"
+                size="small"
+                icon={<ScissorOutlined />}
+                onClick={() => setCopyMove("move")}
+                title="移动选中到…"
+              />
+            </>
+          )}
           {selectedFiles.length > 0 && mode === "remote" && (
             <Popconfirm
               title={`确认删除 ${selectedFiles.length} 个项目？`}
@@ -242,6 +307,19 @@ const FilePanel: React.FC<FilePanelProps> = ({
           autoFocus
         />
       </Modal>
+
+      {mode === "remote" && (
+        <CopyMoveModal
+          operation={copyMove}
+          paths={selectedFiles}
+          defaultDir={path || "/"}
+          onClose={() => setCopyMove(null)}
+          onDone={() => {
+            setCopyMove(null);
+            onSelect([]);
+          }}
+        />
+      )}
     </Card>
   );
 };
@@ -7,9 +7,9 @@ import FileTable from "./FileTable";
 import type { FileEntry } from "../../types";
 
 const sampleFiles: FileEntry[] = [
-  { name: "b.txt", path: "/a/b.txt", is_dir: false, size: 100, modified: "1700000000" },
-  { name: "Alpha", path: "/a/Alpha", is_dir: true, size: 0, modified: "1700000100" },
-  { name: "a.doc", path: "/a/a.doc", is_dir: false, size: 500, modified: "1700000050" },
+  { name: "b.txt", path: "/a/b.txt", is_dir: false, size: 100, modified: "2023-11-14T22:13:20Z" },
+  { name: "Alpha", path: "/a/Alpha", is_dir: true, size: 0, modified: "2023-11-14T22:15:00Z" },
+  { name: "a.doc", path: "/a/a.doc", is_dir: false, size: 500, modified: "2023-11-14T22:14:10Z" },
   { name: "zoo", path: "/a/zoo", is_dir: false, size: 200, modified: undefined },
 ];
 
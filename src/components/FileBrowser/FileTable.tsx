@@ -65,10 +65,8 @@ const FileTable: React.FC<FileTableProps> = ({
 
   const parseModifiedTs = (modified: string | undefined): number => {
     if (!modified) return 0;
-    const ts = Number(modified);
-    if (!isNaN(ts)) return ts;
-    const d = new Date(modified);
-    return isNaN(d.getTime()) ? 0 : Math.floor(d.getTime() / 1000);
+    const ts = Date.parse(modified);
+    return isNaN(ts) ? 0 : ts;
   };
 
   const columns = [
@@ -115,14 +113,7 @@ const FileTable: React.FC<FileTableProps> = ({
       sorter: (a: FileEntry, b: FileEntry) =>
         parseModifiedTs(a.modified) - parseModifiedTs(b.modified),
       showSorterTooltip: false,
-      render: (modified: string | undefined) => {
-        if (!modified) return "--";
-        const ts = Number(modified);
-        if (!isNaN(ts)) {
-          return formatTimestamp(new Date(ts * 1000).toISOString());
-        }
-        return formatTimestamp(modified);
-      },
+      render: (modified: string | undefined) => formatTimestamp(modified),
     },
   ];
 
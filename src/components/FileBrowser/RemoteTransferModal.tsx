@@ -0,0 +1,115 @@
+import React, { useEffect, useState } from "react";
+import { Input, Modal, Select, Space, Typography, message } from "antd";
+import { invoke } from "@tauri-apps/api/core";
+import { useHostStore } from "../../stores/hostStore";
+import { useTransferStore } from "../../stores/transferStore";
+import type { FileEntry } from "../../types";
+
+const { Text } = Typography;
+
+interface RemoteTransferModalProps {
+  open: boolean;
+  sourceHostId: number;
+  files: FileEntry[];
+  onClose: () => void;
+}
+
+const joinPath = (dir: string, name: string) =>
+  dir.endsWith("/") ? `${dir}${name}` : `${dir}/${name}`;
+
+/** 把当前主机上选中的文件直接传到另一台已连接的主机，数据不经过本地磁盘 */
+const RemoteTransferModal: React.FC<RemoteTransferModalProps> = ({
+  open,
+  sourceHostId,
+  files,
+  onClose,
+}) => {
+  const hosts = useHostStore((s) => s.hosts);
+  const startRemoteTransfer = useTransferStore((s) => s.startRemoteTransfer);
+  const [connected, setConnected] = useState<number[]>([]);
+  const [destHostId, setDestHostId] = useState<number>();
+  const [destDir, setDestDir] = useState("/");
+  const [submitting, setSubmitting] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setDestHostId(undefined);
+    invoke<number[]>("active_connections")
+      .then((ids) => setConnected(ids.filter((id) => id !== sourceHostId)))
+      .catch(() => setConnected([]));
+  }, [open, sourceHostId]);
+
+  const hostOptions = hosts
+    .filter((h) => h.id != null && h.id !== sourceHostId)
+    .map((h) => {
+      const isConnected = connected.includes(h.id!);
+      return {
+        label: isConnected ? h.name : `${h.name}（未连接）`,
+        value: h.id!,
+        disabled: !isConnected,
+      };
+    });
+
+  const handleOk = async () => {
+    if (destHostId == null) {
+      message.warning("请选择目标主机");
+      return;
+    }
+    setSubmitting(true);
+    let queued = 0;
+    for (const file of files) {
+      try {
+        await startRemoteTransfer(
+          sourceHostId,
+          file.path,
+          destHostId,
+          joinPath(destDir || "/", file.name),
+          file.name,
+          file.size,
+        );
+        queued += 1;
+      } catch (err) {
+        message.error(`传输 ${file.name} 失败: ${err}`);
+      }
+    }
+    setSubmitting(false);
+    if (queued > 0) {
+      message.info(`已加入传输队列（${queued} 个文件）`);
+    }
+    onClose();
+  };
+
+  return (
+    <Modal
+      title="发送到其他主机"
+      open={open}
+      onOk={handleOk}
+      onCancel={onClose}
+      confirmLoading={submitting}
+      okText="发送"
+      cancelText="取消"
+      okButtonProps={{ disabled: files.length === 0 }}
+      destroyOnClose
+    >
+      <Space direction="vertical" style={{ width: "100%" }}>
+        <Text type="secondary">
+          已选择 {files.length} 个文件，目标主机需先建立连接
+        </Text>
+        <Select
+          placeholder="目标主机"
+          style={{ width: "100%" }}
+          options={hostOptions}
+          value={destHostId}
+          onChange={(v) => setDestHostId(v)}
+        />
+        <Input
+          addonBefore="目标目录"
+          value={destDir}
+          onChange={(e) => setDestDir(e.target.value)}
+        />
+      </Space>
+    </Modal>
+  );
+};
+
+export default RemoteTransferModal;
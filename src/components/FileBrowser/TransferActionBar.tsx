@@ -1,10 +1,15 @@
-import React, { useCallback } from "react";
+import React, { useCallback, useState } from "react";
 import { Button, Tooltip, message } from "antd";
-import { UploadOutlined, DownloadOutlined } from "@ant-design/icons";
+import {
+  UploadOutlined,
+  DownloadOutlined,
+  SwapOutlined,
+} from "@ant-design/icons";
 import { invoke } from "@tauri-apps/api/core";
 import { useFileBrowserStore } from "../../stores/fileBrowserStore";
 import { useTransferStore } from "../../stores/transferStore";
 import { useOverwriteStore } from "../../stores/overwriteStore";
+import RemoteTransferModal from "./RemoteTransferModal";
 
 const TransferActionBar: React.FC = () => {
   const {
@@ -24,6 +29,7 @@ const TransferActionBar: React.FC = () => {
   );
   const showDialog = useOverwriteStore((s) => s.showDialog);
   const resetOverwriteAll = useOverwriteStore((s) => s.resetOverwriteAll);
+  const [remoteTransferOpen, setRemoteTransferOpen] = useState(false);
 
   const handleUpload = useCallback(async () => {
     if (!connectedHostId) {
@@ -234,6 +240,9 @@ const TransferActionBar: React.FC = () => {
 
   const hasLocalSelection = selectedLocalFiles.length > 0;
   const hasRemoteSelection = selectedRemoteFiles.length > 0;
+  const selectedRemoteEntries = remoteFiles.filter(
+    (f) => selectedRemoteFiles.includes(f.path) && !f.is_dir,
+  );
 
   return (
     <div
@@ -263,6 +272,22 @@ const TransferActionBar: React.FC = () => {
           size="small"
         />
       </Tooltip>
+      <Tooltip title="发送选中文件到其他主机">
+        <Button
+          icon={<SwapOutlined />}
+          onClick={() => setRemoteTransferOpen(true)}
+          disabled={selectedRemoteEntries.length === 0 || !connectedHostId}
+          size="small"
+        />
+      </Tooltip>
+      {connectedHostId != null && (
+        <RemoteTransferModal
+          open={remoteTransferOpen}
+          sourceHostId={connectedHostId}
+          files={selectedRemoteEntries}
+          onClose={() => setRemoteTransferOpen(false)}
+        />
+      )}
     </div>
   );
 };
@@ -2,6 +2,10 @@ import React, { useCallback, useEffect, useState } from "react";
 import { Col, Row, message, Modal, Input } from "antd";
 import { useFileBrowser } from "../../hooks/useFileBrowser";
 import { useBookmarkStore } from "../../stores/bookmarkStore";
+import { useHostStore } from "../../stores/hostStore";
+import { useTerminalStore } from "../../stores/terminalStore";
+import RemoteCommandModal from "../RemoteCommand";
+import PortForwardModal from "../PortForward";
 import FilePanel from "./FilePanel";
 import TransferActionBar from "./TransferActionBar";
 
@@ -26,6 +30,19 @@ const FileBrowser: React.FC = () => {
     refreshRemote,
   } = useFileBrowser();
   const { createBookmark } = useBookmarkStore();
+  const hosts = useHostStore((s) => s.hosts);
+  const [commandOpen, setCommandOpen] = useState(false);
+  const [forwardsOpen, setForwardsOpen] = useState(false);
+  const connectedHost = hosts.find((h) => h.id === connectedHostId);
+  const isSshHost = connectedHost?.protocol === "sftp";
+  const openTerminal = useTerminalStore((s) => s.open);
+
+  const handleOpenTerminal = useCallback(() => {
+    if (!connectedHost?.id) return;
+    openTerminal(connectedHost.id, connectedHost.name, 80, 24).catch((err) =>
+      message.error(`打开终端失败: ${err}`),
+    );
+  }, [connectedHost, openTerminal]);
 
   const [bookmarkModal, setBookmarkModal] = useState<{
     hostId: number;
@@ -114,6 +131,9 @@ const FileBrowser: React.FC = () => {
           onRefresh={refreshRemote}
           onSelect={setSelectedRemoteFiles}
           onAddBookmark={handleAddBookmark}
+          onRunCommand={isSshHost ? () => setCommandOpen(true) : undefined}
+          onOpenTerminal={isSshHost ? handleOpenTerminal : undefined}
+          onManageForwards={isSshHost ? () => setForwardsOpen(true) : undefined}
         />
       </Col>
     </Row>
@@ -147,6 +167,19 @@ const FileBrowser: React.FC = () => {
         </div>
       )}
     </Modal>
+
+    <RemoteCommandModal
+      open={commandOpen}
+      hostId={connectedHostId}
+      workingDir={remotePath || "/"}
+      onClose={() => setCommandOpen(false)}
+    />
+
+    <PortForwardModal
+      open={forwardsOpen}
+      hostId={connectedHostId}
+      onClose={() => setForwardsOpen(false)}
+    />
     </>
   );
 };
@@ -1,12 +1,41 @@
 import React, { useEffect } from "react";
-import { Modal, Form, Input, InputNumber, Radio } from "antd";
-import type { Host, Protocol } from "../../types";
+import { Modal, Form, Input, InputNumber, Radio, Select, Switch } from "antd";
+import { useHostStore } from "../../stores/hostStore";
+import { useProxyStore } from "../../stores/proxyStore";
+import type { FtpsMode, Host, Protocol, ProxyConfig } from "../../types";
+import ProxyFields from "./ProxyFields";
+
+/** UTC-12:00 至 UTC+14:00，包含 +05:30、+05:45 等非整点时区 */
+const TIMEZONE_OFFSETS = [
+  -720, -660, -600, -570, -540, -480, -420, -360, -300, -240, -210, -180, -120, -60,
+  0, 60, 120, 180, 210, 240, 270, 300, 330, 345, 360, 390, 420, 480, 525, 540, 570,
+  600, 630, 660, 720, 765, 780, 840,
+];
+
+const DEFAULT_PORTS: Record<Protocol, number> = {
+  sftp: 22,
+  ftp: 21,
+  ftps: 21,
+  webdav: 80,
+  webdavs: 443,
+  s3: 443,
+  local: 0,
+};
+
+const formatOffset = (minutes: number) => {
+  const sign = minutes < 0 ? "-" : "+";
+  const abs = Math.abs(minutes);
+  const h = String(Math.floor(abs / 60)).padStart(2, "0");
+  const m = String(abs % 60).padStart(2, "0");
+  return `UTC${sign}${h}:${m}`;
+};
 
 interface HostFormModalProps {
   open: boolean;
   host: Host | null;
   confirmLoading?: boolean;
-  onOk: (values: Host) => void;
+  /** proxy 为代理模式为 custom 时填写的主机代理，其余模式为 null */
+  onOk: (values: Host, proxy: ProxyConfig | null) => void;
   onCancel: () => void;
 }
 
@@ -17,31 +46,74 @@ const HostFormModal: React.FC<HostFormModalProps> = ({
   onOk,
   onCancel,
 }) => {
-  const [form] = Form.useForm<Host>();
+  const [form] = Form.useForm<Host & { proxy?: ProxyConfig }>();
   const protocol = Form.useWatch("protocol", form);
+  const dataMode = Form.useWatch("ftp_data_mode", form);
+  const proxyMode = Form.useWatch("proxy_mode", form);
+  const isFtp = protocol === "ftp" || protocol === "ftps";
+  const isS3 = protocol === "s3";
+  const isLocal = protocol === "local";
+  const fetchHostProxy = useProxyStore((s) => s.fetchHostProxy);
+  const hosts = useHostStore((s) => s.hosts);
+  const jumpHostOptions = hosts
+    .filter((h) => h.protocol === "sftp" && h.id !== host?.id)
+    .map((h) => ({ value: h.id, label: `${h.name}（${h.host}）` }));
 
   useEffect(() => {
     if (open) {
       if (host) {
-        form.setFieldsValue(host);
+        form.setFieldsValue({
+          proxy_mode: "global",
+          proxy: { proxy_type: "socks5", host: "", port: 1080 },
+          ...host,
+        });
+        if (host.id && host.proxy_mode === "custom") {
+          fetchHostProxy(host.id)
+            .then((proxy) => proxy && form.setFieldsValue({ proxy }))
+            .catch(() => {});
+        }
       } else {
         form.resetFields();
-        form.setFieldsValue({ protocol: "sftp", port: 22 });
+        form.setFieldsValue({
+          protocol: "sftp",
+          port: 22,
+          timezone_offset: 0,
+          ftp_data_mode: "passive",
+          ftp_ignore_pasv_address: false,
+          s3_use_http: false,
+          ssh_transfer_mode: "auto",
+          proxy_mode: "global",
+          proxy: { proxy_type: "socks5", host: "", port: 1080 },
+        });
       }
     }
-  }, [open, host, form]);
+  }, [open, host, form, fetchHostProxy]);
 
   const handleProtocolChange = (value: Protocol) => {
-    form.setFieldsValue({ port: value === "sftp" ? 22 : 21 });
+    form.setFieldsValue({ port: DEFAULT_PORTS[value] });
+    if (value !== "sftp") {
+      form.setFieldsValue({ ssh_transfer_mode: "auto" });
+    }
+    if (value === "ftps" && !form.getFieldValue("ftps_mode")) {
+      form.setFieldsValue({ ftps_mode: "explicit" });
+    }
+  };
+
+  const handleFtpsModeChange = (value: FtpsMode) => {
+    form.setFieldsValue({ port: value === "implicit" ? 990 : 21 });
+  };
+
+  const handleS3HttpChange = (checked: boolean) => {
+    form.setFieldsValue({ port: checked ? 80 : 443 });
   };
 
   const handleOk = async () => {
     try {
-      const values = await form.validateFields();
+      const { proxy, ...values } = await form.validateFields();
       if (host?.id) {
         values.id = host.id;
       }
-      onOk(values);
+      onOk(values, values.proxy_mode === "custom" ? (proxy ?? null) : null);
     } catch {
       // validation failed
     }
@@ -85,51 +157,246 @@ const HostFormModal: React.FC<HostFormModalProps> = ({
           <Radio.Group onChange={(e) => handleProtocolChange(e.target.value)}>
             <Radio.Button value="sftp">SFTP</Radio.Button>
             <Radio.Button value="ftp">FTP</Radio.Button>
+            <Radio.Button value="ftps">FTPS</Radio.Button>
+            <Radio.Button value="webdav">WebDAV</Radio.Button>
+            <Radio.Button value="webdavs">WebDAVS</Radio.Button>
+            <Radio.Button value="s3">S3</Radio.Button>
+            <Radio.Button value="local">本地目录</Radio.Button>
           </Radio.Group>
         </Form.Item>
 
+        {protocol === "ftps" && (
+          <Form.Item name="ftps_mode" label="TLS 模式">
+            <Radio.Group
+              onChange={(e) => handleFtpsModeChange(e.target.value)}
+            >
+              <Radio.Button value="explicit">显式 (AUTH TLS)</Radio.Button>
+              <Radio.Button value="implicit">隐式 (990)</Radio.Button>
+            </Radio.Group>
+          </Form.Item>
+        )}
+
         <div style={{ display: "flex", gap: 12 }}>
           <Form.Item
             name="host"
-            label="主机地址"
-            rules={[{ required: true, message: "请输入主机地址" }]}
+            label={isS3 ? "端点" : isLocal ? "本地目录" : "主机地址"}
+            rules={[{ required: true, message: isLocal ? "请输入目录路径" : "请输入主机地址" }]}
+            tooltip={isLocal ? "本机或已挂载的网络共享目录，作为远程端的根目录" : undefined}
             style={{ flex: 1 }}
           >
-            <Input placeholder="例如：192.168.1.100" />
+            <Input
+              placeholder={
+                isS3
+                  ? "例如：s3.amazonaws.com"
+                  : isLocal
+                    ? "例如：/Volumes/share"
+                    : "例如：192.168.1.100"
+              }
+            />
           </Form.Item>
 
           <Form.Item
             name="port"
             label="端口"
-            rules={[{ required: true, message: "请输入端口" }]}
+            rules={[{ required: !isLocal, message: "请输入端口" }]}
+            hidden={isLocal}
             style={{ width: 120 }}
           >
             <InputNumber min={1} max={65535} style={{ width: "100%" }} />
           </Form.Item>
         </div>
 
+        {isS3 && (
+          <div style={{ display: "flex", gap: 12 }}>
+            <Form.Item
+              name="s3_bucket"
+              label="存储桶"
+              rules={[{ required: true, message: "请输入存储桶名称" }]}
+              style={{ flex: 1 }}
+            >
+              <Input autoCapitalize="none" autoCorrect="off" spellCheck={false} />
+            </Form.Item>
+
+            <Form.Item name="s3_region" label="区域" style={{ width: 160 }}>
+              <Input placeholder="us-east-1" spellCheck={false} />
+            </Form.Item>
+          </div>
+        )}
+
+        {isS3 && (
+          <Form.Item
+            name="s3_use_http"
+            label="使用 HTTP"
+            valuePropName="checked"
+            tooltip="不加密访问端点，仅用于内网部署的 MinIO 等服务"
+          >
+            <Switch onChange={handleS3HttpChange} />
+          </Form.Item>
+        )}
+
         <Form.Item
           name="username"
-          label="用户名"
-          rules={[{ required: true, message: "请输入用户名" }]}
+          label={isS3 ? "Access Key ID" : "用户名"}
+          rules={[{ required: !isLocal, message: "请输入用户名" }]}
+          hidden={isLocal}
         >
           <Input
-            placeholder="例如：root"
+            placeholder={isS3 ? undefined : "例如：root"}
             autoCapitalize="none"
             autoCorrect="off"
             spellCheck={false}
           />
         </Form.Item>
 
-        <Form.Item name="password" label="密码">
-          <Input.Password placeholder="输入密码" />
+        <Form.Item name="password" label={isS3 ? "Secret Access Key" : "密码"} hidden={isLocal}>
+          <Input.Password placeholder={isS3 ? undefined : "输入密码"} />
         </Form.Item>
 
+        {isFtp && (
+          <Form.Item
+            name="timezone_offset"
+            label="服务器时区"
+            tooltip="FTP 列表中的时间为服务器本地时间，按此时区换算"
+          >
+            <Select
+              options={TIMEZONE_OFFSETS.map((m) => ({
+                value: m,
+                label: formatOffset(m),
+              }))}
+            />
+          </Form.Item>
+        )}
+
+        {isFtp && (
+          <Form.Item name="ftp_data_mode" label="数据连接">
+            <Radio.Group>
+              <Radio.Button value="passive">被动 (PASV)</Radio.Button>
+              <Radio.Button value="epsv">扩展被动 (EPSV)</Radio.Button>
+              <Radio.Button value="active">主动 (PORT)</Radio.Button>
+            </Radio.Group>
+          </Form.Item>
+        )}
+
+        {isFtp && dataMode === "passive" && (
+          <Form.Item
+            name="ftp_ignore_pasv_address"
+            label="忽略 PASV 返回的地址"
+            tooltip="服务器在 PASV 应答中返回内网地址时开启，改用连接时的服务器地址"
+            valuePropName="checked"
+          >
+            <Switch />
+          </Form.Item>
+        )}
+
+        {isFtp && dataMode === "active" && (
+          <>
+            <Form.Item
+              name="ftp_active_address"
+              label="主动模式地址"
+              tooltip="位于 NAT 后时填写公网 IP，留空则使用本机地址"
+            >
+              <Input placeholder="例如：203.0.113.7" />
+            </Form.Item>
+            <div style={{ display: "flex", gap: 12 }}>
+              <Form.Item name="ftp_active_port_min" label="起始端口" style={{ flex: 1 }}>
+                <InputNumber min={1} max={65535} style={{ width: "100%" }} />
+              </Form.Item>
+              <Form.Item
+                name="ftp_active_port_max"
+                label="结束端口"
+                dependencies={["ftp_active_port_min"]}
+                style={{ flex: 1 }}
+                rules={[
+                  ({ getFieldValue }) => ({
+                    validator(_, value) {
+                      const min = getFieldValue("ftp_active_port_min");
+                      if ((min == null) !== (value == null)) {
+                        return Promise.reject(new Error("请同时填写起止端口"));
+                      }
+                      if (min != null && value < min) {
+                        return Promise.reject(new Error("结束端口不能小于起始端口"));
+                      }
+                      return Promise.resolve();
+                    },
+                  }),
+                ]}
+              >
+                <InputNumber min={1} max={65535} style={{ width: "100%" }} />
+              </Form.Item>
+            </div>
+          </>
+        )}
+
         {protocol === "sftp" && (
           <Form.Item name="key_path" label="密钥文件路径">
             <Input placeholder="例如：/Users/you/.ssh/id_rsa" />
           </Form.Item>
         )}
+
+        {protocol === "sftp" && (
+          <Form.Item
+            name="ssh_auth_order"
+            label="认证顺序"
+            tooltip="按选择的先后依次尝试；留空时有密钥依次尝试密钥、SSH Agent 和密码，没有密钥时有密码用密码，都未填写则使用 SSH Agent，服务器要求验证码时再弹窗询问"
+          >
+            <Select
+              mode="multiple"
+              allowClear
+              placeholder="自动"
+              options={[
+                { value: "key", label: "密钥文件" },
+                { value: "agent", label: "SSH Agent" },
+                { value: "password", label: "密码" },
+                { value: "keyboard-interactive", label: "键盘交互（验证码）" },
+              ]}
+            />
+          </Form.Item>
+        )}
+
+        {protocol === "sftp" && (
+          <Form.Item
+            name="jump_host_id"
+            label="跳板机"
+            tooltip="先连接所选主机，再经其转发到本主机；跳板机自身也可以配置跳板机"
+          >
+            <Select allowClear placeholder="直接连接" options={jumpHostOptions} />
+          </Form.Item>
+        )}
+
+        {protocol === "sftp" && (
+          <Form.Item
+            name="ssh_transfer_mode"
+            label="传输方式"
+            tooltip="自动：服务器没有 SFTP 子系统（如 Dropbear）时改用 SCP 传输，列目录等操作通过远程命令完成"
+          >
+            <Radio.Group>
+              <Radio.Button value="auto">自动</Radio.Button>
+              <Radio.Button value="sftp">SFTP</Radio.Button>
+              <Radio.Button value="scp">SCP</Radio.Button>
+            </Radio.Group>
+          </Form.Item>
+        )}
+
+        <Form.Item
+          name="proxy_mode"
+          label="代理"
+          hidden={isLocal}
+          tooltip={
+            protocol === "sftp"
+              ? "使用跳板机时，代理用于连接第一台跳板机"
+              : isFtp
+                ? "经代理连接时仅支持被动模式数据连接"
+                : undefined
+          }
+        >
+          <Radio.Group>
+            <Radio.Button value="global">跟随全局</Radio.Button>
+            <Radio.Button value="direct">不使用代理</Radio.Button>
+            <Radio.Button value="custom">自定义</Radio.Button>
+          </Radio.Group>
+        </Form.Item>
+
+        {proxyMode === "custom" && <ProxyFields prefix={["proxy"]} />}
       </Form>
     </Modal>
   );
@@ -0,0 +1,250 @@
+import React, { useCallback, useEffect, useState } from "react";
+import {
+  Modal,
+  Table,
+  Input,
+  Button,
+  Segmented,
+  Space,
+  Tag,
+  Tooltip,
+  Typography,
+  message,
+} from "antd";
+import { useHostStore } from "../../stores/hostStore";
+import type { HostImportEntry, HostImportResult, HostImportStatus } from "../../types";
+
+const { Text } = Typography;
+
+type ImportSource = "ssh-config" | "site-file";
+
+interface ImportHostsModalProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+const STATUS_TAGS: Record<HostImportStatus, { color: string; label: string }> = {
+  imported: { color: "success", label: "已导入" },
+  skipped: { color: "default", label: "已跳过" },
+  unsupported: { color: "warning", label: "不支持" },
+};
+
+/** 勾选记录时一并勾选其依赖的跳板机，避免导入时跳板机缺失 */
+const withJumpHosts = (keys: string[], entries: HostImportEntry[]) => {
+  const selected = new Set(keys);
+  let pending = [...keys];
+  while (pending.length > 0) {
+    const next: string[] = [];
+    for (const key of pending) {
+      const jump = entries.find((e) => e.source === key)?.jump_source;
+      if (jump && !selected.has(jump)) {
+        selected.add(jump);
+        next.push(jump);
+      }
+    }
+    pending = next;
+  }
+  return Array.from(selected);
+};
+
+const importable = (entry: HostImportEntry) =>
+  !entry.error && !entry.unsupported && entry.duplicate_of == null;
+
+const ImportHostsModal: React.FC<ImportHostsModalProps> = ({ open, onClose }) => {
+  const { previewSshConfigImport, previewSiteImport, importHosts } = useHostStore();
+  const [source, setSource] = useState<ImportSource>("ssh-config");
+  const [path, setPath] = useState("");
+  const [entries, setEntries] = useState<HostImportEntry[]>([]);
+  const [selectedKeys, setSelectedKeys] = useState<string[]>([]);
+  const [results, setResults] = useState<HostImportResult[] | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [importing, setImporting] = useState(false);
+
+  const loadPreview = useCallback(
+    async (from: ImportSource, filePath: string) => {
+      setResults(null);
+      if (from === "site-file" && !filePath) {
+        setEntries([]);
+        setSelectedKeys([]);
+        return;
+      }
+      setLoading(true);
+      try {
+        const preview =
+          from === "ssh-config"
+            ? await previewSshConfigImport(filePath || undefined)
+            : await previewSiteImport(filePath);
+        setEntries(preview);
+        setSelectedKeys(preview.filter(importable).map((e) => e.source));
+      } catch (err) {
+        setEntries([]);
+        setSelectedKeys([]);
+        message.error(`读取失败: ${err}`);
+      } finally {
+        setLoading(false);
+      }
+    },
+    [previewSshConfigImport, previewSiteImport],
+  );
+
+  useEffect(() => {
+    if (open) {
+      setSource("ssh-config");
+      setPath("");
+      loadPreview("ssh-config", "");
+    }
+  }, [open, loadPreview]);
+
+  const handleSourceChange = (value: ImportSource) => {
+    setSource(value);
+    setPath("");
+    loadPreview(value, "");
+  };
+
+  const handleImport = async () => {
+    if (results) {
+      onClose();
+      return;
+    }
+    const selected = entries.filter((e) => selectedKeys.includes(e.source));
+    setImporting(true);
+    try {
+      const report = await importHosts(selected);
+      const imported = report.filter((r) => r.status === "imported").length;
+      message.success(`已导入 ${imported} 台主机`);
+      setResults(report);
+    } catch (err) {
+      message.error(`导入失败: ${err}`);
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  return (
+    <Modal
+      title="导入主机"
+      open={open}
+      onOk={handleImport}
+      onCancel={onClose}
+      okText={results ? "完成" : `导入 ${selectedKeys.length} 台`}
+      okButtonProps={{ disabled: !results && selectedKeys.length === 0 }}
+      cancelButtonProps={{ style: results ? { display: "none" } : undefined }}
+      confirmLoading={importing}
+      destroyOnHidden
+      width={760}
+    >
+      {!results && (
+        <>
+          <Segmented<ImportSource>
+            value={source}
+            onChange={handleSourceChange}
+            options={[
+              { value: "ssh-config", label: "SSH 配置" },
+              { value: "site-file", label: "FileZilla / WinSCP" },
+            ]}
+            style={{ marginTop: 16 }}
+          />
+          <Space.Compact style={{ width: "100%", margin: "12px 0" }}>
+            <Input
+              value={path}
+              onChange={(e) => setPath(e.target.value)}
+              placeholder={
+                source === "ssh-config"
+                  ? "默认读取 ~/.ssh/config"
+                  : "sitemanager.xml 或 WinSCP 导出的 .ini 文件路径"
+              }
+              spellCheck={false}
+            />
+            <Button onClick={() => loadPreview(source, path.trim())} loading={loading}>
+              读取
+            </Button>
+          </Space.Compact>
+
+          <Table<HostImportEntry>
+            rowKey="source"
+            size="small"
+            loading={loading}
+            dataSource={entries}
+            pagination={false}
+            scroll={{ y: 360 }}
+            rowSelection={{
+              selectedRowKeys: selectedKeys,
+              onChange: (keys) => {
+                const expanded = withJumpHosts(keys as string[], entries);
+                setSelectedKeys(
+                  expanded.filter((k) => entries.some((e) => e.source === k && importable(e))),
+                );
+              },
+              getCheckboxProps: (entry) => ({ disabled: !importable(entry) }),
+            }}
+            columns={[
+              { title: "名称", dataIndex: "source", ellipsis: true },
+              {
+                title: "地址",
+                render: (_, e) =>
+                  `${e.host.protocol.toUpperCase()}://${e.host.username}@${e.host.host}:${e.host.port}`,
+                ellipsis: true,
+              },
+              {
+                title: "跳板机",
+                render: (_, e) => e.jump_source ?? (e.host.jump_host_id ? "已保存的主机" : "-"),
+                ellipsis: true,
+              },
+              {
+                title: "状态",
+                width: 160,
+                render: (_, e) => (
+                  <Space size={4} wrap>
+                    {e.unsupported && (
+                      <Tooltip title={e.unsupported}>
+                        <Tag color="warning">不支持</Tag>
+                      </Tooltip>
+                    )}
+                    {e.error && (
+                      <Tooltip title={e.error}>
+                        <Tag color="error">无效</Tag>
+                      </Tooltip>
+                    )}
+                    {e.duplicate_of != null && <Tag>已存在</Tag>}
+                    {e.warnings.map((w) => (
+                      <Tooltip key={w} title={w}>
+                        <Tag color="warning">注意</Tag>
+                      </Tooltip>
+                    ))}
+                    {importable(e) && e.warnings.length === 0 && (
+                      <Text type="success">可导入</Text>
+                    )}
+                  </Space>
+                ),
+              },
+            ]}
+          />
+        </>
+      )}
+
+      {results && (
+        <Table<HostImportResult>
+          rowKey="source"
+          size="small"
+          dataSource={results}
+          pagination={false}
+          scroll={{ y: 420 }}
+          style={{ marginTop: 16 }}
+          columns={[
+            { title: "名称", dataIndex: "source", ellipsis: true },
+            {
+              title: "结果",
+              width: 100,
+              render: (_, r) => (
+                <Tag color={STATUS_TAGS[r.status].color}>{STATUS_TAGS[r.status].label}</Tag>
+              ),
+            },
+            { title: "说明", dataIndex: "message", ellipsis: true },
+          ]}
+        />
+      )}
+    </Modal>
+  );
+};
+
+export default ImportHostsModal;
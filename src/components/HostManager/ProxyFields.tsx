@@ -0,0 +1,61 @@
+import React from "react";
+import { Form, Input, InputNumber, Radio } from "antd";
+
+interface ProxyFieldsProps {
+  /** 字段在表单中的路径前缀，如 ["proxy"] */
+  prefix?: string[];
+}
+
+/** 代理类型、地址和可选的认证信息，全局代理与主机自定义代理共用 */
+const ProxyFields: React.FC<ProxyFieldsProps> = ({ prefix = [] }) => (
+  <>
+    <Form.Item name={[...prefix, "proxy_type"]} label="代理类型">
+      <Radio.Group>
+        <Radio.Button value="socks5">SOCKS5</Radio.Button>
+        <Radio.Button value="http">HTTP CONNECT</Radio.Button>
+      </Radio.Group>
+    </Form.Item>
+
+    <div style={{ display: "flex", gap: 12 }}>
+      <Form.Item
+        name={[...prefix, "host"]}
+        label="代理地址"
+        rules={[{ required: true, message: "请输入代理地址" }]}
+        style={{ flex: 1 }}
+      >
+        <Input placeholder="例如：127.0.0.1" />
+      </Form.Item>
+
+      <Form.Item
+        name={[...prefix, "port"]}
+        label="端口"
+        rules={[{ required: true, message: "请输入端口" }]}
+        style={{ width: 120 }}
+      >
+        <InputNumber min={1} max={65535} style={{ width: "100%" }} />
+      </Form.Item>
+    </div>
+
+    <div style={{ display: "flex", gap: 12 }}>
+      <Form.Item name={[...prefix, "username"]} label="代理用户名" style={{ flex: 1 }}>
+        <Input
+          placeholder="无需认证时留空"
+          autoCapitalize="none"
+          autoCorrect="off"
+          spellCheck={false}
+        />
+      </Form.Item>
+
+      <Form.Item
+        name={[...prefix, "password"]}
+        label="代理密码"
+        tooltip="留空则沿用已保存的密码"
+        style={{ flex: 1 }}
+      >
+        <Input.Password placeholder="输入密码" />
+      </Form.Item>
+    </div>
+  </>
+);
+
+export default ProxyFields;
@@ -0,0 +1,83 @@
+import React, { useEffect, useState } from "react";
+import { Modal, Form, Switch, message } from "antd";
+import { useProxyStore } from "../../stores/proxyStore";
+import type { ProxyConfig } from "../../types";
+import ProxyFields from "./ProxyFields";
+
+interface ProxySettingsModalProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+interface ProxySettingsValues extends ProxyConfig {
+  enabled: boolean;
+}
+
+/** 全局代理设置，代理模式为“跟随全局”的主机使用 */
+const ProxySettingsModal: React.FC<ProxySettingsModalProps> = ({ open, onClose }) => {
+  const [form] = Form.useForm<ProxySettingsValues>();
+  const enabled = Form.useWatch("enabled", form);
+  const { fetchGlobalProxy, saveGlobalProxy } = useProxyStore();
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    form.resetFields();
+    fetchGlobalProxy()
+      .then(() => {
+        const proxy = useProxyStore.getState().globalProxy;
+        form.setFieldsValue(
+          proxy
+            ? { enabled: true, ...proxy }
+            : { enabled: false, proxy_type: "socks5", port: 1080 },
+        );
+      })
+      .catch((err) => message.error(`读取代理设置失败: ${err}`));
+  }, [open, form, fetchGlobalProxy]);
+
+  const handleOk = async () => {
+    let values: ProxySettingsValues;
+    try {
+      values = await form.validateFields();
+    } catch {
+      return;
+    }
+    const { enabled: on, ...proxy } = values;
+    setSaving(true);
+    try {
+      await saveGlobalProxy(on ? proxy : null);
+      message.success("代理设置已保存");
+      onClose();
+    } catch (err) {
+      message.error(`保存失败: ${err}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <Modal
+      title="全局代理"
+      open={open}
+      onOk={handleOk}
+      onCancel={onClose}
+      confirmLoading={saving}
+      destroyOnHidden
+      width={520}
+    >
+      <Form form={form} layout="vertical" autoComplete="off" style={{ marginTop: 16 }}>
+        <Form.Item
+          name="enabled"
+          label="启用全局代理"
+          tooltip="SFTP 会话及 FTP 控制连接、被动模式数据连接都经代理建立"
+          valuePropName="checked"
+        >
+          <Switch />
+        </Form.Item>
+        {enabled && <ProxyFields />}
+      </Form>
+    </Modal>
+  );
+};
+
+export default ProxySettingsModal;
@@ -16,11 +16,16 @@ import {
   LoadingOutlined,
   LinkOutlined,
   DisconnectOutlined,
+  GlobalOutlined,
+  ImportOutlined,
 } from "@ant-design/icons";
 import { useHostStore } from "../../stores/hostStore";
+import { useProxyStore } from "../../stores/proxyStore";
 import { useFileBrowser } from "../../hooks/useFileBrowser";
 import HostFormModal from "./HostFormModal";
-import type { Host } from "../../types";
+import ProxySettingsModal from "./ProxySettingsModal";
+import ImportHostsModal from "./ImportHostsModal";
+import type { Host, ProxyConfig } from "../../types";
 
 const { Text } = Typography;
 
@@ -39,10 +44,13 @@ const HostManager: React.FC = () => {
 
   const { connectedHostId, connectAndBrowse, disconnectHost } =
     useFileBrowser();
+  const saveHostProxy = useProxyStore((s) => s.saveHostProxy);
 
   const [modalOpen, setModalOpen] = useState(false);
   const [editingHost, setEditingHost] = useState<Host | null>(null);
   const [saving, setSaving] = useState(false);
+  const [proxyModalOpen, setProxyModalOpen] = useState(false);
+  const [importModalOpen, setImportModalOpen] = useState(false);
   const [testingId, setTestingId] = useState<number | null>(null);
   const [connectingId, setConnectingId] = useState<number | null>(null);
 
@@ -62,14 +70,16 @@ const HostManager: React.FC = () => {
   }, []);
 
   const handleModalOk = useCallback(
-    async (values: Host) => {
+    async (values: Host, proxy: ProxyConfig | null) => {
       setSaving(true);
       try {
         if (values.id) {
           await updateHost(values);
+          if (proxy) await saveHostProxy(values.id, proxy);
           message.success("主机更新成功");
         } else {
-          await createHost(values);
+          const created = await createHost(values);
+          if (proxy && created.id) await saveHostProxy(created.id, proxy);
           message.success("主机创建成功");
         }
         setModalOpen(false);
@@ -79,7 +89,7 @@ const HostManager: React.FC = () => {
         setSaving(false);
       }
     },
-    [createHost, updateHost],
+    [createHost, updateHost, saveHostProxy],
   );
 
   const handleDelete = useCallback(
@@ -163,14 +173,30 @@ const HostManager: React.FC = () => {
         }}
       >
         <Text strong>主机列表</Text>
-        <Button
-          type="primary"
-          size="small"
-          icon={<PlusOutlined />}
-          onClick={handleAdd}
-        >
-          新增
-        </Button>
+        <Space size={4}>
+          <Tooltip title="导入主机">
+            <Button
+              size="small"
+              icon={<ImportOutlined />}
+              onClick={() => setImportModalOpen(true)}
+            />
+          </Tooltip>
+          <Tooltip title="全局代理">
+            <Button
+              size="small"
+              icon={<GlobalOutlined />}
+              onClick={() => setProxyModalOpen(true)}
+            />
+          </Tooltip>
+          <Button
+            type="primary"
+            size="small"
+            icon={<PlusOutlined />}
+            onClick={handleAdd}
+          >
+            新增
+          </Button>
+        </Space>
       </div>
 
       <div style={{ flex: 1, overflow: "auto", minHeight: 0 }}>
@@ -271,7 +297,9 @@ const HostManager: React.FC = () => {
                 }
                 description={
                   <Text type="secondary" style={{ fontSize: 12 }}>
-                    {host.protocol.toUpperCase()}://{host.host}:{host.port}
+                    {host.protocol === "local"
+                      ? host.host
+                      : `${host.protocol.toUpperCase()}://${host.host}:${host.port}`}
                   </Text>
                 }
               />
@@ -287,6 +315,16 @@ const HostManager: React.FC = () => {
         onOk={handleModalOk}
         onCancel={() => setModalOpen(false)}
       />
+
+      <ImportHostsModal
+        open={importModalOpen}
+        onClose={() => setImportModalOpen(false)}
+      />
+
+      <ProxySettingsModal
+        open={proxyModalOpen}
+        onClose={() => setProxyModalOpen(false)}
+      />
     </div>
   );
 };
@@ -0,0 +1,261 @@
+import React, { useEffect, useState } from "react";
+import {
+  Modal,
+  Table,
+  Button,
+  Space,
+  Form,
+  Input,
+  InputNumber,
+  Radio,
+  Tag,
+  Tooltip,
+  Popconfirm,
+  message,
+} from "antd";
+import {
+  PlusOutlined,
+  PlayCircleOutlined,
+  PauseCircleOutlined,
+  EditOutlined,
+  DeleteOutlined,
+} from "@ant-design/icons";
+import { usePortForwardStore } from "../../stores/portForwardStore";
+import { formatFileSize } from "../../utils/formatters";
+import type { PortForward } from "../../types";
+
+/** 打开期间刷新运行状态和流量的间隔 */
+const STATUS_POLL_MS = 2000;
+
+interface PortForwardModalProps {
+  open: boolean;
+  hostId: number | null;
+  onClose: () => void;
+}
+
+const PortForwardModal: React.FC<PortForwardModalProps> = ({ open, hostId, onClose }) => {
+  const {
+    forwards,
+    statuses,
+    loading,
+    fetchForwards,
+    fetchStatuses,
+    saveForward,
+    deleteForward,
+    startForward,
+    stopForward,
+  } = usePortForwardStore();
+  const [form] = Form.useForm<PortForward>();
+  const kind = Form.useWatch("kind", form);
+  const [editing, setEditing] = useState<PortForward | null>(null);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    if (!open || !hostId) return;
+    fetchForwards(hostId).catch((err) => message.error(`读取端口转发失败: ${err}`));
+    const refresh = () => fetchStatuses(hostId).catch(() => {});
+    refresh();
+    const timer = setInterval(refresh, STATUS_POLL_MS);
+    return () => clearInterval(timer);
+  }, [open, hostId, fetchForwards, fetchStatuses]);
+
+  const handleEdit = (forward?: PortForward) => {
+    const initial: PortForward = forward ?? {
+      host_id: hostId ?? 0,
+      name: "",
+      kind: "local",
+      bind_host: "127.0.0.1",
+      bind_port: 0,
+      target_host: "127.0.0.1",
+      target_port: 0,
+    };
+    setEditing(initial);
+    form.setFieldsValue(initial);
+  };
+
+  const handleSave = async () => {
+    if (!editing) return;
+    let values: PortForward;
+    try {
+      values = await form.validateFields();
+    } catch {
+      return;
+    }
+    setSaving(true);
+    try {
+      await saveForward({ ...editing, ...values });
+      setEditing(null);
+    } catch (err) {
+      message.error(`保存失败: ${err}`);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleError = (action: string) => (err: unknown) => {
+    message.error(`${action}失败: ${err}`);
+  };
+
+  const columns = [
+    { title: "名称", dataIndex: "name", key: "name" },
+    {
+      title: "转发",
+      key: "route",
+      render: (_: unknown, f: PortForward) => {
+        const bound = f.id ? statuses[f.id]?.bound_port : null;
+        const bind = `${f.bind_host}:${bound ?? f.bind_port}`;
+        const target = `${f.target_host}:${f.target_port}`;
+        return f.kind === "local"
+          ? `本机 ${bind} → 服务器侧 ${target}`
+          : `服务器 ${bind} → 本机侧 ${target}`;
+      },
+    },
+    {
+      title: "状态",
+      key: "status",
+      render: (_: unknown, f: PortForward) => {
+        const status = f.id ? statuses[f.id] : undefined;
+        if (!status) return <Tag>未启动</Tag>;
+        const tag = status.running ? (
+          <Tag color="success">运行中 · {status.active_connections} 个连接</Tag>
+        ) : (
+          <Tag color={status.error ? "error" : "default"}>已停止</Tag>
+        );
+        return (
+          <Tooltip title={status.error}>
+            <Space direction="vertical" size={0}>
+              {tag}
+              <span style={{ fontSize: 12, color: "#999" }}>
+                ↑ {formatFileSize(status.bytes_sent)} ↓ {formatFileSize(status.bytes_received)}
+              </span>
+            </Space>
+          </Tooltip>
+        );
+      },
+    },
+    {
+      title: "操作",
+      key: "actions",
+      render: (_: unknown, f: PortForward) => {
+        const running = !!(f.id && statuses[f.id]?.running);
+        return (
+          <Space size={0}>
+            {running ? (
+              <Button
+                type="text"
+                size="small"
+                icon={<PauseCircleOutlined />}
+                title="停止"
+                onClick={() => stopForward(f).catch(handleError("停止"))}
+              />
+            ) : (
+              <Button
+                type="text"
+                size="small"
+                icon={<PlayCircleOutlined />}
+                title="启动"
+                onClick={() => f.id && startForward(f.id).catch(handleError("启动"))}
+              />
+            )}
+            <Button
+              type="text"
+              size="small"
+              icon={<EditOutlined />}
+              title="编辑"
+              onClick={() => handleEdit(f)}
+            />
+            <Popconfirm
+              title="确认删除该转发？"
+              onConfirm={() => deleteForward(f).catch(handleError("删除"))}
+              okText="删除"
+              cancelText="取消"
+              okButtonProps={{ danger: true }}
+            >
+              <Button type="text" size="small" danger icon={<DeleteOutlined />} title="删除" />
+            </Popconfirm>
+          </Space>
+        );
+      },
+    },
+  ];
+
+  return (
+    <Modal title="端口转发" open={open} onCancel={onClose} footer={null} width={760}>
+      <Button
+        icon={<PlusOutlined />}
+        size="small"
+        style={{ marginBottom: 8 }}
+        onClick={() => handleEdit()}
+        disabled={!hostId}
+      >
+        新建转发
+      </Button>
+      <Table
+        rowKey={(f) => f.id ?? f.name}
+        size="small"
+        loading={loading}
+        dataSource={forwards}
+        columns={columns}
+        pagination={false}
+      />
+
+      <Modal
+        title={editing?.id ? "编辑转发" : "新建转发"}
+        open={!!editing}
+        onOk={handleSave}
+        onCancel={() => setEditing(null)}
+        confirmLoading={saving}
+        okText="保存"
+        cancelText="取消"
+        destroyOnClose
+      >
+        <Form form={form} layout="vertical">
+          <Form.Item name="name" label="名称" rules={[{ required: true, message: "请输入名称" }]}>
+            <Input placeholder="如 postgres" />
+          </Form.Item>
+          <Form.Item name="kind" label="类型">
+            <Radio.Group>
+              <Radio value="local">本地转发（-L）</Radio>
+              <Radio value="remote">远程转发（-R）</Radio>
+            </Radio.Group>
+          </Form.Item>
+          <Space align="start">
+            <Form.Item
+              name="bind_host"
+              label={kind === "remote" ? "服务器监听地址" : "本机监听地址"}
+              rules={[{ required: true, message: "请输入监听地址" }]}
+            >
+              <Input style={{ width: 220 }} />
+            </Form.Item>
+            <Form.Item
+              name="bind_port"
+              label="监听端口"
+              tooltip={kind === "remote" ? "填 0 由服务器分配" : undefined}
+              rules={[{ required: true, message: "请输入端口" }]}
+            >
+              <InputNumber min={kind === "remote" ? 0 : 1} max={65535} />
+            </Form.Item>
+          </Space>
+          <Space align="start">
+            <Form.Item
+              name="target_host"
+              label={kind === "remote" ? "目标地址（从本机连接）" : "目标地址（从服务器连接）"}
+              rules={[{ required: true, message: "请输入目标地址" }]}
+            >
+              <Input style={{ width: 220 }} />
+            </Form.Item>
+            <Form.Item
+              name="target_port"
+              label="目标端口"
+              rules={[{ required: true, message: "请输入端口" }]}
+            >
+              <InputNumber min={1} max={65535} />
+            </Form.Item>
+          </Space>
+        </Form>
+      </Modal>
+    </Modal>
+  );
+};
+
+export default PortForwardModal;
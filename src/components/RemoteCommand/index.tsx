@@ -0,0 +1,114 @@
+import React, { useEffect, useState } from "react";
+import { Modal, Input, InputNumber, Space, Button, Tag, Typography } from "antd";
+import { useRemoteExecStore } from "../../stores/remoteExecStore";
+
+interface RemoteCommandModalProps {
+  open: boolean;
+  hostId: number | null;
+  /** 默认工作目录，通常为远程面板当前路径 */
+  workingDir: string;
+  onClose: () => void;
+}
+
+const RemoteCommandModal: React.FC<RemoteCommandModalProps> = ({
+  open,
+  hostId,
+  workingDir,
+  onClose,
+}) => {
+  const { running, output, exitCode, error, run, clear } = useRemoteExecStore();
+  const [command, setCommand] = useState("");
+  const [dir, setDir] = useState(workingDir);
+  const [timeoutSecs, setTimeoutSecs] = useState<number | null>(60);
+
+  useEffect(() => {
+    if (open) setDir(workingDir);
+  }, [open, workingDir]);
+
+  const handleRun = () => {
+    if (!hostId || !command.trim() || running) return;
+    run(hostId, command, dir, timeoutSecs ?? undefined);
+  };
+
+  const handleClose = () => {
+    if (!running) clear();
+    onClose();
+  };
+
+  return (
+    <Modal
+      title="执行远程命令"
+      open={open}
+      onCancel={handleClose}
+      width={720}
+      footer={[
+        <Button key="close" onClick={handleClose}>
+          关闭
+        </Button>,
+        <Button
+          key="run"
+          type="primary"
+          loading={running}
+          disabled={!hostId || !command.trim()}
+          onClick={handleRun}
+        >
+          执行
+        </Button>,
+      ]}
+    >
+      <Space direction="vertical" style={{ width: "100%" }}>
+        <Input
+          placeholder="命令，如 ls -la"
+          value={command}
+          onChange={(e) => setCommand(e.target.value)}
+          onPressEnter={handleRun}
+          autoFocus
+        />
+        <Space style={{ width: "100%" }}>
+          <Input
+            addonBefore="工作目录"
+            value={dir}
+            onChange={(e) => setDir(e.target.value)}
+            style={{ width: 420 }}
+          />
+          <InputNumber
+            addonBefore="超时"
+            addonAfter="秒"
+            min={0}
+            value={timeoutSecs}
+            onChange={setTimeoutSecs}
+            placeholder="不限"
+          />
+        </Space>
+        <pre
+          style={{
+            height: 320,
+            overflow: "auto",
+            margin: 0,
+            padding: 8,
+            background: "#1e1e1e",
+            color: "#d4d4d4",
+            fontSize: 12,
+            whiteSpace: "pre-wrap",
+            wordBreak: "break-all",
+          }}
+        >
+          {output.map((chunk, index) => (
+            <span
+              key={index}
+              style={chunk.stream === "stderr" ? { color: "#f48771" } : undefined}
+            >
+              {chunk.data}
+            </span>
+          ))}
+        </pre>
+        {exitCode !== null && (
+          <Tag color={exitCode === 0 ? "success" : "error"}>退出码 {exitCode}</Tag>
+        )}
+        {error && <Typography.Text type="danger">{error}</Typography.Text>}
+      </Space>
+    </Modal>
+  );
+};
+
+export default RemoteCommandModal;
@@ -0,0 +1,125 @@
+import React, { useEffect, useMemo, useRef } from "react";
+import { Card, Tabs, Typography, message } from "antd";
+import { useTerminalStore, type TerminalSession } from "../../stores/terminalStore";
+import { keyToTerminalInput, renderTerminal } from "../../utils/terminal";
+
+/** 等宽字体下单个字符的近似尺寸，用于按面板大小计算行列数 */
+const CHAR_WIDTH = 7.8;
+const LINE_HEIGHT = 18;
+
+const TerminalView: React.FC<{ terminal: TerminalSession; active: boolean }> = ({
+  terminal,
+  active,
+}) => {
+  const write = useTerminalStore((s) => s.write);
+  const resize = useTerminalStore((s) => s.resize);
+  const ref = useRef<HTMLPreElement>(null);
+  const text = useMemo(() => renderTerminal(terminal.output), [terminal.output]);
+
+  useEffect(() => {
+    const el = ref.current;
+    if (el) el.scrollTop = el.scrollHeight;
+  }, [text]);
+
+  useEffect(() => {
+    if (active) ref.current?.focus();
+  }, [active]);
+
+  useEffect(() => {
+    const el = ref.current;
+    if (!el || typeof ResizeObserver === "undefined") return;
+    const observer = new ResizeObserver(() => {
+      const cols = Math.max(20, Math.floor((el.clientWidth - 16) / CHAR_WIDTH));
+      const rows = Math.max(5, Math.floor((el.clientHeight - 16) / LINE_HEIGHT));
+      resize(terminal.id, cols, rows).catch(() => {});
+    });
+    observer.observe(el);
+    return () => observer.disconnect();
+  }, [terminal.id, resize]);
+
+  const send = (data: string) => {
+    write(terminal.id, data).catch((e) => message.error(`发送失败: ${e}`));
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    const input = keyToTerminalInput(e);
+    if (input === null) return;
+    e.preventDefault();
+    send(input);
+  };
+
+  const handlePaste = (e: React.ClipboardEvent) => {
+    e.preventDefault();
+    const data = e.clipboardData.getData("text");
+    if (data) send(data.replace(/\r?\n/g, "\r"));
+  };
+
+  return (
+    <>
+      <pre
+        ref={ref}
+        tabIndex={0}
+        onKeyDown={handleKeyDown}
+        onPaste={handlePaste}
+        style={{
+          height: 320,
+          overflow: "auto",
+          margin: 0,
+          padding: 8,
+          background: "#1e1e1e",
+          color: "#d4d4d4",
+          fontFamily: "Menlo, Consolas, monospace",
+          fontSize: 13,
+          lineHeight: `${LINE_HEIGHT}px`,
+          whiteSpace: "pre-wrap",
+          wordBreak: "break-all",
+          outline: "none",
+        }}
+      >
+        {text}
+      </pre>
+      {terminal.exited && (
+        <Typography.Text type={terminal.error ? "danger" : "secondary"}>
+          {terminal.error
+            ? `连接已断开: ${terminal.error}`
+            : terminal.exitStatus !== null
+              ? `会话已结束（退出码 ${terminal.exitStatus}）`
+              : "会话已关闭"}
+        </Typography.Text>
+      )}
+    </>
+  );
+};
+
+const TerminalPanel: React.FC = () => {
+  const terminals = useTerminalStore((s) => s.terminals);
+  const activeId = useTerminalStore((s) => s.activeId);
+  const setActive = useTerminalStore((s) => s.setActive);
+  const close = useTerminalStore((s) => s.close);
+
+  if (terminals.length === 0) return null;
+
+  return (
+    <Card title="终端" size="small" style={{ marginTop: 16 }}>
+      <Tabs
+        type="editable-card"
+        hideAdd
+        size="small"
+        activeKey={activeId ?? undefined}
+        onChange={setActive}
+        onEdit={(key, action) => {
+          if (action === "remove" && typeof key === "string") {
+            close(key).catch((e) => message.error(`关闭失败: ${e}`));
+          }
+        }}
+        items={terminals.map((t) => ({
+          key: t.id,
+          label: t.exited ? `${t.title}（已结束）` : t.title,
+          children: <TerminalView terminal={t} active={t.id === activeId} />,
+        }))}
+      />
+    </Card>
+  );
+};
+
+export default TerminalPanel;
@@ -5,6 +5,7 @@ import {
   FolderOpenOutlined,
   CloudServerOutlined,
   RetweetOutlined,
+  SwapOutlined,
   UploadOutlined,
   DownloadOutlined,
 } from "@ant-design/icons";
@@ -90,6 +91,9 @@ const TransferHistory: React.FC = () => {
 
   const displayHistory = history;
 
+  const hostName = (id?: number) =>
+    hosts.find((h) => h.id === id)?.name ?? `#${id}`;
+
   const hostOptions = useMemo(
     () => [
       { label: "全部", value: ALL_HOSTS },
@@ -107,11 +111,17 @@ const TransferHistory: React.FC = () => {
       dataIndex: "direction",
       key: "direction",
       width: 50,
-      render: (d: string) =>
+      render: (d: string, record) =>
         d === "upload" ? (
           <Tooltip title="上传">
             <UploadOutlined style={{ color: "#1890ff" }} />
           </Tooltip>
+        ) : d === "remote" ? (
+          <Tooltip
+            title={`${hostName(record.host_id)} → ${hostName(record.dest_host_id)}`}
+          >
+            <SwapOutlined style={{ color: "#722ed1" }} />
+          </Tooltip>
         ) : (
           <Tooltip title="下载">
             <DownloadOutlined style={{ color: "#52c41a" }} />
@@ -137,11 +147,18 @@ const TransferHistory: React.FC = () => {
       key: "local_path",
       width: 160,
       ellipsis: true,
-      render: (p: string) => (
-        <Tooltip title={p}>
-          <span style={{ fontSize: 12 }}>{p}</span>
-        </Tooltip>
-      ),
+      // 主机间传输的 local_path 是目标主机上的路径
+      render: (p: string, record) => {
+        const text =
+          record.direction === "remote"
+            ? `${hostName(record.dest_host_id)}: ${p}`
+            : p;
+        return (
+          <Tooltip title={text}>
+            <span style={{ fontSize: 12 }}>{text}</span>
+          </Tooltip>
+        );
+      },
     },
     {
       title: "远程路径",
@@ -189,14 +206,16 @@ const TransferHistory: React.FC = () => {
               />
             </Tooltip>
           )}
-          <Tooltip title="打开本地目录">
-            <Button
-              type="text"
-              size="small"
-              icon={<FolderOpenOutlined />}
-              onClick={() => handleNavigateLocal(record)}
-            />
-          </Tooltip>
+          {record.direction !== "remote" && (
+            <Tooltip title="打开本地目录">
+              <Button
+                type="text"
+                size="small"
+                icon={<FolderOpenOutlined />}
+                onClick={() => handleNavigateLocal(record)}
+              />
+            </Tooltip>
+          )}
           <Tooltip title="浏览远程目录">
             <Button
               type="text"
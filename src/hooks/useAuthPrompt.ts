@@ -0,0 +1,18 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useAuthPromptStore } from "../stores/authPromptStore";
+import type { AuthPromptRequest } from "../types";
+
+export function useAuthPromptListener() {
+  const enqueue = useAuthPromptStore((s) => s.enqueue);
+
+  useEffect(() => {
+    const unlisten = listen<AuthPromptRequest>("ssh-auth-prompt", (event) => {
+      enqueue(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [enqueue]);
+}
@@ -0,0 +1,18 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useRemoteExecStore } from "../stores/remoteExecStore";
+import type { RemoteExecOutput } from "../types";
+
+export function useRemoteExecListener() {
+  const appendOutput = useRemoteExecStore((s) => s.appendOutput);
+
+  useEffect(() => {
+    const unlisten = listen<RemoteExecOutput>("remote-exec-output", (event) => {
+      appendOutput(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [appendOutput]);
+}
@@ -0,0 +1,23 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useTerminalStore } from "../stores/terminalStore";
+import type { TerminalExitEvent, TerminalOutputEvent } from "../types";
+
+export function useTerminalListener() {
+  const appendOutput = useTerminalStore((s) => s.appendOutput);
+  const markExited = useTerminalStore((s) => s.markExited);
+
+  useEffect(() => {
+    const unlistenOutput = listen<TerminalOutputEvent>("terminal-output", (event) => {
+      appendOutput(event.payload);
+    });
+    const unlistenExit = listen<TerminalExitEvent>("terminal-exit", (event) => {
+      markExited(event.payload);
+    });
+
+    return () => {
+      unlistenOutput.then((fn) => fn());
+      unlistenExit.then((fn) => fn());
+    };
+  }, [appendOutput, markExited]);
+}
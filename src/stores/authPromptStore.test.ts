@@ -0,0 +1,58 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useAuthPromptStore } from "./authPromptStore";
+import type { AuthPromptRequest } from "../types";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+const request = (id: string): AuthPromptRequest => ({
+  request_id: id,
+  host: "bastion.example.com",
+  port: 22,
+  username: "ops",
+  instructions: "",
+  prompts: [{ text: "Verification code: ", echo: false }],
+});
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  useAuthPromptStore.setState({ queue: [] });
+});
+
+describe("authPromptStore", () => {
+  it("responds to prompts in arrival order", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+    useAuthPromptStore.getState().enqueue(request("a"));
+    useAuthPromptStore.getState().enqueue(request("b"));
+
+    await useAuthPromptStore.getState().respond(["123456"]);
+
+    expect(mockInvoke).toHaveBeenCalledWith("respond_auth_prompt", {
+      requestId: "a",
+      answers: ["123456"],
+    });
+    expect(useAuthPromptStore.getState().queue.map((r) => r.request_id)).toEqual(["b"]);
+  });
+
+  it("sends null answers on cancel", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+    useAuthPromptStore.getState().enqueue(request("a"));
+
+    await useAuthPromptStore.getState().cancel();
+
+    expect(mockInvoke).toHaveBeenCalledWith("respond_auth_prompt", {
+      requestId: "a",
+      answers: null,
+    });
+    expect(useAuthPromptStore.getState().queue).toEqual([]);
+  });
+
+  it("does nothing when no prompt is pending", async () => {
+    await useAuthPromptStore.getState().respond([]);
+    expect(mockInvoke).not.toHaveBeenCalled();
+  });
+});
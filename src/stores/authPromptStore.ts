@@ -0,0 +1,36 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { AuthPromptRequest } from "../types";
+
+interface AuthPromptState {
+  /** 同时连接多台主机时，提示按到达顺序逐个显示 */
+  queue: AuthPromptRequest[];
+
+  enqueue: (request: AuthPromptRequest) => void;
+  respond: (answers: string[]) => Promise<void>;
+  cancel: () => Promise<void>;
+}
+
+export const useAuthPromptStore = create<AuthPromptState>((set, get) => {
+  const reply = async (answers: string[] | null) => {
+    const [current, ...rest] = get().queue;
+    if (!current) return;
+    set({ queue: rest });
+    await invoke("respond_auth_prompt", {
+      requestId: current.request_id,
+      answers,
+    });
+  };
+
+  return {
+    queue: [],
+
+    enqueue: (request) => {
+      set((state) => ({ queue: [...state.queue, request] }));
+    },
+
+    respond: (answers) => reply(answers),
+
+    cancel: () => reply(null),
+  };
+});
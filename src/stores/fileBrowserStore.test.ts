@@ -175,4 +175,56 @@ describe("fileBrowserStore", () => {
       expect(mockInvoke).not.toHaveBeenCalled();
     });
   });
+
+  describe("copyRemoteItems / moveRemoteItems", () => {
+    it("调用批量复制命令并刷新远程列表", async () => {
+      useFileBrowserStore.setState({ connectedHostId: 1, remotePath: "/var" });
+      const results = [
+        { source: "/var/www", target: "/backup/www", status: "done" },
+        { source: "/var/index.html", status: "skipped" },
+      ];
+      mockInvoke.mockResolvedValueOnce(results);
+      mockInvoke.mockResolvedValueOnce(sampleRemoteFiles);
+
+      const returned = await useFileBrowserStore
+        .getState()
+        .copyRemoteItems(["/var/www", "/var/index.html"], "/backup", "skip");
+
+      expect(returned).toEqual(results);
+      expect(mockInvoke).toHaveBeenNthCalledWith(1, "copy_remote_items", {
+        hostId: 1,
+        paths: ["/var/www", "/var/index.html"],
+        destDir: "/backup",
+        conflictPolicy: "skip",
+      });
+      expect(mockInvoke).toHaveBeenNthCalledWith(2, "list_remote_dir", {
+        hostId: 1,
+        path: "/var",
+      });
+    });
+
+    it("调用批量移动命令", async () => {
+      useFileBrowserStore.setState({ connectedHostId: 2, remotePath: "/" });
+      mockInvoke.mockResolvedValueOnce([]);
+      mockInvoke.mockResolvedValueOnce([]);
+
+      await useFileBrowserStore
+        .getState()
+        .moveRemoteItems(["/a.txt"], "/archive", "rename");
+
+      expect(mockInvoke).toHaveBeenCalledWith("move_remote_items", {
+        hostId: 2,
+        paths: ["/a.txt"],
+        destDir: "/archive",
+        conflictPolicy: "rename",
+      });
+    });
+
+    it("未连接时报错且不请求", async () => {
+      await expect(
+        useFileBrowserStore.getState().copyRemoteItems(["/a"], "/b", "skip"),
+      ).rejects.toThrow("请先连接主机");
+      expect(mockInvoke).not.toHaveBeenCalled();
+    });
+  });
 });
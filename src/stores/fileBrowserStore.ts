@@ -1,6 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
-import type { FileEntry } from "../types";
+import type { ConflictPolicy, FileEntry, FileOpResult } from "../types";
 
 interface FileBrowserState {
   localPath: string;
@@ -23,8 +23,37 @@ interface FileBrowserState {
   setSelectedRemoteFiles: (paths: string[]) => void;
   refreshLocal: () => Promise<void>;
   refreshRemote: () => Promise<void>;
+  /** 在当前主机上把多个条目复制或移动到 destDir，完成后刷新远程列表 */
+  copyRemoteItems: (
+    paths: string[],
+    destDir: string,
+    conflictPolicy: ConflictPolicy,
+  ) => Promise<FileOpResult[]>;
+  moveRemoteItems: (
+    paths: string[],
+    destDir: string,
+    conflictPolicy: ConflictPolicy,
+  ) => Promise<FileOpResult[]>;
 }
 
+const runRemoteItems = async (
+  command: "copy_remote_items" | "move_remote_items",
+  hostId: number | null,
+  paths: string[],
+  destDir: string,
+  conflictPolicy: ConflictPolicy,
+) => {
+  if (hostId == null) {
+    throw new Error("请先连接主机");
+  }
+  return await invoke<FileOpResult[]>(command, {
+    hostId,
+    paths,
+    destDir,
+    conflictPolicy,
+  });
+};
+
 export const useFileBrowserStore = create<FileBrowserState>((set, get) => ({
   localPath: "",
   remotePath: "/",
@@ -89,4 +118,28 @@ export const useFileBrowserStore = create<FileBrowserState>((set, get) => ({
       await get().fetchRemoteFiles(connectedHostId, remotePath);
     }
   },
+
+  copyRemoteItems: async (paths, destDir, conflictPolicy) => {
+    const results = await runRemoteItems(
+      "copy_remote_items",
+      get().connectedHostId,
+      paths,
+      destDir,
+      conflictPolicy,
+    );
+    await get().refreshRemote();
+    return results;
+  },
+
+  moveRemoteItems: async (paths, destDir, conflictPolicy) => {
+    const results = await runRemoteItems(
+      "move_remote_items",
+      get().connectedHostId,
+      paths,
+      destDir,
+      conflictPolicy,
+    );
+    await get().refreshRemote();
+    return results;
+  },
 }));
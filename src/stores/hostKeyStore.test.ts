@@ -0,0 +1,53 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useHostKeyStore } from "./hostKeyStore";
+import type { KnownHostKey } from "../types";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+const sampleKey: KnownHostKey = {
+  id: 1,
+  host: "10.0.0.5",
+  port: 22,
+  key_type: "ssh-ed25519",
+  fingerprint: "SHA256:ZkAslGjFiUHdGf/WUL8rQvkib4PTvQatUV0OUQSncCA",
+  key: "AAAAC3NzaC1lZDI1NTE5AAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f",
+};
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  useHostKeyStore.setState({ knownKeys: [], loading: false });
+});
+
+describe("hostKeyStore", () => {
+  it("fetches known keys", async () => {
+    mockInvoke.mockResolvedValueOnce([sampleKey]);
+    await useHostKeyStore.getState().fetchKnownKeys();
+    expect(mockInvoke).toHaveBeenCalledWith("get_known_host_keys");
+    expect(useHostKeyStore.getState().knownKeys).toEqual([sampleKey]);
+    expect(useHostKeyStore.getState().loading).toBe(false);
+  });
+
+  it("trusts a key without replacing by default", async () => {
+    mockInvoke.mockResolvedValueOnce(sampleKey).mockResolvedValueOnce([sampleKey]);
+    await useHostKeyStore.getState().trustHostKey(3, sampleKey.key);
+    expect(mockInvoke).toHaveBeenCalledWith("trust_host_key", {
+      hostId: 3,
+      key: sampleKey.key,
+      replace: false,
+    });
+    expect(useHostKeyStore.getState().knownKeys).toEqual([sampleKey]);
+  });
+
+  it("removes a key and refreshes", async () => {
+    useHostKeyStore.setState({ knownKeys: [sampleKey] });
+    mockInvoke.mockResolvedValueOnce(undefined).mockResolvedValueOnce([]);
+    await useHostKeyStore.getState().removeKnownKey(1);
+    expect(mockInvoke).toHaveBeenCalledWith("remove_known_host_key", { id: 1 });
+    expect(useHostKeyStore.getState().knownKeys).toEqual([]);
+  });
+});
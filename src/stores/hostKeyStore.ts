@@ -0,0 +1,47 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { HostKeyInfo, KnownHostKey } from "../types";
+
+interface HostKeyState {
+  knownKeys: KnownHostKey[];
+  loading: boolean;
+  fetchKnownKeys: () => Promise<void>;
+  fetchHostKey: (hostId: number) => Promise<HostKeyInfo>;
+  /** replace 为 true 时覆盖该地址已保存的旧密钥（服务器更换密钥后使用） */
+  trustHostKey: (hostId: number, key: string, replace?: boolean) => Promise<KnownHostKey>;
+  removeKnownKey: (id: number) => Promise<void>;
+}
+
+export const useHostKeyStore = create<HostKeyState>((set, get) => ({
+  knownKeys: [],
+  loading: false,
+
+  fetchKnownKeys: async () => {
+    set({ loading: true });
+    try {
+      const knownKeys = await invoke<KnownHostKey[]>("get_known_host_keys");
+      set({ knownKeys });
+    } finally {
+      set({ loading: false });
+    }
+  },
+
+  fetchHostKey: async (hostId: number) => {
+    return invoke<HostKeyInfo>("fetch_host_key", { hostId });
+  },
+
+  trustHostKey: async (hostId: number, key: string, replace = false) => {
+    const trusted = await invoke<KnownHostKey>("trust_host_key", {
+      hostId,
+      key,
+      replace,
+    });
+    await get().fetchKnownKeys();
+    return trusted;
+  },
+
+  removeKnownKey: async (id: number) => {
+    await invoke("remove_known_host_key", { id });
+    await get().fetchKnownKeys();
+  },
+}));
@@ -167,4 +167,44 @@ describe("hostStore", () => {
       ).rejects.toBe("Connection refused");
     });
   });
+
+  describe("importHosts", () => {
+    it("预览默认读取 ~/.ssh/config", async () => {
+      mockInvoke.mockResolvedValueOnce([]);
+
+      await useHostStore.getState().previewSshConfigImport();
+
+      expect(mockInvoke).toHaveBeenCalledWith("preview_ssh_config_import", {
+        path: null,
+      });
+    });
+
+    it("导入选中的记录并刷新列表", async () => {
+      const entry = {
+        source: "web",
+        host: { ...sampleHost, id: undefined },
+        warnings: [],
+      };
+      const report = [{ source: "web", status: "imported", host_id: 1 }];
+      mockInvoke
+        .mockResolvedValueOnce(report) // import_hosts
+        .mockResolvedValueOnce([sampleHost]); // get_hosts
+
+      const results = await useHostStore.getState().importHosts([entry]);
+
+      expect(mockInvoke).toHaveBeenCalledWith("import_hosts", { entries: [entry] });
+      expect(results).toEqual(report);
+      expect(useHostStore.getState().hosts).toHaveLength(1);
+    });
+
+    it("预览站点管理器文件", async () => {
+      mockInvoke.mockResolvedValueOnce([]);
+
+      await useHostStore.getState().previewSiteImport("/tmp/sitemanager.xml");
+
+      expect(mockInvoke).toHaveBeenCalledWith("preview_site_import", {
+        path: "/tmp/sitemanager.xml",
+      });
+    });
+  });
 });
@@ -1,6 +1,6 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
-import type { Host } from "../types";
+import type { Host, HostImportEntry, HostImportResult } from "../types";
 
 interface HostState {
   hosts: Host[];
@@ -13,6 +13,11 @@ interface HostState {
   setCurrentHost: (host: Host | null) => void;
   testConnection: (host: Host) => Promise<void>;
   testConnectionById: (hostId: number) => Promise<void>;
+  /** 解析 OpenSSH 配置生成导入预览，path 为空时读取 ~/.ssh/config */
+  previewSshConfigImport: (path?: string) => Promise<HostImportEntry[]>;
+  /** 解析 FileZilla 的 sitemanager.xml 或 WinSCP 导出的 ini */
+  previewSiteImport: (path: string) => Promise<HostImportEntry[]>;
+  importHosts: (entries: HostImportEntry[]) => Promise<HostImportResult[]>;
 }
 
 export const useHostStore = create<HostState>((set, get) => ({
@@ -62,4 +67,20 @@ export const useHostStore = create<HostState>((set, get) => ({
   testConnectionById: async (hostId: number) => {
     await invoke("test_connection_by_id", { hostId });
   },
+
+  previewSshConfigImport: async (path?: string) => {
+    return invoke<HostImportEntry[]>("preview_ssh_config_import", {
+      path: path ?? null,
+    });
+  },
+
+  previewSiteImport: async (path: string) => {
+    return invoke<HostImportEntry[]>("preview_site_import", { path });
+  },
+
+  importHosts: async (entries: HostImportEntry[]) => {
+    const results = await invoke<HostImportResult[]>("import_hosts", { entries });
+    await get().fetchHosts();
+    return results;
+  },
 }));
@@ -0,0 +1,75 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { usePortForwardStore } from "./portForwardStore";
+import type { PortForward, PortForwardStatus } from "../types";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+const forward: PortForward = {
+  id: 1,
+  host_id: 2,
+  name: "postgres",
+  kind: "local",
+  bind_host: "127.0.0.1",
+  bind_port: 15432,
+  target_host: "db.internal",
+  target_port: 5432,
+};
+
+const status: PortForwardStatus = {
+  forward_id: 1,
+  running: true,
+  bound_port: 15432,
+  active_connections: 0,
+  bytes_sent: 0,
+  bytes_received: 0,
+  error: null,
+};
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  usePortForwardStore.setState({ forwards: [], statuses: {}, loading: false });
+});
+
+describe("portForwardStore", () => {
+  it("fetches forwards for a host", async () => {
+    mockInvoke.mockResolvedValue([forward]);
+
+    await usePortForwardStore.getState().fetchForwards(2);
+
+    expect(mockInvoke).toHaveBeenCalledWith("get_port_forwards", { hostId: 2 });
+    expect(usePortForwardStore.getState().forwards).toEqual([forward]);
+    expect(usePortForwardStore.getState().loading).toBe(false);
+  });
+
+  it("creates new forwards and updates existing ones", async () => {
+    mockInvoke.mockResolvedValue([]);
+    const { id: _, ...draft } = forward;
+
+    await usePortForwardStore.getState().saveForward(draft);
+    await usePortForwardStore.getState().saveForward(forward);
+
+    expect(mockInvoke).toHaveBeenCalledWith("create_port_forward", { forward: draft });
+    expect(mockInvoke).toHaveBeenCalledWith("update_port_forward", { forward });
+    expect(mockInvoke).toHaveBeenCalledWith("get_port_forwards", { hostId: 2 });
+  });
+
+  it("tracks status after starting and stopping", async () => {
+    mockInvoke.mockResolvedValueOnce(status);
+    await usePortForwardStore.getState().startForward(1);
+    expect(usePortForwardStore.getState().statuses[1]).toEqual(status);
+
+    mockInvoke
+      .mockResolvedValueOnce(undefined)
+      .mockResolvedValueOnce([{ ...status, running: false, bytes_sent: 42 }]);
+    await usePortForwardStore.getState().stopForward(forward);
+
+    expect(mockInvoke).toHaveBeenCalledWith("stop_port_forward", { id: 1 });
+    expect(usePortForwardStore.getState().statuses[1].running).toBe(false);
+    expect(usePortForwardStore.getState().statuses[1].bytes_sent).toBe(42);
+  });
+});
@@ -0,0 +1,61 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { PortForward, PortForwardStatus } from "../types";
+
+interface PortForwardState {
+  forwards: PortForward[];
+  /** 按 forward_id 索引，未启动过的转发没有条目 */
+  statuses: Record<number, PortForwardStatus>;
+  loading: boolean;
+  fetchForwards: (hostId: number) => Promise<void>;
+  fetchStatuses: (hostId: number) => Promise<void>;
+  saveForward: (forward: PortForward) => Promise<void>;
+  deleteForward: (forward: PortForward) => Promise<void>;
+  startForward: (id: number) => Promise<void>;
+  stopForward: (forward: PortForward) => Promise<void>;
+}
+
+export const usePortForwardStore = create<PortForwardState>((set, get) => ({
+  forwards: [],
+  statuses: {},
+  loading: false,
+
+  fetchForwards: async (hostId) => {
+    set({ loading: true });
+    try {
+      const forwards = await invoke<PortForward[]>("get_port_forwards", { hostId });
+      set({ forwards });
+    } finally {
+      set({ loading: false });
+    }
+  },
+
+  fetchStatuses: async (hostId) => {
+    const list = await invoke<PortForwardStatus[]>("get_port_forward_statuses", { hostId });
+    set({ statuses: Object.fromEntries(list.map((s) => [s.forward_id, s])) });
+  },
+
+  saveForward: async (forward) => {
+    if (forward.id) {
+      await invoke("update_port_forward", { forward });
+    } else {
+      await invoke<PortForward>("create_port_forward", { forward });
+    }
+    await get().fetchForwards(forward.host_id);
+  },
+
+  deleteForward: async (forward) => {
+    await invoke("delete_port_forward", { id: forward.id });
+    await get().fetchForwards(forward.host_id);
+  },
+
+  startForward: async (id) => {
+    const status = await invoke<PortForwardStatus>("start_port_forward", { id });
+    set((state) => ({ statuses: { ...state.statuses, [id]: status } }));
+  },
+
+  stopForward: async (forward) => {
+    await invoke("stop_port_forward", { id: forward.id });
+    await get().fetchStatuses(forward.host_id);
+  },
+}));
@@ -0,0 +1,48 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useProxyStore } from "./proxyStore";
+import type { ProxyConfig } from "../types";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+const sampleProxy: ProxyConfig = {
+  proxy_type: "socks5",
+  host: "127.0.0.1",
+  port: 1080,
+  username: "alice",
+};
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  useProxyStore.setState({ globalProxy: null });
+});
+
+describe("proxyStore", () => {
+  it("fetches the global proxy", async () => {
+    mockInvoke.mockResolvedValueOnce(sampleProxy);
+    await useProxyStore.getState().fetchGlobalProxy();
+    expect(mockInvoke).toHaveBeenCalledWith("get_global_proxy");
+    expect(useProxyStore.getState().globalProxy).toEqual(sampleProxy);
+  });
+
+  it("clears the global proxy and refreshes", async () => {
+    useProxyStore.setState({ globalProxy: sampleProxy });
+    mockInvoke.mockResolvedValueOnce(undefined).mockResolvedValueOnce(null);
+    await useProxyStore.getState().saveGlobalProxy(null);
+    expect(mockInvoke).toHaveBeenCalledWith("set_global_proxy", { proxy: null });
+    expect(useProxyStore.getState().globalProxy).toBeNull();
+  });
+
+  it("saves a host proxy", async () => {
+    mockInvoke.mockResolvedValueOnce(undefined);
+    await useProxyStore.getState().saveHostProxy(4, sampleProxy);
+    expect(mockInvoke).toHaveBeenCalledWith("set_host_proxy", {
+      hostId: 4,
+      proxy: sampleProxy,
+    });
+  });
+});
@@ -0,0 +1,34 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { ProxyConfig } from "../types";
+
+interface ProxyState {
+  globalProxy: ProxyConfig | null;
+  fetchGlobalProxy: () => Promise<void>;
+  /** 传入 null 时清除全局代理 */
+  saveGlobalProxy: (proxy: ProxyConfig | null) => Promise<void>;
+  fetchHostProxy: (hostId: number) => Promise<ProxyConfig | null>;
+  saveHostProxy: (hostId: number, proxy: ProxyConfig | null) => Promise<void>;
+}
+
+export const useProxyStore = create<ProxyState>((set, get) => ({
+  globalProxy: null,
+
+  fetchGlobalProxy: async () => {
+    const globalProxy = await invoke<ProxyConfig | null>("get_global_proxy");
+    set({ globalProxy });
+  },
+
+  saveGlobalProxy: async (proxy: ProxyConfig | null) => {
+    await invoke("set_global_proxy", { proxy });
+    await get().fetchGlobalProxy();
+  },
+
+  fetchHostProxy: async (hostId: number) => {
+    return invoke<ProxyConfig | null>("get_host_proxy", { hostId });
+  },
+
+  saveHostProxy: async (hostId: number, proxy: ProxyConfig | null) => {
+    await invoke("set_host_proxy", { hostId, proxy });
+  },
+}));
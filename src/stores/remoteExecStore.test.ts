@@ -0,0 +1,57 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useRemoteExecStore } from "./remoteExecStore";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  useRemoteExecStore.getState().clear();
+});
+
+describe("remoteExecStore", () => {
+  it("runs a command and records the exit code", async () => {
+    mockInvoke.mockResolvedValue(2);
+
+    await useRemoteExecStore.getState().run(1, "ls -la", "/var/www", 30);
+
+    const execId = useRemoteExecStore.getState().execId;
+    expect(mockInvoke).toHaveBeenCalledWith("run_remote_command", {
+      hostId: 1,
+      execId,
+      command: "ls -la",
+      workingDir: "/var/www",
+      timeoutSecs: 30,
+    });
+    expect(useRemoteExecStore.getState().exitCode).toBe(2);
+    expect(useRemoteExecStore.getState().running).toBe(false);
+  });
+
+  it("records errors such as timeouts", async () => {
+    mockInvoke.mockRejectedValue("命令执行超时（30 秒）");
+
+    await useRemoteExecStore.getState().run(1, "sleep 60");
+
+    expect(useRemoteExecStore.getState().error).toBe("命令执行超时（30 秒）");
+    expect(useRemoteExecStore.getState().exitCode).toBeNull();
+  });
+
+  it("merges output of the current command by stream", () => {
+    useRemoteExecStore.setState({ execId: "e1" });
+    const { appendOutput } = useRemoteExecStore.getState();
+
+    appendOutput({ exec_id: "e1", stream: "stdout", data: "a" });
+    appendOutput({ exec_id: "e1", stream: "stdout", data: "b" });
+    appendOutput({ exec_id: "e1", stream: "stderr", data: "oops" });
+    appendOutput({ exec_id: "other", stream: "stdout", data: "ignored" });
+
+    expect(useRemoteExecStore.getState().output).toEqual([
+      { stream: "stdout", data: "ab" },
+      { stream: "stderr", data: "oops" },
+    ]);
+  });
+});
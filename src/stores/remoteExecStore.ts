@@ -0,0 +1,72 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { RemoteExecOutput, RemoteExecStream } from "../types";
+
+export interface RemoteExecChunk {
+  stream: RemoteExecStream;
+  data: string;
+}
+
+interface RemoteExecState {
+  /** 当前命令的 exec_id，用于过滤 `remote-exec-output` 事件 */
+  execId: string | null;
+  running: boolean;
+  output: RemoteExecChunk[];
+  exitCode: number | null;
+  error: string | null;
+
+  run: (
+    hostId: number,
+    command: string,
+    workingDir?: string,
+    timeoutSecs?: number,
+  ) => Promise<void>;
+  appendOutput: (payload: RemoteExecOutput) => void;
+  clear: () => void;
+}
+
+export const useRemoteExecStore = create<RemoteExecState>((set, get) => ({
+  execId: null,
+  running: false,
+  output: [],
+  exitCode: null,
+  error: null,
+
+  run: async (hostId, command, workingDir, timeoutSecs) => {
+    const execId = crypto.randomUUID();
+    set({ execId, running: true, output: [], exitCode: null, error: null });
+    try {
+      const exitCode = await invoke<number>("run_remote_command", {
+        hostId,
+        execId,
+        command,
+        workingDir: workingDir || null,
+        timeoutSecs: timeoutSecs ?? null,
+      });
+      if (get().execId === execId) set({ exitCode });
+    } catch (e) {
+      if (get().execId === execId) set({ error: String(e) });
+    } finally {
+      if (get().execId === execId) set({ running: false });
+    }
+  },
+
+  appendOutput: (payload) => {
+    if (payload.exec_id !== get().execId) return;
+    set((state) => {
+      const last = state.output[state.output.length - 1];
+      // 同一流的连续输出合并为一段，减少渲染的节点数
+      if (last && last.stream === payload.stream) {
+        const merged = { ...last, data: last.data + payload.data };
+        return { output: [...state.output.slice(0, -1), merged] };
+      }
+      return {
+        output: [...state.output, { stream: payload.stream, data: payload.data }],
+      };
+    });
+  },
+
+  clear: () => {
+    set({ execId: null, running: false, output: [], exitCode: null, error: null });
+  },
+}));
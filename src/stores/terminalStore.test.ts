@@ -0,0 +1,66 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useTerminalStore, MAX_TERMINAL_BUFFER } from "./terminalStore";
+
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from "@tauri-apps/api/core";
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => {
+  vi.clearAllMocks();
+  useTerminalStore.setState({ terminals: [], activeId: null });
+});
+
+describe("terminalStore", () => {
+  it("opens several terminals for one host", async () => {
+    mockInvoke.mockResolvedValueOnce("t1").mockResolvedValueOnce("t2");
+
+    await useTerminalStore.getState().open(1, "web", 80, 24);
+    await useTerminalStore.getState().open(1, "web", 80, 24);
+
+    expect(mockInvoke).toHaveBeenCalledWith("open_terminal", { hostId: 1, cols: 80, rows: 24 });
+    const { terminals, activeId } = useTerminalStore.getState();
+    expect(terminals.map((t) => t.title)).toEqual(["web", "web (2)"]);
+    expect(activeId).toBe("t2");
+  });
+
+  it("appends output per terminal and caps the buffer", async () => {
+    mockInvoke.mockResolvedValueOnce("t1");
+    await useTerminalStore.getState().open(1, "web", 80, 24);
+    const { appendOutput } = useTerminalStore.getState();
+
+    appendOutput({ terminal_id: "t1", data: "$ " });
+    appendOutput({ terminal_id: "other", data: "ignored" });
+    expect(useTerminalStore.getState().terminals[0].output).toBe("$ ");
+
+    appendOutput({ terminal_id: "t1", data: "x".repeat(MAX_TERMINAL_BUFFER) });
+    expect(useTerminalStore.getState().terminals[0].output).toHaveLength(MAX_TERMINAL_BUFFER);
+  });
+
+  it("sends keystrokes until the shell exits", async () => {
+    mockInvoke.mockResolvedValue("t1");
+    await useTerminalStore.getState().open(1, "web", 80, 24);
+
+    await useTerminalStore.getState().write("t1", "ls\r");
+    expect(mockInvoke).toHaveBeenCalledWith("write_terminal", { terminalId: "t1", data: "ls\r" });
+
+    useTerminalStore.getState().markExited({ terminal_id: "t1", exit_status: 0, error: null });
+    mockInvoke.mockClear();
+    await useTerminalStore.getState().write("t1", "ls\r");
+    await useTerminalStore.getState().close("t1");
+    expect(mockInvoke).not.toHaveBeenCalled();
+    expect(useTerminalStore.getState().terminals).toEqual([]);
+  });
+
+  it("closes a running terminal on the backend", async () => {
+    mockInvoke.mockResolvedValue("t1");
+    await useTerminalStore.getState().open(1, "web", 80, 24);
+
+    await useTerminalStore.getState().close("t1");
+
+    expect(mockInvoke).toHaveBeenCalledWith("close_terminal", { terminalId: "t1" });
+    expect(useTerminalStore.getState().activeId).toBeNull();
+  });
+});
@@ -0,0 +1,105 @@
+import { create } from "zustand";
+import { invoke } from "@tauri-apps/api/core";
+import type { TerminalExitEvent, TerminalOutputEvent } from "../types";
+
+/** 每个终端保留的原始输出上限（字符数），超出时丢弃最早的部分 */
+export const MAX_TERMINAL_BUFFER = 200_000;
+
+export interface TerminalSession {
+  id: string;
+  hostId: number;
+  title: string;
+  output: string;
+  exited: boolean;
+  exitStatus: number | null;
+  error: string | null;
+}
+
+interface TerminalState {
+  terminals: TerminalSession[];
+  activeId: string | null;
+
+  open: (hostId: number, title: string, cols: number, rows: number) => Promise<void>;
+  write: (id: string, data: string) => Promise<void>;
+  resize: (id: string, cols: number, rows: number) => Promise<void>;
+  /** 关闭并移除标签；已退出的终端只移除 */
+  close: (id: string) => Promise<void>;
+  setActive: (id: string) => void;
+  appendOutput: (event: TerminalOutputEvent) => void;
+  markExited: (event: TerminalExitEvent) => void;
+}
+
+export const useTerminalStore = create<TerminalState>((set, get) => {
+  const update = (id: string, patch: (t: TerminalSession) => Partial<TerminalSession>) => {
+    set((state) => ({
+      terminals: state.terminals.map((t) => (t.id === id ? { ...t, ...patch(t) } : t)),
+    }));
+  };
+
+  return {
+    terminals: [],
+    activeId: null,
+
+    open: async (hostId, title, cols, rows) => {
+      const id = await invoke<string>("open_terminal", { hostId, cols, rows });
+      set((state) => {
+        // 同一主机的多个终端按序号区分
+        const count = state.terminals.filter((t) => t.hostId === hostId).length;
+        const session: TerminalSession = {
+          id,
+          hostId,
+          title: count > 0 ? `${title} (${count + 1})` : title,
+          output: "",
+          exited: false,
+          exitStatus: null,
+          error: null,
+        };
+        return { terminals: [...state.terminals, session], activeId: id };
+      });
+    },
+
+    write: async (id, data) => {
+      const terminal = get().terminals.find((t) => t.id === id);
+      if (!terminal || terminal.exited) return;
+      await invoke("write_terminal", { terminalId: id, data });
+    },
+
+    resize: async (id, cols, rows) => {
+      const terminal = get().terminals.find((t) => t.id === id);
+      if (!terminal || terminal.exited) return;
+      await invoke("resize_terminal", { terminalId: id, cols, rows });
+    },
+
+    close: async (id) => {
+      const terminal = get().terminals.find((t) => t.id === id);
+      if (!terminal) return;
+      set((state) => {
+        const terminals = state.terminals.filter((t) => t.id !== id);
+        const activeId =
+          state.activeId === id ? (terminals[terminals.length - 1]?.id ?? null) : state.activeId;
+        return { terminals, activeId };
+      });
+      if (!terminal.exited) {
+        await invoke("close_terminal", { terminalId: id });
+      }
+    },
+
+    setActive: (id) => {
+      set({ activeId: id });
+    },
+
+    appendOutput: (event) => {
+      update(event.terminal_id, (t) => ({
+        output: (t.output + event.data).slice(-MAX_TERMINAL_BUFFER),
+      }));
+    },
+
+    markExited: (event) => {
+      update(event.terminal_id, () => ({
+        exited: true,
+        exitStatus: event.exit_status,
+        error: event.error,
+      }));
+    },
+  };
+});
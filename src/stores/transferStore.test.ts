@@ -122,6 +122,28 @@ describe("transferStore", () => {
     });
   });
 
+    it("按主机清空时一并移除以该主机为目标的主机间传输", async () => {
+      const remoteHistory: TransferHistoryItem = {
+        id: 4,
+        host_id: 2,
+        dest_host_id: 1,
+        filename: "site.tar",
+        remote_path: "/srv/site.tar",
+        local_path: "/backup/site.tar",
+        direction: "remote",
+        file_size: 800,
+        transferred_size: 800,
+        status: "success",
+      };
+      useTransferStore.setState({ history: [...sampleHistory, remoteHistory] });
+      mockInvoke.mockResolvedValueOnce(undefined);
+
+      await useTransferStore.getState().clearHistory(1);
+
+      expect(useTransferStore.getState().history).toEqual([]);
+    });
+  });
+
   describe("updateProgress", () => {
     it("新增进度条目", () => {
       useTransferStore.getState().updateProgress(sampleProgress);
@@ -201,6 +223,26 @@ describe("transferStore", () => {
     });
   });
 
+  describe("startRemoteTransfer", () => {
+    it("调用 start_remote_transfer 命令", async () => {
+      mockInvoke.mockResolvedValueOnce("tid-remote");
+
+      const id = await useTransferStore
+        .getState()
+        .startRemoteTransfer(1, "/srv/a.tar", 2, "/backup/a.tar", "a.tar", 800);
+
+      expect(id).toBe("tid-remote");
+      expect(mockInvoke).toHaveBeenCalledWith("start_remote_transfer", {
+        sourceHostId: 1,
+        sourcePath: "/srv/a.tar",
+        destHostId: 2,
+        destPath: "/backup/a.tar",
+        filename: "a.tar",
+        fileSize: 800,
+      });
+    });
+  });
+
   describe("cancelTransfer", () => {
     it("调用 cancel_transfer 命令", async () => {
       mockInvoke.mockResolvedValueOnce(undefined);
@@ -1,6 +1,10 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
-import type { TransferHistoryItem, TransferProgress } from "../types";
+import type {
+  SymlinkPolicy,
+  TransferHistoryItem,
+  TransferProgress,
+} from "../types";
 
 interface TransferState {
   history: TransferHistoryItem[];
@@ -24,15 +28,25 @@ interface TransferState {
     filename: string,
     fileSize: number,
   ) => Promise<string>;
+  startRemoteTransfer: (
+    sourceHostId: number,
+    sourcePath: string,
+    destHostId: number,
+    destPath: string,
+    filename: string,
+    fileSize: number,
+  ) => Promise<string>;
   startDirectoryUpload: (
     hostId: number,
     localDir: string,
     remoteDir: string,
+    symlinkPolicy?: SymlinkPolicy,
   ) => Promise<string[]>;
   startDirectoryDownload: (
     hostId: number,
     remoteDir: string,
     localDir: string,
+    symlinkPolicy?: SymlinkPolicy,
   ) => Promise<string[]>;
   cancelTransfer: (transferId: string) => Promise<void>;
   retryTransfer: (historyId: number) => Promise<string>;
@@ -60,7 +74,9 @@ export const useTransferStore = create<TransferState>((set, _get) => ({
     if (hostId != null) {
       await invoke("clear_transfer_history_by_host", { hostId });
       set((s) => ({
-        history: s.history.filter((h) => h.host_id !== hostId),
+        history: s.history.filter(
+          (h) => h.host_id !== hostId && h.dest_host_id !== hostId,
+        ),
       }));
     } else {
       await invoke("clear_transfer_history");
@@ -111,19 +127,44 @@ export const useTransferStore = create<TransferState>((set, _get) => ({
     });
   },
 
-  startDirectoryUpload: async (hostId, localDir, remoteDir) => {
+  startRemoteTransfer: async (
+    sourceHostId,
+    sourcePath,
+    destHostId,
+    destPath,
+    filename,
+    fileSize,
+  ) => {
+    return await invoke<string>("start_remote_transfer", {
+      sourceHostId,
+      sourcePath,
+      destHostId,
+      destPath,
+      filename,
+      fileSize,
+    });
+  },
+
+  startDirectoryUpload: async (hostId, localDir, remoteDir, symlinkPolicy) => {
     return await invoke<string[]>("start_directory_upload", {
       hostId,
       localDir,
       remoteDir,
+      symlinkPolicy,
     });
   },
 
-  startDirectoryDownload: async (hostId, remoteDir, localDir) => {
+  startDirectoryDownload: async (
+    hostId,
+    remoteDir,
+    localDir,
+    symlinkPolicy,
+  ) => {
     return await invoke<string[]>("start_directory_download", {
       hostId,
       remoteDir,
       localDir,
+      symlinkPolicy,
     });
   },
 
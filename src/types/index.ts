@@ -1,4 +1,27 @@
-export type Protocol = "ftp" | "sftp";
+export type Protocol = "ftp" | "ftps" | "sftp" | "webdav" | "webdavs" | "s3" | "local";
+
+export type FtpsMode = "explicit" | "implicit";
+
+export type FtpDataMode = "passive" | "epsv" | "active";
+
+export type SshAuthMethod = "key" | "agent" | "password" | "keyboard-interactive";
+
+/** auto 优先 SFTP，服务器没有 SFTP 子系统时改用 SCP */
+export type SshTransferMode = "auto" | "sftp" | "scp";
+
+export type ProxyType = "socks5" | "http";
+
+/** global 跟随全局代理，direct 直连，custom 使用主机单独的代理 */
+export type ProxyMode = "global" | "direct" | "custom";
+
+export interface ProxyConfig {
+  proxy_type: ProxyType;
+  host: string;
+  port: number;
+  username?: string | null;
+  /** 读取时不返回；保存时留空表示沿用已保存的密码 */
+  password?: string | null;
+}
 
 export interface Host {
   id?: number;
@@ -9,11 +32,154 @@ export interface Host {
   username: string;
   password?: string;
   key_path?: string;
+  ftps_mode?: FtpsMode;
+  /** 服务器时区相对 UTC 的偏移（分钟），用于换算 FTP 列表时间 */
+  timezone_offset?: number;
+  ftp_data_mode?: FtpDataMode;
+  /** 主动模式通告的地址，为空时使用本机地址 */
+  ftp_active_address?: string;
+  ftp_active_port_min?: number;
+  ftp_active_port_max?: number;
+  /** 被动模式忽略 PASV 应答中的服务器地址 */
+  ftp_ignore_pasv_address?: boolean;
+  /** SFTP 认证方式的尝试顺序，为空时按是否填写密钥/密码自动选择 */
+  ssh_auth_order?: SshAuthMethod[];
+  /** SFTP 跳板机，引用另一台已保存的 SFTP 主机 */
+  jump_host_id?: number | null;
+  ssh_transfer_mode?: SshTransferMode;
+  proxy_mode?: ProxyMode;
+  /** S3 区域，为空时使用 us-east-1 */
+  s3_region?: string | null;
+  s3_bucket?: string | null;
+  /** 使用 HTTP 而非 HTTPS 访问端点，用于内网的 MinIO 等服务 */
+  s3_use_http?: boolean;
   created_at?: string;
   updated_at?: string;
 }
 
-export type TransferDirection = "upload" | "download";
+/** 导入预览中的一条记录，勾选后原样提交给 import_hosts */
+export interface HostImportEntry {
+  /** 来源中的名称，如 ssh_config 的 Host 别名、带文件夹的站点路径 */
+  source: string;
+  host: Host;
+  /** 跳板机是同一批导入中的另一条记录（按 source 引用） */
+  jump_source?: string | null;
+  /** 站点的默认目录，导入后保存为目录书签 */
+  remote_dir?: string | null;
+  local_dir?: string | null;
+  /** 与已保存主机重复时为该主机的 id */
+  duplicate_of?: number | null;
+  /** 来源使用了不支持的协议或登录方式 */
+  unsupported?: string | null;
+  /** 校验未通过的原因，有错误的记录不能导入 */
+  error?: string | null;
+  warnings: string[];
+}
+
+export type HostImportStatus = "imported" | "skipped" | "unsupported";
+
+export interface HostImportResult {
+  source: string;
+  status: HostImportStatus;
+  host_id?: number | null;
+  message?: string | null;
+}
+
+export interface CertificateInfo {
+  fingerprint: string;
+  subject: string;
+  certificate: string;
+}
+
+export interface AuthPrompt {
+  text: string;
+  echo: boolean;
+}
+
+export interface AuthPromptRequest {
+  request_id: string;
+  host: string;
+  port: number;
+  username: string;
+  instructions: string;
+  prompts: AuthPrompt[];
+}
+
+export type RemoteExecStream = "stdout" | "stderr";
+
+export interface RemoteExecOutput {
+  exec_id: string;
+  stream: RemoteExecStream;
+  data: string;
+}
+
+export interface TerminalOutputEvent {
+  terminal_id: string;
+  data: string;
+}
+
+/** exit_status 为 null 表示被关闭或连接中断 */
+export interface TerminalExitEvent {
+  terminal_id: string;
+  exit_status: number | null;
+  error: string | null;
+}
+
+/** local：本机监听经服务器连接目标（ssh -L）；remote：服务器监听连回本机一侧（ssh -R） */
+export type ForwardKind = "local" | "remote";
+
+export interface PortForward {
+  id?: number;
+  host_id: number;
+  name: string;
+  kind: ForwardKind;
+  bind_host: string;
+  /** 远程转发可为 0，由服务器分配 */
+  bind_port: number;
+  target_host: string;
+  target_port: number;
+  created_at?: string;
+}
+
+export interface PortForwardStatus {
+  forward_id: number;
+  running: boolean;
+  bound_port: number | null;
+  active_connections: number;
+  bytes_sent: number;
+  bytes_received: number;
+  error: string | null;
+}
+
+export interface TrustedCertificate {
+  id?: number;
+  host_id: number;
+  fingerprint: string;
+  subject: string;
+  certificate: string;
+  trusted_at?: string;
+}
+
+export interface HostKeyInfo {
+  host: string;
+  port: number;
+  key_type: string;
+  /** OpenSSH 格式，如 SHA256:xxxx */
+  fingerprint: string;
+  key: string;
+}
+
+export interface KnownHostKey {
+  id?: number;
+  host: string;
+  port: number;
+  key_type: string;
+  fingerprint: string;
+  key: string;
+  trusted_at?: string;
+}
+
+export type TransferDirection = "upload" | "download" | "remote";
 
 export type TransferStatus =
   | "pending"
@@ -35,6 +201,8 @@ export interface TransferHistoryItem {
   error_message?: string;
   started_at?: string;
   finished_at?: string;
+  /** 主机间传输的目标主机，此时 remote_path 为源路径、local_path 为目标路径 */
+  dest_host_id?: number;
 }
 
 export interface DirectoryBookmark {
@@ -51,9 +219,20 @@ export interface FileEntry {
   path: string;
   is_dir: boolean;
   size: number;
+  /** UTC 时间，RFC 3339 格式 */
   modified?: string;
+  permissions?: string;
+  is_symlink?: boolean;
+  link_target?: string;
+  mode?: number;
+  uid?: number;
+  gid?: number;
+  owner?: string;
+  group?: string;
 }
 
+export type SymlinkPolicy = "follow" | "skip" | "preserve";
+
 export interface TransferProgress {
   transfer_id: string;
   filename: string;
@@ -75,10 +254,24 @@ export interface ResumeRecord {
   transferred_bytes: number;
   checksum?: string;
   created_at?: string;
+  dest_host_id?: number;
 }
 
 export type OverwriteAction = "overwrite" | "skip" | "rename";
 
+/** 同一主机内批量复制、移动时目标已有同名条目的处理方式，取值与 OverwriteAction 相同 */
+export type ConflictPolicy = OverwriteAction;
+
+export type FileOpStatus = "done" | "skipped" | "failed";
+
+export interface FileOpResult {
+  source: string;
+  /** 实际写入的路径，按冲突策略改名时与原名称不同 */
+  target?: string;
+  status: FileOpStatus;
+  error?: string;
+}
+
 export interface TransferEvent {
   transfer_id: string;
   filename: string;
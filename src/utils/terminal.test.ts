@@ -0,0 +1,40 @@
+import { describe, it, expect } from "vitest";
+import { renderTerminal, keyToTerminalInput } from "./terminal";
+
+const key = (k: string, mods: Partial<{ ctrlKey: boolean; altKey: boolean; metaKey: boolean }> = {}) =>
+  keyToTerminalInput({ key: k, ctrlKey: false, altKey: false, metaKey: false, ...mods });
+
+describe("renderTerminal", () => {
+  it("strips colors and normalizes line endings", () => {
+    expect(renderTerminal("\x1b[01;34mdir\x1b[0m\r\nfile\r\n$ ")).toBe("dir\nfile\n$ ");
+  });
+
+  it("strips window title sequences", () => {
+    expect(renderTerminal("\x1b]0;user@host: ~\x07$ ")).toBe("$ ");
+  });
+
+  it("applies backspace and erase to end of line", () => {
+    expect(renderTerminal("$ lsx\b \b")).toBe("$ ls ");
+    expect(renderTerminal("$ hello\r$ hi\x1b[K")).toBe("$ hi");
+  });
+});
+
+describe("keyToTerminalInput", () => {
+  it("maps special keys", () => {
+    expect(key("Enter")).toBe("\r");
+    expect(key("Backspace")).toBe("\x7f");
+    expect(key("ArrowUp")).toBe("\x1b[A");
+  });
+
+  it("maps control and alt combinations", () => {
+    expect(key("c", { ctrlKey: true })).toBe("\x03");
+    expect(key("d", { ctrlKey: true })).toBe("\x04");
+    expect(key("b", { altKey: true })).toBe("\x1bb");
+  });
+
+  it("passes printable characters and ignores modifiers alone", () => {
+    expect(key("a")).toBe("a");
+    expect(key("Shift")).toBeNull();
+    expect(key("c", { metaKey: true })).toBeNull();
+  });
+});
@@ -0,0 +1,69 @@
+// eslint-disable-next-line no-control-regex
+const ESCAPE_SEQUENCE = /\x1b(\[[0-9;?]*[ -/]*[@-~]|\][^\x07\x1b]*(\x07|\x1b\\)|[()][0-9A-Za-z]|[=>78M])/g;
+
+/**
+ * 把终端原始输出渲染为纯文本：去掉颜色等控制序列，处理回车、退格和清除行尾，
+ * 足以显示 shell 提示符、命令输出和行编辑；全屏程序（vim、top）不在支持范围内
+ */
+export function renderTerminal(raw: string): string {
+  const lines: string[][] = [[]];
+  let col = 0;
+  let last = 0;
+  const write = (text: string) => {
+    for (const ch of text) {
+      if (ch === "\n") {
+        lines.push([]);
+        col = 0;
+      } else if (ch === "\r") {
+        col = 0;
+      } else if (ch === "\b") {
+        col = Math.max(0, col - 1);
+      } else if (ch >= " " || ch === "\t") {
+        lines[lines.length - 1][col++] = ch;
+      }
+    }
+  };
+  for (const match of raw.matchAll(ESCAPE_SEQUENCE)) {
+    write(raw.slice(last, match.index));
+    last = match.index + match[0].length;
+    // CSI K：清除光标到行尾
+    if (/^\x1b\[0?K$/.test(match[0])) {
+      lines[lines.length - 1].length = col;
+    }
+  }
+  write(raw.slice(last));
+  return lines.map((line) => Array.from(line, (ch) => ch ?? " ").join("")).join("\n");
+}
+
+/** 把浏览器按键转换为发送给 PTY 的字节，无对应输入时返回 null */
+export function keyToTerminalInput(e: {
+  key: string;
+  ctrlKey: boolean;
+  altKey: boolean;
+  metaKey: boolean;
+}): string | null {
+  if (e.metaKey) return null;
+  const special: Record<string, string> = {
+    Enter: "\r",
+    Backspace: "\x7f",
+    Tab: "\t",
+    Escape: "\x1b",
+    ArrowUp: "\x1b[A",
+    ArrowDown: "\x1b[B",
+    ArrowRight: "\x1b[C",
+    ArrowLeft: "\x1b[D",
+    Home: "\x1b[H",
+    End: "\x1b[F",
+    Delete: "\x1b[3~",
+    PageUp: "\x1b[5~",
+    PageDown: "\x1b[6~",
+  };
+  if (special[e.key]) return special[e.key];
+  if (e.key.length !== 1) return null;
+  if (e.ctrlKey) {
+    const code = e.key.toUpperCase().charCodeAt(0);
+    // Ctrl+@ 到 Ctrl+_ 对应 0x00-0x1f
+    return code >= 0x40 && code <= 0x5f ? String.fromCharCode(code - 0x40) : null;
+  }
+  return e.altKey ? `\x1b${e.key}` : e.key;
+}